@@ -1,7 +1,7 @@
 extern crate kiss3d;
 extern crate nalgebra as na;
 
-use kiss3d::camera::{ArcBall, FirstPerson};
+use kiss3d::camera::{ArcBall, FirstPerson, Orthographic};
 use kiss3d::event::{Action, Key, WindowEvent};
 use kiss3d::light::Light;
 use kiss3d::window::Window;
@@ -12,7 +12,8 @@ fn main() {
     let at = Point3::origin();
     let mut first_person = FirstPerson::new(eye, at);
     let mut arc_ball = ArcBall::new(eye, at);
-    let mut use_arc_ball = true;
+    let mut orthographic = Orthographic::new(eye, at);
+    let mut curr_camera = 1;
 
     let mut window = Window::new("Kiss3d: camera");
     window.set_light(Light::StickToCamera);
@@ -27,9 +28,11 @@ fn main() {
             match event.value {
                 WindowEvent::Key(key, Action::Release, _) => {
                     if key == Key::Numpad1 {
-                        use_arc_ball = true
+                        curr_camera = 1
                     } else if key == Key::Numpad2 {
-                        use_arc_ball = false
+                        curr_camera = 2
+                    } else if key == Key::Numpad3 {
+                        curr_camera = 3
                     }
                 }
                 _ => {}
@@ -52,10 +55,10 @@ fn main() {
             &Point3::new(0.0, 0.0, 1.0),
         );
 
-        if use_arc_ball {
-            window.render_with_camera(&mut arc_ball);
-        } else {
-            window.render_with_camera(&mut first_person);
-        }
+        match curr_camera {
+            1 => window.render_with_camera(&mut arc_ball),
+            2 => window.render_with_camera(&mut first_person),
+            _ => window.render_with_camera(&mut orthographic),
+        };
     }
 }
@@ -3,7 +3,7 @@ extern crate nalgebra as na;
 
 use kiss3d::camera::Camera;
 use kiss3d::context::Context;
-use kiss3d::light::Light;
+use kiss3d::environment::Environment;
 use kiss3d::resource::{Effect, Material, Mesh, ShaderAttribute, ShaderUniform};
 use kiss3d::scene::ObjectData;
 use kiss3d::window::Window;
@@ -64,7 +64,7 @@ impl Material for NormalMaterial {
         transform: &Isometry3<f32>,
         scale: &Vector3<f32>,
         camera: &mut dyn Camera,
-        _: &Light,
+        _: &Environment,
         _: &ObjectData,
         mesh: &mut Mesh,
     ) {
@@ -97,7 +97,7 @@ impl Material for NormalMaterial {
         Context::get().draw_elements(
             Context::TRIANGLES,
             mesh.num_pts() as i32,
-            Context::UNSIGNED_SHORT,
+            mesh.index_type(),
             0,
         );
 
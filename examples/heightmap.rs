@@ -0,0 +1,36 @@
+extern crate kiss3d;
+extern crate nalgebra as na;
+
+use kiss3d::light::Light;
+use kiss3d::resource::Mesh;
+use kiss3d::window::Window;
+use na::Vector3;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn main() {
+    let mut window = Window::new("Kiss3d: heightmap");
+    let (width, depth) = (120, 80);
+    let mut heights = Vec::with_capacity(width * depth);
+
+    for j in 0..depth {
+        for i in 0..width {
+            let (x, z) = (i as f32 / 10.0, j as f32 / 10.0);
+
+            // Leave a round hole in the middle of the terrain.
+            if (x - 6.0).powi(2) + (z - 4.0).powi(2) < 1.0 {
+                heights.push(std::f32::NAN);
+            } else {
+                heights.push((x.sin() * z.cos() + (x * 0.5 + z).sin() * 0.5) * 0.5);
+            }
+        }
+    }
+
+    let mesh = Mesh::from_heightmap(&heights, width, depth, 0.1);
+    let mut terrain = window.add_mesh(Rc::new(RefCell::new(mesh)), Vector3::from_element(1.0));
+
+    terrain.set_color(0.4, 0.7, 0.3);
+    window.set_light(Light::StickToCamera);
+
+    while window.render() {}
+}
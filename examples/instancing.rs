@@ -0,0 +1,33 @@
+extern crate kiss3d;
+extern crate nalgebra as na;
+
+use kiss3d::light::Light;
+use kiss3d::resource::MeshManager;
+use kiss3d::window::Window;
+use na::{Isometry3, UnitQuaternion, Vector3};
+
+fn main() {
+    let mut window = Window::new("Kiss3d: instancing");
+    let cube = MeshManager::get_global_manager(|mm| mm.get("cube")).unwrap();
+    let mut instances = Vec::new();
+
+    for i in 0..100 {
+        for j in 0..100 {
+            let shift = Vector3::new(i as f32 - 50.0, 0.0, j as f32 - 50.0);
+            let rot = Vector3::new(0.0, (i * j) as f32 * 0.01, 0.0);
+            instances.push(Isometry3::new(shift * 0.5, rot));
+        }
+    }
+
+    let mut c = window.add_instanced(cube, Vector3::new(0.25, 0.25, 0.25), &instances);
+
+    c.set_color(0.0, 1.0, 0.5);
+
+    window.set_light(Light::StickToCamera);
+
+    let rot = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.014);
+
+    while window.render() {
+        c.prepend_to_local_rotation(&rot);
+    }
+}
@@ -0,0 +1,37 @@
+extern crate kiss3d;
+extern crate nalgebra as na;
+
+use kiss3d::builtin::PbrMaterial;
+use kiss3d::light::Light;
+use kiss3d::resource::Material;
+use kiss3d::window::Window;
+use na::{Point3, Translation3, Vector3};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn main() {
+    let mut window = Window::new("Kiss3d: pbr");
+
+    // Spheres getting more metallic from bottom to top, and rougher from left to right.
+    for i in 0..5 {
+        for j in 0..5 {
+            let mut material = PbrMaterial::new();
+            material.set_metallic(i as f32 / 4.0);
+            material.set_roughness(j as f32 / 4.0);
+
+            let mut s = window.add_sphere(0.4);
+            s.set_color(1.0, 0.6, 0.2);
+            s.set_material(Rc::new(RefCell::new(
+                Box::new(material) as Box<dyn Material + 'static>
+            )));
+            s.append_translation(&Translation3::new(j as f32 - 2.0, i as f32 - 2.0, 0.0));
+        }
+    }
+
+    window.set_light(Light::Directional {
+        direction: Vector3::new(-1.0, -1.0, -1.0),
+        color: Point3::new(1.0, 1.0, 1.0),
+    });
+
+    while window.render() {}
+}
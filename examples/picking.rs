@@ -0,0 +1,44 @@
+extern crate kiss3d;
+extern crate nalgebra as na;
+
+use kiss3d::event::{Action, MouseButton, WindowEvent};
+use kiss3d::light::Light;
+use kiss3d::scene::SceneNode;
+use kiss3d::window::Window;
+use na::{Point2, Translation3};
+
+fn main() {
+    let mut window = Window::new("Kiss3d: picking");
+    let mut nodes = Vec::new();
+
+    for i in 0..5 {
+        let mut c = window.add_cube(0.2, 0.2, 0.2);
+        c.set_local_translation(Translation3::new(i as f32 * 0.4 - 0.8, 0.0, 0.0));
+        nodes.push(c);
+    }
+
+    window.set_light(Light::StickToCamera);
+
+    let mut last_pos = Point2::new(0.0f32, 0.0f32);
+    let mut selected: Option<SceneNode> = None;
+
+    while window.render() {
+        for event in window.events().iter() {
+            match event.value {
+                WindowEvent::CursorPos(x, y, _) => last_pos = Point2::new(x as f32, y as f32),
+                WindowEvent::MouseButton(MouseButton::Button1, Action::Press, _) => {
+                    if let Some(mut node) = selected.take() {
+                        node.set_color(1.0, 1.0, 1.0);
+                    }
+
+                    selected = window.pick(&last_pos);
+
+                    if let Some(ref mut node) = selected {
+                        node.set_color(1.0, 0.0, 0.0);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
@@ -4,7 +4,7 @@ extern crate rand;
 
 use kiss3d::light::Light;
 use kiss3d::post_processing::SobelEdgeHighlight;
-use kiss3d::post_processing::{Grayscales, Waves};
+use kiss3d::post_processing::{Fxaa, Grayscales, Waves};
 use kiss3d::window::Window;
 use na::Translation3;
 use rand::random;
@@ -33,6 +33,7 @@ fn main() {
     let mut sobel = SobelEdgeHighlight::new(4.0);
     let mut waves = Waves::new();
     let mut grays = Grayscales::new();
+    let mut fxaa = Fxaa::new();
 
     window.set_background_color(1.0, 1.0, 1.0);
     window.set_light(Light::StickToCamera);
@@ -44,7 +45,7 @@ fn main() {
     while !window.should_close() {
         if time % 200 == 0 {
             time = 0;
-            counter = (counter + 1) % 4;
+            counter = (counter + 1) % 5;
         }
 
         time = time + 1;
@@ -54,6 +55,7 @@ fn main() {
             1 => window.render_with_effect(&mut grays),
             2 => window.render_with_effect(&mut waves),
             3 => window.render_with_effect(&mut sobel),
+            4 => window.render_with_effect(&mut fxaa),
             _ => unreachable!(),
         };
     }
@@ -15,18 +15,21 @@ fn main() {
     let mut p = window.add_cone(0.5, 1.0);
     let mut y = window.add_cylinder(0.5, 1.0);
     let mut a = window.add_capsule(0.5, 1.0);
+    let mut t = window.add_torus(0.5, 0.2, 48, 24);
 
     c.set_color(random(), random(), random());
     s.set_color(random(), random(), random());
     p.set_color(random(), random(), random());
     y.set_color(random(), random(), random());
     a.set_color(random(), random(), random());
+    t.set_color(random(), random(), random());
 
     c.append_translation(&Translation3::new(2.0, 0.0, 0.0));
     s.append_translation(&Translation3::new(4.0, 0.0, 0.0));
     p.append_translation(&Translation3::new(-2.0, 0.0, 0.0));
     y.append_translation(&Translation3::new(-4.0, 0.0, 0.0));
     a.append_translation(&Translation3::new(0.0, 0.0, 0.0));
+    t.append_translation(&Translation3::new(0.0, 2.0, 0.0));
 
     window.set_light(Light::StickToCamera);
 
@@ -38,5 +41,6 @@ fn main() {
         p.append_rotation_wrt_center(&rot);
         y.append_rotation_wrt_center(&rot);
         a.append_rotation_wrt_center(&rot);
+        t.append_rotation_wrt_center(&rot);
     }
 }
@@ -2,7 +2,7 @@ extern crate kiss3d;
 extern crate nalgebra as na;
 
 use kiss3d::window::Window;
-use na::{Translation2, UnitComplex};
+use na::{Point2, Translation2, UnitComplex, Vector2};
 
 fn main() {
     let mut window = Window::new("Kiss3d: rectangle");
@@ -10,6 +10,20 @@ fn main() {
     let mut circ = window.add_circle(50.0);
     circ.append_translation(&Translation2::new(200.0, 0.0));
 
+    // A concave arrow, given in clockwise order.
+    let arrow = vec![
+        Point2::new(-40.0, 20.0),
+        Point2::new(0.0, 20.0),
+        Point2::new(0.0, 50.0),
+        Point2::new(50.0, 0.0),
+        Point2::new(0.0, -50.0),
+        Point2::new(0.0, -20.0),
+        Point2::new(-40.0, -20.0),
+    ];
+    let mut poly = window.add_polygon(arrow, Vector2::repeat(1.0));
+    poly.append_translation(&Translation2::new(-200.0, 0.0));
+    poly.set_color(1.0, 0.0, 0.0);
+
     rect.set_color(0.0, 1.0, 0.0);
     circ.set_color(0.0, 0.0, 1.0);
 
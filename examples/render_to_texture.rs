@@ -0,0 +1,34 @@
+extern crate kiss3d;
+extern crate nalgebra as na;
+
+use kiss3d::camera::ArcBall;
+use kiss3d::light::Light;
+use kiss3d::resource::FramebufferManager;
+use kiss3d::window::Window;
+use na::{Point3, Translation3, UnitQuaternion, Vector3};
+
+fn main() {
+    let mut window = Window::new("Kiss3d: render_to_texture");
+    let mut c = window.add_cube(1.0, 1.0, 1.0);
+    let mut monitor = window.add_quad(2.0, 2.0, 1, 1);
+
+    c.set_color(1.0, 0.0, 0.0);
+    monitor.append_translation(&Translation3::new(0.0, 0.0, -3.0));
+
+    // The monitor displays the cube seen from above. It is hidden while the security camera
+    // renders, so that it does not display itself.
+    let target = FramebufferManager::new_render_target(512, 512, false);
+    let mut security_camera = ArcBall::new(Point3::new(2.0, 3.0, 2.0), Point3::origin());
+    monitor.set_texture(target.texture().unwrap());
+
+    window.set_light(Light::StickToCamera);
+
+    let rot = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.014);
+
+    while window.render() {
+        c.prepend_to_local_rotation(&rot);
+        monitor.set_visible(false);
+        window.render_to_texture(&target, &mut security_camera);
+        monitor.set_visible(true);
+    }
+}
@@ -22,9 +22,9 @@ fn main() {
     window.set_light(Light::StickToCamera);
 
     while window.render() {
-        let img = window.snap_image();
-        let img_path = Path::new("screenshot.png");
-        img.save(img_path).unwrap();
+        window
+            .save_screenshot(Path::new("screenshot.png"))
+            .unwrap();
         println!("Screeshot saved to `screenshot.png`");
         break;
     }
@@ -0,0 +1,29 @@
+extern crate kiss3d;
+extern crate nalgebra as na;
+
+use kiss3d::light::Light;
+use kiss3d::window::Window;
+use na::{Point3, Translation3, UnitQuaternion, Vector3};
+
+fn main() {
+    let mut window = Window::new("Kiss3d: shadows");
+    let mut ground = window.add_cube(10.0, 0.1, 10.0);
+    let mut c = window.add_cube(1.0, 1.0, 1.0);
+    let mut s = window.add_sphere(0.5);
+
+    ground.append_translation(&Translation3::new(0.0, -1.0, 0.0));
+    s.append_translation(&Translation3::new(2.0, 0.0, 0.0));
+
+    ground.set_color(0.8, 0.8, 0.8);
+    c.set_color(1.0, 0.0, 0.0);
+    s.set_color(0.0, 0.5, 1.0);
+
+    window.set_light(Light::absolute(Point3::new(3.0, 6.0, 3.0)));
+    window.set_shadow_map_size(Some(2048));
+
+    let rot = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.014);
+
+    while window.render() {
+        c.prepend_to_local_rotation(&rot);
+    }
+}
@@ -1,13 +1,16 @@
 extern crate kiss3d;
 extern crate nalgebra as na;
 
-use kiss3d::text::Font;
+use kiss3d::text::{Font, TextAlignment};
 use kiss3d::window::Window;
-use na::{Point2, Point3};
+use na::{Point2, Point3, Vector2};
 
 fn main() {
     let mut window = Window::new("Kiss3d: text");
     let font = Font::default();
+    let mut cube = window.add_cube(1.0, 1.0, 1.0);
+
+    cube.set_color(1.0, 0.0, 0.0);
 
     while window.render() {
         window.draw_text(
@@ -27,5 +30,24 @@ fn main() {
             &font,
             &Point3::new(1.0, 1.0, 0.0),
         );
+
+        window.draw_aligned_text(
+            "This long sentence is wrapped and centered on the middle of the window.",
+            &Point2::new(window.width() as f32, 400.0),
+            60.0,
+            &font,
+            &Point3::new(0.0, 1.0, 0.0),
+            TextAlignment::Center,
+            Some(800.0),
+        );
+
+        window.draw_text_at_with_offset(
+            "A cube",
+            &Point3::new(0.0, 0.5, 0.0),
+            &Vector2::new(0.0, -60.0),
+            60.0,
+            &font,
+            &Point3::new(1.0, 1.0, 1.0),
+        );
     }
 }
@@ -18,6 +18,10 @@ fn main() {
     r.set_color(0.0, 0.0, 1.0);
     r.set_texture_from_memory(include_bytes!("./media/kitten.png"), "kitten_mem");
 
+    // A sprite has the size of its texture, in pixels.
+    let mut sprite = window.add_sprite("kitten");
+    sprite.append_translation(&Translation2::new(150.0, 100.0));
+
     window.set_light(Light::StickToCamera);
 
     let rot3d = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.014);
@@ -0,0 +1,91 @@
+//! Keyframe animations of the scene nodes.
+
+use na::Isometry3;
+
+/// A timeline of transformations, interpolated between keyframes.
+///
+/// The translations are interpolated linearly, and the rotations with a spherical linear
+/// interpolation. Before the first keyframe and after the last one, the transformation of the
+/// nearest keyframe is used. Play a track on a node with `SceneNode::play_animation`.
+#[derive(Clone, Debug, Default)]
+pub struct TransformTrack {
+    // Sorted by increasing time.
+    keyframes: Vec<(f32, Isometry3<f32>)>,
+}
+
+impl TransformTrack {
+    /// Creates a new track without keyframes.
+    pub fn new() -> TransformTrack {
+        TransformTrack {
+            keyframes: Vec::new(),
+        }
+    }
+
+    /// Creates a track from a list of `(time, transformation)` keyframes, in any order.
+    pub fn from_keyframes(keyframes: Vec<(f32, Isometry3<f32>)>) -> TransformTrack {
+        let mut res = TransformTrack::new();
+
+        for (time, transform) in keyframes {
+            res.add_keyframe(time, transform);
+        }
+
+        res
+    }
+
+    /// Adds a keyframe at `time` seconds from the start of the track.
+    ///
+    /// A keyframe added at the time of an existing one replaces it.
+    ///
+    /// # Failures:
+    /// Fails if `time` is negative.
+    pub fn add_keyframe(&mut self, time: f32, transform: Isometry3<f32>) {
+        assert!(time >= 0.0, "The keyframe time must be non-negative.");
+
+        match self.keyframes.iter().position(|k| k.0 >= time) {
+            Some(i) if self.keyframes[i].0 == time => self.keyframes[i].1 = transform,
+            Some(i) => self.keyframes.insert(i, (time, transform)),
+            None => self.keyframes.push((time, transform)),
+        }
+    }
+
+    /// The keyframes of this track, sorted by increasing time.
+    pub fn keyframes(&self) -> &[(f32, Isometry3<f32>)] {
+        &self.keyframes[..]
+    }
+
+    /// The time of the last keyframe, in seconds.
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |k| k.0)
+    }
+
+    /// The transformation at `time` seconds from the start of the track.
+    ///
+    /// Returns `None` if this track has no keyframe.
+    pub fn sample(&self, time: f32) -> Option<Isometry3<f32>> {
+        let first = self.keyframes.first()?;
+        let last = self.keyframes.last()?;
+
+        if time <= first.0 {
+            return Some(first.1);
+        }
+
+        if time >= last.0 {
+            return Some(last.1);
+        }
+
+        // There is a keyframe after `time` since it is before the last one.
+        let i = self.keyframes.iter().position(|k| k.0 > time).unwrap();
+        let (t0, a) = self.keyframes[i - 1];
+        let (t1, b) = self.keyframes[i];
+        let s = (time - t0) / (t1 - t0);
+
+        let translation = a.translation.vector.lerp(&b.translation.vector, s);
+        // Opposite quaternions represent the same rotation, so the interpolation is not defined.
+        let rotation = a
+            .rotation
+            .try_slerp(&b.rotation, s, 1.0e-6)
+            .unwrap_or(a.rotation);
+
+        Some(Isometry3::from_parts(translation.into(), rotation))
+    }
+}
@@ -5,31 +5,144 @@
    precision mediump float;
 #endif
 
-varying vec3 local_light_position;
+#define MAX_LIGHTS 8
+#define MAX_CLIP_PLANES 4
+
 varying vec2 tex_coord_v;
 varying vec3 normalInterp;
 varying vec3 vertPos;
+varying vec4 shadow_coord;
+varying vec3 worldPos;
 
 uniform vec3 color;
+uniform float alpha;
+uniform vec3 specular_color;
+uniform float shininess;
+// Color emitted independently from the lighting, modulated by `emissive_map` if
+// `use_emissive_map` is non-zero.
+uniform vec3 emissive_color;
+uniform int use_emissive_map;
+uniform sampler2D emissive_map;
 uniform sampler2D tex;
-const vec3 specColor = vec3(0.4, 0.4, 0.4);
+// Light positions in view space. Directional lights have a `w` equal to 0 and store the
+// direction toward the light instead.
+uniform vec4 light_position[MAX_LIGHTS];
+uniform vec3 light_color[MAX_LIGHTS];
+uniform int num_lights;
+uniform vec3 ambient;
+// Shadows cast by the first light. The depth seen from the light is packed into `shadow_map`.
+uniform int shadows;
+uniform sampler2D shadow_map;
+uniform float shadow_bias;
+uniform float shadow_texel;
+// Fog: `fog_mode` is 0 without fog, 1 for a linear fog, and 2 for an exponential fog.
+uniform int fog_mode;
+uniform vec3 fog_color;
+uniform float fog_start;
+uniform float fog_end;
+uniform float fog_density;
+// Clipping planes in world space. Disabled planes are set to (0, 0, 0, 1).
+uniform vec4 clip_planes[MAX_CLIP_PLANES];
+
+float unpack_depth(vec4 rgba) {
+  return dot(rgba, vec4(1.0, 1.0 / 255.0, 1.0 / 65025.0, 1.0 / 16581375.0));
+}
+
+// The fraction of the 3x3 neighborhood of the fragment lit by the first light.
+float shadow_visibility() {
+  vec3 coord = shadow_coord.xyz / shadow_coord.w * 0.5 + 0.5;
+
+  if (coord.x < 0.0 || coord.x > 1.0 || coord.y < 0.0 || coord.y > 1.0 || coord.z > 1.0) {
+    return 1.0;
+  }
+
+  float visibility = 0.0;
+
+  for (int x = -1; x <= 1; x++) {
+    for (int y = -1; y <= 1; y++) {
+      vec2 offset = vec2(float(x), float(y)) * shadow_texel;
+      float depth = unpack_depth(texture2D(shadow_map, coord.xy + offset));
+
+      if (coord.z - shadow_bias <= depth) {
+        visibility += 1.0;
+      }
+    }
+  }
+
+  return visibility / 9.0;
+}
 
 void main() {
+  for (int i = 0; i < MAX_CLIP_PLANES; i++) {
+    if (dot(clip_planes[i].xyz, worldPos) + clip_planes[i].w < 0.0) {
+      discard;
+    }
+  }
+
   vec3 normal = normalize(normalInterp);
-  vec3 lightDir = normalize(local_light_position - vertPos);
+  vec3 viewDir = normalize(-vertPos);
+
+  vec3 lambertian = vec3(0.0, 0.0, 0.0);
+  vec3 specular = vec3(0.0, 0.0, 0.0);
+  float visibility = 1.0;
 
-  float lambertian = max(dot(lightDir, normal), 0.0);
-  float specular = 0.0;
+  if (shadows != 0) {
+    visibility = shadow_visibility();
+  }
+
+  for (int i = 0; i < MAX_LIGHTS; i++) {
+    if (i >= num_lights) {
+      break;
+    }
+
+    vec3 lightDir;
+
+    if (light_position[i].w == 0.0) {
+      lightDir = normalize(light_position[i].xyz);
+    } else {
+      lightDir = normalize(light_position[i].xyz - vertPos);
+    }
 
-  if(lambertian > 0.0) {
-    vec3 viewDir = normalize(-vertPos);
-    vec3 halfDir = normalize(lightDir + viewDir);
-    float specAngle = max(dot(halfDir, normal), 0.0);
-    specular = pow(specAngle, 30.0);
+    vec3 lcolor = light_color[i];
+
+    if (i == 0) {
+      lcolor *= visibility;
+    }
+
+    float l = max(dot(lightDir, normal), 0.0);
+    lambertian += l * lcolor;
+
+    if(l > 0.0) {
+      vec3 halfDir = normalize(lightDir + viewDir);
+      float specAngle = max(dot(halfDir, normal), 0.0);
+      specular += pow(specAngle, shininess) * lcolor;
+    }
   }
 
   vec4 tex_color = texture2D(tex, tex_coord_v);
-  gl_FragColor = tex_color * vec4(color / 3.0 +
+  gl_FragColor = tex_color * vec4(ambient * color +
                                   lambertian * color / 3.0 +
-                                  specular * specColor / 3.0, 1.0);
+                                  specular * specular_color / 3.0, alpha);
+
+  vec3 emission = emissive_color;
+
+  if (use_emissive_map != 0) {
+    emission *= texture2D(emissive_map, tex_coord_v).rgb;
+  }
+
+  gl_FragColor.rgb += emission;
+
+  if (fog_mode != 0) {
+    float dist = length(vertPos);
+    float fog;
+
+    if (fog_mode == 1) {
+      // The fog is a step at `fog_start` if it is not before `fog_end`.
+      fog = (dist - fog_start) / max(fog_end - fog_start, 1.0e-4);
+    } else {
+      fog = 1.0 - exp(-(fog_density * dist) * (fog_density * dist));
+    }
+
+    gl_FragColor.rgb = mix(gl_FragColor.rgb, fog_color, clamp(fog, 0.0, 1.0));
+  }
 }
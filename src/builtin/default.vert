@@ -2,21 +2,32 @@
 attribute vec3 position;
 attribute vec2 tex_coord;
 attribute vec3 normal;
+attribute vec3 inst_tra;
+attribute vec4 inst_rot;
 
 uniform mat3 ntransform, scale;
 uniform mat4 proj, view, transform;
-uniform vec3 light_position;
+// Transformation from world coordinates to the clip space of the shadow-casting light.
+uniform mat4 light_transform;
 
-varying vec3 local_light_position;
 varying vec2 tex_coord_v;
 varying vec3 normalInterp;
 varying vec3 vertPos;
+varying vec4 shadow_coord;
+varying vec3 worldPos;
+
+// Rotates a vector by a unit quaternion.
+vec3 rotate(vec4 q, vec3 v) {
+    return v + 2.0 * cross(q.xyz, cross(q.xyz, v) + q.w * v);
+}
 
 void main(){
-    gl_Position = proj * view * transform * vec4(scale * position, 1.0);
-    vec4 vertPos4 = view * transform * vec4(scale * position, 1.0);
+    vec4 pos = transform * vec4(rotate(inst_rot, scale * position) + inst_tra, 1.0);
+    gl_Position = proj * view * pos;
+    vec4 vertPos4 = view * pos;
     vertPos = vec3(vertPos4) / vertPos4.w;
-    normalInterp = mat3(view) * ntransform * normal;
+    normalInterp = mat3(view) * ntransform * rotate(inst_rot, normal);
     tex_coord_v = tex_coord;
-    local_light_position = (view * vec4(light_position, 1.0)).xyz;
+    shadow_coord = light_transform * pos;
+    worldPos = pos.xyz / pos.w;
 }
@@ -0,0 +1,133 @@
+use super::object_material::InstancesAttributes;
+use crate::camera::Camera;
+use crate::context::Context;
+use crate::environment::Environment;
+use crate::resource::Material;
+use crate::resource::{Effect, Mesh, ShaderAttribute, ShaderUniform};
+use crate::scene::ObjectData;
+use na::{Isometry3, Matrix3, Matrix4, Point3, Vector3};
+
+#[path = "../error.rs"]
+mod error;
+
+/// A material that draws the depth of the objects seen from a light.
+///
+/// The depth is packed into the four channels of the output color. This is used to fill shadow
+/// maps: the camera is ignored in favor of the light transformation.
+pub struct DepthMaterial {
+    effect: Effect,
+    pos: ShaderAttribute<Point3<f32>>,
+    instances: InstancesAttributes,
+    transform: ShaderUniform<Matrix4<f32>>,
+    scale: ShaderUniform<Matrix3<f32>>,
+    light_transform: ShaderUniform<Matrix4<f32>>,
+    light_transformation: Matrix4<f32>,
+}
+
+impl DepthMaterial {
+    /// Creates a new `DepthMaterial`.
+    pub fn new() -> DepthMaterial {
+        let mut effect = Effect::new_from_str(DEPTH_VERTEX_SRC, DEPTH_FRAGMENT_SRC);
+
+        effect.use_program();
+
+        DepthMaterial {
+            pos: effect.get_attrib("position").unwrap(),
+            instances: InstancesAttributes::new(&effect),
+            transform: effect.get_uniform("transform").unwrap(),
+            scale: effect.get_uniform("scale").unwrap(),
+            light_transform: effect.get_uniform("light_transform").unwrap(),
+            light_transformation: Matrix4::identity(),
+            effect: effect,
+        }
+    }
+
+    /// Sets the transformation from world coordinates to the clip space of the light.
+    #[inline]
+    pub fn set_light_transformation(&mut self, transformation: Matrix4<f32>) {
+        self.light_transformation = transformation
+    }
+}
+
+impl Material for DepthMaterial {
+    fn render(
+        &mut self,
+        _: usize,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+        _: &mut dyn Camera,
+        _: &Environment,
+        data: &ObjectData,
+        mesh: &mut Mesh,
+    ) {
+        if !data.surface_rendering_active() {
+            return;
+        }
+
+        let ctxt = Context::get();
+        let mut instances = data.instances().map(|i| i.borrow_mut());
+        let formated_transform = transform.to_homogeneous();
+        let formated_scale = Matrix3::from_diagonal(&Vector3::new(scale.x, scale.y, scale.z));
+
+        self.effect.use_program();
+        self.pos.enable();
+
+        self.light_transform.upload(&self.light_transformation);
+        self.transform.upload(&formated_transform);
+        self.scale.upload(&formated_scale);
+
+        mesh.bind_coords(&mut self.pos);
+        mesh.bind_faces();
+        self.instances.bind(instances.as_deref_mut());
+
+        // Both faces cast shadows, even for objects drawn with backface culling.
+        verify!(ctxt.disable(Context::CULL_FACE));
+        let _ = verify!(ctxt.polygon_mode(Context::FRONT_AND_BACK, Context::FILL));
+        self.instances.draw_elements(
+            Context::TRIANGLES,
+            mesh.num_pts() as i32,
+            mesh.index_type(),
+            instances.as_deref(),
+        );
+
+        self.instances.unbind(instances.as_deref_mut());
+        mesh.unbind();
+        self.pos.disable();
+    }
+}
+
+/// Vertex shader of the depth material.
+pub static DEPTH_VERTEX_SRC: &'static str = A_VERY_LONG_STRING;
+/// Fragment shader of the depth material.
+pub static DEPTH_FRAGMENT_SRC: &'static str = ANOTHER_VERY_LONG_STRING;
+
+const A_VERY_LONG_STRING: &'static str = "#version 100
+    attribute vec3 position;
+    attribute vec3 inst_tra;
+    attribute vec4 inst_rot;
+    uniform mat3 scale;
+    uniform mat4 light_transform, transform;
+
+    // Rotates a vector by a unit quaternion.
+    vec3 rotate(vec4 q, vec3 v) {
+        return v + 2.0 * cross(q.xyz, cross(q.xyz, v) + q.w * v);
+    }
+
+    void main() {
+        vec4 pos = transform * vec4(rotate(inst_rot, scale * position) + inst_tra, 1.0);
+        gl_Position = light_transform * pos;
+    }";
+
+// The depth is split into four 8-bits chunks.
+const ANOTHER_VERY_LONG_STRING: &'static str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+    void main() {
+        vec4 enc = fract(vec4(1.0, 255.0, 65025.0, 16581375.0) * gl_FragCoord.z);
+        enc -= enc.yzww * vec4(1.0 / 255.0, 1.0 / 255.0, 1.0 / 255.0, 0.0);
+        gl_FragColor = enc;
+    }";
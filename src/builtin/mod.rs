@@ -1,13 +1,21 @@
 //! Built-in geometries, shaders and effects.
 
+pub use self::depth_material::{DepthMaterial, DEPTH_FRAGMENT_SRC, DEPTH_VERTEX_SRC};
 pub use self::normals_material::{NormalsMaterial, NORMAL_FRAGMENT_SRC, NORMAL_VERTEX_SRC};
 pub use self::object_material::{ObjectMaterial, OBJECT_FRAGMENT_SRC, OBJECT_VERTEX_SRC};
+pub use self::pbr_material::{PbrMaterial, PBR_FRAGMENT_SRC, PBR_VERTEX_SRC};
 pub use self::uvs_material::{UvsMaterial, UVS_FRAGMENT_SRC, UVS_VERTEX_SRC};
+pub use self::vertex_color_material::{
+    VertexColorMaterial, VERTEX_COLOR_FRAGMENT_SRC, VERTEX_COLOR_VERTEX_SRC,
+};
 
 pub use self::planar_object_material::PlanarObjectMaterial;
 
+mod depth_material;
 mod normals_material;
 mod object_material;
+mod pbr_material;
 mod uvs_material;
+mod vertex_color_material;
 
 mod planar_object_material;
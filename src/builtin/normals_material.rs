@@ -1,6 +1,6 @@
 use crate::camera::Camera;
 use crate::context::Context;
-use crate::light::Light;
+use crate::environment::Environment;
 use crate::resource::Material;
 use crate::resource::{Effect, Mesh, ShaderAttribute, ShaderUniform};
 use crate::scene::ObjectData;
@@ -46,7 +46,7 @@ impl Material for NormalsMaterial {
         transform: &Isometry3<f32>,
         scale: &Vector3<f32>,
         camera: &mut dyn Camera,
-        _: &Light,
+        _: &Environment,
         data: &ObjectData,
         mesh: &mut Mesh,
     ) {
@@ -91,7 +91,7 @@ impl Material for NormalsMaterial {
             ctxt.draw_elements(
                 Context::TRIANGLES,
                 mesh.num_pts() as i32,
-                Context::UNSIGNED_INT,
+                mesh.index_type(),
                 0,
             );
         }
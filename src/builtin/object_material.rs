@@ -1,10 +1,11 @@
 use crate::camera::Camera;
-use crate::context::Context;
-use crate::light::Light;
+use crate::context::{Context, GLenum};
+use crate::environment::{Environment, FogMode, MAX_CLIP_PLANES};
+use crate::light::{Light, MAX_LIGHTS};
 use crate::resource::Material;
-use crate::resource::{Effect, Mesh, ShaderAttribute, ShaderUniform};
+use crate::resource::{Effect, InstancesBuffer, Mesh, ShaderAttribute, ShaderUniform};
 use crate::scene::ObjectData;
-use na::{Isometry3, Matrix3, Matrix4, Point2, Point3, Vector3};
+use na::{Isometry3, Matrix3, Matrix4, Point2, Point3, Vector3, Vector4};
 
 #[path = "../error.rs"]
 mod error;
@@ -15,8 +16,29 @@ pub struct ObjectMaterial {
     pos: ShaderAttribute<Point3<f32>>,
     normal: ShaderAttribute<Vector3<f32>>,
     tex_coord: ShaderAttribute<Point2<f32>>,
-    light: ShaderUniform<Point3<f32>>,
+    instances: InstancesAttributes,
+    light_positions: Vec<ShaderUniform<Vector4<f32>>>,
+    light_colors: Vec<ShaderUniform<Point3<f32>>>,
+    num_lights: ShaderUniform<i32>,
+    ambient: ShaderUniform<Point3<f32>>,
+    shadows: ShaderUniform<i32>,
+    shadow_map: ShaderUniform<i32>,
+    shadow_bias: ShaderUniform<f32>,
+    shadow_texel: ShaderUniform<f32>,
+    light_transform: ShaderUniform<Matrix4<f32>>,
+    fog_mode: ShaderUniform<i32>,
+    fog_color: ShaderUniform<Point3<f32>>,
+    fog_start: ShaderUniform<f32>,
+    fog_end: ShaderUniform<f32>,
+    fog_density: ShaderUniform<f32>,
+    clip_planes: Vec<ShaderUniform<Vector4<f32>>>,
     color: ShaderUniform<Point3<f32>>,
+    alpha: ShaderUniform<f32>,
+    specular_color: ShaderUniform<Point3<f32>>,
+    shininess: ShaderUniform<f32>,
+    emissive_color: ShaderUniform<Point3<f32>>,
+    use_emissive_map: ShaderUniform<i32>,
+    emissive_map: ShaderUniform<i32>,
     transform: ShaderUniform<Matrix4<f32>>,
     scale: ShaderUniform<Matrix3<f32>>,
     ntransform: ShaderUniform<Matrix3<f32>>,
@@ -37,8 +59,39 @@ impl ObjectMaterial {
             pos: effect.get_attrib("position").unwrap(),
             normal: effect.get_attrib("normal").unwrap(),
             tex_coord: effect.get_attrib("tex_coord").unwrap(),
-            light: effect.get_uniform("light_position").unwrap(),
+            instances: InstancesAttributes::new(&effect),
+            light_positions: (0..MAX_LIGHTS)
+                .map(|i| {
+                    effect
+                        .get_uniform(&format!("light_position[{}]", i))
+                        .unwrap()
+                })
+                .collect(),
+            light_colors: (0..MAX_LIGHTS)
+                .map(|i| effect.get_uniform(&format!("light_color[{}]", i)).unwrap())
+                .collect(),
+            num_lights: effect.get_uniform("num_lights").unwrap(),
+            ambient: effect.get_uniform("ambient").unwrap(),
+            shadows: effect.get_uniform("shadows").unwrap(),
+            shadow_map: effect.get_uniform("shadow_map").unwrap(),
+            shadow_bias: effect.get_uniform("shadow_bias").unwrap(),
+            shadow_texel: effect.get_uniform("shadow_texel").unwrap(),
+            light_transform: effect.get_uniform("light_transform").unwrap(),
+            fog_mode: effect.get_uniform("fog_mode").unwrap(),
+            fog_color: effect.get_uniform("fog_color").unwrap(),
+            fog_start: effect.get_uniform("fog_start").unwrap(),
+            fog_end: effect.get_uniform("fog_end").unwrap(),
+            fog_density: effect.get_uniform("fog_density").unwrap(),
+            clip_planes: (0..MAX_CLIP_PLANES)
+                .map(|i| effect.get_uniform(&format!("clip_planes[{}]", i)).unwrap())
+                .collect(),
             color: effect.get_uniform("color").unwrap(),
+            alpha: effect.get_uniform("alpha").unwrap(),
+            specular_color: effect.get_uniform("specular_color").unwrap(),
+            shininess: effect.get_uniform("shininess").unwrap(),
+            emissive_color: effect.get_uniform("emissive_color").unwrap(),
+            use_emissive_map: effect.get_uniform("use_emissive_map").unwrap(),
+            emissive_map: effect.get_uniform("emissive_map").unwrap(),
             transform: effect.get_uniform("transform").unwrap(),
             scale: effect.get_uniform("scale").unwrap(),
             ntransform: effect.get_uniform("ntransform").unwrap(),
@@ -69,7 +122,7 @@ impl Material for ObjectMaterial {
         transform: &Isometry3<f32>,
         scale: &Vector3<f32>,
         camera: &mut dyn Camera,
-        light: &Light,
+        environment: &Environment,
         data: &ObjectData,
         mesh: &mut Mesh,
     ) {
@@ -83,18 +136,78 @@ impl Material for ObjectMaterial {
          */
         camera.upload(pass, &mut self.proj, &mut self.view);
 
-        let pos = match *light {
-            Light::Absolute(ref p) => p.clone(),
-            Light::StickToCamera => camera.eye(),
-        };
+        // Lights are uploaded in view space.
+        let view = camera.view_transform();
+        let lights = environment.lights();
+        let lights = &lights[..lights.len().min(MAX_LIGHTS)];
 
-        self.light.upload(&pos);
+        for (i, light) in lights.iter().enumerate() {
+            // The `w` component is 0 for directional lights, and 1 for positional lights.
+            let pos = match *light {
+                Light::Absolute(ref pos) | Light::AbsoluteWithColor { ref pos, .. } => {
+                    (view * pos).to_homogeneous()
+                }
+                Light::StickToCamera => Vector4::w(),
+                Light::Directional { ref direction, .. } => (view * -direction).to_homogeneous(),
+            };
+
+            self.light_positions[i].upload(&pos);
+            self.light_colors[i].upload(&light.color());
+        }
+
+        self.num_lights.upload(&(lights.len() as i32));
+        self.ambient.upload(environment.ambient_color());
+
+        // The shadow map is bound to the texture unit 1.
+        let shadow_map = environment
+            .shadow_map()
+            .and_then(|s| s.transformation().map(|t| (s, t)));
+
+        match shadow_map {
+            Some((shadow_map, transformation)) => {
+                self.shadows.upload(&1);
+                self.light_transform.upload(&transformation);
+                self.shadow_bias.upload(&shadow_map.bias());
+                self.shadow_texel.upload(&(1.0 / shadow_map.size() as f32));
+
+                verify!(ctxt.active_texture(Context::TEXTURE1));
+                verify!(ctxt.bind_texture(Context::TEXTURE_2D, shadow_map.texture()));
+            }
+            None => {
+                self.shadows.upload(&0);
+                self.light_transform.upload(&Matrix4::identity());
+            }
+        }
+
+        self.shadow_map.upload(&1);
+
+        match environment.fog() {
+            Some(fog) => {
+                let mode = match fog.mode {
+                    FogMode::Linear => 1,
+                    FogMode::Exp2 => 2,
+                };
+
+                self.fog_mode.upload(&mode);
+                self.fog_color.upload(&fog.color);
+                self.fog_start.upload(&fog.start);
+                self.fog_end.upload(&fog.end);
+                self.fog_density.upload(&fog.density);
+            }
+            None => self.fog_mode.upload(&0),
+        }
+
+        for (uniform, plane) in self.clip_planes.iter_mut().zip(environment.clip_planes()) {
+            // A plane keeping every point.
+            uniform.upload(&plane.unwrap_or_else(Vector4::w));
+        }
 
         /*
          *
          * Setup object-related stuffs.
          *
          */
+        let mut instances = data.instances().map(|i| i.borrow_mut());
         let formated_transform = transform.to_homogeneous();
         let formated_ntransform = transform.rotation.to_rotation_matrix().into_inner();
         let formated_scale = Matrix3::from_diagonal(&Vector3::new(scale.x, scale.y, scale.z));
@@ -105,10 +218,42 @@ impl Material for ObjectMaterial {
             self.scale.upload(&formated_scale);
 
             mesh.bind(&mut self.pos, &mut self.normal, &mut self.tex_coord);
+            self.instances.bind(instances.as_deref_mut());
 
             verify!(ctxt.active_texture(Context::TEXTURE0));
             verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&*data.texture())));
 
+            self.alpha.upload(&data.alpha());
+            self.specular_color.upload(data.specular_color());
+            self.shininess.upload(&data.shininess());
+            self.emissive_color.upload(data.emissive_color());
+
+            // The emissive texture is bound to the texture unit 2.
+            self.emissive_map.upload(&2);
+
+            match data.emissive_texture() {
+                Some(emissive_texture) => {
+                    self.use_emissive_map.upload(&1);
+                    verify!(ctxt.active_texture(Context::TEXTURE2));
+                    verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&*emissive_texture)));
+                    verify!(ctxt.active_texture(Context::TEXTURE0));
+                }
+                None => self.use_emissive_map.upload(&0),
+            }
+
+            if data.is_transparent() {
+                verify!(ctxt.enable(Context::BLEND));
+                verify!(ctxt.blend_func_separate(
+                    Context::SRC_ALPHA,
+                    Context::ONE_MINUS_SRC_ALPHA,
+                    Context::ONE,
+                    Context::ONE_MINUS_SRC_ALPHA,
+                ));
+                // Transparent objects are drawn after the opaque ones, so they are blended with
+                // them, but must not hide the transparent objects drawn after them.
+                verify!(ctxt.depth_mask(false));
+            }
+
             if data.surface_rendering_active() {
                 self.color.upload(data.color());
 
@@ -118,13 +263,26 @@ impl Material for ObjectMaterial {
                     verify!(ctxt.disable(Context::CULL_FACE));
                 }
 
+                // Push the faces slightly away from the camera so that the wireframe drawn on
+                // top of them does not z-fight with them.
+                let overlay = data.lines_width() != 0.0;
+
+                if overlay {
+                    verify!(ctxt.enable(Context::POLYGON_OFFSET_FILL));
+                    verify!(ctxt.polygon_offset(1.0, 1.0));
+                }
+
                 let _ = verify!(ctxt.polygon_mode(Context::FRONT_AND_BACK, Context::FILL));
-                verify!(ctxt.draw_elements(
+                self.instances.draw_elements(
                     Context::TRIANGLES,
                     mesh.num_pts() as i32,
-                    Context::UNSIGNED_SHORT,
-                    0
-                ));
+                    mesh.index_type(),
+                    instances.as_deref(),
+                );
+
+                if overlay {
+                    verify!(ctxt.disable(Context::POLYGON_OFFSET_FILL));
+                }
             }
 
             if data.lines_width() != 0.0 {
@@ -135,20 +293,20 @@ impl Material for ObjectMaterial {
                 ignore!(ctxt.line_width(data.lines_width()));
 
                 if verify!(ctxt.polygon_mode(Context::FRONT_AND_BACK, Context::LINE)) {
-                    verify!(ctxt.draw_elements(
+                    self.instances.draw_elements(
                         Context::TRIANGLES,
                         mesh.num_pts() as i32,
-                        Context::UNSIGNED_SHORT,
-                        0
-                    ));
+                        mesh.index_type(),
+                        instances.as_deref(),
+                    );
                 } else {
                     mesh.bind_edges();
-                    verify!(ctxt.draw_elements(
+                    self.instances.draw_elements(
                         Context::LINES,
                         mesh.num_pts() as i32 * 2,
-                        Context::UNSIGNED_SHORT,
-                        0
-                    ));
+                        mesh.index_type(),
+                        instances.as_deref(),
+                    );
                 }
                 ctxt.line_width(1.0);
             }
@@ -159,29 +317,129 @@ impl Material for ObjectMaterial {
                 verify!(ctxt.disable(Context::CULL_FACE));
                 ctxt.point_size(data.points_size());
                 if verify!(ctxt.polygon_mode(Context::FRONT_AND_BACK, Context::POINT)) {
-                    verify!(ctxt.draw_elements(
+                    self.instances.draw_elements(
                         Context::TRIANGLES,
                         mesh.num_pts() as i32,
-                        Context::UNSIGNED_SHORT,
-                        0
-                    ));
+                        mesh.index_type(),
+                        instances.as_deref(),
+                    );
                 } else {
-                    verify!(ctxt.draw_elements(
+                    self.instances.draw_elements(
                         Context::POINTS,
                         mesh.num_pts() as i32,
-                        Context::UNSIGNED_SHORT,
-                        0
-                    ));
+                        mesh.index_type(),
+                        instances.as_deref(),
+                    );
                 }
                 ctxt.point_size(1.0);
             }
+
+            if data.is_transparent() {
+                verify!(ctxt.disable(Context::BLEND));
+                verify!(ctxt.depth_mask(true));
+            }
         }
 
+        if shadow_map.is_some() {
+            verify!(ctxt.active_texture(Context::TEXTURE1));
+            verify!(ctxt.bind_texture(Context::TEXTURE_2D, None));
+            verify!(ctxt.active_texture(Context::TEXTURE0));
+        }
+
+        if data.emissive_texture().is_some() {
+            verify!(ctxt.active_texture(Context::TEXTURE2));
+            verify!(ctxt.bind_texture(Context::TEXTURE_2D, None));
+            verify!(ctxt.active_texture(Context::TEXTURE0));
+        }
+
+        self.instances.unbind(instances.as_deref_mut());
         mesh.unbind();
         self.deactivate();
     }
 }
 
+/// The per-instance vertex attributes of a material supporting instancing.
+///
+/// The shader must declare the `inst_tra` (vec3) and `inst_rot` (vec4) attributes.
+pub(crate) struct InstancesAttributes {
+    inst_tra: ShaderAttribute<Point3<f32>>,
+    inst_rot: ShaderAttribute<Vector4<f32>>,
+}
+
+impl InstancesAttributes {
+    pub fn new(effect: &Effect) -> InstancesAttributes {
+        InstancesAttributes {
+            inst_tra: effect.get_attrib("inst_tra").unwrap(),
+            inst_rot: effect.get_attrib("inst_rot").unwrap(),
+        }
+    }
+
+    pub fn bind(&mut self, instances: Option<&mut InstancesBuffer>) {
+        match instances {
+            Some(instances) if Context::get().supports_instancing() => {
+                self.inst_tra.enable();
+                self.inst_rot.enable();
+                self.inst_tra.bind(instances.translations_mut());
+                self.inst_rot.bind(instances.rotations_mut());
+                self.inst_tra.set_divisor(1);
+                self.inst_rot.set_divisor(1);
+            }
+            _ => {
+                // The identity transformation.
+                self.inst_tra.set_constant(&Vector4::zeros());
+                self.inst_rot.set_constant(&Vector4::w());
+            }
+        }
+    }
+
+    pub fn unbind(&mut self, instances: Option<&mut InstancesBuffer>) {
+        if let Some(instances) = instances {
+            if Context::get().supports_instancing() {
+                self.inst_tra.set_divisor(0);
+                self.inst_rot.set_divisor(0);
+                self.inst_tra.disable();
+                self.inst_rot.disable();
+                instances.translations_mut().unbind();
+                instances.rotations_mut().unbind();
+            }
+        }
+    }
+
+    pub fn draw_elements(
+        &mut self,
+        mode: GLenum,
+        count: i32,
+        index_type: GLenum,
+        instances: Option<&InstancesBuffer>,
+    ) {
+        let ctxt = Context::get();
+
+        match instances {
+            None => verify!(ctxt.draw_elements(mode, count, index_type, 0)),
+            Some(instances) if ctxt.supports_instancing() => {
+                verify!(ctxt.draw_elements_instanced(
+                    mode,
+                    count,
+                    index_type,
+                    0,
+                    instances.len() as i32
+                ))
+            }
+            Some(instances) => {
+                // Without instancing support, fall back to one draw call per instance.
+                for t in instances.transforms() {
+                    let tra = &t.translation.vector;
+
+                    self.inst_tra
+                        .set_constant(&Vector4::new(tra.x, tra.y, tra.z, 0.0));
+                    self.inst_rot.set_constant(&t.rotation.coords);
+                    verify!(ctxt.draw_elements(mode, count, index_type, 0));
+                }
+            }
+        }
+    }
+}
+
 /// Vertex shader of the default object material.
 pub static OBJECT_VERTEX_SRC: &'static str = A_VERY_LONG_STRING;
 /// Fragment shader of the default object material.
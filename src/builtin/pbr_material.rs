@@ -0,0 +1,412 @@
+use crate::camera::Camera;
+use crate::context::{Context, Texture};
+use crate::environment::Environment;
+use crate::light::{Light, MAX_LIGHTS};
+use crate::resource::Material;
+use crate::resource::{Effect, Mesh, ShaderAttribute, ShaderUniform};
+use crate::scene::ObjectData;
+use na::{Isometry3, Matrix3, Matrix4, Point2, Point3, Vector3, Vector4};
+use std::rc::Rc;
+
+#[path = "../error.rs"]
+mod error;
+
+/// A physically based material, following the metallic-roughness workflow.
+///
+/// The albedo of an object is its color multiplied by its texture. The surface is lit by the
+/// lights of the environment with a Cook-Torrance BRDF, and by its ambient color. Shadows and
+/// fog are not supported, and lights are not attenuated with the distance.
+///
+/// The metallic and roughness factors, as well as the optional normal and ambient occlusion
+/// maps, are shared by all the objects using this material. Create one material per set of
+/// parameters and assign it with `SceneNode::set_material`.
+pub struct PbrMaterial {
+    effect: Effect,
+    pos: ShaderAttribute<Point3<f32>>,
+    normal: ShaderAttribute<Vector3<f32>>,
+    tex_coord: ShaderAttribute<Point2<f32>>,
+    light_positions: Vec<ShaderUniform<Vector4<f32>>>,
+    light_colors: Vec<ShaderUniform<Point3<f32>>>,
+    num_lights: ShaderUniform<i32>,
+    ambient: ShaderUniform<Point3<f32>>,
+    albedo: ShaderUniform<Point3<f32>>,
+    metallic_uniform: ShaderUniform<f32>,
+    roughness_uniform: ShaderUniform<f32>,
+    tex: ShaderUniform<i32>,
+    // Optimized out when screen-space derivatives are not supported.
+    normal_map_uniform: Option<ShaderUniform<i32>>,
+    use_normal_map: ShaderUniform<i32>,
+    ao_map_uniform: ShaderUniform<i32>,
+    use_ao_map: ShaderUniform<i32>,
+    transform: ShaderUniform<Matrix4<f32>>,
+    scale: ShaderUniform<Matrix3<f32>>,
+    ntransform: ShaderUniform<Matrix3<f32>>,
+    proj: ShaderUniform<Matrix4<f32>>,
+    view: ShaderUniform<Matrix4<f32>>,
+    metallic: f32,
+    roughness: f32,
+    normal_map: Option<Rc<Texture>>,
+    ao_map: Option<Rc<Texture>>,
+}
+
+impl PbrMaterial {
+    /// Creates a new dielectric `PbrMaterial`, with a roughness of 0.5 and no textures.
+    pub fn new() -> PbrMaterial {
+        let mut effect = Effect::new_from_str(PBR_VERTEX_SRC, PBR_FRAGMENT_SRC);
+
+        effect.use_program();
+
+        PbrMaterial {
+            pos: effect.get_attrib("position").unwrap(),
+            normal: effect.get_attrib("normal").unwrap(),
+            tex_coord: effect.get_attrib("tex_coord").unwrap(),
+            light_positions: (0..MAX_LIGHTS)
+                .map(|i| {
+                    effect
+                        .get_uniform(&format!("light_position[{}]", i))
+                        .unwrap()
+                })
+                .collect(),
+            light_colors: (0..MAX_LIGHTS)
+                .map(|i| effect.get_uniform(&format!("light_color[{}]", i)).unwrap())
+                .collect(),
+            num_lights: effect.get_uniform("num_lights").unwrap(),
+            ambient: effect.get_uniform("ambient").unwrap(),
+            albedo: effect.get_uniform("albedo").unwrap(),
+            metallic_uniform: effect.get_uniform("metallic").unwrap(),
+            roughness_uniform: effect.get_uniform("roughness").unwrap(),
+            tex: effect.get_uniform("tex").unwrap(),
+            normal_map_uniform: effect.get_uniform("normal_map"),
+            use_normal_map: effect.get_uniform("use_normal_map").unwrap(),
+            ao_map_uniform: effect.get_uniform("ao_map").unwrap(),
+            use_ao_map: effect.get_uniform("use_ao_map").unwrap(),
+            transform: effect.get_uniform("transform").unwrap(),
+            scale: effect.get_uniform("scale").unwrap(),
+            ntransform: effect.get_uniform("ntransform").unwrap(),
+            view: effect.get_uniform("view").unwrap(),
+            proj: effect.get_uniform("proj").unwrap(),
+            effect: effect,
+            metallic: 0.0,
+            roughness: 0.5,
+            normal_map: None,
+            ao_map: None,
+        }
+    }
+
+    /// How metallic the surface is, in `[0, 1]`.
+    pub fn metallic(&self) -> f32 {
+        self.metallic
+    }
+
+    /// Sets how metallic the surface is, clamped to `[0, 1]`.
+    ///
+    /// Metals have no diffuse reflection, and their specular reflection is tinted by the albedo.
+    pub fn set_metallic(&mut self, metallic: f32) {
+        self.metallic = metallic.max(0.0).min(1.0)
+    }
+
+    /// The roughness of the surface, in `[0, 1]`.
+    pub fn roughness(&self) -> f32 {
+        self.roughness
+    }
+
+    /// Sets the roughness of the surface, clamped to `[0, 1]`.
+    ///
+    /// Smooth surfaces have small and sharp highlights, rough surfaces have wide and dim ones.
+    pub fn set_roughness(&mut self, roughness: f32) {
+        self.roughness = roughness.max(0.0).min(1.0)
+    }
+
+    /// The tangent-space normal map of this material, if any.
+    pub fn normal_map(&self) -> Option<&Rc<Texture>> {
+        self.normal_map.as_ref()
+    }
+
+    /// Sets the tangent-space normal map of this material, sampled with the mesh uvs.
+    ///
+    /// The tangent frame is computed with screen-space derivatives, so the mesh does not need
+    /// tangents. The map is ignored on platforms which do not support the
+    /// `OES_standard_derivatives` extension.
+    pub fn set_normal_map(&mut self, normal_map: Option<Rc<Texture>>) {
+        self.normal_map = normal_map
+    }
+
+    /// The ambient occlusion map of this material, if any.
+    pub fn ao_map(&self) -> Option<&Rc<Texture>> {
+        self.ao_map.as_ref()
+    }
+
+    /// Sets the ambient occlusion map of this material, sampled with the mesh uvs.
+    ///
+    /// Its red channel scales the ambient lighting.
+    pub fn set_ao_map(&mut self, ao_map: Option<Rc<Texture>>) {
+        self.ao_map = ao_map
+    }
+}
+
+impl Material for PbrMaterial {
+    fn render(
+        &mut self,
+        pass: usize,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+        camera: &mut dyn Camera,
+        environment: &Environment,
+        data: &ObjectData,
+        mesh: &mut Mesh,
+    ) {
+        if !data.surface_rendering_active() {
+            return;
+        }
+
+        let ctxt = Context::get();
+
+        self.effect.use_program();
+        self.pos.enable();
+        self.normal.enable();
+        self.tex_coord.enable();
+
+        /*
+         *
+         * Setup camera and light.
+         *
+         */
+        camera.upload(pass, &mut self.proj, &mut self.view);
+
+        // Lights are uploaded in view space, as for the `ObjectMaterial`.
+        let view = camera.view_transform();
+        let lights = environment.lights();
+        let lights = &lights[..lights.len().min(MAX_LIGHTS)];
+
+        for (i, light) in lights.iter().enumerate() {
+            let pos = match *light {
+                Light::Absolute(ref pos) | Light::AbsoluteWithColor { ref pos, .. } => {
+                    (view * pos).to_homogeneous()
+                }
+                Light::StickToCamera => Vector4::w(),
+                Light::Directional { ref direction, .. } => (view * -direction).to_homogeneous(),
+            };
+
+            self.light_positions[i].upload(&pos);
+            self.light_colors[i].upload(&light.color());
+        }
+
+        self.num_lights.upload(&(lights.len() as i32));
+        self.ambient.upload(environment.ambient_color());
+
+        /*
+         *
+         * Setup object-related stuffs.
+         *
+         */
+        let formated_transform = transform.to_homogeneous();
+        let formated_ntransform = transform.rotation.to_rotation_matrix().into_inner();
+        let formated_scale = Matrix3::from_diagonal(&Vector3::new(scale.x, scale.y, scale.z));
+
+        self.transform.upload(&formated_transform);
+        self.ntransform.upload(&formated_ntransform);
+        self.scale.upload(&formated_scale);
+        self.albedo.upload(data.color());
+        self.metallic_uniform.upload(&self.metallic);
+        self.roughness_uniform.upload(&self.roughness);
+
+        // The albedo, normal, and ambient occlusion textures are bound to the units 0, 1, and 2.
+        self.tex.upload(&0);
+        if let Some(ref mut normal_map) = self.normal_map_uniform {
+            normal_map.upload(&1);
+        }
+        self.ao_map_uniform.upload(&2);
+        self.use_normal_map
+            .upload(&(self.normal_map.is_some() as i32));
+        self.use_ao_map.upload(&(self.ao_map.is_some() as i32));
+
+        verify!(ctxt.active_texture(Context::TEXTURE0));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&*data.texture())));
+        verify!(ctxt.active_texture(Context::TEXTURE1));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, self.normal_map.as_deref()));
+        verify!(ctxt.active_texture(Context::TEXTURE2));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, self.ao_map.as_deref()));
+
+        if data.backface_culling_enabled() {
+            verify!(ctxt.enable(Context::CULL_FACE));
+        } else {
+            verify!(ctxt.disable(Context::CULL_FACE));
+        }
+
+        mesh.bind(&mut self.pos, &mut self.normal, &mut self.tex_coord);
+
+        unsafe {
+            ctxt.draw_elements(
+                Context::TRIANGLES,
+                mesh.num_pts() as i32,
+                mesh.index_type(),
+                0,
+            );
+        }
+
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, None));
+        verify!(ctxt.active_texture(Context::TEXTURE1));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, None));
+        verify!(ctxt.active_texture(Context::TEXTURE0));
+
+        mesh.unbind();
+
+        self.pos.disable();
+        self.normal.disable();
+        self.tex_coord.disable();
+    }
+}
+
+/// Vertex shader of the physically based material.
+pub static PBR_VERTEX_SRC: &'static str = A_VERY_LONG_STRING;
+/// Fragment shader of the physically based material.
+pub static PBR_FRAGMENT_SRC: &'static str = ANOTHER_VERY_LONG_STRING;
+
+const A_VERY_LONG_STRING: &'static str = "#version 100
+attribute vec3 position;
+attribute vec3 normal;
+attribute vec2 tex_coord;
+uniform mat3 ntransform, scale;
+uniform mat4 proj, view, transform;
+varying vec3 pos_v;
+varying vec3 normal_v;
+varying vec2 tex_coord_v;
+
+void main() {
+    vec4 pos    = view * transform * vec4(scale * position, 1.0);
+    pos_v       = pos.xyz / pos.w;
+    normal_v    = mat3(view) * ntransform * normal;
+    tex_coord_v = tex_coord;
+    gl_Position = proj * pos;
+}
+";
+
+// Cook-Torrance BRDF with the GGX distribution, the Schlick-GGX geometry term, and the Schlick
+// approximation of the Fresnel term. Computations are done in view space.
+const ANOTHER_VERY_LONG_STRING: &'static str = "#version 100
+#ifdef GL_OES_standard_derivatives
+#extension GL_OES_standard_derivatives : enable
+#endif
+
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+#define MAX_LIGHTS 8
+#define PI 3.14159265
+
+varying vec3 pos_v;
+varying vec3 normal_v;
+varying vec2 tex_coord_v;
+
+uniform vec3 albedo;
+uniform float metallic;
+uniform float roughness;
+uniform sampler2D tex;
+uniform int use_normal_map;
+uniform sampler2D normal_map;
+uniform int use_ao_map;
+uniform sampler2D ao_map;
+// Directional lights have a `w` equal to 0 and store the direction toward the light.
+uniform vec4 light_position[MAX_LIGHTS];
+uniform vec3 light_color[MAX_LIGHTS];
+uniform int num_lights;
+uniform vec3 ambient;
+
+// Applies the normal map, with a tangent frame computed from the screen-space derivatives of
+// the position and uvs.
+vec3 perturb_normal(vec3 n) {
+#ifdef GL_OES_standard_derivatives
+    vec3 dp1 = dFdx(pos_v);
+    vec3 dp2 = dFdy(pos_v);
+    vec2 duv1 = dFdx(tex_coord_v);
+    vec2 duv2 = dFdy(tex_coord_v);
+
+    vec3 dp2perp = cross(dp2, n);
+    vec3 dp1perp = cross(n, dp1);
+    vec3 t = dp2perp * duv1.x + dp1perp * duv2.x;
+    vec3 b = dp2perp * duv1.y + dp1perp * duv2.y;
+    float invmax = inversesqrt(max(max(dot(t, t), dot(b, b)), 1.0e-12));
+    mat3 tbn = mat3(t * invmax, b * invmax, n);
+
+    vec3 m = texture2D(normal_map, tex_coord_v).xyz * 2.0 - 1.0;
+    return normalize(tbn * m);
+#else
+    return n;
+#endif
+}
+
+float distribution_ggx(float ndoth, float a) {
+    float a2 = a * a;
+    float d = ndoth * ndoth * (a2 - 1.0) + 1.0;
+    return a2 / (PI * d * d);
+}
+
+float geometry_schlick_ggx(float ndotx, float k) {
+    return ndotx / (ndotx * (1.0 - k) + k);
+}
+
+vec3 fresnel_schlick(float cos_theta, vec3 f0) {
+    return f0 + (1.0 - f0) * pow(1.0 - cos_theta, 5.0);
+}
+
+void main() {
+    vec4 tex_color = texture2D(tex, tex_coord_v);
+    vec3 base = albedo * tex_color.rgb;
+
+    vec3 n = normalize(normal_v);
+
+    if (use_normal_map != 0) {
+        n = perturb_normal(n);
+    }
+
+    vec3 v = normalize(-pos_v);
+    float rough = clamp(roughness, 0.04, 1.0);
+    float a = rough * rough;
+    float k = (rough + 1.0) * (rough + 1.0) / 8.0;
+    vec3 f0 = mix(vec3(0.04), base, metallic);
+    float ndotv = max(dot(n, v), 1.0e-4);
+    vec3 radiance = vec3(0.0);
+
+    for (int i = 0; i < MAX_LIGHTS; i++) {
+        if (i >= num_lights) {
+            break;
+        }
+
+        vec3 l;
+
+        if (light_position[i].w == 0.0) {
+            l = normalize(light_position[i].xyz);
+        } else {
+            l = normalize(light_position[i].xyz - pos_v);
+        }
+
+        float ndotl = dot(n, l);
+
+        if (ndotl <= 0.0) {
+            continue;
+        }
+
+        vec3 h = normalize(l + v);
+        vec3 f = fresnel_schlick(max(dot(h, v), 0.0), f0);
+        float d = distribution_ggx(max(dot(n, h), 0.0), a);
+        float g = geometry_schlick_ggx(ndotv, k) * geometry_schlick_ggx(ndotl, k);
+        vec3 specular = d * g * f / (4.0 * ndotv * ndotl + 1.0e-4);
+        vec3 diffuse = (1.0 - f) * (1.0 - metallic) * base / PI;
+
+        // Light colors are scaled by PI so that a white light fully lights a white diffuse
+        // surface facing it.
+        radiance += (diffuse + specular) * light_color[i] * PI * ndotl;
+    }
+
+    float ao = 1.0;
+
+    if (use_ao_map != 0) {
+        ao = texture2D(ao_map, tex_coord_v).r;
+    }
+
+    gl_FragColor = vec4(ambient * base * ao + radiance, tex_color.a);
+}
+";
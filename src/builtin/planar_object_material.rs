@@ -93,6 +93,14 @@ impl PlanarMaterial for PlanarObjectMaterial {
             if data.surface_rendering_active() {
                 self.color.upload(data.color());
 
+                // Blend with the alpha channel of the texture, e.g., for sprites.
+                verify!(ctxt.enable(Context::BLEND));
+                verify!(ctxt.blend_func_separate(
+                    Context::SRC_ALPHA,
+                    Context::ONE_MINUS_SRC_ALPHA,
+                    Context::ONE,
+                    Context::ONE_MINUS_SRC_ALPHA,
+                ));
                 let _ = verify!(ctxt.polygon_mode(Context::FRONT_AND_BACK, Context::FILL));
                 verify!(ctxt.draw_elements(
                     Context::TRIANGLES,
@@ -100,6 +108,7 @@ impl PlanarMaterial for PlanarObjectMaterial {
                     Context::UNSIGNED_SHORT,
                     0
                 ));
+                verify!(ctxt.disable(Context::BLEND));
             }
 
             if data.lines_width() != 0.0 {
@@ -0,0 +1,198 @@
+use crate::camera::Camera;
+use crate::context::Context;
+use crate::environment::Environment;
+use crate::resource::Material;
+use crate::resource::{Effect, Mesh, ShaderAttribute, ShaderUniform};
+use crate::scene::ObjectData;
+use na::{Isometry3, Matrix3, Matrix4, Point3, Vector3};
+
+#[path = "../error.rs"]
+mod error;
+
+/// A material that draws the per-vertex colors of an object, set with `Mesh::set_colors`.
+///
+/// The colors are interpolated on the faces. Objects without vertex colors are drawn with their
+/// own color. The colors are displayed as is, unless lighting is enabled, in which case they are
+/// shaded by a light attached to the camera.
+pub struct VertexColorMaterial {
+    shader: Effect,
+    position: ShaderAttribute<Point3<f32>>,
+    normal: ShaderAttribute<Vector3<f32>>,
+    color: ShaderAttribute<Point3<f32>>,
+    proj: ShaderUniform<Matrix4<f32>>,
+    view: ShaderUniform<Matrix4<f32>>,
+    transform: ShaderUniform<Matrix4<f32>>,
+    ntransform: ShaderUniform<Matrix3<f32>>,
+    scale: ShaderUniform<Matrix3<f32>>,
+    object_color: ShaderUniform<Point3<f32>>,
+    use_vertex_colors: ShaderUniform<i32>,
+    lit: ShaderUniform<i32>,
+    lighting: bool,
+}
+
+impl VertexColorMaterial {
+    /// Creates a new VertexColorMaterial, without lighting.
+    pub fn new() -> VertexColorMaterial {
+        let mut shader = Effect::new_from_str(VERTEX_COLOR_VERTEX_SRC, VERTEX_COLOR_FRAGMENT_SRC);
+
+        shader.use_program();
+
+        VertexColorMaterial {
+            position: shader.get_attrib("position").unwrap(),
+            normal: shader.get_attrib("normal").unwrap(),
+            color: shader.get_attrib("color").unwrap(),
+            transform: shader.get_uniform("transform").unwrap(),
+            ntransform: shader.get_uniform("ntransform").unwrap(),
+            scale: shader.get_uniform("scale").unwrap(),
+            view: shader.get_uniform("view").unwrap(),
+            proj: shader.get_uniform("proj").unwrap(),
+            object_color: shader.get_uniform("object_color").unwrap(),
+            use_vertex_colors: shader.get_uniform("use_vertex_colors").unwrap(),
+            lit: shader.get_uniform("lit").unwrap(),
+            shader: shader,
+            lighting: false,
+        }
+    }
+
+    /// Whether the colors are shaded by a light attached to the camera.
+    pub fn lighting(&self) -> bool {
+        self.lighting
+    }
+
+    /// Enables or disables the shading of the colors by a light attached to the camera.
+    pub fn set_lighting(&mut self, lighting: bool) {
+        self.lighting = lighting
+    }
+}
+
+impl Material for VertexColorMaterial {
+    fn render(
+        &mut self,
+        pass: usize,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+        camera: &mut dyn Camera,
+        _: &Environment,
+        data: &ObjectData,
+        mesh: &mut Mesh,
+    ) {
+        if !data.surface_rendering_active() {
+            return;
+        }
+
+        let ctxt = Context::get();
+        // enable/disable culling.
+        if data.backface_culling_enabled() {
+            verify!(ctxt.enable(Context::CULL_FACE));
+        } else {
+            verify!(ctxt.disable(Context::CULL_FACE));
+        }
+
+        self.shader.use_program();
+        self.position.enable();
+        self.normal.enable();
+
+        /*
+         *
+         * Setup camera.
+         *
+         */
+        camera.upload(pass, &mut self.proj, &mut self.view);
+
+        /*
+         *
+         * Setup object-related stuffs.
+         *
+         */
+        let formated_transform = transform.to_homogeneous();
+        let formated_ntransform = transform.rotation.to_rotation_matrix().into_inner();
+        let formated_scale = Matrix3::from_diagonal(&Vector3::new(scale.x, scale.y, scale.z));
+
+        self.transform.upload(&formated_transform);
+        self.ntransform.upload(&formated_ntransform);
+        self.scale.upload(&formated_scale);
+        self.object_color.upload(data.color());
+        self.lit.upload(&(self.lighting as i32));
+
+        // Without vertex colors, the attribute stays disabled and is ignored by the shader.
+        let has_colors = mesh.colors().is_some();
+
+        if has_colors {
+            self.color.enable();
+            let _ = mesh.bind_colors(&mut self.color);
+        }
+
+        self.use_vertex_colors.upload(&(has_colors as i32));
+
+        mesh.bind_coords(&mut self.position);
+        mesh.bind_normals(&mut self.normal);
+        mesh.bind_faces();
+
+        unsafe {
+            ctxt.draw_elements(
+                Context::TRIANGLES,
+                mesh.num_pts() as i32,
+                mesh.index_type(),
+                0,
+            );
+        }
+
+        mesh.unbind();
+
+        self.position.disable();
+        self.normal.disable();
+
+        if has_colors {
+            self.color.disable();
+        }
+    }
+}
+
+/// A vertex shader for drawing the per-vertex colors of an object.
+pub static VERTEX_COLOR_VERTEX_SRC: &'static str = A_VERY_LONG_STRING;
+
+/// A fragment shader for drawing the per-vertex colors of an object.
+pub static VERTEX_COLOR_FRAGMENT_SRC: &'static str = ANOTHER_VERY_LONG_STRING;
+
+const A_VERY_LONG_STRING: &'static str = "#version 100
+attribute vec3 position;
+attribute vec3 normal;
+attribute vec3 color;
+uniform mat4 proj;
+uniform mat4 view;
+uniform mat4 transform;
+uniform mat3 ntransform;
+uniform mat3 scale;
+uniform vec3 object_color;
+uniform int use_vertex_colors;
+varying vec3 vs_color;
+varying vec3 vs_normal;
+
+void main() {
+    vs_color    = use_vertex_colors != 0 ? color : object_color;
+    vs_normal   = mat3(view) * ntransform * normal;
+    gl_Position = proj * view * transform * vec4(scale * position, 1.0);
+}
+";
+
+const ANOTHER_VERY_LONG_STRING: &'static str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+uniform int lit;
+varying vec3 vs_color;
+varying vec3 vs_normal;
+
+void main() {
+    if (lit != 0) {
+        // The light is at the camera position, so it lights both sides of the faces.
+        float diffuse = abs(normalize(vs_normal).z);
+        gl_FragColor  = vec4(vs_color * (0.3 + 0.7 * diffuse), 1.0);
+    } else {
+        gl_FragColor = vec4(vs_color, 1.0);
+    }
+}
+";
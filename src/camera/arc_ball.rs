@@ -1,5 +1,6 @@
-use crate::camera::Camera;
-use crate::event::{Action, Key, Modifiers, MouseButton, WindowEvent};
+use crate::camera::transition::{self, Transition};
+use crate::camera::{self, Camera};
+use crate::event::{Action, Key, Modifiers, MouseButton, TouchAction, WindowEvent};
 use crate::resource::ShaderUniform;
 use crate::window::Canvas;
 use na::{self, Isometry3, Matrix4, Perspective3, Point3, Unit, UnitQuaternion, Vector2, Vector3};
@@ -15,6 +16,14 @@ use std::f32;
 /// direction
 /// * Scroll in/out - zoom in/out
 /// * Enter key - set the focus point to the origin
+///
+/// The buttons and modifiers can be changed with `ArcBall::rebind_rotate_control` and
+/// `ArcBall::rebind_drag_control`, e.g., to rotate with the middle button like most modeling
+/// tools. Rotation, translation and zoom can also be disabled individually.
+///
+/// Two-finger gestures can also be enabled with `ArcBall::set_touch_gestures`.
+///
+/// The camera can be moved smoothly to another viewpoint with `ArcBall::animate_to`.
 #[derive(Clone, Debug)]
 pub struct ArcBall {
     /// The focus point.
@@ -45,6 +54,14 @@ pub struct ArcBall {
     drag_button: Option<MouseButton>,
     drag_modifiers: Option<Modifiers>,
     reset_key: Option<Key>,
+    rotation_enabled: bool,
+    drag_enabled: bool,
+    zoom_enabled: bool,
+    touch_gestures: bool,
+    /// The position of each finger currently touching the screen, identified by its id.
+    touches: Vec<(u64, Vector2<f32>)>,
+    /// The ongoing animation started by `animate_to`.
+    transition: Option<Transition<ArcBallPose>>,
 
     projection: Perspective3<f32>,
     view: Matrix4<f32>,
@@ -86,6 +103,12 @@ impl ArcBall {
             drag_button: Some(MouseButton::Button2),
             drag_modifiers: None,
             reset_key: Some(Key::Return),
+            rotation_enabled: true,
+            drag_enabled: true,
+            zoom_enabled: true,
+            touch_gestures: false,
+            touches: Vec::new(),
+            transition: None,
             projection: Perspective3::new(800.0 / 600.0, fov, znear, zfar),
             view: na::zero(),
             proj: na::zero(),
@@ -190,6 +213,19 @@ impl ArcBall {
         self.max_dist = max_dist;
     }
 
+    /// The vertical field of view of this camera, in radians.
+    pub fn fov(&self) -> f32 {
+        self.projection.fovy()
+    }
+
+    /// Sets the vertical field of view of this camera, in radians.
+    ///
+    /// The value is clamped to `[camera::MIN_FOV, camera::MAX_FOV]`, i.e., 1° to 179°.
+    pub fn set_fov(&mut self, fov: f32) {
+        camera::set_fov(&mut self.projection, fov);
+        self.update_projviews();
+    }
+
     /// Set the increment for a unit scroll (default at 40).
     pub fn set_dist_step(&mut self, dist_step: f32) {
         self.dist_step = dist_step;
@@ -197,6 +233,131 @@ impl ArcBall {
 
     /// Move and orient the camera such that it looks at a specific point.
     pub fn look_at(&mut self, eye: Point3<f32>, at: Point3<f32>) {
+        let pose = self.pose_looking_at(eye, at);
+        self.set_pose(&pose);
+    }
+
+    /// Moves the camera so that the whole axis-aligned box `[min, max]` is visible.
+    ///
+    /// The focus point is set to the center of the box and the distance is chosen so that the
+    /// bounding sphere of the box fits within the field of view, with a small margin. The
+    /// orientation of the camera is preserved.
+    pub fn fit_to_aabb(&mut self, min: Point3<f32>, max: Point3<f32>) {
+        // The margin around the box, relative to its size.
+        const MARGIN: f32 = 1.1;
+
+        let radius = (max - min).norm() / 2.0;
+        let half_fovy = self.projection.fovy() / 2.0;
+        let half_fovx = (half_fovy.tan() * self.projection.aspect()).atan();
+        let half_fov = half_fovy.min(half_fovx);
+
+        self.at = na::center(&min, &max);
+        self.dist = radius * MARGIN / half_fov.sin();
+        self.transition = None;
+
+        self.update_restrictions();
+        self.update_projviews();
+    }
+
+    /// Creates an arc-ball camera with the viewpoint and projection described by `state`, and
+    /// with the default controls.
+    pub fn from_state(state: &ArcBallState) -> ArcBall {
+        let mut res = ArcBall::new_with_frustrum(
+            state.fov,
+            state.znear,
+            state.zfar,
+            Point3::new(0.0, 0.0, 1.0),
+            Point3::origin(),
+        );
+        res.set_state(state);
+        res
+    }
+
+    /// The viewpoint and projection parameters of this camera, e.g., to save them for later.
+    pub fn to_state(&self) -> ArcBallState {
+        ArcBallState {
+            at: self.at.coords.into(),
+            yaw: self.yaw,
+            pitch: self.pitch,
+            dist: self.dist,
+            fov: self.projection.fovy(),
+            znear: self.projection.znear(),
+            zfar: self.projection.zfar(),
+            up_axis: self.coord_system.up_axis.into_inner().into(),
+        }
+    }
+
+    /// Moves this camera to the viewpoint described by `state`, and sets its projection
+    /// parameters.
+    ///
+    /// The controls of the camera are kept, and the resulting view matrix is exactly the one of
+    /// the camera `state` was taken from. The distance and pitch limits are not applied.
+    pub fn set_state(&mut self, state: &ArcBallState) {
+        self.coord_system = CoordSystemRh::from_up_axis(camera::up_axis_from_state(state.up_axis));
+        self.projection.set_fovy(state.fov);
+        self.projection.set_znear_and_zfar(state.znear, state.zfar);
+        self.at = Point3::from(state.at);
+        self.yaw = state.yaw;
+        self.pitch = state.pitch;
+        self.dist = state.dist;
+        self.transition = None;
+
+        self.update_projviews();
+    }
+
+    /// Moves this camera toward the viewpoint of `target`.
+    ///
+    /// The focus point, distance, yaw and pitch are interpolated with an easing, `t = 0.0`
+    /// leaving this camera unchanged and `t = 1.0` moving it to the viewpoint of `target`. The
+    /// yaw is interpolated along the shortest arc. The projection and controls of `target` are
+    /// ignored.
+    pub fn slerp_to(&mut self, target: &ArcBall, t: f32) {
+        let from = self.pose();
+        let to = self.pose_looking_at(target.eye(), target.at);
+        self.interpolate(&from, &to, transition::ease(t));
+    }
+
+    /// Animates this camera toward the viewpoint of `target` for `duration` seconds.
+    ///
+    /// The animation is advanced by `Camera::update`, which is called by the window at each
+    /// frame, and follows the same path as `ArcBall::slerp_to`. It is interrupted when the
+    /// user moves the camera.
+    pub fn animate_to(&mut self, target: &ArcBall, duration: f32) {
+        let to = self.pose_looking_at(target.eye(), target.at);
+        self.transition = Some(Transition::new(self.pose(), to, duration));
+    }
+
+    /// Whether an animation started by `ArcBall::animate_to` is ongoing.
+    pub fn is_animating(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// Stops the animation started by `ArcBall::animate_to`, leaving the camera where it is.
+    pub fn stop_animation(&mut self) {
+        self.transition = None;
+    }
+
+    fn pose(&self) -> ArcBallPose {
+        ArcBallPose {
+            at: self.at,
+            yaw: self.yaw,
+            pitch: self.pitch,
+            dist: self.dist,
+        }
+    }
+
+    fn set_pose(&mut self, pose: &ArcBallPose) {
+        self.at = pose.at;
+        self.yaw = pose.yaw;
+        self.pitch = pose.pitch;
+        self.dist = pose.dist;
+
+        self.update_restrictions();
+        self.update_projviews();
+    }
+
+    // The pose of this camera once moved to `eye` and looking at `at`.
+    fn pose_looking_at(&self, eye: Point3<f32>, at: Point3<f32>) -> ArcBallPose {
         let dist = (eye - at).norm();
 
         let view_eye = self.coord_system.rotation_to_y_up * eye;
@@ -204,13 +365,23 @@ impl ArcBall {
         let pitch = ((view_eye.y - view_at.y) / dist).acos();
         let yaw = (view_eye.z - view_at.z).atan2(view_eye.x - view_at.x);
 
-        self.at = at;
-        self.dist = dist;
-        self.yaw = yaw;
-        self.pitch = pitch;
+        ArcBallPose {
+            at,
+            yaw,
+            pitch,
+            dist,
+        }
+    }
 
-        self.update_restrictions();
-        self.update_projviews();
+    fn interpolate(&mut self, from: &ArcBallPose, to: &ArcBallPose, t: f32) {
+        let pose = ArcBallPose {
+            at: from.at + (to.at - from.at) * t,
+            yaw: transition::lerp_angle(from.yaw, to.yaw, t),
+            pitch: transition::lerp(from.pitch, to.pitch, t),
+            dist: transition::lerp(from.dist, to.dist, t),
+        };
+
+        self.set_pose(&pose);
     }
 
     /// Transformation applied by the camera without perspective.
@@ -293,7 +464,121 @@ impl ArcBall {
         self.reset_key = new_key;
     }
 
+    /// Sets the button and modifiers used to rotate the ArcBall camera.
+    ///
+    /// This is the same as calling both `ArcBall::rebind_rotate_button` and
+    /// `ArcBall::set_rotate_modifiers`.
+    pub fn rebind_rotate_control(
+        &mut self,
+        button: Option<MouseButton>,
+        modifiers: Option<Modifiers>,
+    ) {
+        self.rotate_button = button;
+        self.rotate_modifiers = modifiers;
+    }
+
+    /// Sets the button and modifiers used to drag, i.e., pan, the ArcBall camera.
+    ///
+    /// This is the same as calling both `ArcBall::rebind_drag_button` and
+    /// `ArcBall::set_drag_modifiers`.
+    pub fn rebind_drag_control(
+        &mut self,
+        button: Option<MouseButton>,
+        modifiers: Option<Modifiers>,
+    ) {
+        self.drag_button = button;
+        self.drag_modifiers = modifiers;
+    }
+
+    /// Whether the user can rotate the camera around the focus point.
+    pub fn rotation_enabled(&self) -> bool {
+        self.rotation_enabled
+    }
+
+    /// Enables or disables the rotation of the camera by the user, without changing the
+    /// rotation button and modifiers. Enabled by default.
+    pub fn set_rotation_enabled(&mut self, enabled: bool) {
+        self.rotation_enabled = enabled;
+    }
+
+    /// Whether the user can drag the focus point, either with the mouse or with two fingers.
+    pub fn drag_enabled(&self) -> bool {
+        self.drag_enabled
+    }
+
+    /// Enables or disables the translation of the focus point by the user, without changing the
+    /// drag button and modifiers. Enabled by default.
+    pub fn set_drag_enabled(&mut self, enabled: bool) {
+        self.drag_enabled = enabled;
+    }
+
+    /// Whether the user can zoom in/out, either by scrolling or by pinching.
+    pub fn zoom_enabled(&self) -> bool {
+        self.zoom_enabled
+    }
+
+    /// Enables or disables zooming by the user. Enabled by default.
+    pub fn set_zoom_enabled(&mut self, enabled: bool) {
+        self.zoom_enabled = enabled;
+    }
+
+    /// Whether this camera handles two-finger touch gestures.
+    pub fn touch_gestures(&self) -> bool {
+        self.touch_gestures
+    }
+
+    /// Enables or disables the handling of two-finger touch gestures.
+    ///
+    /// When enabled, pinching zooms in/out and moving two fingers together translates the focus
+    /// point. Single-finger drags are left to the mouse emulation of the platform. Disabled by
+    /// default.
+    pub fn set_touch_gestures(&mut self, enabled: bool) {
+        self.touch_gestures = enabled;
+        self.touches.clear();
+    }
+
+    fn handle_touch(&mut self, id: u64, pos: Vector2<f32>, action: TouchAction) {
+        let i = self.touches.iter().position(|t| t.0 == id);
+
+        match action {
+            TouchAction::Start => match i {
+                Some(i) => self.touches[i].1 = pos,
+                None => self.touches.push((id, pos)),
+            },
+            TouchAction::Move => {
+                if let Some(i) = i {
+                    if self.touches.len() == 2 {
+                        let other = self.touches[1 - i].1;
+                        let prev = self.touches[i].1;
+                        let prev_spread = (prev - other).norm();
+                        let spread = (pos - other).norm();
+
+                        // The middle of both fingers moves by half the finger displacement.
+                        if self.drag_enabled {
+                            self.handle_right_button_displacement(&((pos - prev) / 2.0));
+                        }
+
+                        if self.zoom_enabled && prev_spread > 0.0 && spread > 0.0 {
+                            self.transition = None;
+                            self.dist = self.dist * prev_spread / spread;
+                            self.update_restrictions();
+                            self.update_projviews();
+                        }
+                    }
+
+                    self.touches[i].1 = pos;
+                }
+            }
+            TouchAction::End | TouchAction::Cancel => {
+                if let Some(i) = i {
+                    let _ = self.touches.swap_remove(i);
+                }
+            }
+        }
+    }
+
     fn handle_left_button_displacement(&mut self, dpos: &Vector2<f32>) {
+        self.transition = None;
         self.yaw = self.yaw + dpos.x * self.yaw_step;
         self.pitch = self.pitch - dpos.y * self.pitch_step;
 
@@ -302,6 +587,7 @@ impl ArcBall {
     }
 
     fn handle_right_button_displacement(&mut self, dpos: &Vector2<f32>) {
+        self.transition = None;
         let eye = self.eye();
         let dir = (self.at - eye).normalize();
         let tangent = self.coord_system.up_axis.cross(&dir).normalize();
@@ -313,6 +599,7 @@ impl ArcBall {
     }
 
     fn handle_scroll(&mut self, off: f32) {
+        self.transition = None;
         self.dist = self.dist + self.dist_step * (off) / 120.0;
         self.update_restrictions();
         self.update_projviews();
@@ -369,7 +656,7 @@ impl Camera for ArcBall {
             WindowEvent::CursorPos(x, y, modifiers) => {
                 let curr_pos = Vector2::new(x as f32, y as f32);
 
-                if let Some(rotate_button) = self.rotate_button {
+                if let Some(rotate_button) = self.rotate_button.filter(|_| self.rotation_enabled) {
                     if canvas.get_mouse_button(rotate_button) == Action::Press
                         && self
                             .rotate_modifiers
@@ -381,7 +668,7 @@ impl Camera for ArcBall {
                     }
                 }
 
-                if let Some(drag_button) = self.drag_button {
+                if let Some(drag_button) = self.drag_button.filter(|_| self.drag_enabled) {
                     if canvas.get_mouse_button(drag_button) == Action::Press
                         && self.drag_modifiers.map(|m| m == modifiers).unwrap_or(true)
                     {
@@ -393,10 +680,14 @@ impl Camera for ArcBall {
                 self.last_cursor_pos = curr_pos;
             }
             WindowEvent::Key(key, Action::Press, _) if Some(key) == self.reset_key => {
+                self.transition = None;
                 self.at = Point3::origin();
                 self.update_projviews();
             }
-            WindowEvent::Scroll(_, off, _) => self.handle_scroll(off as f32),
+            WindowEvent::Scroll(_, off, _) if self.zoom_enabled => self.handle_scroll(off as f32),
+            WindowEvent::Touch(id, x, y, action, _) if self.touch_gestures => {
+                self.handle_touch(id, Vector2::new(x as f32, y as f32), action)
+            }
             WindowEvent::FramebufferSize(w, h) => {
                 self.projection.set_aspect(w as f32 / h as f32);
                 self.update_projviews();
@@ -424,7 +715,54 @@ impl Camera for ArcBall {
         self.inverse_proj_view
     }
 
-    fn update(&mut self, _: &Canvas) {}
+    fn update(&mut self, canvas: &Canvas) {
+        if camera::sync_aspect(&mut self.projection, canvas) {
+            self.update_projviews();
+        }
+
+        if let Some(mut transition) = self.transition.take() {
+            let t = transition.advance();
+            self.interpolate(&transition.from, &transition.to, t);
+
+            if !transition.is_finished() {
+                self.transition = Some(transition);
+            }
+        }
+    }
+}
+
+/// The viewpoint and projection parameters of an `ArcBall` camera.
+///
+/// This can be serialized to save and restore viewpoints. The aspect ratio is not part of the
+/// state since it follows the window size.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ArcBallState {
+    /// The focus point.
+    pub at: [f32; 3],
+    /// The rotation of the camera along the up axis, in radians.
+    pub yaw: f32,
+    /// The angle between the up axis and the direction from the focus point to the camera, in
+    /// radians.
+    pub pitch: f32,
+    /// The distance from the camera to the focus point.
+    pub dist: f32,
+    /// The vertical field of view, in radians.
+    pub fov: f32,
+    /// The distance to the near clipping plane.
+    pub znear: f32,
+    /// The distance to the far clipping plane.
+    pub zfar: f32,
+    /// The up axis of the camera.
+    pub up_axis: [f32; 3],
+}
+
+// The parameters of an arc-ball camera that are interpolated by animations.
+#[derive(Clone, Copy, Debug)]
+struct ArcBallPose {
+    at: Point3<f32>,
+    yaw: f32,
+    pitch: f32,
+    dist: f32,
 }
 
 #[derive(Clone, Copy, Debug)]
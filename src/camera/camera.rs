@@ -71,7 +71,11 @@ pub trait Camera {
 
     /// Converts a point in 2d screen coordinates to a ray (a 3d position and a direction).
     ///
-    /// The screen is assumed to have a size given by `size`.
+    /// The screen is assumed to have a size given by `size`, and `window_coord` is expressed in
+    /// the same unit, with the origin at the top-left corner of the screen (like the cursor
+    /// positions given by `WindowEvent::CursorPos`). The returned ray starts on the near
+    /// clipping plane and has a normalized direction. This works for both perspective and
+    /// orthographic projections since it relies on `self.inverse_transformation()`.
     fn unproject(
         &self,
         window_coord: &Point2<f32>,
@@ -1,4 +1,5 @@
-use crate::camera::Camera;
+use crate::camera::transition::{self, Transition};
+use crate::camera::{self, Camera};
 use crate::event::{Action, Key, MouseButton, WindowEvent};
 use crate::resource::ShaderUniform;
 use crate::window::Canvas;
@@ -15,6 +16,11 @@ use std::f32;
 ///   * Right button press + drag - translates the camera position on the plane orthogonal to the
 ///   view direction
 ///   * Scroll in/out - zoom in/out
+///
+/// With `FirstPerson::set_mouse_look`, the camera also looks around when the mouse moves while
+/// the cursor is grabbed by `Window::set_cursor_grab`.
+///
+/// The camera can be moved smoothly to another viewpoint with `FirstPerson::animate_to`.
 #[derive(Debug, Clone)]
 pub struct FirstPerson {
     eye: Point3<f32>,
@@ -26,10 +32,12 @@ pub struct FirstPerson {
     move_step: f32,
     rotate_button: Option<MouseButton>,
     drag_button: Option<MouseButton>,
+    mouse_look: bool,
     up_key: Option<Key>,
     down_key: Option<Key>,
     left_key: Option<Key>,
     right_key: Option<Key>,
+    transition: Option<Transition<FirstPersonPose>>,
 
     projection: Perspective3<f32>,
     proj: Matrix4<f32>,
@@ -63,10 +71,12 @@ impl FirstPerson {
             move_step: 0.5,
             rotate_button: Some(MouseButton::Button1),
             drag_button: Some(MouseButton::Button2),
+            mouse_look: false,
             up_key: Some(Key::Up),
             down_key: Some(Key::Down),
             left_key: Some(Key::Left),
             right_key: Some(Key::Right),
+            transition: None,
             projection: Perspective3::new(800.0 / 600.0, fov, znear, zfar),
             proj: na::zero(),
             view: na::zero(),
@@ -123,8 +133,117 @@ impl FirstPerson {
         self.yaw_step
     }
 
+    /// The vertical field of view of this camera, in radians.
+    pub fn fov(&self) -> f32 {
+        self.projection.fovy()
+    }
+
+    /// Sets the vertical field of view of this camera, in radians.
+    ///
+    /// The value is clamped to `[camera::MIN_FOV, camera::MAX_FOV]`, i.e., 1° to 179°.
+    pub fn set_fov(&mut self, fov: f32) {
+        camera::set_fov(&mut self.projection, fov);
+        self.update_projviews();
+    }
+
     /// Changes the orientation and position of the camera to look at the specified point.
     pub fn look_at(&mut self, eye: Point3<f32>, at: Point3<f32>) {
+        let pose = self.pose_looking_at(eye, at);
+        self.set_pose(&pose);
+    }
+
+    /// Creates a first-person camera with the viewpoint and projection described by `state`,
+    /// and with the default controls.
+    pub fn from_state(state: &FirstPersonState) -> FirstPerson {
+        let mut res = FirstPerson::new_with_frustrum(
+            state.fov,
+            state.znear,
+            state.zfar,
+            Point3::origin(),
+            Point3::new(0.0, 0.0, 1.0),
+        );
+        res.set_state(state);
+        res
+    }
+
+    /// The viewpoint and projection parameters of this camera, e.g., to save them for later.
+    pub fn to_state(&self) -> FirstPersonState {
+        FirstPersonState {
+            eye: self.eye.coords.into(),
+            yaw: self.yaw,
+            pitch: self.pitch,
+            fov: self.projection.fovy(),
+            znear: self.projection.znear(),
+            zfar: self.projection.zfar(),
+            up_axis: self.coord_system.up_axis.into_inner().into(),
+        }
+    }
+
+    /// Moves this camera to the viewpoint described by `state`, and sets its projection
+    /// parameters.
+    ///
+    /// The controls of the camera are kept, and the resulting view matrix is exactly the one of
+    /// the camera `state` was taken from.
+    pub fn set_state(&mut self, state: &FirstPersonState) {
+        self.coord_system = CoordSystemRh::from_up_axis(camera::up_axis_from_state(state.up_axis));
+        self.projection.set_fovy(state.fov);
+        self.projection.set_znear_and_zfar(state.znear, state.zfar);
+        self.eye = Point3::from(state.eye);
+        self.yaw = state.yaw;
+        self.pitch = state.pitch;
+        self.transition = None;
+
+        self.update_projviews();
+    }
+
+    /// Moves this camera toward the viewpoint of `target`.
+    ///
+    /// The position, yaw and pitch are interpolated with an easing, `t = 0.0` leaving this
+    /// camera unchanged and `t = 1.0` moving it to the viewpoint of `target`. The yaw is
+    /// interpolated along the shortest arc. The projection and controls of `target` are ignored.
+    pub fn slerp_to(&mut self, target: &FirstPerson, t: f32) {
+        let from = self.pose();
+        let to = self.pose_looking_at(target.eye, target.at());
+        self.interpolate(&from, &to, transition::ease(t));
+    }
+
+    /// Animates this camera toward the viewpoint of `target` for `duration` seconds.
+    ///
+    /// The animation is advanced by `Camera::update`, which is called by the window at each
+    /// frame, and follows the same path as `FirstPerson::slerp_to`. It is interrupted when the
+    /// user moves the camera.
+    pub fn animate_to(&mut self, target: &FirstPerson, duration: f32) {
+        let to = self.pose_looking_at(target.eye, target.at());
+        self.transition = Some(Transition::new(self.pose(), to, duration));
+    }
+
+    /// Whether an animation started by `FirstPerson::animate_to` is ongoing.
+    pub fn is_animating(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// Stops the animation started by `FirstPerson::animate_to`, leaving the camera where it is.
+    pub fn stop_animation(&mut self) {
+        self.transition = None;
+    }
+
+    fn pose(&self) -> FirstPersonPose {
+        FirstPersonPose {
+            eye: self.eye,
+            yaw: self.yaw,
+            pitch: self.pitch,
+        }
+    }
+
+    fn set_pose(&mut self, pose: &FirstPersonPose) {
+        self.eye = pose.eye;
+        self.yaw = pose.yaw;
+        self.pitch = pose.pitch;
+        self.update_projviews();
+    }
+
+    // The pose of this camera once moved to `eye` and looking at `at`.
+    fn pose_looking_at(&self, eye: Point3<f32>, at: Point3<f32>) -> FirstPersonPose {
         let dist = (eye - at).norm();
 
         let view_eye = self.coord_system.rotation_to_y_up * eye;
@@ -132,10 +251,17 @@ impl FirstPerson {
         let pitch = ((view_at.y - view_eye.y) / dist).acos();
         let yaw = (view_at.z - view_eye.z).atan2(view_at.x - view_eye.x);
 
-        self.eye = eye;
-        self.yaw = yaw;
-        self.pitch = pitch;
-        self.update_projviews();
+        FirstPersonPose { eye, yaw, pitch }
+    }
+
+    fn interpolate(&mut self, from: &FirstPersonPose, to: &FirstPersonPose, t: f32) {
+        let pose = FirstPersonPose {
+            eye: from.eye + (to.eye - from.eye) * t,
+            yaw: transition::lerp_angle(from.yaw, to.yaw, t),
+            pitch: transition::lerp(from.pitch, to.pitch, t),
+        };
+
+        self.set_pose(&pose);
     }
 
     /// The point the camera is looking at.
@@ -169,6 +295,19 @@ impl FirstPerson {
         self.rotate_button = new_button;
     }
 
+    /// Whether the relative mouse displacements rotate the camera without any button pressed.
+    pub fn mouse_look(&self) -> bool {
+        self.mouse_look
+    }
+
+    /// Enables or disables the rotation of the camera by the relative mouse displacements
+    /// reported by `WindowEvent::MouseMotion`, without any button pressed.
+    ///
+    /// Those displacements are only reported while the cursor is grabbed. Disabled by default.
+    pub fn set_mouse_look(&mut self, enabled: bool) {
+        self.mouse_look = enabled;
+    }
+
     /// The button used to drag the FirstPerson camera.
     pub fn drag_button(&self) -> Option<MouseButton> {
         self.drag_button
@@ -234,6 +373,7 @@ impl FirstPerson {
 
     #[doc(hidden)]
     pub fn handle_left_button_displacement(&mut self, dpos: &Vector2<f32>) {
+        self.transition = None;
         self.yaw = self.yaw + dpos.x * self.yaw_step;
         self.pitch = self.pitch + dpos.y * self.pitch_step;
 
@@ -243,6 +383,7 @@ impl FirstPerson {
 
     #[doc(hidden)]
     pub fn handle_right_button_displacement(&mut self, dpos: &Vector2<f32>) {
+        self.transition = None;
         let at = self.at();
         let dir = (at - self.eye).normalize();
         let tangent = self.coord_system.up_axis.cross(&dir).normalize();
@@ -255,6 +396,7 @@ impl FirstPerson {
 
     #[doc(hidden)]
     pub fn handle_scroll(&mut self, yoff: f32) {
+        self.transition = None;
         let front = self.observer_frame() * Vector3::z();
 
         self.eye = self.eye + front * (self.move_step * yoff);
@@ -391,6 +533,9 @@ impl Camera for FirstPerson {
 
                 self.last_cursor_pos = curr_pos;
             }
+            WindowEvent::MouseMotion(dx, dy) if self.mouse_look => {
+                self.handle_left_button_displacement(&Vector2::new(dx as f32, dy as f32))
+            }
             WindowEvent::Scroll(_, off, _) => self.handle_scroll(off as f32),
             WindowEvent::FramebufferSize(w, h) => {
                 self.projection.set_aspect(w as f32 / h as f32);
@@ -424,12 +569,27 @@ impl Camera for FirstPerson {
     }
 
     fn update(&mut self, canvas: &Canvas) {
+        if camera::sync_aspect(&mut self.projection, canvas) {
+            self.update_projviews();
+        }
+
         let up = check_optional_key_state(canvas, self.up_key, Action::Press);
         let down = check_optional_key_state(canvas, self.down_key, Action::Press);
         let right = check_optional_key_state(canvas, self.right_key, Action::Press);
         let left = check_optional_key_state(canvas, self.left_key, Action::Press);
         let dir = self.move_dir(up, down, right, left);
 
+        if !dir.is_zero() {
+            self.transition = None;
+        } else if let Some(mut transition) = self.transition.take() {
+            let t = transition.advance();
+            self.interpolate(&transition.from, &transition.to, t);
+
+            if !transition.is_finished() {
+                self.transition = Some(transition);
+            }
+        }
+
         let move_amount = dir * self.move_step;
         self.translate_mut(&Translation3::from(move_amount));
     }
@@ -462,3 +622,33 @@ impl CoordSystemRh {
         }
     }
 }
+
+/// The viewpoint and projection parameters of a `FirstPerson` camera.
+///
+/// This can be serialized to save and restore viewpoints. The aspect ratio is not part of the
+/// state since it follows the window size.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FirstPersonState {
+    /// The camera position.
+    pub eye: [f32; 3],
+    /// The rotation of the camera along the up axis, in radians.
+    pub yaw: f32,
+    /// The angle between the up axis and the view direction, in radians.
+    pub pitch: f32,
+    /// The vertical field of view, in radians.
+    pub fov: f32,
+    /// The distance to the near clipping plane.
+    pub znear: f32,
+    /// The distance to the far clipping plane.
+    pub zfar: f32,
+    /// The up axis of the camera.
+    pub up_axis: [f32; 3],
+}
+
+// The parameters of a first-person camera that are interpolated by animations.
+#[derive(Clone, Copy, Debug)]
+struct FirstPersonPose {
+    eye: Point3<f32>,
+    yaw: f32,
+    pitch: f32,
+}
@@ -1,4 +1,4 @@
-use crate::camera::Camera;
+use crate::camera::{self, Camera};
 use crate::event::WindowEvent;
 use crate::resource::ShaderUniform;
 use crate::window::Canvas;
@@ -21,11 +21,28 @@ impl FixedView {
 
     /// Creates a new arc ball camera with default sensitivity values.
     pub fn new_with_frustrum(fov: f32, znear: f32, zfar: f32) -> FixedView {
-        FixedView {
+        let mut res = FixedView {
             projection: Perspective3::new(800.0 / 600.0, fov, znear, zfar),
             proj: na::one(),
             inv_proj: na::one(),
-        }
+        };
+
+        res.update_projviews();
+
+        res
+    }
+
+    /// The vertical field of view of this camera, in radians.
+    pub fn fov(&self) -> f32 {
+        self.projection.fovy()
+    }
+
+    /// Sets the vertical field of view of this camera, in radians.
+    ///
+    /// The value is clamped to `[camera::MIN_FOV, camera::MAX_FOV]`, i.e., 1° to 179°.
+    pub fn set_fov(&mut self, fov: f32) {
+        camera::set_fov(&mut self.projection, fov);
+        self.update_projviews();
     }
 
     fn update_projviews(&mut self) {
@@ -80,5 +97,9 @@ impl Camera for FixedView {
         self.inv_proj
     }
 
-    fn update(&mut self, _: &Canvas) {}
+    fn update(&mut self, canvas: &Canvas) {
+        if camera::sync_aspect(&mut self.projection, canvas) {
+            self.update_projviews();
+        }
+    }
 }
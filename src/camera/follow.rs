@@ -0,0 +1,267 @@
+use crate::camera::{self, Camera};
+use crate::event::WindowEvent;
+use crate::resource::ShaderUniform;
+use crate::scene::SceneNode;
+use crate::window::Canvas;
+use instant::Instant;
+use na::{self, Isometry3, Matrix4, Perspective3, Point3, Unit, Vector3};
+use std::f32;
+
+/// The space in which the offset of a `Follow` camera is expressed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OffsetSpace {
+    /// The offset is expressed in world coordinates: the camera does not turn with the node.
+    World,
+    /// The offset is expressed in the local frame of the node: the camera turns with the node,
+    /// e.g., to stay behind a vehicle.
+    Local,
+}
+
+/// A camera that follows a scene node.
+///
+/// At each frame, the camera is placed at a fixed offset from the world position of the node,
+/// and looks at it. The offset can be expressed in world coordinates or in the local frame of
+/// the node. The camera motion can be smoothed with `Follow::set_smoothing`.
+///
+/// This camera does not handle any user input.
+#[derive(Clone)]
+pub struct Follow {
+    node: SceneNode,
+    offset: Vector3<f32>,
+    offset_space: OffsetSpace,
+    /// The time, in seconds, the camera takes to move about two thirds of the way to its target.
+    smoothing: f32,
+    up_axis: Unit<Vector3<f32>>,
+    eye: Point3<f32>,
+    at: Point3<f32>,
+    last_update: Option<Instant>,
+
+    projection: Perspective3<f32>,
+    proj: Matrix4<f32>,
+    view: Matrix4<f32>,
+    proj_view: Matrix4<f32>,
+    inverse_proj_view: Matrix4<f32>,
+}
+
+impl Follow {
+    /// Creates a camera following `node` at the world-space `offset` from its position.
+    pub fn new(node: SceneNode, offset: Vector3<f32>) -> Follow {
+        Follow::new_with_frustrum(f32::consts::PI / 4.0, 0.1, 1024.0, node, offset)
+    }
+
+    /// Creates a camera following `node` at the world-space `offset` from its position, with
+    /// the given projection parameters.
+    pub fn new_with_frustrum(
+        fov: f32,
+        znear: f32,
+        zfar: f32,
+        node: SceneNode,
+        offset: Vector3<f32>,
+    ) -> Follow {
+        assert!(
+            offset.norm_squared() > 0.0,
+            "The offset of a follow camera must not be zero."
+        );
+
+        let mut res = Follow {
+            node,
+            offset,
+            offset_space: OffsetSpace::World,
+            smoothing: 0.0,
+            up_axis: Vector3::y_axis(),
+            eye: Point3::origin(),
+            at: Point3::origin(),
+            last_update: None,
+            projection: Perspective3::new(800.0 / 600.0, fov, znear, zfar),
+            proj: na::zero(),
+            view: na::zero(),
+            proj_view: na::zero(),
+            inverse_proj_view: na::zero(),
+        };
+
+        res.snap();
+
+        res
+    }
+
+    /// The node followed by this camera.
+    pub fn node(&self) -> &SceneNode {
+        &self.node
+    }
+
+    /// Sets the node followed by this camera.
+    ///
+    /// If smoothing is enabled, the camera moves smoothly toward the new node.
+    pub fn set_node(&mut self, node: SceneNode) {
+        self.node = node;
+    }
+
+    /// The offset from the followed node to the camera.
+    pub fn offset(&self) -> Vector3<f32> {
+        self.offset
+    }
+
+    /// Sets the offset from the followed node to the camera, expressed in the space given by
+    /// `Follow::offset_space`.
+    ///
+    /// The offset must not be zero, and should not be parallel to the up axis.
+    pub fn set_offset(&mut self, offset: Vector3<f32>) {
+        assert!(
+            offset.norm_squared() > 0.0,
+            "The offset of a follow camera must not be zero."
+        );
+        self.offset = offset;
+    }
+
+    /// The space in which the offset is expressed.
+    pub fn offset_space(&self) -> OffsetSpace {
+        self.offset_space
+    }
+
+    /// Sets the space in which the offset is expressed. Defaults to `OffsetSpace::World`.
+    pub fn set_offset_space(&mut self, space: OffsetSpace) {
+        self.offset_space = space;
+    }
+
+    /// The smoothing time of the camera motion, in seconds.
+    pub fn smoothing(&self) -> f32 {
+        self.smoothing
+    }
+
+    /// Sets the smoothing time of the camera motion, in seconds.
+    ///
+    /// The camera catches up with the node exponentially, moving about two thirds of the way to
+    /// its target position in `smoothing` seconds. Set to 0, which is the default, to disable
+    /// smoothing.
+    pub fn set_smoothing(&mut self, smoothing: f32) {
+        assert!(smoothing >= 0.0, "The smoothing time must be non-negative.");
+        self.smoothing = smoothing;
+    }
+
+    /// The point the camera is looking at.
+    pub fn at(&self) -> Point3<f32> {
+        self.at
+    }
+
+    /// Moves the camera to its target position immediately, ignoring the smoothing.
+    pub fn snap(&mut self) {
+        let (eye, at) = self.target();
+        self.eye = eye;
+        self.at = at;
+        self.update_projviews();
+    }
+
+    /// The vertical field of view of this camera, in radians.
+    pub fn fov(&self) -> f32 {
+        self.projection.fovy()
+    }
+
+    /// Sets the vertical field of view of this camera, in radians.
+    ///
+    /// The value is clamped to `[camera::MIN_FOV, camera::MAX_FOV]`, i.e., 1° to 179°.
+    pub fn set_fov(&mut self, fov: f32) {
+        camera::set_fov(&mut self.projection, fov);
+        self.update_projviews();
+    }
+
+    /// Sets the up vector of this camera. Prefer using [`set_up_axis_dir`](#method.set_up_axis_dir)
+    /// if your up vector is already normalized.
+    #[inline]
+    pub fn set_up_axis(&mut self, up_axis: Vector3<f32>) {
+        self.set_up_axis_dir(Unit::new_normalize(up_axis));
+    }
+
+    /// Sets the up-axis direction of this camera.
+    #[inline]
+    pub fn set_up_axis_dir(&mut self, up_axis: Unit<Vector3<f32>>) {
+        self.up_axis = up_axis;
+        self.update_projviews();
+    }
+
+    // The position of the camera and of the point it looks at, without smoothing.
+    fn target(&self) -> (Point3<f32>, Point3<f32>) {
+        let transform = self.node.data().world_transformation();
+        let at = Point3::from(transform.translation.vector);
+        let offset = match self.offset_space {
+            OffsetSpace::World => self.offset,
+            OffsetSpace::Local => transform.rotation * self.offset,
+        };
+
+        (at + offset, at)
+    }
+
+    fn update_projviews(&mut self) {
+        self.proj = *self.projection.as_matrix();
+        self.view = self.view_transform().to_homogeneous();
+        self.proj_view = self.proj * self.view;
+        let _ = self
+            .proj_view
+            .try_inverse()
+            .map(|inverse_proj| self.inverse_proj_view = inverse_proj);
+    }
+}
+
+impl Camera for Follow {
+    fn clip_planes(&self) -> (f32, f32) {
+        (self.projection.znear(), self.projection.zfar())
+    }
+
+    fn view_transform(&self) -> Isometry3<f32> {
+        Isometry3::look_at_rh(&self.eye, &self.at, &self.up_axis)
+    }
+
+    fn eye(&self) -> Point3<f32> {
+        self.eye
+    }
+
+    fn handle_event(&mut self, _: &Canvas, event: &WindowEvent) {
+        if let WindowEvent::FramebufferSize(w, h) = *event {
+            self.projection.set_aspect(w as f32 / h as f32);
+            self.update_projviews();
+        }
+    }
+
+    #[inline]
+    fn upload(
+        &self,
+        _: usize,
+        proj: &mut ShaderUniform<Matrix4<f32>>,
+        view: &mut ShaderUniform<Matrix4<f32>>,
+    ) {
+        proj.upload(&self.proj);
+        view.upload(&self.view);
+    }
+
+    fn transformation(&self) -> Matrix4<f32> {
+        self.proj_view
+    }
+
+    fn inverse_transformation(&self) -> Matrix4<f32> {
+        self.inverse_proj_view
+    }
+
+    fn update(&mut self, canvas: &Canvas) {
+        // The projection matrices are updated below.
+        let _ = camera::sync_aspect(&mut self.projection, canvas);
+
+        let now = Instant::now();
+        let (eye, at) = self.target();
+
+        match self.last_update {
+            Some(last_update) if self.smoothing > 0.0 => {
+                let dt = now.duration_since(last_update).as_secs_f32();
+                let t = 1.0 - (-dt / self.smoothing).exp();
+
+                self.eye = self.eye + (eye - self.eye) * t;
+                self.at = self.at + (at - self.at) * t;
+            }
+            _ => {
+                self.eye = eye;
+                self.at = at;
+            }
+        }
+
+        self.last_update = Some(now);
+        self.update_projviews();
+    }
+}
@@ -1,10 +1,15 @@
 //! Camera trait with some common implementations.
 
-pub use self::arc_ball::ArcBall;
+pub use self::arc_ball::{ArcBall, ArcBallState};
 pub use self::camera::Camera;
-pub use self::first_person::FirstPerson;
+pub use self::first_person::{FirstPerson, FirstPersonState};
 pub use self::first_person_stereo::FirstPersonStereo;
 pub use self::fixed_view::FixedView;
+pub use self::follow::{Follow, OffsetSpace};
+pub use self::orthographic::Orthographic;
+
+use crate::window::Canvas;
+use na::{Perspective3, Unit, Vector3};
 
 mod arc_ball;
 #[doc(hidden)]
@@ -12,3 +17,56 @@ pub mod camera;
 mod first_person;
 mod first_person_stereo;
 mod fixed_view;
+mod follow;
+mod orthographic;
+mod transition;
+
+/// The smallest vertical field of view accepted by the cameras `set_fov` methods, in radians.
+pub const MIN_FOV: f32 = std::f32::consts::PI / 180.0;
+/// The largest vertical field of view accepted by the cameras `set_fov` methods, in radians.
+pub const MAX_FOV: f32 = std::f32::consts::PI * 179.0 / 180.0;
+
+// Sets the aspect ratio of `projection` to the one of `canvas`, so that it is kept in sync even
+// if no resize event was received. Returns `true` if the aspect ratio changed.
+fn sync_aspect(projection: &mut Perspective3<f32>, canvas: &Canvas) -> bool {
+    let (w, h) = canvas.size();
+
+    if w != 0 && h != 0 && projection.aspect() != w as f32 / h as f32 {
+        projection.set_aspect(w as f32 / h as f32);
+        true
+    } else {
+        false
+    }
+}
+
+// Sets the vertical field of view of `projection`, clamped to `[MIN_FOV, MAX_FOV]`.
+fn set_fov(projection: &mut Perspective3<f32>, fov: f32) {
+    projection.set_fovy(fov.max(MIN_FOV).min(MAX_FOV));
+}
+
+// Converts an up axis from a camera state, keeping it exact if it is already normalized so that
+// states round-trip exactly. A zero or non-finite axis is replaced by the `y` axis.
+fn up_axis_from_state(up_axis: [f32; 3]) -> Unit<Vector3<f32>> {
+    let up_axis = Vector3::from(up_axis);
+
+    if up_axis.norm_squared() == 1.0 {
+        Unit::new_unchecked(up_axis)
+    } else {
+        Unit::try_new(up_axis, 0.0)
+            .filter(|axis| axis.iter().all(|c| c.is_finite()))
+            .unwrap_or_else(Vector3::y_axis)
+    }
+}
+
+/// Converts a value of the depth buffer to the distance from the camera along its view axis.
+///
+/// `depth` is in `[0, 1]`, as returned by `Window::read_depth`, and `znear` and `zfar` are the
+/// clip planes of the perspective camera, as returned by `Camera::clip_planes`. The depth buffer
+/// has a much better precision near the camera than far from it, so linearized far depths are
+/// not accurate.
+///
+/// Orthographic cameras have a linear depth, equal to `znear + depth * (zfar - znear)`.
+pub fn linearize_depth(depth: f32, znear: f32, zfar: f32) -> f32 {
+    let ndc = depth * 2.0 - 1.0;
+    2.0 * znear * zfar / (zfar + znear - ndc * (zfar - znear))
+}
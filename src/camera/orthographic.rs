@@ -0,0 +1,296 @@
+use crate::camera::Camera;
+use crate::event::{Action, Modifiers, MouseButton, WindowEvent};
+use crate::resource::ShaderUniform;
+use crate::window::Canvas;
+use na::{self, Isometry3, Matrix4, Orthographic3, Point3, Unit, Vector2, Vector3};
+
+/// Orthographic camera mode.
+///
+/// An orthographic camera looks at a fixed direction, and does not apply any perspective
+/// effect: the apparent size of an object does not depend on its distance to the camera. The
+/// following inputs are handled:
+///
+/// * Right button press + drag - pans the view
+/// * Scroll in/out - zoom in/out, by changing the extents of the view
+#[derive(Clone, Debug)]
+pub struct Orthographic {
+    /// The camera position.
+    eye: Point3<f32>,
+    /// The point the camera is looking at.
+    at: Point3<f32>,
+    /// The up direction of the camera.
+    up_axis: Unit<Vector3<f32>>,
+    /// The height of the visible area, in world units.
+    view_size: f32,
+    /// Minimum height of the visible area.
+    min_view_size: f32,
+    /// Maximum height of the visible area.
+    max_view_size: f32,
+    /// Relative change of the view size per scroll notch. The default value is 0.1.
+    zoom_step: f32,
+    znear: f32,
+    zfar: f32,
+    drag_button: Option<MouseButton>,
+    drag_modifiers: Option<Modifiers>,
+
+    viewport_size: Vector2<f32>,
+    view: Matrix4<f32>,
+    proj: Matrix4<f32>,
+    proj_view: Matrix4<f32>,
+    inverse_proj_view: Matrix4<f32>,
+    last_cursor_pos: Vector2<f32>,
+}
+
+impl Orthographic {
+    /// Creates a new orthographic camera, showing a visible area 10 units high.
+    pub fn new(eye: Point3<f32>, at: Point3<f32>) -> Orthographic {
+        Orthographic::new_with_view_size(10.0, 0.1, 1024.0, eye, at)
+    }
+
+    /// Creates a new orthographic camera.
+    ///
+    /// # Arguments
+    /// * `view_size` - the height of the visible area, in world units. Its width is deduced from
+    /// the aspect ratio of the window.
+    /// * `znear` - the distance from the camera to the near clipping plane.
+    /// * `zfar` - the distance from the camera to the far clipping plane.
+    pub fn new_with_view_size(
+        view_size: f32,
+        znear: f32,
+        zfar: f32,
+        eye: Point3<f32>,
+        at: Point3<f32>,
+    ) -> Orthographic {
+        let mut res = Orthographic {
+            eye,
+            at,
+            up_axis: Vector3::y_axis(),
+            view_size,
+            min_view_size: 0.00001,
+            max_view_size: std::f32::MAX,
+            zoom_step: 0.1,
+            znear,
+            zfar,
+            drag_button: Some(MouseButton::Button2),
+            drag_modifiers: None,
+            viewport_size: Vector2::new(800.0, 600.0),
+            view: na::zero(),
+            proj: na::zero(),
+            proj_view: na::zero(),
+            inverse_proj_view: na::zero(),
+            last_cursor_pos: na::zero(),
+        };
+
+        res.update_projviews();
+
+        res
+    }
+
+    /// The point the camera is looking at.
+    pub fn at(&self) -> Point3<f32> {
+        self.at
+    }
+
+    /// Move and orient the camera such that it looks at a specific point.
+    pub fn look_at(&mut self, eye: Point3<f32>, at: Point3<f32>) {
+        self.eye = eye;
+        self.at = at;
+        self.update_projviews();
+    }
+
+    /// Sets the up vector of this camera.
+    pub fn set_up_axis(&mut self, up_axis: Vector3<f32>) {
+        self.up_axis = Unit::new_normalize(up_axis);
+        self.update_projviews();
+    }
+
+    /// The height of the visible area, in world units.
+    pub fn view_size(&self) -> f32 {
+        self.view_size
+    }
+
+    /// Sets the height of the visible area, in world units.
+    ///
+    /// Its width is deduced from the aspect ratio of the window.
+    pub fn set_view_size(&mut self, view_size: f32) {
+        self.view_size = view_size;
+        self.update_restrictions();
+        self.update_projviews();
+    }
+
+    /// The minimum height of the visible area.
+    pub fn min_view_size(&self) -> f32 {
+        self.min_view_size
+    }
+
+    /// Sets the minimum height of the visible area.
+    pub fn set_min_view_size(&mut self, min_view_size: f32) {
+        self.min_view_size = min_view_size;
+    }
+
+    /// The maximum height of the visible area.
+    pub fn max_view_size(&self) -> f32 {
+        self.max_view_size
+    }
+
+    /// Sets the maximum height of the visible area.
+    pub fn set_max_view_size(&mut self, max_view_size: f32) {
+        self.max_view_size = max_view_size;
+    }
+
+    /// Sets the relative change of the view size per scroll notch (default at 0.1).
+    pub fn set_zoom_step(&mut self, zoom_step: f32) {
+        self.zoom_step = zoom_step;
+    }
+
+    /// Sets the distances from the camera to the near and far clipping planes.
+    pub fn set_clip_planes(&mut self, znear: f32, zfar: f32) {
+        self.znear = znear;
+        self.zfar = zfar;
+        self.update_projviews();
+    }
+
+    /// The button used to pan the camera.
+    pub fn drag_button(&self) -> Option<MouseButton> {
+        self.drag_button
+    }
+
+    /// Set the button used to pan the camera.
+    /// Use None to disable panning.
+    pub fn rebind_drag_button(&mut self, new_button: Option<MouseButton>) {
+        self.drag_button = new_button;
+    }
+
+    /// Modifiers that must be pressed for the camera panning to occur.
+    pub fn drag_modifiers(&self) -> Option<Modifiers> {
+        self.drag_modifiers
+    }
+
+    /// Sets the modifiers that must be pressed for the camera panning to occur.
+    ///
+    /// If this is set to `None`, then pressing any modifier will not prevent panning from
+    /// occurring. Otherwise, panning will occur only if the exact specified set of modifiers
+    /// is pressed.
+    pub fn set_drag_modifiers(&mut self, modifiers: Option<Modifiers>) {
+        self.drag_modifiers = modifiers
+    }
+
+    fn update_restrictions(&mut self) {
+        if self.view_size < self.min_view_size {
+            self.view_size = self.min_view_size
+        }
+
+        if self.view_size > self.max_view_size {
+            self.view_size = self.max_view_size
+        }
+    }
+
+    fn handle_drag(&mut self, dpos: &Vector2<f32>) {
+        let dir = (self.at - self.eye).normalize();
+        let right = dir.cross(&self.up_axis).normalize();
+        let up = right.cross(&dir);
+        // The displacement is converted from pixels to world units so that the scene follows
+        // the cursor.
+        let mult = self.view_size / self.viewport_size.y;
+        let shift = up * (dpos.y * mult) - right * (dpos.x * mult);
+
+        self.eye += shift;
+        self.at += shift;
+        self.update_projviews();
+    }
+
+    fn handle_scroll(&mut self, off: f32) {
+        // A scroll notch yields an offset of 10.
+        self.view_size *= (1.0 + self.zoom_step).powf(-off / 10.0);
+        self.update_restrictions();
+        self.update_projviews();
+    }
+
+    fn set_viewport_size(&mut self, w: f32, h: f32) {
+        if w > 0.0 && h > 0.0 && (w != self.viewport_size.x || h != self.viewport_size.y) {
+            self.viewport_size = Vector2::new(w, h);
+            self.update_projviews();
+        }
+    }
+
+    fn update_projviews(&mut self) {
+        let half_height = self.view_size / 2.0;
+        let half_width = half_height * self.viewport_size.x / self.viewport_size.y;
+        let projection = Orthographic3::new(
+            -half_width,
+            half_width,
+            -half_height,
+            half_height,
+            self.znear,
+            self.zfar,
+        );
+
+        self.proj = projection.to_homogeneous();
+        self.view = self.view_transform().to_homogeneous();
+        self.proj_view = self.proj * self.view;
+        let _ = self
+            .proj_view
+            .try_inverse()
+            .map(|inverse_proj_view| self.inverse_proj_view = inverse_proj_view);
+    }
+}
+
+impl Camera for Orthographic {
+    fn clip_planes(&self) -> (f32, f32) {
+        (self.znear, self.zfar)
+    }
+
+    fn view_transform(&self) -> Isometry3<f32> {
+        Isometry3::look_at_rh(&self.eye, &self.at, &self.up_axis)
+    }
+
+    fn eye(&self) -> Point3<f32> {
+        self.eye
+    }
+
+    fn handle_event(&mut self, canvas: &Canvas, event: &WindowEvent) {
+        match *event {
+            WindowEvent::CursorPos(x, y, modifiers) => {
+                let curr_pos = Vector2::new(x as f32, y as f32);
+
+                if let Some(drag_button) = self.drag_button {
+                    if canvas.get_mouse_button(drag_button) == Action::Press
+                        && self.drag_modifiers.map(|m| m == modifiers).unwrap_or(true)
+                    {
+                        let dpos = curr_pos - self.last_cursor_pos;
+                        self.handle_drag(&dpos)
+                    }
+                }
+
+                self.last_cursor_pos = curr_pos;
+            }
+            WindowEvent::Scroll(_, off, _) => self.handle_scroll(off as f32),
+            WindowEvent::FramebufferSize(w, h) => self.set_viewport_size(w as f32, h as f32),
+            _ => {}
+        }
+    }
+
+    #[inline]
+    fn upload(
+        &self,
+        _: usize,
+        proj: &mut ShaderUniform<Matrix4<f32>>,
+        view: &mut ShaderUniform<Matrix4<f32>>,
+    ) {
+        proj.upload(&self.proj);
+        view.upload(&self.view);
+    }
+
+    fn transformation(&self) -> Matrix4<f32> {
+        self.proj_view
+    }
+
+    fn inverse_transformation(&self) -> Matrix4<f32> {
+        self.inverse_proj_view
+    }
+
+    fn update(&mut self, canvas: &Canvas) {
+        let (w, h) = canvas.size();
+        self.set_viewport_size(w as f32, h as f32);
+    }
+}
@@ -0,0 +1,78 @@
+//! Interpolation between camera states.
+
+use instant::Instant;
+use std::f32;
+
+/// Smoothly maps `t` from `[0, 1]` to `[0, 1]`, with a zero slope at both ends.
+pub(crate) fn ease(t: f32) -> f32 {
+    let t = t.max(0.0).min(1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Interpolates linearly between two values.
+pub(crate) fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Interpolates between two angles (in radians) along the shortest arc.
+pub(crate) fn lerp_angle(a: f32, b: f32, t: f32) -> f32 {
+    let two_pi = f32::consts::PI * 2.0;
+    let mut delta = (b - a) % two_pi;
+
+    if delta > f32::consts::PI {
+        delta -= two_pi;
+    } else if delta < -f32::consts::PI {
+        delta += two_pi;
+    }
+
+    a + delta * t
+}
+
+/// An animation from one camera state to another, advanced with the time elapsed between
+/// two frames.
+#[derive(Clone, Debug)]
+pub(crate) struct Transition<S> {
+    pub from: S,
+    pub to: S,
+    duration: f32,
+    elapsed: f32,
+    last_update: Option<Instant>,
+}
+
+impl<S> Transition<S> {
+    /// Creates a transition lasting `duration` seconds.
+    pub fn new(from: S, to: S, duration: f32) -> Self {
+        Transition {
+            from,
+            to,
+            duration,
+            elapsed: 0.0,
+            last_update: None,
+        }
+    }
+
+    /// Advances this transition by the time elapsed since the last call, and returns the eased
+    /// progress of the transition.
+    ///
+    /// The first call starts the transition and returns 0.
+    pub fn advance(&mut self) -> f32 {
+        let now = Instant::now();
+
+        if let Some(last_update) = self.last_update {
+            self.elapsed += now.duration_since(last_update).as_secs_f32();
+        }
+
+        self.last_update = Some(now);
+
+        if self.duration > 0.0 {
+            ease(self.elapsed / self.duration)
+        } else {
+            1.0
+        }
+    }
+
+    /// Whether the transition reached its end.
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
@@ -0,0 +1,122 @@
+//! Colormaps, to turn scalar values into colors.
+
+use na::Point3;
+
+/// A map from values in `[0, 1]` to colors.
+///
+/// The result can be used as the colors of points, or as the vertex colors of a mesh set with
+/// `Mesh::set_colors`, e.g., to draw heatmaps.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Colormap {
+    /// The perceptually uniform colormap of matplotlib, from dark blue to yellow.
+    Viridis,
+    /// The perceptually uniform colormap of matplotlib, from dark blue to yellow through red.
+    Plasma,
+    /// The rainbow colormap of MATLAB, from dark blue to dark red.
+    Jet,
+    /// From black to white through red and yellow.
+    Hot,
+    /// From black to white.
+    Grayscale,
+}
+
+impl Colormap {
+    /// The color of the value `t`, clamped to `[0, 1]`.
+    pub fn sample(&self, t: f32) -> Point3<f32> {
+        // NaN values are mapped to 0.
+        let t = t.max(0.0).min(1.0);
+
+        match *self {
+            Colormap::Viridis => interpolate(&VIRIDIS, t),
+            Colormap::Plasma => interpolate(&PLASMA, t),
+            Colormap::Jet => Point3::new(
+                clamp(1.5 - (4.0 * t - 3.0).abs()),
+                clamp(1.5 - (4.0 * t - 2.0).abs()),
+                clamp(1.5 - (4.0 * t - 1.0).abs()),
+            ),
+            Colormap::Hot => {
+                Point3::new(clamp(3.0 * t), clamp(3.0 * t - 1.0), clamp(3.0 * t - 2.0))
+            }
+            Colormap::Grayscale => Point3::new(t, t, t),
+        }
+    }
+
+    /// The colors of `values`, mapped linearly from their smallest to their largest value.
+    ///
+    /// See `normalize` for details.
+    pub fn map(&self, values: &[f32]) -> Vec<Point3<f32>> {
+        normalize(values)
+            .into_iter()
+            .map(|t| self.sample(t))
+            .collect()
+    }
+}
+
+/// Maps `values` linearly to `[0, 1]`, from their smallest to their largest value.
+///
+/// Non-finite values are ignored to compute the range, and mapped to 0. If every value is
+/// equal, they are all mapped to 0.
+pub fn normalize(values: &[f32]) -> Vec<f32> {
+    let finite = values.iter().cloned().filter(|v| v.is_finite());
+    let min = finite.clone().fold(f32::MAX, f32::min);
+    let max = finite.fold(-f32::MAX, f32::max);
+    let range = if max > min { max - min } else { 1.0 };
+
+    values
+        .iter()
+        .map(|v| {
+            if v.is_finite() {
+                (v - min) / range
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+fn clamp(x: f32) -> f32 {
+    x.max(0.0).min(1.0)
+}
+
+// Linear interpolation between colors evenly spaced on `[0, 1]`.
+fn interpolate(colors: &[[f32; 3]], t: f32) -> Point3<f32> {
+    let x = t * (colors.len() - 1) as f32;
+    let i = (x.floor() as usize).min(colors.len() - 2);
+    let s = x - i as f32;
+    let (a, b) = (colors[i], colors[i + 1]);
+
+    Point3::new(
+        a[0] + (b[0] - a[0]) * s,
+        a[1] + (b[1] - a[1]) * s,
+        a[2] + (b[2] - a[2]) * s,
+    )
+}
+
+// Samples of the matplotlib colormaps, every 0.1.
+const VIRIDIS: [[f32; 3]; 11] = [
+    [0.267004, 0.004874, 0.329415],
+    [0.282623, 0.140926, 0.457517],
+    [0.253935, 0.265254, 0.529983],
+    [0.206756, 0.371758, 0.553117],
+    [0.163625, 0.471133, 0.558148],
+    [0.127568, 0.566949, 0.550556],
+    [0.134692, 0.658636, 0.517649],
+    [0.266941, 0.748751, 0.440573],
+    [0.477504, 0.821444, 0.318195],
+    [0.741388, 0.873449, 0.149561],
+    [0.993248, 0.906157, 0.143936],
+];
+
+const PLASMA: [[f32; 3]; 11] = [
+    [0.050383, 0.029803, 0.527975],
+    [0.254627, 0.013882, 0.615419],
+    [0.417642, 0.000564, 0.658390],
+    [0.562738, 0.051545, 0.641509],
+    [0.692840, 0.165141, 0.564522],
+    [0.798216, 0.280197, 0.469538],
+    [0.881443, 0.392529, 0.383229],
+    [0.949217, 0.517763, 0.295662],
+    [0.988260, 0.652325, 0.211364],
+    [0.988648, 0.809579, 0.145357],
+    [0.940015, 0.975158, 0.131326],
+];
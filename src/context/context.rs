@@ -1,5 +1,6 @@
 #![allow(missing_docs)]
 
+use std::cell::Cell;
 use std::sync::Once;
 
 use crate::context::GLContext as ContextImpl;
@@ -18,9 +19,21 @@ pub struct Program(<ContextImpl as AbstractContext>::Program);
 pub struct Shader(<ContextImpl as AbstractContext>::Shader);
 pub struct Framebuffer(<ContextImpl as AbstractContext>::Framebuffer);
 pub struct Renderbuffer(<ContextImpl as AbstractContext>::Renderbuffer);
-pub struct Texture(<ContextImpl as AbstractContext>::Texture);
+pub struct Texture(
+    <ContextImpl as AbstractContext>::Texture,
+    pub(crate) TextureState,
+);
 pub struct VertexArray(<ContextImpl as AbstractContext>::VertexArray);
 
+// The state of a texture, tracked on the CPU since it cannot be queried on WebGL 1.
+#[derive(Default)]
+pub(crate) struct TextureState {
+    // The size of the level 0 of the texture, if it was uploaded by kiss3d.
+    pub size: Cell<Option<(u32, u32)>>,
+    // Whether the minification filter of the texture uses mipmaps.
+    pub mipmaps: Cell<bool>,
+}
+
 impl Drop for Buffer {
     fn drop(&mut self) {
         unsafe {
@@ -53,6 +66,7 @@ impl Context {
     pub const VERTEX_SHADER: u32 = ContextImpl::VERTEX_SHADER;
     pub const FRAGMENT_SHADER: u32 = ContextImpl::FRAGMENT_SHADER;
     pub const COMPILE_STATUS: u32 = ContextImpl::COMPILE_STATUS;
+    pub const LINK_STATUS: u32 = ContextImpl::LINK_STATUS;
     pub const FRAMEBUFFER: u32 = ContextImpl::FRAMEBUFFER;
     pub const RENDERBUFFER: u32 = ContextImpl::RENDERBUFFER;
     pub const DEPTH_ATTACHMENT: u32 = ContextImpl::DEPTH_ATTACHMENT;
@@ -60,6 +74,9 @@ impl Context {
     pub const TEXTURE_2D: u32 = ContextImpl::TEXTURE_2D;
     pub const DEPTH_COMPONENT: u32 = ContextImpl::DEPTH_COMPONENT;
     pub const DEPTH_COMPONENT16: u32 = ContextImpl::DEPTH_COMPONENT16;
+    pub const DEPTH_COMPONENT24: u32 = ContextImpl::DEPTH_COMPONENT24;
+    pub const RGBA8: u32 = ContextImpl::RGBA8;
+    pub const READ_FRAMEBUFFER: u32 = ContextImpl::READ_FRAMEBUFFER;
     pub const UNSIGNED_BYTE: u32 = ContextImpl::UNSIGNED_BYTE;
     pub const TEXTURE_WRAP_S: u32 = ContextImpl::TEXTURE_WRAP_S;
     pub const TEXTURE_WRAP_T: u32 = ContextImpl::TEXTURE_WRAP_T;
@@ -72,16 +89,19 @@ impl Context {
     pub const RGBA: u32 = ContextImpl::RGBA;
     pub const TEXTURE0: u32 = ContextImpl::TEXTURE0;
     pub const TEXTURE1: u32 = ContextImpl::TEXTURE1;
+    pub const TEXTURE2: u32 = ContextImpl::TEXTURE2;
     pub const REPEAT: u32 = ContextImpl::REPEAT;
     pub const MIRRORED_REPEAT: u32 = ContextImpl::MIRRORED_REPEAT;
     pub const LINEAR_MIPMAP_LINEAR: u32 = ContextImpl::LINEAR_MIPMAP_LINEAR;
     pub const TRIANGLES: u32 = ContextImpl::TRIANGLES;
     pub const CULL_FACE: u32 = ContextImpl::CULL_FACE;
+    pub const POLYGON_OFFSET_FILL: u32 = ContextImpl::POLYGON_OFFSET_FILL;
     pub const FRONT_AND_BACK: u32 = ContextImpl::FRONT_AND_BACK;
     pub const FILL: u32 = ContextImpl::FILL;
     pub const LINE: u32 = ContextImpl::LINE;
     pub const POINT: u32 = ContextImpl::POINT;
     pub const LINES: u32 = ContextImpl::LINES;
+    pub const LINE_STRIP: u32 = ContextImpl::LINE_STRIP;
     pub const POINTS: u32 = ContextImpl::POINTS;
     pub const TRIANGLE_STRIP: u32 = ContextImpl::TRIANGLE_STRIP;
     pub const COLOR_BUFFER_BIT: u32 = ContextImpl::COLOR_BUFFER_BIT;
@@ -99,6 +119,13 @@ impl Context {
     pub const ONE: u32 = ContextImpl::ONE;
     pub const UNPACK_ALIGNMENT: u32 = ContextImpl::UNPACK_ALIGNMENT;
     pub const ALPHA: u32 = ContextImpl::ALPHA;
+    pub const TEXTURE_CUBE_MAP: u32 = ContextImpl::TEXTURE_CUBE_MAP;
+    pub const TEXTURE_CUBE_MAP_POSITIVE_X: u32 = ContextImpl::TEXTURE_CUBE_MAP_POSITIVE_X;
+    pub const TEXTURE_CUBE_MAP_NEGATIVE_X: u32 = ContextImpl::TEXTURE_CUBE_MAP_NEGATIVE_X;
+    pub const TEXTURE_CUBE_MAP_POSITIVE_Y: u32 = ContextImpl::TEXTURE_CUBE_MAP_POSITIVE_Y;
+    pub const TEXTURE_CUBE_MAP_NEGATIVE_Y: u32 = ContextImpl::TEXTURE_CUBE_MAP_NEGATIVE_Y;
+    pub const TEXTURE_CUBE_MAP_POSITIVE_Z: u32 = ContextImpl::TEXTURE_CUBE_MAP_POSITIVE_Z;
+    pub const TEXTURE_CUBE_MAP_NEGATIVE_Z: u32 = ContextImpl::TEXTURE_CUBE_MAP_NEGATIVE_Z;
     pub const RED: u32 = ContextImpl::RED;
 
     pub fn init(get_ctxt: impl Fn() -> glow::Context) {
@@ -273,6 +300,14 @@ impl Context {
         self.ctxt.get_shader_info_log(&shader.0)
     }
 
+    pub fn get_program_link_status(&self, program: &Program) -> bool {
+        self.ctxt.get_program_link_status(&program.0)
+    }
+
+    pub fn get_program_info_log(&self, program: &Program) -> Option<String> {
+        self.ctxt.get_program_info_log(&program.0)
+    }
+
     pub fn vertex_attrib_pointer(
         &self,
         index: u32,
@@ -294,6 +329,14 @@ impl Context {
         self.ctxt.disable_vertex_attrib_array(index)
     }
 
+    pub fn vertex_attrib_divisor(&self, index: u32, divisor: u32) {
+        self.ctxt.vertex_attrib_divisor(index, divisor)
+    }
+
+    pub fn vertex_attrib4f(&self, index: u32, x: f32, y: f32, z: f32, w: f32) {
+        self.ctxt.vertex_attrib4f(index, x, y, z, w)
+    }
+
     pub fn get_attrib_location(&self, program: &Program, name: &str) -> i32 {
         self.ctxt.get_attrib_location(&program.0, name)
     }
@@ -362,6 +405,27 @@ impl Context {
             .renderbuffer_storage(internal_format, width, height)
     }
 
+    pub fn renderbuffer_storage_multisample(
+        &self,
+        samples: i32,
+        internal_format: GLenum,
+        width: i32,
+        height: i32,
+    ) {
+        self.ctxt
+            .renderbuffer_storage_multisample(samples, internal_format, width, height)
+    }
+
+    pub fn blit_framebuffer(
+        &self,
+        src: (i32, i32, i32, i32),
+        dst: (i32, i32, i32, i32),
+        mask: u32,
+        filter: GLenum,
+    ) {
+        self.ctxt.blit_framebuffer(src, dst, mask, filter)
+    }
+
     pub fn framebuffer_renderbuffer(
         &self,
         attachment: GLenum,
@@ -398,6 +462,22 @@ impl Context {
         )
     }
 
+    pub fn tex_sub_image2d(
+        &self,
+        target: GLenum,
+        level: i32,
+        xoffset: i32,
+        yoffset: i32,
+        width: i32,
+        height: i32,
+        format: GLenum,
+        pixels: &[u8],
+    ) {
+        self.ctxt.tex_sub_image2d(
+            target, level, xoffset, yoffset, width, height, format, pixels,
+        )
+    }
+
     pub fn tex_image2di(
         &self,
         target: GLenum,
@@ -441,12 +521,18 @@ impl Context {
         self.ctxt.tex_parameteri(target, pname, param)
     }
 
+    pub fn generate_mipmap(&self, target: GLenum) {
+        self.ctxt.generate_mipmap(target)
+    }
+
     pub fn is_texture(&self, texture: Option<&Texture>) -> bool {
         self.ctxt.is_texture(texture.map(|e| &e.0))
     }
 
     pub fn create_texture(&self) -> Option<Texture> {
-        self.ctxt.create_texture().map(|e| Texture(e))
+        self.ctxt
+            .create_texture()
+            .map(|e| Texture(e, TextureState::default()))
     }
 
     pub fn delete_texture(&self, texture: Option<&Texture>) {
@@ -465,14 +551,42 @@ impl Context {
         self.ctxt.disable(cap)
     }
 
+    pub fn is_enabled(&self, cap: GLenum) -> bool {
+        self.ctxt.is_enabled(cap)
+    }
+
     pub fn draw_elements(&self, mode: GLenum, count: i32, type_: GLenum, offset: GLintptr) {
         self.ctxt.draw_elements(mode, count, type_, offset)
     }
 
+    pub fn draw_elements_instanced(
+        &self,
+        mode: GLenum,
+        count: i32,
+        type_: GLenum,
+        offset: GLintptr,
+        instance_count: i32,
+    ) {
+        self.ctxt
+            .draw_elements_instanced(mode, count, type_, offset, instance_count)
+    }
+
     pub fn draw_arrays(&self, mode: GLenum, first: i32, count: i32) {
         self.ctxt.draw_arrays(mode, first, count)
     }
 
+    pub fn supports_instancing(&self) -> bool {
+        self.ctxt.supports_instancing()
+    }
+
+    pub fn supports_u32_indices(&self) -> bool {
+        self.ctxt.supports_u32_indices()
+    }
+
+    pub fn max_samples(&self) -> u32 {
+        self.ctxt.max_samples()
+    }
+
     pub fn point_size(&self, size: f32) {
         self.ctxt.point_size(size)
     }
@@ -501,10 +615,18 @@ impl Context {
         self.ctxt.depth_func(mode)
     }
 
+    pub fn depth_mask(&self, flag: bool) {
+        self.ctxt.depth_mask(flag)
+    }
+
     pub fn cull_face(&self, mode: GLenum) {
         self.ctxt.cull_face(mode)
     }
 
+    pub fn polygon_offset(&self, factor: f32, units: f32) {
+        self.ctxt.polygon_offset(factor, units)
+    }
+
     pub fn read_pixels(
         &self,
         x: i32,
@@ -517,6 +639,10 @@ impl Context {
         self.ctxt.read_pixels(x, y, width, height, format, pixels)
     }
 
+    pub fn read_depth_pixels(&self, x: i32, y: i32, width: i32, height: i32, pixels: &mut [f32]) {
+        self.ctxt.read_depth_pixels(x, y, width, height, pixels)
+    }
+
     pub fn pixel_storei(&self, pname: GLenum, param: i32) {
         self.ctxt.pixel_storei(pname, param)
     }
@@ -546,6 +672,7 @@ pub(crate) trait AbstractContextConst {
     const VERTEX_SHADER: u32;
     const FRAGMENT_SHADER: u32;
     const COMPILE_STATUS: u32;
+    const LINK_STATUS: u32;
     const FRAMEBUFFER: u32;
     const RENDERBUFFER: u32;
     const DEPTH_ATTACHMENT: u32;
@@ -553,6 +680,9 @@ pub(crate) trait AbstractContextConst {
     const TEXTURE_2D: u32;
     const DEPTH_COMPONENT: u32;
     const DEPTH_COMPONENT16: u32;
+    const DEPTH_COMPONENT24: u32;
+    const RGBA8: u32;
+    const READ_FRAMEBUFFER: u32;
     const UNSIGNED_BYTE: u32;
     const TEXTURE_WRAP_S: u32;
     const TEXTURE_WRAP_T: u32;
@@ -565,16 +695,19 @@ pub(crate) trait AbstractContextConst {
     const RGBA: u32;
     const TEXTURE0: u32;
     const TEXTURE1: u32;
+    const TEXTURE2: u32;
     const REPEAT: u32;
     const MIRRORED_REPEAT: u32;
     const LINEAR_MIPMAP_LINEAR: u32;
     const TRIANGLES: u32;
     const CULL_FACE: u32;
+    const POLYGON_OFFSET_FILL: u32;
     const FRONT_AND_BACK: u32;
     const FILL: u32;
     const LINE: u32;
     const POINT: u32;
     const LINES: u32;
+    const LINE_STRIP: u32;
     const POINTS: u32;
     const TRIANGLE_STRIP: u32;
     const COLOR_BUFFER_BIT: u32;
@@ -592,6 +725,13 @@ pub(crate) trait AbstractContextConst {
     const ONE: u32;
     const UNPACK_ALIGNMENT: u32;
     const ALPHA: u32;
+    const TEXTURE_CUBE_MAP: u32;
+    const TEXTURE_CUBE_MAP_POSITIVE_X: u32;
+    const TEXTURE_CUBE_MAP_NEGATIVE_X: u32;
+    const TEXTURE_CUBE_MAP_POSITIVE_Y: u32;
+    const TEXTURE_CUBE_MAP_NEGATIVE_Y: u32;
+    const TEXTURE_CUBE_MAP_POSITIVE_Z: u32;
+    const TEXTURE_CUBE_MAP_NEGATIVE_Z: u32;
     const RED: u32;
 }
 
@@ -656,6 +796,8 @@ pub(crate) trait AbstractContext {
     fn attach_shader(&self, program: &Self::Program, shader: &Self::Shader);
     fn get_shader_parameter_int(&self, shader: &Self::Shader, pname: GLenum) -> Option<i32>;
     fn get_shader_info_log(&self, shader: &Self::Shader) -> Option<String>;
+    fn get_program_link_status(&self, program: &Self::Program) -> bool;
+    fn get_program_info_log(&self, program: &Self::Program) -> Option<String>;
     fn vertex_attrib_pointer(
         &self,
         index: u32,
@@ -667,6 +809,8 @@ pub(crate) trait AbstractContext {
     );
     fn enable_vertex_attrib_array(&self, index: u32);
     fn disable_vertex_attrib_array(&self, index: u32);
+    fn vertex_attrib_divisor(&self, index: u32, divisor: u32);
+    fn vertex_attrib4f(&self, index: u32, x: f32, y: f32, z: f32, w: f32);
 
     fn get_attrib_location(&self, program: &Self::Program, name: &str) -> i32;
     fn get_uniform_location(
@@ -695,6 +839,20 @@ pub(crate) trait AbstractContext {
     fn delete_renderbuffer(&self, buffer: Option<&Self::Renderbuffer>);
     fn bind_renderbuffer(&self, buffer: Option<&Self::Renderbuffer>);
     fn renderbuffer_storage(&self, internal_format: GLenum, width: i32, height: i32);
+    fn renderbuffer_storage_multisample(
+        &self,
+        samples: i32,
+        internal_format: GLenum,
+        width: i32,
+        height: i32,
+    );
+    fn blit_framebuffer(
+        &self,
+        src: (i32, i32, i32, i32),
+        dst: (i32, i32, i32, i32),
+        mask: u32,
+        filter: GLenum,
+    );
     fn framebuffer_renderbuffer(
         &self,
         attachment: GLenum,
@@ -713,6 +871,17 @@ pub(crate) trait AbstractContext {
         format: GLenum,
         pixels: Option<&[u8]>,
     );
+    fn tex_sub_image2d(
+        &self,
+        target: GLenum,
+        level: i32,
+        xoffset: i32,
+        yoffset: i32,
+        width: i32,
+        height: i32,
+        format: GLenum,
+        pixels: &[u8],
+    );
     fn tex_image2di(
         &self,
         target: GLenum,
@@ -736,6 +905,7 @@ pub(crate) trait AbstractContext {
         pixels: Option<&[u8]>,
     );
     fn tex_parameteri(&self, target: GLenum, pname: GLenum, param: i32);
+    fn generate_mipmap(&self, target: GLenum);
     fn is_texture(&self, texture: Option<&Self::Texture>) -> bool;
     fn create_texture(&self) -> Option<Self::Texture>;
     fn delete_texture(&self, texture: Option<&Self::Texture>);
@@ -743,9 +913,21 @@ pub(crate) trait AbstractContext {
 
     fn enable(&self, cap: GLenum);
     fn disable(&self, cap: GLenum);
+    fn is_enabled(&self, cap: GLenum) -> bool;
 
     fn draw_elements(&self, mode: GLenum, count: i32, type_: GLenum, offset: GLintptr);
+    fn draw_elements_instanced(
+        &self,
+        mode: GLenum,
+        count: i32,
+        type_: GLenum,
+        offset: GLintptr,
+        instance_count: i32,
+    );
     fn draw_arrays(&self, mode: GLenum, first: i32, count: i32);
+    fn supports_instancing(&self) -> bool;
+    fn supports_u32_indices(&self) -> bool;
+    fn max_samples(&self) -> u32;
 
     fn point_size(&self, size: f32);
     fn line_width(&self, size: f32);
@@ -757,7 +939,9 @@ pub(crate) trait AbstractContext {
 
     fn front_face(&self, mode: GLenum);
     fn depth_func(&self, mode: GLenum);
+    fn depth_mask(&self, flag: bool);
     fn cull_face(&self, mode: GLenum);
+    fn polygon_offset(&self, factor: f32, units: f32);
 
     fn read_pixels(
         &self,
@@ -768,6 +952,7 @@ pub(crate) trait AbstractContext {
         format: GLenum,
         pixels: Option<&mut [u8]>,
     );
+    fn read_depth_pixels(&self, x: i32, y: i32, width: i32, height: i32, pixels: &mut [f32]);
     fn pixel_storei(&self, pname: GLenum, param: i32);
 
     fn blend_func_separate(
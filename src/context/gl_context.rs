@@ -39,6 +39,7 @@ impl AbstractContextConst for GLContext {
     const VERTEX_SHADER: u32 = glow::VERTEX_SHADER;
     const FRAGMENT_SHADER: u32 = glow::FRAGMENT_SHADER;
     const COMPILE_STATUS: u32 = glow::COMPILE_STATUS;
+    const LINK_STATUS: u32 = glow::LINK_STATUS;
     const FRAMEBUFFER: u32 = glow::FRAMEBUFFER;
     const RENDERBUFFER: u32 = glow::RENDERBUFFER;
     const DEPTH_ATTACHMENT: u32 = glow::DEPTH_ATTACHMENT;
@@ -46,6 +47,9 @@ impl AbstractContextConst for GLContext {
     const TEXTURE_2D: u32 = glow::TEXTURE_2D;
     const DEPTH_COMPONENT: u32 = glow::DEPTH_COMPONENT;
     const DEPTH_COMPONENT16: u32 = glow::DEPTH_COMPONENT16;
+    const DEPTH_COMPONENT24: u32 = glow::DEPTH_COMPONENT24;
+    const RGBA8: u32 = glow::RGBA8;
+    const READ_FRAMEBUFFER: u32 = glow::READ_FRAMEBUFFER;
     const UNSIGNED_BYTE: u32 = glow::UNSIGNED_BYTE;
     const TEXTURE_WRAP_S: u32 = glow::TEXTURE_WRAP_S;
     const TEXTURE_WRAP_T: u32 = glow::TEXTURE_WRAP_T;
@@ -58,13 +62,16 @@ impl AbstractContextConst for GLContext {
     const RGBA: u32 = glow::RGBA;
     const TEXTURE0: u32 = glow::TEXTURE0;
     const TEXTURE1: u32 = glow::TEXTURE1;
+    const TEXTURE2: u32 = glow::TEXTURE2;
     const REPEAT: u32 = glow::REPEAT;
     const MIRRORED_REPEAT: u32 = glow::MIRRORED_REPEAT;
     const LINEAR_MIPMAP_LINEAR: u32 = glow::LINEAR_MIPMAP_LINEAR;
     const TRIANGLES: u32 = glow::TRIANGLES;
     const CULL_FACE: u32 = glow::CULL_FACE;
+    const POLYGON_OFFSET_FILL: u32 = glow::POLYGON_OFFSET_FILL;
     const FRONT_AND_BACK: u32 = glow::FRONT_AND_BACK;
     const LINES: u32 = glow::LINES;
+    const LINE_STRIP: u32 = glow::LINE_STRIP;
     const POINTS: u32 = glow::POINTS;
     const TRIANGLE_STRIP: u32 = glow::TRIANGLE_STRIP;
     const COLOR_BUFFER_BIT: u32 = glow::COLOR_BUFFER_BIT;
@@ -85,6 +92,13 @@ impl AbstractContextConst for GLContext {
     const ONE: u32 = glow::ONE;
     const UNPACK_ALIGNMENT: u32 = glow::UNPACK_ALIGNMENT;
     const ALPHA: u32 = glow::ALPHA;
+    const TEXTURE_CUBE_MAP: u32 = glow::TEXTURE_CUBE_MAP;
+    const TEXTURE_CUBE_MAP_POSITIVE_X: u32 = glow::TEXTURE_CUBE_MAP_POSITIVE_X;
+    const TEXTURE_CUBE_MAP_NEGATIVE_X: u32 = glow::TEXTURE_CUBE_MAP_NEGATIVE_X;
+    const TEXTURE_CUBE_MAP_POSITIVE_Y: u32 = glow::TEXTURE_CUBE_MAP_POSITIVE_Y;
+    const TEXTURE_CUBE_MAP_NEGATIVE_Y: u32 = glow::TEXTURE_CUBE_MAP_NEGATIVE_Y;
+    const TEXTURE_CUBE_MAP_POSITIVE_Z: u32 = glow::TEXTURE_CUBE_MAP_POSITIVE_Z;
+    const TEXTURE_CUBE_MAP_NEGATIVE_Z: u32 = glow::TEXTURE_CUBE_MAP_NEGATIVE_Z;
     #[cfg(not(target_arch = "wasm32"))]
     const RED: u32 = glow::RED;
     #[cfg(target_arch = "wasm32")]
@@ -307,6 +321,14 @@ impl AbstractContext for GLContext {
         unsafe { Some(self.context.get_shader_info_log(*shader)) }
     }
 
+    fn get_program_link_status(&self, program: &Self::Program) -> bool {
+        unsafe { self.context.get_program_link_status(*program) }
+    }
+
+    fn get_program_info_log(&self, program: &Self::Program) -> Option<String> {
+        unsafe { Some(self.context.get_program_info_log(*program)) }
+    }
+
     fn vertex_attrib_pointer(
         &self,
         index: u32,
@@ -336,6 +358,14 @@ impl AbstractContext for GLContext {
         unsafe { self.context.disable_vertex_attrib_array(index) }
     }
 
+    fn vertex_attrib_divisor(&self, index: u32, divisor: u32) {
+        unsafe { self.context.vertex_attrib_divisor(index, divisor) }
+    }
+
+    fn vertex_attrib4f(&self, index: u32, x: f32, y: f32, z: f32, w: f32) {
+        unsafe { self.context.vertex_attrib_4_f32(index, x, y, z, w) }
+    }
+
     fn get_attrib_location(&self, program: &Self::Program, name: &str) -> i32 {
         unsafe {
             self.context
@@ -425,6 +455,56 @@ impl AbstractContext for GLContext {
         }
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    fn renderbuffer_storage_multisample(
+        &self,
+        samples: i32,
+        internal_format: GLenum,
+        width: i32,
+        height: i32,
+    ) {
+        unsafe {
+            self.context.renderbuffer_storage_multisample(
+                Self::RENDERBUFFER,
+                samples,
+                internal_format,
+                width,
+                height,
+            )
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn renderbuffer_storage_multisample(&self, _: i32, _: GLenum, _: i32, _: i32) {
+        // Not supported by WebGL 1.
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn blit_framebuffer(
+        &self,
+        src: (i32, i32, i32, i32),
+        dst: (i32, i32, i32, i32),
+        mask: u32,
+        filter: GLenum,
+    ) {
+        unsafe {
+            self.context.blit_framebuffer(
+                src.0, src.1, src.2, src.3, dst.0, dst.1, dst.2, dst.3, mask, filter,
+            )
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn blit_framebuffer(
+        &self,
+        _: (i32, i32, i32, i32),
+        _: (i32, i32, i32, i32),
+        _: u32,
+        _: GLenum,
+    ) {
+        // Not supported by WebGL 1.
+    }
+
     fn framebuffer_renderbuffer(
         &self,
         attachment: GLenum,
@@ -470,6 +550,32 @@ impl AbstractContext for GLContext {
         }
     }
 
+    fn tex_sub_image2d(
+        &self,
+        target: GLenum,
+        level: i32,
+        xoffset: i32,
+        yoffset: i32,
+        width: i32,
+        height: i32,
+        format: GLenum,
+        pixels: &[u8],
+    ) {
+        unsafe {
+            self.context.tex_sub_image_2d(
+                target,
+                level,
+                xoffset,
+                yoffset,
+                width,
+                height,
+                format,
+                Self::UNSIGNED_BYTE,
+                glow::PixelUnpackData::Slice(pixels),
+            )
+        }
+    }
+
     fn tex_image2di(
         &self,
         target: GLenum,
@@ -532,6 +638,10 @@ impl AbstractContext for GLContext {
         unsafe { self.context.tex_parameter_i32(target, pname, param) }
     }
 
+    fn generate_mipmap(&self, target: GLenum) {
+        unsafe { self.context.generate_mipmap(target) }
+    }
+
     fn is_texture(&self, texture: Option<&Self::Texture>) -> bool {
         if let Some(t) = texture {
             unsafe { self.context.is_texture(t.clone()) }
@@ -562,6 +672,10 @@ impl AbstractContext for GLContext {
         unsafe { self.context.disable(cap) }
     }
 
+    fn is_enabled(&self, cap: GLenum) -> bool {
+        unsafe { self.context.is_enabled(cap) }
+    }
+
     fn draw_elements(&self, mode: GLenum, count: i32, type_: GLenum, offset: GLintptr) {
         unsafe {
             self.context
@@ -569,10 +683,61 @@ impl AbstractContext for GLContext {
         }
     }
 
+    fn draw_elements_instanced(
+        &self,
+        mode: GLenum,
+        count: i32,
+        type_: GLenum,
+        offset: GLintptr,
+        instance_count: i32,
+    ) {
+        unsafe {
+            self.context
+                .draw_elements_instanced(mode, count, type_, offset as i32, instance_count)
+        }
+    }
+
     fn draw_arrays(&self, mode: GLenum, first: i32, count: i32) {
         unsafe { self.context.draw_arrays(mode, first, count) }
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    fn supports_instancing(&self) -> bool {
+        true
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn supports_instancing(&self) -> bool {
+        // WebGL 1 only supports instancing through an extension.
+        self.context
+            .supported_extensions()
+            .contains("ANGLE_instanced_arrays")
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn supports_u32_indices(&self) -> bool {
+        true
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn supports_u32_indices(&self) -> bool {
+        // WebGL 1 only supports 32-bits indices through an extension, enabled by the canvas.
+        self.context
+            .supported_extensions()
+            .contains("OES_element_index_uint")
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn max_samples(&self) -> u32 {
+        unsafe { self.context.get_parameter_i32(glow::MAX_SAMPLES).max(0) as u32 }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn max_samples(&self) -> u32 {
+        // WebGL 1 has no multisampled renderbuffers.
+        0
+    }
+
     fn point_size(&self, _size: f32) {
         unsafe { self.context.point_size(_size) }
     }
@@ -605,10 +770,18 @@ impl AbstractContext for GLContext {
         unsafe { self.context.depth_func(mode) }
     }
 
+    fn depth_mask(&self, flag: bool) {
+        unsafe { self.context.depth_mask(flag) }
+    }
+
     fn cull_face(&self, mode: GLenum) {
         unsafe { self.context.cull_face(mode) }
     }
 
+    fn polygon_offset(&self, factor: f32, units: f32) {
+        unsafe { self.context.polygon_offset(factor, units) }
+    }
+
     fn read_pixels(
         &self,
         x: i32,
@@ -634,6 +807,24 @@ impl AbstractContext for GLContext {
         }
     }
 
+    fn read_depth_pixels(&self, x: i32, y: i32, width: i32, height: i32, pixels: &mut [f32]) {
+        unsafe {
+            let bytes = std::slice::from_raw_parts_mut(
+                pixels.as_mut_ptr() as *mut u8,
+                pixels.len() * std::mem::size_of::<f32>(),
+            );
+            self.context.read_pixels(
+                x,
+                y,
+                width,
+                height,
+                Self::DEPTH_COMPONENT,
+                Self::FLOAT,
+                glow::PixelPackData::Slice(bytes),
+            );
+        }
+    }
+
     fn pixel_storei(&self, pname: GLenum, param: i32) {
         unsafe { self.context.pixel_store_i32(pname, param) }
     }
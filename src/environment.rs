@@ -0,0 +1,136 @@
+//! Global parameters shared by all the objects of a scene.
+
+use crate::light::Light;
+use crate::resource::ShadowMap;
+use na::{Point3, Vector4};
+use std::rc::Rc;
+
+/// The maximum number of clipping planes taken into account by the built-in materials.
+pub const MAX_CLIP_PLANES: usize = 4;
+
+/// The way the fog density increases with the distance to the camera.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FogMode {
+    /// The fog density increases linearly from `Fog::start` to `Fog::end`.
+    Linear,
+    /// The fog density increases as `1 - exp(-(density * distance)²)`.
+    Exp2,
+}
+
+/// A fog hiding the objects far away from the camera.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fog {
+    /// The fog color.
+    ///
+    /// This should usually be the background color, for a seamless horizon.
+    pub color: Point3<f32>,
+    /// The distance to the camera where the fog starts, for the linear mode.
+    pub start: f32,
+    /// The distance to the camera where the fog becomes opaque, for the linear mode.
+    ///
+    /// If it is not greater than `start`, the fog becomes opaque at `start`.
+    pub end: f32,
+    /// The fog density, for the exponential mode.
+    pub density: f32,
+    /// The way the fog density increases with the distance to the camera.
+    pub mode: FogMode,
+}
+
+/// The environment a scene is rendered in: its lights and global shading parameters.
+///
+/// This is given to every material when an object is rendered.
+#[derive(Clone)]
+pub struct Environment {
+    lights: Vec<Light>,
+    ambient_color: Point3<f32>,
+    shadow_map: Option<Rc<ShadowMap>>,
+    fog: Option<Fog>,
+    clip_planes: [Option<Vector4<f32>>; MAX_CLIP_PLANES],
+}
+
+impl Environment {
+    /// Creates a new environment with the given lights.
+    pub fn new(lights: Vec<Light>) -> Environment {
+        Environment {
+            lights,
+            ambient_color: Point3::new(1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0),
+            shadow_map: None,
+            fog: None,
+            clip_planes: [None; MAX_CLIP_PLANES],
+        }
+    }
+
+    /// The lights of the scene.
+    #[inline]
+    pub fn lights(&self) -> &[Light] {
+        &self.lights[..]
+    }
+
+    /// Mutable reference to the lights of the scene.
+    #[inline]
+    pub fn lights_mut(&mut self) -> &mut Vec<Light> {
+        &mut self.lights
+    }
+
+    /// The ambient light color.
+    ///
+    /// It is multiplied by the object color and added to the contribution of every light.
+    #[inline]
+    pub fn ambient_color(&self) -> &Point3<f32> {
+        &self.ambient_color
+    }
+
+    /// Sets the ambient light color.
+    #[inline]
+    pub fn set_ambient_color(&mut self, color: Point3<f32>) {
+        self.ambient_color = color;
+    }
+
+    /// The shadow map of the first light of the scene, if shadows are enabled.
+    #[inline]
+    pub fn shadow_map(&self) -> Option<&ShadowMap> {
+        self.shadow_map.as_deref()
+    }
+
+    /// Sets the shadow map of the first light of the scene.
+    ///
+    /// The shadow map must be updated before each rendering. Set to `None` to disable shadows.
+    #[inline]
+    pub fn set_shadow_map(&mut self, shadow_map: Option<Rc<ShadowMap>>) {
+        self.shadow_map = shadow_map;
+    }
+
+    /// The fog of the scene.
+    #[inline]
+    pub fn fog(&self) -> Option<&Fog> {
+        self.fog.as_ref()
+    }
+
+    /// Sets the fog of the scene. Set to `None` to disable fog.
+    #[inline]
+    pub fn set_fog(&mut self, fog: Option<Fog>) {
+        self.fog = fog;
+    }
+
+    /// The clipping planes of the scene, `None` for the disabled ones.
+    #[inline]
+    pub fn clip_planes(&self) -> &[Option<Vector4<f32>>] {
+        &self.clip_planes[..]
+    }
+
+    /// Sets the `i`-th clipping plane of the scene. Set to `None` to disable it.
+    ///
+    /// The plane `(a, b, c, d)` keeps the points satisfying `a * x + b * y + c * z + d >= 0`, in
+    /// world coordinates.
+    ///
+    /// # Failures:
+    /// Fails if `i` is not smaller than `MAX_CLIP_PLANES`.
+    #[inline]
+    pub fn set_clip_plane(&mut self, i: usize, plane: Option<Vector4<f32>>) {
+        assert!(
+            i < MAX_CLIP_PLANES,
+            "The clipping plane index must be smaller than MAX_CLIP_PLANES."
+        );
+        self.clip_planes[i] = plane;
+    }
+}
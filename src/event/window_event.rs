@@ -1,6 +1,12 @@
 #![allow(missing_docs)]
 
-#[derive(Copy, Clone, PartialEq, PartialOrd, Debug, Serialize, Deserialize)]
+use std::path::PathBuf;
+
+/// An event received by a window.
+///
+/// This is not `Copy` since the file events own the path of their file: events kept beyond
+/// the iteration over `Window::events` must be cloned.
+#[derive(Clone, PartialEq, PartialOrd, Debug, Serialize, Deserialize)]
 pub enum WindowEvent {
     Pos(i32, i32),
     Size(u32, u32),
@@ -11,12 +17,25 @@ pub enum WindowEvent {
     FramebufferSize(u32, u32),
     MouseButton(MouseButton, Action, Modifiers),
     CursorPos(f64, f64, Modifiers),
+    /// The relative displacement of the mouse, only reported while the cursor is grabbed.
+    MouseMotion(f64, f64),
     CursorEnter(bool),
     Scroll(f64, f64, Modifiers),
     Key(Key, Action, Modifiers),
     Char(char),
     CharModifiers(char, Modifiers),
     Touch(u64, f64, f64, TouchAction, Modifiers),
+    GamepadConnected(usize),
+    GamepadDisconnected(usize),
+    GamepadButton(usize, GamepadButton, Action),
+    GamepadAxis(usize, GamepadAxis, f32),
+    /// A file has been dropped on the window. One event is sent for each file. Not supported on
+    /// web platforms.
+    FileDropped(PathBuf),
+    /// A file is being dragged over the window. Not supported on web platforms.
+    FileHovered(PathBuf),
+    /// The files dragged over the window left it without being dropped.
+    FileHoverCancelled,
 }
 
 impl WindowEvent {
@@ -33,6 +52,7 @@ impl WindowEvent {
         match self {
             WindowEvent::MouseButton(..)
             | WindowEvent::CursorPos(..)
+            | WindowEvent::MouseMotion(..)
             | WindowEvent::CursorEnter(..)
             | WindowEvent::Scroll(..) => true,
             _ => false,
@@ -46,6 +66,27 @@ impl WindowEvent {
             _ => false,
         }
     }
+
+    /// Tests if this event is related to files dragged and dropped on the window.
+    pub fn is_file_event(&self) -> bool {
+        match self {
+            WindowEvent::FileDropped(..)
+            | WindowEvent::FileHovered(..)
+            | WindowEvent::FileHoverCancelled => true,
+            _ => false,
+        }
+    }
+
+    /// Tests if this event is related to a gamepad.
+    pub fn is_gamepad_event(&self) -> bool {
+        match self {
+            WindowEvent::GamepadConnected(..)
+            | WindowEvent::GamepadDisconnected(..)
+            | WindowEvent::GamepadButton(..)
+            | WindowEvent::GamepadAxis(..) => true,
+            _ => false,
+        }
+    }
 }
 
 // NOTE: list of keys inspired from glutin.
@@ -226,6 +267,44 @@ pub enum MouseButton {
     Button8,
 }
 
+// NOTE: list of buttons and axes inspired from gilrs.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    C,
+    Z,
+    LeftTrigger,
+    LeftTrigger2,
+    RightTrigger,
+    RightTrigger2,
+    Select,
+    Start,
+    Mode,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    Unknown,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    LeftZ,
+    RightStickX,
+    RightStickY,
+    RightZ,
+    DPadX,
+    DPadY,
+    Unknown,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
 pub enum Action {
     Release,
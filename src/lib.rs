@@ -155,9 +155,12 @@ pub use crate::renderer::line_renderer;
 #[deprecated(note = "Use the `renderer` module instead.")]
 pub use crate::renderer::point_renderer;
 
+pub mod animation;
 pub mod builtin;
 pub mod camera;
+pub mod colormap;
 pub mod context;
+pub mod environment;
 mod error;
 pub mod event;
 pub mod light;
@@ -165,6 +168,7 @@ pub mod loader;
 pub mod planar_camera;
 pub mod planar_line_renderer;
 pub mod post_processing;
+pub mod procedural;
 pub mod renderer;
 pub mod resource;
 pub mod scene;
@@ -1,12 +1,51 @@
 //! Lights.
 
-use na::Point3;
+use na::{Point3, Vector3};
+
+/// The maximum number of lights taken into account by the built-in materials.
+pub const MAX_LIGHTS: usize = 8;
 
 /// The light configuration.
 #[derive(Clone)]
 pub enum Light {
-    /// A light with an absolute world position.
+    /// A white light with an absolute world position.
     Absolute(Point3<f32>),
+    /// A colored light with an absolute world position.
+    AbsoluteWithColor {
+        /// The light position, in world coordinates.
+        pos: Point3<f32>,
+        /// The light color.
+        color: Point3<f32>,
+    },
     /// A light superimposed with the camera position.
     StickToCamera,
+    /// A light infinitely far away, with parallel rays, like the sun.
+    Directional {
+        /// The direction of the light rays, in world coordinates.
+        direction: Vector3<f32>,
+        /// The light color.
+        color: Point3<f32>,
+    },
+}
+
+impl Light {
+    /// Creates a white light with an absolute world position.
+    pub fn absolute(pos: Point3<f32>) -> Light {
+        Light::Absolute(pos)
+    }
+
+    /// Creates a colored light with an absolute world position.
+    pub fn absolute_with_color(pos: Point3<f32>, color: Point3<f32>) -> Light {
+        Light::AbsoluteWithColor { pos, color }
+    }
+
+    /// The color of this light.
+    ///
+    /// `Light::Absolute` lights and lights superimposed with the camera are always white.
+    pub fn color(&self) -> Point3<f32> {
+        match *self {
+            Light::AbsoluteWithColor { color, .. } | Light::Directional { color, .. } => color,
+            Light::Absolute(_) | Light::StickToCamera => Point3::new(1.0, 1.0, 1.0),
+        }
+    }
 }
@@ -0,0 +1,180 @@
+//! Loading of meshes without blocking the rendering loop.
+//!
+//! This is not available on `wasm32`, where files cannot be read from the file system, nor
+//! parsed on another thread.
+
+use crate::loader::mtl::MtlMaterial;
+use crate::loader::{obj, stl};
+use crate::resource::Mesh;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Result as IoResult};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+/// The meshes of a loaded file, with their names and materials.
+pub type LoadedMeshes = Vec<(String, Mesh, Option<MtlMaterial>)>;
+
+// The number of bytes read at once, between two progress updates.
+const CHUNK_SIZE: usize = 1 << 20;
+
+// The number of bytes of the file read so far, and its total size.
+#[derive(Default)]
+struct Progress {
+    read: AtomicUsize,
+    len: AtomicUsize,
+}
+
+enum State {
+    // Loading on a worker thread.
+    Loading(Receiver<IoResult<LoadedMeshes>>),
+    Ready(IoResult<LoadedMeshes>),
+    Taken,
+}
+
+/// A handle to a file being loaded in the background, created by `load_async`.
+///
+/// The handle must be polled, e.g., once per frame, until the meshes are ready. They can then
+/// be added to the scene, with `SceneNode::add_mesh` for example, which uploads them to the GPU
+/// from the rendering thread.
+pub struct LoadHandle {
+    path: PathBuf,
+    progress: Arc<Progress>,
+    state: State,
+}
+
+/// Starts loading an OBJ or STL file in the background, without blocking the rendering loop.
+///
+/// The format is deduced from the extension of `path`. The meshes are parsed on a worker
+/// thread, so that the window remains responsive while large files load. The materials of an
+/// OBJ file are read from the directory containing it. This is not available on `wasm32`.
+pub fn load_async<P: AsRef<Path>>(path: P) -> LoadHandle {
+    let path = path.as_ref().to_path_buf();
+    let progress = Arc::new(Progress::default());
+
+    let (sender, receiver) = mpsc::channel();
+    let worker_path = path.clone();
+    let worker_progress = progress.clone();
+
+    let _ = thread::spawn(move || {
+        let _ = sender.send(load(&worker_path, &worker_progress));
+    });
+
+    LoadHandle {
+        path,
+        progress,
+        state: State::Loading(receiver),
+    }
+}
+
+impl LoadHandle {
+    /// The path of the file being loaded.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The fraction of the file read so far, in `[0, 1]`.
+    ///
+    /// The meshes are parsed once the whole file is read, so this remains at 1 until they are
+    /// ready.
+    pub fn progress(&self) -> f32 {
+        let len = self.progress.len.load(Ordering::Relaxed);
+
+        if len == 0 {
+            return if self.is_ready() { 1.0 } else { 0.0 };
+        }
+
+        let read = self.progress.read.load(Ordering::Relaxed);
+        (read as f32 / len as f32).min(1.0)
+    }
+
+    /// Checks whether the loading ended, and returns `true` if the result is ready to be taken.
+    pub fn poll(&mut self) -> bool {
+        match self.state {
+            State::Loading(ref receiver) => match receiver.try_recv() {
+                Ok(result) => self.state = State::Ready(result),
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => {
+                    self.state = State::Ready(Err(Error::new(
+                        ErrorKind::Other,
+                        format!("the loading of {:?} failed unexpectedly.", self.path),
+                    )))
+                }
+            },
+            State::Ready(_) | State::Taken => {}
+        }
+
+        self.is_ready()
+    }
+
+    /// Whether the result is ready to be taken, as of the last call to `poll`.
+    pub fn is_ready(&self) -> bool {
+        match self.state {
+            State::Ready(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Takes the loaded meshes, or the loading error, if they are ready.
+    ///
+    /// Returns `None` if the loading has not ended yet, or if the result was already taken.
+    pub fn take(&mut self) -> Option<IoResult<LoadedMeshes>> {
+        let _ = self.poll();
+
+        match std::mem::replace(&mut self.state, State::Taken) {
+            State::Ready(result) => Some(result),
+            State::Taken => None,
+            state => {
+                self.state = state;
+                None
+            }
+        }
+    }
+}
+
+fn load(path: &Path, progress: &Progress) -> IoResult<LoadedMeshes> {
+    let data = read(path, progress)?;
+    let name = path.to_str().unwrap_or_default();
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+
+    match extension.as_ref().map(|e| &e[..]) {
+        Some("obj") => {
+            let data = String::from_utf8(data)
+                .map_err(|_| Error::new(ErrorKind::InvalidData, "OBJ: the file is not UTF-8."))?;
+            let mtl_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+            Ok(obj::parse(&data[..], mtl_dir, name))
+        }
+        Some("stl") => Ok(vec![(name.to_string(), stl::parse(&data[..])?, None)]),
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("unsupported file format: {:?}.", path),
+        )),
+    }
+}
+
+// Reads the whole file by chunks, to report the progress.
+fn read(path: &Path, progress: &Progress) -> IoResult<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len() as usize;
+    let mut data = Vec::with_capacity(len);
+    let mut chunk = vec![0; CHUNK_SIZE];
+
+    progress.len.store(len, Ordering::Relaxed);
+
+    loop {
+        let n = file.read(&mut chunk)?;
+
+        if n == 0 {
+            return Ok(data);
+        }
+
+        data.extend_from_slice(&chunk[..n]);
+        progress.read.store(data.len(), Ordering::Relaxed);
+    }
+}
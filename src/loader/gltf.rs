@@ -0,0 +1,160 @@
+//! Simplistic glTF 2.0 loader.
+//!
+//! Only static geometries are supported: skins, morph targets and animations are ignored.
+
+use crate::resource::Mesh;
+use gltf::buffer;
+use gltf::image::{self as gltf_image, Format};
+use gltf::mesh::Mode;
+use image::{DynamicImage, ImageBuffer};
+use na::{Isometry3, Point2, Point3, Quaternion, Translation3, UnitQuaternion, Vector3};
+use std::path::Path;
+
+/// A primitive of a glTF mesh, together with its material.
+pub struct GltfPrimitive {
+    /// The primitive geometry.
+    pub mesh: Mesh,
+    /// The base color factor of its material.
+    pub base_color: Point3<f32>,
+    /// The index, on `GltfScene::images`, of the base color texture of its material.
+    pub base_color_texture: Option<usize>,
+}
+
+/// A node of a glTF scene.
+pub struct GltfNode {
+    /// The name of this node, if any.
+    pub name: Option<String>,
+    /// The transformation of this node relative to its parent.
+    pub transform: Isometry3<f32>,
+    /// The scale of this node relative to its parent.
+    pub scale: Vector3<f32>,
+    /// The primitives directly attached to this node.
+    pub primitives: Vec<GltfPrimitive>,
+    /// The children of this node.
+    pub children: Vec<GltfNode>,
+}
+
+/// The content of a glTF file.
+pub struct GltfScene {
+    /// The root nodes of the default scene of the file.
+    pub roots: Vec<GltfNode>,
+    /// The images referenced by the file. `None` if the image pixel format is not supported.
+    pub images: Vec<Option<DynamicImage>>,
+}
+
+/// Parses a `.gltf` or `.glb` file.
+///
+/// External buffers and images are resolved relative to the file location.
+pub fn parse_file(path: &Path) -> gltf::Result<GltfScene> {
+    let (document, buffers, images) = gltf::import(path)?;
+    Ok(build_scene(&document, &buffers, images))
+}
+
+/// Parses the content of a `.gltf` or `.glb` file.
+///
+/// All the buffers and images must be embedded into the file.
+pub fn parse(data: &[u8]) -> gltf::Result<GltfScene> {
+    let (document, buffers, images) = gltf::import_slice(data)?;
+    Ok(build_scene(&document, &buffers, images))
+}
+
+fn build_scene(
+    document: &gltf::Document,
+    buffers: &[buffer::Data],
+    images: Vec<gltf_image::Data>,
+) -> GltfScene {
+    let roots = match document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+    {
+        Some(scene) => scene.nodes().map(|n| build_node(&n, buffers)).collect(),
+        None => Vec::new(),
+    };
+
+    GltfScene {
+        roots,
+        images: images.into_iter().map(convert_image).collect(),
+    }
+}
+
+fn build_node(node: &gltf::Node, buffers: &[buffer::Data]) -> GltfNode {
+    let (t, r, s) = node.transform().decomposed();
+    let rotation = UnitQuaternion::from_quaternion(Quaternion::new(r[3], r[0], r[1], r[2]));
+    let transform = Isometry3::from_parts(Translation3::new(t[0], t[1], t[2]), rotation);
+
+    let mut primitives = Vec::new();
+
+    if let Some(mesh) = node.mesh() {
+        for primitive in mesh.primitives() {
+            if let Some(primitive) = build_primitive(&primitive, buffers) {
+                primitives.push(primitive);
+            }
+        }
+    }
+
+    GltfNode {
+        name: node.name().map(|n| n.to_string()),
+        transform,
+        scale: Vector3::new(s[0], s[1], s[2]),
+        primitives,
+        children: node.children().map(|c| build_node(&c, buffers)).collect(),
+    }
+}
+
+fn build_primitive(primitive: &gltf::Primitive, buffers: &[buffer::Data]) -> Option<GltfPrimitive> {
+    if primitive.mode() != Mode::Triangles {
+        println!(
+            "glTF: ignoring primitive with unsupported mode {:?}.",
+            primitive.mode()
+        );
+        return None;
+    }
+
+    let reader = primitive.reader(|b| Some(&buffers[b.index()]));
+    let coords: Vec<Point3<f32>> = reader.read_positions()?.map(Point3::from).collect();
+
+    let normals = reader
+        .read_normals()
+        .map(|ns| ns.map(Vector3::from).collect());
+    let uvs = reader
+        .read_tex_coords(0)
+        .map(|uvs| uvs.into_f32().map(Point2::from).collect());
+    let indices: Vec<u32> = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect(),
+        None => (0..coords.len() as u32).collect(),
+    };
+    let faces = indices
+        .chunks_exact(3)
+        .map(|f| Point3::new(f[0], f[1], f[2]))
+        .collect();
+
+    let pbr = primitive.material().pbr_metallic_roughness();
+    let color = pbr.base_color_factor();
+
+    Some(GltfPrimitive {
+        mesh: Mesh::new_with_smallest_indices(coords, faces, normals, uvs, false),
+        base_color: Point3::new(color[0], color[1], color[2]),
+        base_color_texture: pbr
+            .base_color_texture()
+            .map(|info| info.texture().source().index()),
+    })
+}
+
+fn convert_image(data: gltf_image::Data) -> Option<DynamicImage> {
+    let (w, h, pixels) = (data.width, data.height, data.pixels);
+
+    let image = match data.format {
+        Format::R8 => DynamicImage::ImageLuma8(ImageBuffer::from_raw(w, h, pixels)?),
+        Format::R8G8 => DynamicImage::ImageLumaA8(ImageBuffer::from_raw(w, h, pixels)?),
+        Format::R8G8B8 => DynamicImage::ImageRgb8(ImageBuffer::from_raw(w, h, pixels)?),
+        Format::R8G8B8A8 => DynamicImage::ImageRgba8(ImageBuffer::from_raw(w, h, pixels)?),
+        Format::B8G8R8 => DynamicImage::ImageBgr8(ImageBuffer::from_raw(w, h, pixels)?),
+        Format::B8G8R8A8 => DynamicImage::ImageBgra8(ImageBuffer::from_raw(w, h, pixels)?),
+        format => {
+            println!("glTF: ignoring image with unsupported format {:?}.", format);
+            return None;
+        }
+    };
+
+    Some(image)
+}
@@ -1,4 +1,14 @@
 //! File loading.
 
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::async_loader::{load_async, LoadHandle, LoadedMeshes};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod async_loader;
+#[cfg(feature = "gltf")]
+pub mod gltf;
 pub mod mtl;
 pub mod obj;
+pub mod pcd;
+pub mod ply;
+pub mod stl;
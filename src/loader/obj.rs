@@ -57,12 +57,32 @@ pub fn parse_file(
     path: &Path,
     mtl_base_dir: &Path,
     basename: &str,
+) -> IoResult<Vec<(String, Mesh, Option<MtlMaterial>)>> {
+    do_parse_file(path, mtl_base_dir, basename, false)
+}
+
+/// Parses an obj file, keeping its objects and groups apart.
+///
+/// See `parse_grouped` for details.
+pub fn parse_file_grouped(
+    path: &Path,
+    mtl_base_dir: &Path,
+    basename: &str,
+) -> IoResult<Vec<(String, Mesh, Option<MtlMaterial>)>> {
+    do_parse_file(path, mtl_base_dir, basename, true)
+}
+
+fn do_parse_file(
+    path: &Path,
+    mtl_base_dir: &Path,
+    basename: &str,
+    grouped: bool,
 ) -> IoResult<Vec<(String, Mesh, Option<MtlMaterial>)>> {
     match File::open(path) {
         Ok(mut file) => {
             let mut sfile = String::new();
             file.read_to_string(&mut sfile)
-                .map(|_| parse(&sfile[..], mtl_base_dir, basename))
+                .map(|_| do_parse(&sfile[..], mtl_base_dir, basename, grouped))
         }
         Err(e) => Err(e),
     }
@@ -73,12 +93,34 @@ pub fn parse(
     string: &str,
     mtl_base_dir: &Path,
     basename: &str,
+) -> Vec<(String, Mesh, Option<MtlMaterial>)> {
+    do_parse(string, mtl_base_dir, basename, false)
+}
+
+/// Parses a string representing an obj file, keeping its objects and groups apart.
+///
+/// Unlike `parse`, each object (`o`) starts a new group like a group (`g`) does, and the faces
+/// of a group using several materials are split into one subgroup per material, named
+/// `group/material`.
+pub fn parse_grouped(
+    string: &str,
+    mtl_base_dir: &Path,
+    basename: &str,
+) -> Vec<(String, Mesh, Option<MtlMaterial>)> {
+    do_parse(string, mtl_base_dir, basename, true)
+}
+
+fn do_parse(
+    string: &str,
+    mtl_base_dir: &Path,
+    basename: &str,
+    grouped: bool,
 ) -> Vec<(String, Mesh, Option<MtlMaterial>)> {
     let mut coords: Vec<Coord> = Vec::new();
     let mut normals: Vec<Normal> = Vec::new();
     let mut uvs: Vec<UV> = Vec::new();
     let mut groups: HashMap<String, usize> = HashMap::new();
-    let mut groups_ids: Vec<Vec<Point3<u16>>> = Vec::new();
+    let mut groups_ids: Vec<Vec<Point3<u32>>> = Vec::new();
     let mut curr_group: usize = 0;
     let mut ignore_normals = false;
     let mut ignore_uvs = false;
@@ -119,7 +161,8 @@ pub fn parse(
                                 uvs.push(parse_vt(l, words))
                             }
                         }
-                        "g" => {
+                        // Objects are only handled as groups when grouping.
+                        "o" | "g" if grouped || w == "g" => {
                             curr_group = parse_g(l, words, basename, &mut groups, &mut groups_ids);
                             let _ = curr_mtl
                                 .as_ref()
@@ -136,6 +179,7 @@ pub fn parse(
                                 &mut groups,
                                 &mut groups_ids,
                                 &mut curr_mtl,
+                                grouped,
                             )
                         }
                         _ => {
@@ -172,8 +216,9 @@ fn parse_usemtl<'a>(
     mtllib: &HashMap<String, MtlMaterial>,
     group2mtl: &mut HashMap<usize, MtlMaterial>,
     groups: &mut HashMap<String, usize>,
-    groups_ids: &mut Vec<Vec<Point3<u16>>>,
+    groups_ids: &mut Vec<Vec<Point3<u32>>>,
     curr_mtl: &mut Option<MtlMaterial>,
+    grouped: bool,
 ) -> usize {
     let mname: Vec<&'a str> = ws.collect();
     let mname = mname.join(" ");
@@ -194,16 +239,28 @@ fn parse_usemtl<'a>(
                 } else {
                     // multiple usemtls for one group
                     // NOTE: this is a violation of the obj specification, but we support it anyway
-                    let mut g = curr_group.to_string();
-                    g.push_str(&mname[..]);
-
-                    let new_group = parse_g(
-                        l,
-                        split_words(&g[..]),
-                        "auto_generated_group_",
-                        groups,
-                        groups_ids,
-                    );
+                    let new_group = if grouped {
+                        // The faces using this material are moved to the subgroup
+                        // `group/material`.
+                        let group_name = groups
+                            .iter()
+                            .find(|g| *g.1 == curr_group)
+                            .map(|g| g.0.clone())
+                            .unwrap_or_else(|| "auto_generated_group_".to_string());
+
+                        parse_g(l, split_words(&mname[..]), &group_name, groups, groups_ids)
+                    } else {
+                        let mut g = curr_group.to_string();
+                        g.push_str(&mname[..]);
+
+                        parse_g(
+                            l,
+                            split_words(&g[..]),
+                            "auto_generated_group_",
+                            groups,
+                            groups_ids,
+                        )
+                    };
 
                     let _ = group2mtl.insert(new_group, m.clone());
                     *curr_mtl = Some(m.clone());
@@ -276,7 +333,7 @@ fn parse_f<'a>(
     normals: &[Vector3<f32>],
     ignore_uvs: &mut bool,
     ignore_normals: &mut bool,
-    groups_ids: &mut Vec<Vec<Point3<u16>>>,
+    groups_ids: &mut Vec<Vec<Point3<u32>>>,
     curr_group: usize,
 ) {
     // Four formats possible: v   v/t   v//n   v/t/n
@@ -335,7 +392,7 @@ fn parse_f<'a>(
         }
 
         assert!(x >= 0 && y >= 0 && z >= 0);
-        groups_ids[curr_group].push(Point3::new(x as u16, y as u16, z as u16));
+        groups_ids[curr_group].push(Point3::new(x as u32, y as u32, z as u32));
 
         i = i + 1;
     }
@@ -377,7 +434,7 @@ fn parse_g<'a>(
     ws: Words<'a>,
     prefix: &str,
     groups: &mut HashMap<String, usize>,
-    groups_ids: &mut Vec<Vec<Point3<u16>>>,
+    groups_ids: &mut Vec<Vec<Point3<u32>>>,
 ) -> usize {
     let suffix: Vec<&'a str> = ws.collect();
     let suffix = suffix.join(" ");
@@ -402,20 +459,24 @@ fn reformat(
     coords: Vec<Coord>,
     normals: Option<Vec<Normal>>,
     uvs: Option<Vec<UV>>,
-    groups_ids: Vec<Vec<Point3<u16>>>,
+    groups_ids: Vec<Vec<Point3<u32>>>,
     groups: HashMap<String, usize>,
     group2mtl: HashMap<usize, MtlMaterial>,
 ) -> Vec<(String, Mesh, Option<MtlMaterial>)> {
-    let mut vt2id: HashMap<Point3<u16>, u16> = HashMap::new();
-    let mut vertex_ids: Vec<u16> = Vec::new();
+    let mut vt2id: HashMap<Point3<u32>, u32> = HashMap::new();
+    let mut vertex_ids: Vec<u32> = Vec::new();
     let mut resc: Vec<Coord> = Vec::new();
     let mut resn: Option<Vec<Normal>> = normals.as_ref().map(|_| Vec::new());
     let mut resu: Option<Vec<UV>> = uvs.as_ref().map(|_| Vec::new());
-    let mut resfs: Vec<Vec<Point3<u16>>> = Vec::new();
-    let mut allfs: Vec<Point3<u16>> = Vec::new();
+    let mut resfs: Vec<Vec<Point3<u32>>> = Vec::new();
+    let mut allfs: Vec<Point3<u32>> = Vec::new();
     let mut names: Vec<String> = Vec::new();
     let mut mtls: Vec<Option<MtlMaterial>> = Vec::new();
 
+    // The groups are output in the order of their first appearance in the file.
+    let mut groups: Vec<(String, usize)> = groups.into_iter().collect();
+    groups.sort_by_key(|g| g.1);
+
     for (name, i) in groups.into_iter() {
         names.push(name);
         mtls.push(group2mtl.get(&i).map(|m| m.clone()));
@@ -427,7 +488,7 @@ fn reformat(
                     None
                 }
                 None => {
-                    let idx = resc.len() as u16;
+                    let idx = resc.len() as u32;
 
                     resc.push(coords[point.x as usize]);
 
@@ -472,6 +533,8 @@ fn reformat(
         BufferType::Array,
         AllocationType::StaticDraw,
     )));
+    // 32-bits indices are only used when needed, since they may not be supported.
+    let u32_indices = resc.len() > u16::max_value() as usize + 1;
     let resc = Arc::new(RwLock::new(GPUVec::new(
         resc,
         BufferType::Array,
@@ -485,12 +548,25 @@ fn reformat(
         .zip(mtls.into_iter())
     {
         if fs.len() != 0 {
-            let fs = Arc::new(RwLock::new(GPUVec::new(
-                fs,
-                BufferType::ElementArray,
-                AllocationType::StaticDraw,
-            )));
-            let mesh = Mesh::new_with_gpu_vectors(resc.clone(), fs, resn.clone(), resu.clone());
+            let mesh = if u32_indices {
+                let fs = Arc::new(RwLock::new(GPUVec::new(
+                    fs,
+                    BufferType::ElementArray,
+                    AllocationType::StaticDraw,
+                )));
+                Mesh::new_with_u32_gpu_vectors(resc.clone(), fs, resn.clone(), resu.clone())
+            } else {
+                let fs = fs
+                    .into_iter()
+                    .map(|f| Point3::new(f.x as u16, f.y as u16, f.z as u16))
+                    .collect();
+                let fs = Arc::new(RwLock::new(GPUVec::new(
+                    fs,
+                    BufferType::ElementArray,
+                    AllocationType::StaticDraw,
+                )));
+                Mesh::new_with_gpu_vectors(resc.clone(), fs, resn.clone(), resu.clone())
+            };
             meshes.push((name, mesh, mtl))
         }
     }
@@ -0,0 +1,355 @@
+//! Simplistic PCD (Point Cloud Library) loader, supporting ASCII and binary files.
+//!
+//! Only the `x y z`, `rgb` (or `rgba`), and `intensity` fields are read. Other fields are
+//! skipped. Compressed files (`DATA binary_compressed`) are not supported.
+
+use na::Point3;
+use std::fs::File;
+use std::io::Read;
+use std::io::{Error, ErrorKind, Result as IoResult};
+use std::path::Path;
+use std::str::SplitWhitespace;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    fn new(ty: &str, size: usize) -> IoResult<ScalarType> {
+        match (ty, size) {
+            ("I", 1) => Ok(ScalarType::I8),
+            ("U", 1) => Ok(ScalarType::U8),
+            ("I", 2) => Ok(ScalarType::I16),
+            ("U", 2) => Ok(ScalarType::U16),
+            ("I", 4) => Ok(ScalarType::I32),
+            ("U", 4) => Ok(ScalarType::U32),
+            ("F", 4) => Ok(ScalarType::F32),
+            ("F", 8) => Ok(ScalarType::F64),
+            _ => Err(invalid_data(&format!(
+                "unknown field type `{}` of size {}.",
+                ty, size
+            ))),
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            ScalarType::I8 | ScalarType::U8 => 1,
+            ScalarType::I16 | ScalarType::U16 => 2,
+            ScalarType::I32 | ScalarType::U32 | ScalarType::F32 => 4,
+            ScalarType::F64 => 8,
+        }
+    }
+}
+
+struct Field {
+    name: String,
+    ty: ScalarType,
+    count: usize,
+}
+
+fn invalid_data(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("PCD: {}", msg))
+}
+
+/// Loads a PCD file, as a list of points with their colors.
+///
+/// The colors have components in `[0, 1]`. They are read from the `rgb` or `rgba` field if
+/// present. Otherwise, the `intensity` field, if present, is mapped to a gray level, from black
+/// for the smallest intensity of the cloud to white for the largest one. Otherwise, the points
+/// are white. Points with non-finite coordinates, as found in organized clouds, are skipped.
+/// The result can be drawn with `Window::draw_point`, for example.
+pub fn load(path: &Path) -> IoResult<Vec<(Point3<f32>, Point3<f32>)>> {
+    let mut data = Vec::new();
+    let _ = File::open(path)?.read_to_end(&mut data)?;
+    parse(&data[..])
+}
+
+/// Parses the content of a PCD file, as a list of points with their colors.
+///
+/// See `load` for details.
+pub fn parse(data: &[u8]) -> IoResult<Vec<(Point3<f32>, Point3<f32>)>> {
+    let (binary, fields, npoints, body) = parse_header(data)?;
+    // Each point takes at least one byte, which bounds the allocation for invalid headers.
+    let capacity = npoints.min(body.len());
+    let mut reader = if binary {
+        Reader::Binary(body)
+    } else {
+        let body =
+            std::str::from_utf8(body).map_err(|_| invalid_data("the file is not valid UTF-8."))?;
+        Reader::Ascii(body.split_whitespace())
+    };
+
+    let has_field = |name: &str| fields.iter().any(|f| f.name == name);
+    let has_rgb = has_field("rgb") || has_field("rgba");
+    let has_intensity = has_field("intensity");
+
+    let mut points = Vec::with_capacity(capacity);
+    let mut intensities = Vec::new();
+
+    for _ in 0..npoints {
+        let mut pt = Point3::origin();
+        let mut color = Point3::new(1.0, 1.0, 1.0);
+        let mut intensity = 0.0;
+
+        for field in fields.iter() {
+            for i in 0..field.count {
+                if i == 0 && (field.name == "rgb" || field.name == "rgba") {
+                    // The color is packed as `0xAARRGGBB`, often stored in a float.
+                    let packed = reader.read_packed(field.ty)?;
+
+                    color = Point3::new(
+                        ((packed >> 16) & 0xff) as f32 / 255.0,
+                        ((packed >> 8) & 0xff) as f32 / 255.0,
+                        (packed & 0xff) as f32 / 255.0,
+                    );
+                    continue;
+                }
+
+                let val = reader.read(field.ty)?;
+
+                if i != 0 {
+                    continue;
+                }
+
+                match &field.name[..] {
+                    "x" => pt.x = val as f32,
+                    "y" => pt.y = val as f32,
+                    "z" => pt.z = val as f32,
+                    "intensity" => intensity = val as f32,
+                    _ => {}
+                }
+            }
+        }
+
+        if pt.iter().all(|c| c.is_finite()) {
+            points.push((pt, color));
+            intensities.push(intensity);
+        }
+    }
+
+    if !has_rgb && has_intensity {
+        let finite = intensities.iter().cloned().filter(|i| i.is_finite());
+        let min = finite.clone().fold(f32::MAX, f32::min);
+        let max = finite.fold(-f32::MAX, f32::max);
+        let range = if max > min { max - min } else { 1.0 };
+
+        for (pt, intensity) in points.iter_mut().zip(intensities.into_iter()) {
+            let gray = ((intensity - min) / range).max(0.0).min(1.0);
+            pt.1 = Point3::new(gray, gray, gray);
+        }
+    }
+
+    Ok(points)
+}
+
+// The format of the body (`true` if binary), the fields, the number of points, and the body.
+fn parse_header(data: &[u8]) -> IoResult<(bool, Vec<Field>, usize, &[u8])> {
+    let mut names = Vec::new();
+    let mut sizes = Vec::new();
+    let mut types = Vec::new();
+    let mut counts = Vec::new();
+    let mut npoints = None;
+    let mut rest = data;
+
+    loop {
+        let end = rest
+            .iter()
+            .position(|c| *c == b'\n')
+            .ok_or_else(|| invalid_data("missing `DATA`."))?;
+        let line = std::str::from_utf8(&rest[..end])
+            .map_err(|_| invalid_data("the header is not valid UTF-8."))?;
+        rest = &rest[end + 1..];
+
+        let mut words = line.split_whitespace();
+        let keyword = match words.next() {
+            Some(keyword) if !keyword.starts_with('#') => keyword,
+            _ => continue,
+        };
+        let values: Vec<&str> = words.collect();
+        let parse_all = |values: &[&str]| -> IoResult<Vec<usize>> {
+            values
+                .iter()
+                .map(|v| {
+                    v.parse()
+                        .map_err(|_| invalid_data("invalid field size or count."))
+                })
+                .collect()
+        };
+
+        match keyword {
+            "FIELDS" => names = values.iter().map(|v| v.to_string()).collect(),
+            "SIZE" => sizes = parse_all(&values)?,
+            "TYPE" => types = values.iter().map(|v| v.to_string()).collect(),
+            "COUNT" => counts = parse_all(&values)?,
+            "POINTS" => {
+                npoints = Some(
+                    values
+                        .first()
+                        .and_then(|v| v.parse().ok())
+                        .ok_or_else(|| invalid_data("invalid number of points."))?,
+                )
+            }
+            "DATA" => {
+                let binary = match values.first() {
+                    Some(&"ascii") => false,
+                    Some(&"binary") => true,
+                    Some(&"binary_compressed") => {
+                        return Err(invalid_data("compressed files are not supported."))
+                    }
+                    _ => return Err(invalid_data("unknown data format.")),
+                };
+
+                if sizes.len() != names.len() || types.len() != names.len() {
+                    return Err(invalid_data("inconsistent field declarations."));
+                }
+
+                // `COUNT` is optional, and defaults to 1 for every field.
+                if counts.is_empty() {
+                    counts = vec![1; names.len()];
+                } else if counts.len() != names.len() {
+                    return Err(invalid_data("inconsistent field declarations."));
+                }
+
+                let mut fields = Vec::with_capacity(names.len());
+
+                for (((name, size), ty), count) in names
+                    .into_iter()
+                    .zip(sizes.into_iter())
+                    .zip(types.iter())
+                    .zip(counts.into_iter())
+                {
+                    fields.push(Field {
+                        name,
+                        ty: ScalarType::new(ty, size)?,
+                        count,
+                    });
+                }
+
+                let npoints = npoints.ok_or_else(|| invalid_data("missing `POINTS`."))?;
+
+                return Ok((binary, fields, npoints, rest));
+            }
+            _ => {}
+        }
+    }
+}
+
+enum Reader<'a> {
+    Ascii(SplitWhitespace<'a>),
+    // Binary files are always little endian.
+    Binary(&'a [u8]),
+}
+
+impl<'a> Reader<'a> {
+    fn read(&mut self, ty: ScalarType) -> IoResult<f64> {
+        match self {
+            Reader::Ascii(words) => {
+                let word = words
+                    .next()
+                    .ok_or_else(|| invalid_data("unexpected end of file."))?;
+
+                // Non-finite coordinates are written as `nan`, which `f64` does not parse.
+                if word.eq_ignore_ascii_case("nan") {
+                    return Ok(f64::NAN);
+                }
+
+                word.parse()
+                    .map_err(|_| invalid_data(&format!("invalid value `{}`.", word)))
+            }
+            Reader::Binary(data) => {
+                let size = ty.size();
+
+                if data.len() < size {
+                    return Err(invalid_data("unexpected end of file."));
+                }
+
+                let mut bytes = [0u8; 8];
+                bytes[..size].copy_from_slice(&data[..size]);
+                *data = &data[size..];
+
+                let val = match ty {
+                    ScalarType::I8 => bytes[0] as i8 as f64,
+                    ScalarType::U8 => bytes[0] as f64,
+                    ScalarType::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+                    ScalarType::U16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+                    ScalarType::I32 => i32::from_le_bytes(read4(&bytes)) as f64,
+                    ScalarType::U32 => u32::from_le_bytes(read4(&bytes)) as f64,
+                    ScalarType::F32 => f32::from_le_bytes(read4(&bytes)) as f64,
+                    ScalarType::F64 => f64::from_le_bytes(bytes),
+                };
+
+                Ok(val)
+            }
+        }
+    }
+
+    // Reads a packed color. The 4-bytes binary values are read as raw bits: a packed color is
+    // often a signaling NaN when read as a float, which converting to `f64` would modify.
+    fn read_packed(&mut self, ty: ScalarType) -> IoResult<u32> {
+        match self {
+            Reader::Binary(data) if ty.size() == 4 => {
+                if data.len() < 4 {
+                    return Err(invalid_data("unexpected end of file."));
+                }
+
+                let packed = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+                *data = &data[4..];
+
+                Ok(packed)
+            }
+            _ => {
+                let val = self.read(ty)?;
+
+                if ty == ScalarType::F32 {
+                    Ok((val as f32).to_bits())
+                } else {
+                    Ok(val as u32)
+                }
+            }
+        }
+    }
+}
+
+fn read4(bytes: &[u8; 8]) -> [u8; 4] {
+    [bytes[0], bytes[1], bytes[2], bytes[3]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_packed_colors_are_read_exactly() {
+        // With a red of 0x90, the packed color is a signaling NaN when read as a float.
+        let header = "VERSION .7\nFIELDS x y z rgb\nSIZE 4 4 4 4\nTYPE F F F F\nCOUNT 1 1 1 1\n\
+                      WIDTH 1\nHEIGHT 1\nPOINTS 1\nDATA binary\n";
+        let mut data = header.as_bytes().to_vec();
+
+        for c in [1.0f32, 2.0, 3.0].iter() {
+            data.extend_from_slice(&c.to_le_bytes());
+        }
+        data.extend_from_slice(&0xff90_4020u32.to_le_bytes());
+
+        let points = parse(&data[..]).unwrap();
+
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].0, Point3::new(1.0, 2.0, 3.0));
+        assert_eq!(
+            points[0].1,
+            Point3::new(
+                0x90 as f32 / 255.0,
+                0x40 as f32 / 255.0,
+                0x20 as f32 / 255.0
+            )
+        );
+    }
+}
@@ -0,0 +1,368 @@
+//! Simplistic PLY loader, supporting ASCII and binary (little and big endian) files.
+//!
+//! Only the `x y z`, `nx ny nz`, and `red green blue` properties of the `vertex` element, and
+//! the `vertex_indices` property of the `face` element are read. Other elements and
+//! properties are skipped.
+
+use crate::resource::Mesh;
+use na::{Point3, Vector3};
+use std::fs::File;
+use std::io::Read;
+use std::io::{Error, ErrorKind, Result as IoResult};
+use std::path::Path;
+use std::str::SplitWhitespace;
+
+/// The content of a PLY file.
+pub enum PlyData {
+    /// A set of points, read from a file without `face` element.
+    PointCloud {
+        /// The point coordinates.
+        points: Vec<Point3<f32>>,
+        /// The point normals, if present in the file.
+        normals: Option<Vec<Vector3<f32>>>,
+        /// The point colors, with components in `[0, 1]`, if present in the file.
+        colors: Option<Vec<Point3<f32>>>,
+    },
+    /// A triangle mesh, read from a file with a `face` element.
+    ///
+    /// Polygonal faces are triangulated as fans. If the file has no normals, smooth normals
+    /// are computed.
+    Mesh {
+        /// The mesh.
+        mesh: Mesh,
+        /// The vertex colors, with components in `[0, 1]`, if present in the file. They are
+        /// also set as the colors of the mesh.
+        colors: Option<Vec<Point3<f32>>>,
+    },
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    fn from_name(name: &str) -> IoResult<ScalarType> {
+        match name {
+            "char" | "int8" => Ok(ScalarType::I8),
+            "uchar" | "uint8" => Ok(ScalarType::U8),
+            "short" | "int16" => Ok(ScalarType::I16),
+            "ushort" | "uint16" => Ok(ScalarType::U16),
+            "int" | "int32" => Ok(ScalarType::I32),
+            "uint" | "uint32" => Ok(ScalarType::U32),
+            "float" | "float32" => Ok(ScalarType::F32),
+            "double" | "float64" => Ok(ScalarType::F64),
+            _ => Err(invalid_data(&format!("unknown property type `{}`.", name))),
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            ScalarType::I8 | ScalarType::U8 => 1,
+            ScalarType::I16 | ScalarType::U16 => 2,
+            ScalarType::I32 | ScalarType::U32 | ScalarType::F32 => 4,
+            ScalarType::F64 => 8,
+        }
+    }
+
+    // The value a color component of this type must be divided by to be in `[0, 1]`.
+    fn color_scale(self) -> f64 {
+        match self {
+            ScalarType::I8 => i8::max_value() as f64,
+            ScalarType::U8 => u8::max_value() as f64,
+            ScalarType::I16 => i16::max_value() as f64,
+            ScalarType::U16 => u16::max_value() as f64,
+            ScalarType::I32 => i32::max_value() as f64,
+            ScalarType::U32 => u32::max_value() as f64,
+            ScalarType::F32 | ScalarType::F64 => 1.0,
+        }
+    }
+}
+
+enum PropertyType {
+    Scalar(ScalarType),
+    List(ScalarType, ScalarType),
+}
+
+struct Property {
+    name: String,
+    ty: PropertyType,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+fn invalid_data(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("PLY: {}", msg))
+}
+
+/// Loads a PLY file.
+pub fn load(path: &Path) -> IoResult<PlyData> {
+    let mut data = Vec::new();
+    let _ = File::open(path)?.read_to_end(&mut data)?;
+    parse(&data[..])
+}
+
+/// Parses the content of a PLY file.
+pub fn parse(data: &[u8]) -> IoResult<PlyData> {
+    let (format, elements, body) = parse_header(data)?;
+    let mut reader = match format {
+        Format::Ascii => {
+            let body = std::str::from_utf8(body)
+                .map_err(|_| invalid_data("the file is not valid UTF-8."))?;
+            Reader::Ascii(body.split_whitespace())
+        }
+        _ => Reader::Binary {
+            data: body,
+            big_endian: format == Format::BinaryBigEndian,
+        },
+    };
+
+    let mut points = Vec::new();
+    let mut normals = Vec::new();
+    let mut colors = Vec::new();
+    let mut faces = Vec::new();
+    let mut has_faces = false;
+
+    for element in elements.iter() {
+        let is_vertex = element.name == "vertex";
+        let is_face = element.name == "face";
+        has_faces = has_faces || is_face;
+
+        for _ in 0..element.count {
+            let mut pt = Point3::origin();
+            let mut normal = Vector3::zeros();
+            let mut color = Point3::origin();
+
+            for property in element.properties.iter() {
+                match property.ty {
+                    PropertyType::Scalar(ty) => {
+                        let val = reader.read(ty)?;
+
+                        if is_vertex {
+                            match &property.name[..] {
+                                "x" => pt.x = val as f32,
+                                "y" => pt.y = val as f32,
+                                "z" => pt.z = val as f32,
+                                "nx" => normal.x = val as f32,
+                                "ny" => normal.y = val as f32,
+                                "nz" => normal.z = val as f32,
+                                "red" => color.x = (val / ty.color_scale()) as f32,
+                                "green" => color.y = (val / ty.color_scale()) as f32,
+                                "blue" => color.z = (val / ty.color_scale()) as f32,
+                                _ => {}
+                            }
+                        }
+                    }
+                    PropertyType::List(len_ty, ty) => {
+                        // The length is not preallocated since it is read from the file.
+                        let len = reader.read(len_ty)? as usize;
+                        let mut ids = Vec::new();
+
+                        for _ in 0..len {
+                            ids.push(reader.read(ty)? as usize);
+                        }
+
+                        let is_indices =
+                            property.name == "vertex_indices" || property.name == "vertex_index";
+
+                        if is_face && is_indices {
+                            for i in 2..ids.len() {
+                                faces.push(Point3::new(ids[0], ids[i - 1], ids[i]));
+                            }
+                        }
+                    }
+                }
+            }
+
+            if is_vertex {
+                points.push(pt);
+                normals.push(normal);
+                colors.push(color);
+            }
+        }
+    }
+
+    let vertex = elements.iter().find(|e| e.name == "vertex");
+    let has_property =
+        |name: &str| vertex.map_or(false, |e| e.properties.iter().any(|p| p.name == name));
+    let normals = if has_property("nx") {
+        Some(normals)
+    } else {
+        None
+    };
+    let colors = if has_property("red") {
+        Some(colors)
+    } else {
+        None
+    };
+
+    if !has_faces {
+        return Ok(PlyData::PointCloud {
+            points,
+            normals,
+            colors,
+        });
+    }
+
+    if faces.iter().any(|f| f.iter().any(|i| *i >= points.len())) {
+        return Err(invalid_data("face vertex index out of bounds."));
+    }
+
+    let faces = faces
+        .into_iter()
+        .map(|f| Point3::new(f.x as u32, f.y as u32, f.z as u32))
+        .collect();
+
+    let mut mesh = Mesh::new_with_smallest_indices(points, faces, normals, None, false);
+    mesh.set_colors(colors.clone());
+
+    Ok(PlyData::Mesh { mesh, colors })
+}
+
+fn parse_header(data: &[u8]) -> IoResult<(Format, Vec<Element>, &[u8])> {
+    const END_HEADER: &[u8] = b"end_header";
+
+    let end = data
+        .windows(END_HEADER.len())
+        .position(|w| w == END_HEADER)
+        .ok_or_else(|| invalid_data("missing `end_header`."))?;
+    // The body starts right after the end of the `end_header` line.
+    let body_start = data[end..]
+        .iter()
+        .position(|c| *c == b'\n')
+        .map_or(data.len(), |i| end + i + 1);
+    let header = std::str::from_utf8(&data[..end])
+        .map_err(|_| invalid_data("the header is not valid UTF-8."))?;
+
+    let mut lines = header.lines();
+
+    if lines.next().map(|l| l.trim()) != Some("ply") {
+        return Err(invalid_data("missing `ply` magic number."));
+    }
+
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+
+    for line in lines {
+        let mut words = line.split_whitespace();
+
+        match words.next() {
+            Some("format") => {
+                format = match words.next() {
+                    Some("ascii") => Some(Format::Ascii),
+                    Some("binary_little_endian") => Some(Format::BinaryLittleEndian),
+                    Some("binary_big_endian") => Some(Format::BinaryBigEndian),
+                    _ => return Err(invalid_data("unknown format.")),
+                }
+            }
+            Some("element") => {
+                let name = words
+                    .next()
+                    .ok_or_else(|| invalid_data("missing element name."))?;
+                let count = words
+                    .next()
+                    .and_then(|w| w.parse().ok())
+                    .ok_or_else(|| invalid_data("invalid element count."))?;
+
+                elements.push(Element {
+                    name: name.to_string(),
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            Some("property") => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| invalid_data("property declared outside of an element."))?;
+                let ty = match words.next() {
+                    Some("list") => {
+                        let len_ty = ScalarType::from_name(words.next().unwrap_or(""))?;
+                        let ty = ScalarType::from_name(words.next().unwrap_or(""))?;
+                        PropertyType::List(len_ty, ty)
+                    }
+                    Some(ty) => PropertyType::Scalar(ScalarType::from_name(ty)?),
+                    None => return Err(invalid_data("missing property type.")),
+                };
+                let name = words
+                    .next()
+                    .ok_or_else(|| invalid_data("missing property name."))?;
+
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    ty,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let format = format.ok_or_else(|| invalid_data("missing format."))?;
+
+    Ok((format, elements, &data[body_start..]))
+}
+
+enum Reader<'a> {
+    Ascii(SplitWhitespace<'a>),
+    Binary { data: &'a [u8], big_endian: bool },
+}
+
+impl<'a> Reader<'a> {
+    fn read(&mut self, ty: ScalarType) -> IoResult<f64> {
+        match self {
+            Reader::Ascii(words) => words
+                .next()
+                .and_then(|w| w.parse().ok())
+                .ok_or_else(|| invalid_data("invalid or missing value.")),
+            Reader::Binary { data, big_endian } => {
+                let size = ty.size();
+
+                if data.len() < size {
+                    return Err(invalid_data("unexpected end of file."));
+                }
+
+                let mut bytes = [0u8; 8];
+                bytes[..size].copy_from_slice(&data[..size]);
+                *data = &data[size..];
+
+                if *big_endian {
+                    bytes[..size].reverse();
+                }
+
+                let val = match ty {
+                    ScalarType::I8 => bytes[0] as i8 as f64,
+                    ScalarType::U8 => bytes[0] as f64,
+                    ScalarType::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+                    ScalarType::U16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+                    ScalarType::I32 => i32::from_le_bytes(read4(&bytes)) as f64,
+                    ScalarType::U32 => u32::from_le_bytes(read4(&bytes)) as f64,
+                    ScalarType::F32 => f32::from_le_bytes(read4(&bytes)) as f64,
+                    ScalarType::F64 => f64::from_le_bytes(bytes),
+                };
+
+                Ok(val)
+            }
+        }
+    }
+}
+
+fn read4(bytes: &[u8; 8]) -> [u8; 4] {
+    [bytes[0], bytes[1], bytes[2], bytes[3]]
+}
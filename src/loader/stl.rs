@@ -0,0 +1,149 @@
+//! Simplistic STL loader, supporting both ASCII and binary files.
+
+use crate::resource::Mesh;
+use na::Point3;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::io::{Error, ErrorKind, Result as IoResult};
+use std::path::Path;
+
+const HEADER_LEN: usize = 80;
+const FACET_LEN: usize = 50;
+
+fn invalid_data(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("STL: {}", msg))
+}
+
+/// Parses an STL file.
+pub fn parse_file(path: &Path) -> IoResult<Mesh> {
+    let mut data = Vec::new();
+    let _ = File::open(path)?.read_to_end(&mut data)?;
+    parse(&data[..])
+}
+
+/// Parses the content of an STL file.
+///
+/// Duplicate vertices are merged, and the per-facet normals stored by the file are discarded
+/// in favor of smooth per-vertex normals.
+pub fn parse(data: &[u8]) -> IoResult<Mesh> {
+    let triangles = if is_binary(data) {
+        parse_binary(data)
+    } else if is_ascii(data) {
+        parse_ascii(data)?
+    } else {
+        return Err(invalid_data("unrecognized file format."));
+    };
+
+    let mut coords = Vec::new();
+    let mut faces = Vec::with_capacity(triangles.len());
+    let mut ids = HashMap::new();
+
+    for triangle in triangles.iter() {
+        let mut face = Point3::new(0, 0, 0);
+
+        for (i, pt) in triangle.iter().enumerate() {
+            let key = [pt.x.to_bits(), pt.y.to_bits(), pt.z.to_bits()];
+
+            face[i] = match ids.entry(key) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => {
+                    coords.push(*pt);
+                    *entry.insert(coords.len() as u32 - 1)
+                }
+            };
+        }
+
+        faces.push(face);
+    }
+
+    Ok(Mesh::new_with_smallest_indices(
+        coords, faces, None, None, false,
+    ))
+}
+
+// A binary file has a 80-bytes header, followed by the number of facets and the facets
+// themselves. Because some binary files have a header starting with `solid`, we rely on the
+// file size instead of the header content.
+fn is_binary(data: &[u8]) -> bool {
+    if data.len() < HEADER_LEN + 4 {
+        return false;
+    }
+
+    // The expected size overflows on 32-bits targets for invalid numbers of facets.
+    let nfacets = read_u32(&data[HEADER_LEN..]) as usize;
+    nfacets
+        .checked_mul(FACET_LEN)
+        .and_then(|len| len.checked_add(HEADER_LEN + 4))
+        .map_or(false, |len| data.len() == len)
+}
+
+fn is_ascii(data: &[u8]) -> bool {
+    match data.iter().position(|c| !c.is_ascii_whitespace()) {
+        Some(start) => data[start..].starts_with(b"solid"),
+        None => false,
+    }
+}
+
+fn read_u32(data: &[u8]) -> u32 {
+    u32::from_le_bytes([data[0], data[1], data[2], data[3]])
+}
+
+fn read_point(data: &[u8]) -> Point3<f32> {
+    Point3::new(
+        f32::from_bits(read_u32(&data[0..])),
+        f32::from_bits(read_u32(&data[4..])),
+        f32::from_bits(read_u32(&data[8..])),
+    )
+}
+
+fn parse_binary(data: &[u8]) -> Vec<[Point3<f32>; 3]> {
+    let facets = &data[HEADER_LEN + 4..];
+
+    // Each facet is made of a normal, three vertices, and a 2-bytes attribute.
+    facets
+        .chunks_exact(FACET_LEN)
+        .map(|f| {
+            [
+                read_point(&f[12..]),
+                read_point(&f[24..]),
+                read_point(&f[36..]),
+            ]
+        })
+        .collect()
+}
+
+fn parse_ascii(data: &[u8]) -> IoResult<Vec<[Point3<f32>; 3]>> {
+    let string =
+        std::str::from_utf8(data).map_err(|_| invalid_data("the file is not valid UTF-8."))?;
+    let mut words = string.split_whitespace();
+    let mut vertices = Vec::new();
+    let mut triangles = Vec::new();
+
+    while let Some(word) = words.next() {
+        match word {
+            "vertex" => {
+                let mut coord = || -> IoResult<f32> {
+                    words
+                        .next()
+                        .and_then(|w| w.parse().ok())
+                        .ok_or_else(|| invalid_data("invalid vertex coordinate."))
+                };
+
+                vertices.push(Point3::new(coord()?, coord()?, coord()?));
+            }
+            "endfacet" => {
+                if vertices.len() != 3 {
+                    return Err(invalid_data("only triangular facets are supported."));
+                }
+
+                triangles.push([vertices[0], vertices[1], vertices[2]]);
+                vertices.clear();
+            }
+            _ => {}
+        }
+    }
+
+    Ok(triangles)
+}
@@ -0,0 +1,339 @@
+//! Post-processing effect to make the bright parts of the scene glow.
+
+use na::Vector2;
+
+use crate::context::{Context, Texture};
+use crate::post_processing::post_processing_effect::PostProcessingEffect;
+use crate::resource::{
+    AllocationType, BufferType, Effect, FramebufferManager, GPUVec, RenderTarget, ShaderAttribute,
+    ShaderUniform,
+};
+
+#[path = "../error.rs"]
+mod error;
+
+/// Post processing effect which makes the bright parts of the scene bleed onto their
+/// surroundings.
+///
+/// The pixels brighter than a threshold are extracted into a half-resolution render target,
+/// blurred with a separable gaussian filter, and added back to the scene.
+pub struct Bloom {
+    threshold_pass: Pass,
+    threshold_uniform: ShaderUniform<f32>,
+    blur_pass: Pass,
+    blur_step: ShaderUniform<Vector2<f32>>,
+    composite_pass: Pass,
+    composite_bloom: ShaderUniform<i32>,
+    composite_intensity: ShaderUniform<f32>,
+    fbo_vertices: GPUVec<Vector2<f32>>,
+    targets: Option<[RenderTarget; 2]>,
+    targets_size: (usize, usize),
+    w: f32,
+    h: f32,
+    threshold: f32,
+    intensity: f32,
+    blur_passes: usize,
+}
+
+impl Bloom {
+    /// Creates a new `Bloom` post processing effect.
+    pub fn new() -> Bloom {
+        let fbo_vertices: Vec<Vector2<f32>> = vec![
+            Vector2::new(-1.0, -1.0),
+            Vector2::new(1.0, -1.0),
+            Vector2::new(-1.0, 1.0),
+            Vector2::new(1.0, 1.0),
+        ];
+
+        let mut fbo_vertices =
+            GPUVec::new(fbo_vertices, BufferType::Array, AllocationType::StaticDraw);
+        fbo_vertices.load_to_gpu();
+        fbo_vertices.unload_from_ram();
+
+        let mut threshold_pass = Pass::new(THRESHOLD_FRAGMENT_SHADER);
+        let threshold_uniform = threshold_pass.shader.get_uniform("threshold").unwrap();
+        let mut blur_pass = Pass::new(BLUR_FRAGMENT_SHADER);
+        let blur_step = blur_pass.shader.get_uniform("step").unwrap();
+        let mut composite_pass = Pass::new(COMPOSITE_FRAGMENT_SHADER);
+        let composite_bloom = composite_pass.shader.get_uniform("bloom_texture").unwrap();
+        let composite_intensity = composite_pass.shader.get_uniform("intensity").unwrap();
+
+        Bloom {
+            threshold_pass,
+            threshold_uniform,
+            blur_pass,
+            blur_step,
+            composite_pass,
+            composite_bloom,
+            composite_intensity,
+            fbo_vertices,
+            targets: None,
+            targets_size: (0, 0),
+            w: 0.0,
+            h: 0.0,
+            threshold: 0.8,
+            intensity: 1.0,
+            blur_passes: 2,
+        }
+    }
+
+    /// The luminance above which a pixel starts to glow.
+    #[inline]
+    pub fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    /// Sets the luminance above which a pixel starts to glow.
+    ///
+    /// Defaults to 0.8.
+    #[inline]
+    pub fn set_threshold(&mut self, threshold: f32) {
+        self.threshold = threshold
+    }
+
+    /// The factor applied to the glow before it is added to the scene.
+    #[inline]
+    pub fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    /// Sets the factor applied to the glow before it is added to the scene.
+    ///
+    /// Defaults to 1.0.
+    #[inline]
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity
+    }
+
+    /// The number of times the glow is blurred.
+    #[inline]
+    pub fn blur_passes(&self) -> usize {
+        self.blur_passes
+    }
+
+    /// Sets the number of times the glow is blurred, each pass being one horizontal and one
+    /// vertical gaussian blur.
+    ///
+    /// More passes give a wider glow but are slower. Defaults to 2.
+    #[inline]
+    pub fn set_blur_passes(&mut self, blur_passes: usize) {
+        self.blur_passes = blur_passes
+    }
+
+    fn composite(&mut self, source: &RenderTarget, bloom: Option<&Texture>) {
+        let ctxt = Context::get();
+
+        self.composite_pass.use_program();
+        verify!(ctxt.active_texture(Context::TEXTURE1));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, bloom));
+        verify!(ctxt.active_texture(Context::TEXTURE0));
+        self.composite_bloom.upload(&1);
+        self.composite_intensity
+            .upload(&if bloom.is_some() { self.intensity } else { 0.0 });
+        self.composite_pass
+            .draw(source.texture_id(), &mut self.fbo_vertices);
+
+        verify!(ctxt.active_texture(Context::TEXTURE1));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, None));
+        verify!(ctxt.active_texture(Context::TEXTURE0));
+    }
+}
+
+impl PostProcessingEffect for Bloom {
+    fn update(&mut self, _: f32, w: f32, h: f32, _: f32, _: f32) {
+        self.w = w;
+        self.h = h;
+    }
+
+    /// Adds the glow computed by the last call to `draw_to_target` to the scene.
+    fn draw(&mut self, target: &RenderTarget) {
+        let targets = self.targets.take();
+        self.composite(target, targets.as_ref().and_then(|t| t[0].texture_id()));
+        self.targets = targets;
+    }
+
+    fn draw_to_target(
+        &mut self,
+        source: &RenderTarget,
+        output: &RenderTarget,
+        framebuffers: &mut FramebufferManager,
+    ) {
+        let ctxt = Context::get();
+        let size = (
+            ((self.w / 2.0) as usize).max(1),
+            ((self.h / 2.0) as usize).max(1),
+        );
+
+        let targets = match self.targets.take() {
+            Some(mut targets) => {
+                if self.targets_size != size {
+                    for target in targets.iter_mut() {
+                        target.resize(size.0 as f32, size.1 as f32);
+                    }
+                }
+
+                targets
+            }
+            None => [
+                FramebufferManager::new_render_target(size.0, size.1, false),
+                FramebufferManager::new_render_target(size.0, size.1, false),
+            ],
+        };
+        self.targets_size = size;
+
+        verify!(ctxt.viewport(0, 0, size.0 as i32, size.1 as i32));
+
+        /*
+         * Extract the bright pixels.
+         */
+        framebuffers.select(&targets[0]);
+        self.threshold_pass.use_program();
+        self.threshold_uniform.upload(&self.threshold);
+        self.threshold_pass
+            .draw(source.texture_id(), &mut self.fbo_vertices);
+
+        /*
+         * Blur them, alternating between horizontal and vertical passes.
+         */
+        for _ in 0..self.blur_passes {
+            framebuffers.select(&targets[1]);
+            self.blur_pass.use_program();
+            self.blur_step
+                .upload(&Vector2::new(1.0 / size.0 as f32, 0.0));
+            self.blur_pass
+                .draw(targets[0].texture_id(), &mut self.fbo_vertices);
+
+            framebuffers.select(&targets[0]);
+            self.blur_step
+                .upload(&Vector2::new(0.0, 1.0 / size.1 as f32));
+            self.blur_pass
+                .draw(targets[1].texture_id(), &mut self.fbo_vertices);
+        }
+
+        /*
+         * Add them to the scene.
+         */
+        verify!(ctxt.viewport(0, 0, self.w as i32, self.h as i32));
+        framebuffers.select(output);
+        self.composite(source, targets[0].texture_id());
+
+        self.targets = Some(targets);
+    }
+}
+
+// A shader drawing a fullscreen quad textured by a render target.
+struct Pass {
+    shader: Effect,
+    fbo_texture: ShaderUniform<i32>,
+    v_coord: ShaderAttribute<Vector2<f32>>,
+}
+
+impl Pass {
+    fn new(fragment_shader: &str) -> Pass {
+        let mut shader = Effect::new_from_str(VERTEX_SHADER, fragment_shader);
+
+        shader.use_program();
+
+        Pass {
+            fbo_texture: shader.get_uniform("fbo_texture").unwrap(),
+            v_coord: shader.get_attrib("v_coord").unwrap(),
+            shader,
+        }
+    }
+
+    fn use_program(&mut self) {
+        self.shader.use_program()
+    }
+
+    // The program must be in use.
+    fn draw(&mut self, texture: Option<&Texture>, fbo_vertices: &mut GPUVec<Vector2<f32>>) {
+        let ctxt = Context::get();
+        self.v_coord.enable();
+
+        verify!(ctxt.clear_color(0.0, 0.0, 0.0, 1.0));
+        verify!(ctxt.clear(Context::COLOR_BUFFER_BIT | Context::DEPTH_BUFFER_BIT));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, texture));
+
+        self.fbo_texture.upload(&0);
+        self.v_coord.bind(fbo_vertices);
+
+        verify!(ctxt.draw_arrays(Context::TRIANGLE_STRIP, 0, 4));
+
+        self.v_coord.disable();
+    }
+}
+
+static VERTEX_SHADER: &'static str = "#version 100
+    attribute vec2    v_coord;
+    varying vec2      f_texcoord;
+
+    void main(void) {
+      gl_Position = vec4(v_coord, 0.0, 1.0);
+      f_texcoord  = (v_coord + 1.0) / 2.0;
+    }";
+
+static THRESHOLD_FRAGMENT_SHADER: &'static str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+    uniform sampler2D fbo_texture;
+    uniform float     threshold;
+    varying vec2      f_texcoord;
+
+    void main(void) {
+      vec3 color = texture2D(fbo_texture, f_texcoord).rgb;
+      float luma = dot(color, vec3(0.2126, 0.7152, 0.0722));
+
+      if (luma > threshold) {
+        gl_FragColor = vec4(color, 1.0);
+      } else {
+        gl_FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+      }
+    }";
+
+static BLUR_FRAGMENT_SHADER: &'static str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+    uniform sampler2D fbo_texture;
+    uniform vec2      step;
+    varying vec2      f_texcoord;
+
+    void main(void) {
+      vec3 color = texture2D(fbo_texture, f_texcoord).rgb * 0.227027;
+
+      color += texture2D(fbo_texture, f_texcoord + step).rgb * 0.1945946;
+      color += texture2D(fbo_texture, f_texcoord - step).rgb * 0.1945946;
+      color += texture2D(fbo_texture, f_texcoord + step * 2.0).rgb * 0.1216216;
+      color += texture2D(fbo_texture, f_texcoord - step * 2.0).rgb * 0.1216216;
+      color += texture2D(fbo_texture, f_texcoord + step * 3.0).rgb * 0.054054;
+      color += texture2D(fbo_texture, f_texcoord - step * 3.0).rgb * 0.054054;
+      color += texture2D(fbo_texture, f_texcoord + step * 4.0).rgb * 0.016216;
+      color += texture2D(fbo_texture, f_texcoord - step * 4.0).rgb * 0.016216;
+
+      gl_FragColor = vec4(color, 1.0);
+    }";
+
+static COMPOSITE_FRAGMENT_SHADER: &'static str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+    uniform sampler2D fbo_texture;
+    uniform sampler2D bloom_texture;
+    uniform float     intensity;
+    varying vec2      f_texcoord;
+
+    void main(void) {
+      vec4 color   = texture2D(fbo_texture, f_texcoord);
+      vec3 bloom   = texture2D(bloom_texture, f_texcoord).rgb;
+      gl_FragColor = vec4(color.rgb + bloom * intensity, color.a);
+    }";
@@ -0,0 +1,203 @@
+//! Post-processing effect to smooth the aliased edges of the scene.
+
+use na::Vector2;
+
+use crate::context::Context;
+use crate::post_processing::post_processing_effect::PostProcessingEffect;
+use crate::resource::{
+    AllocationType, BufferType, Effect, GPUVec, RenderTarget, ShaderAttribute, ShaderUniform,
+};
+
+#[path = "../error.rs"]
+mod error;
+
+/// Post processing effect implementing Fast Approximate Anti-Aliasing (FXAA).
+///
+/// Contrary to multisampling, this works with any context since it only operates on the
+/// rendered image.
+pub struct Fxaa {
+    shader: Effect,
+    fbo_texture: ShaderUniform<i32>,
+    inv_resolution: ShaderUniform<Vector2<f32>>,
+    subpixel: ShaderUniform<f32>,
+    edge_threshold: ShaderUniform<f32>,
+    v_coord: ShaderAttribute<Vector2<f32>>,
+    fbo_vertices: GPUVec<Vector2<f32>>,
+    w: f32,
+    h: f32,
+    subpixel_aliasing: f32,
+    edge_threshold_value: f32,
+}
+
+impl Fxaa {
+    /// Creates a new `Fxaa` post processing effect.
+    pub fn new() -> Fxaa {
+        let fbo_vertices: Vec<Vector2<f32>> = vec![
+            Vector2::new(-1.0, -1.0),
+            Vector2::new(1.0, -1.0),
+            Vector2::new(-1.0, 1.0),
+            Vector2::new(1.0, 1.0),
+        ];
+
+        let mut fbo_vertices =
+            GPUVec::new(fbo_vertices, BufferType::Array, AllocationType::StaticDraw);
+        fbo_vertices.load_to_gpu();
+        fbo_vertices.unload_from_ram();
+
+        let mut shader = Effect::new_from_str(VERTEX_SHADER, FRAGMENT_SHADER);
+
+        shader.use_program();
+
+        Fxaa {
+            fbo_texture: shader.get_uniform("fbo_texture").unwrap(),
+            inv_resolution: shader.get_uniform("inv_resolution").unwrap(),
+            subpixel: shader.get_uniform("subpixel").unwrap(),
+            edge_threshold: shader.get_uniform("edge_threshold").unwrap(),
+            v_coord: shader.get_attrib("v_coord").unwrap(),
+            fbo_vertices: fbo_vertices,
+            shader: shader,
+            w: 0.0,
+            h: 0.0,
+            subpixel_aliasing: 0.5,
+            edge_threshold_value: 0.125,
+        }
+    }
+
+    /// The amount of sub-pixel aliasing removal.
+    #[inline]
+    pub fn subpixel_aliasing(&self) -> f32 {
+        self.subpixel_aliasing
+    }
+
+    /// Sets the amount of sub-pixel aliasing removal, between 0.0 (sharper) and 1.0 (softer).
+    ///
+    /// Defaults to 0.5.
+    #[inline]
+    pub fn set_subpixel_aliasing(&mut self, amount: f32) {
+        assert!(
+            amount >= 0.0 && amount <= 1.0,
+            "The sub-pixel aliasing removal must be between 0.0 and 1.0."
+        );
+        self.subpixel_aliasing = amount
+    }
+
+    /// The minimum local contrast required for a pixel to be considered as part of an edge.
+    #[inline]
+    pub fn edge_threshold(&self) -> f32 {
+        self.edge_threshold_value
+    }
+
+    /// Sets the minimum local contrast, relative to the local maximum luminance, required for a
+    /// pixel to be considered as part of an edge.
+    ///
+    /// Lower values smooth more edges but are slower. Defaults to 0.125.
+    #[inline]
+    pub fn set_edge_threshold(&mut self, threshold: f32) {
+        self.edge_threshold_value = threshold
+    }
+}
+
+impl PostProcessingEffect for Fxaa {
+    fn update(&mut self, _: f32, w: f32, h: f32, _: f32, _: f32) {
+        self.w = w;
+        self.h = h;
+    }
+
+    fn draw(&mut self, target: &RenderTarget) {
+        let ctxt = Context::get();
+        self.v_coord.enable();
+
+        /*
+         * Configure the post-process effect.
+         */
+        self.shader.use_program();
+        self.inv_resolution
+            .upload(&Vector2::new(1.0 / self.w, 1.0 / self.h));
+        self.subpixel.upload(&self.subpixel_aliasing);
+        self.edge_threshold.upload(&self.edge_threshold_value);
+
+        /*
+         * Finalize draw
+         */
+        verify!(ctxt.clear_color(0.0, 0.0, 0.0, 1.0));
+        verify!(ctxt.clear(Context::COLOR_BUFFER_BIT | Context::DEPTH_BUFFER_BIT));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, target.texture_id()));
+
+        self.fbo_texture.upload(&0);
+        self.v_coord.bind(&mut self.fbo_vertices);
+
+        verify!(ctxt.draw_arrays(Context::TRIANGLE_STRIP, 0, 4));
+
+        self.v_coord.disable();
+    }
+}
+
+static VERTEX_SHADER: &'static str = "#version 100
+    attribute vec2    v_coord;
+    varying vec2      f_texcoord;
+
+    void main(void) {
+      gl_Position = vec4(v_coord, 0.0, 1.0);
+      f_texcoord  = (v_coord + 1.0) / 2.0;
+    }";
+
+static FRAGMENT_SHADER: &'static str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+    #define FXAA_SPAN_MAX            8.0
+    #define FXAA_REDUCE_MIN          (1.0 / 128.0)
+    #define FXAA_EDGE_THRESHOLD_MIN  (1.0 / 32.0)
+
+    uniform sampler2D fbo_texture;
+    uniform vec2      inv_resolution;
+    uniform float     subpixel;
+    uniform float     edge_threshold;
+    varying vec2      f_texcoord;
+
+    void main(void) {
+      vec3 luma   = vec3(0.299, 0.587, 0.114);
+      vec4 rgba_m = texture2D(fbo_texture, f_texcoord);
+
+      float luma_nw = dot(texture2D(fbo_texture, f_texcoord + vec2(-1.0, -1.0) * inv_resolution).rgb, luma);
+      float luma_ne = dot(texture2D(fbo_texture, f_texcoord + vec2(1.0, -1.0) * inv_resolution).rgb, luma);
+      float luma_sw = dot(texture2D(fbo_texture, f_texcoord + vec2(-1.0, 1.0) * inv_resolution).rgb, luma);
+      float luma_se = dot(texture2D(fbo_texture, f_texcoord + vec2(1.0, 1.0) * inv_resolution).rgb, luma);
+      float luma_m  = dot(rgba_m.rgb, luma);
+
+      float luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+      float luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+      // Not an edge: leave the pixel untouched.
+      if (luma_max - luma_min < max(FXAA_EDGE_THRESHOLD_MIN, luma_max * edge_threshold)) {
+        gl_FragColor = rgba_m;
+        return;
+      }
+
+      vec2 dir;
+      dir.x = -((luma_nw + luma_ne) - (luma_sw + luma_se));
+      dir.y =  ((luma_nw + luma_sw) - (luma_ne + luma_se));
+
+      float reduce_mul  = (1.0 - subpixel) / 4.0;
+      float dir_reduce  = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.25 * reduce_mul, FXAA_REDUCE_MIN);
+      float rcp_dir_min = 1.0 / (min(abs(dir.x), abs(dir.y)) + dir_reduce);
+
+      dir = clamp(dir * rcp_dir_min, vec2(-FXAA_SPAN_MAX), vec2(FXAA_SPAN_MAX)) * inv_resolution;
+
+      vec3 rgb_a = 0.5 * (
+          texture2D(fbo_texture, f_texcoord + dir * (1.0 / 3.0 - 0.5)).rgb +
+          texture2D(fbo_texture, f_texcoord + dir * (2.0 / 3.0 - 0.5)).rgb);
+      vec3 rgb_b = rgb_a * 0.5 + 0.25 * (
+          texture2D(fbo_texture, f_texcoord + dir * -0.5).rgb +
+          texture2D(fbo_texture, f_texcoord + dir * 0.5).rgb);
+      float luma_b = dot(rgb_b, luma);
+
+      if (luma_b < luma_min || luma_b > luma_max) {
+        gl_FragColor = vec4(rgb_a, rgba_m.a);
+      } else {
+        gl_FragColor = vec4(rgb_b, rgba_m.a);
+      }
+    }";
@@ -1,15 +1,21 @@
 //! Post-processing effects.
 
+pub use crate::post_processing::bloom::Bloom;
+pub use crate::post_processing::fxaa::Fxaa;
 pub use crate::post_processing::grayscales::Grayscales;
 pub use crate::post_processing::oculus_stereo::OculusStereo;
 pub use crate::post_processing::post_processing_effect::PostProcessingEffect;
+pub use crate::post_processing::shader_effect::ShaderEffect;
 #[cfg(not(target_arch = "wasm32"))]
 pub use crate::post_processing::sobel_edge_highlight::SobelEdgeHighlight;
 pub use crate::post_processing::waves::Waves;
 
+mod bloom;
+mod fxaa;
 mod grayscales;
 mod oculus_stereo;
 pub mod post_processing_effect;
+mod shader_effect;
 #[cfg(not(target_arch = "wasm32"))]
 mod sobel_edge_highlight;
 mod waves;
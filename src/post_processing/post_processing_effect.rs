@@ -1,11 +1,11 @@
 //! Trait implemented by every post-processing effect.
 
-use crate::resource::RenderTarget;
+use crate::resource::{FramebufferManager, RenderTarget};
 
 /// Trait of post processing effect.
 ///
-/// One post-processing effect can be used at a time. It is executed once the scene has been
-/// rendered on a texture.
+/// A post-processing effect is executed once the scene has been rendered on a texture. Several
+/// effects can be chained with `Window::add_post_processing_effect`.
 pub trait PostProcessingEffect {
     /// Updates the post processing effect.
     fn update(&mut self, dt: f32, w: f32, h: f32, znear: f32, zfar: f32);
@@ -16,4 +16,24 @@ pub trait PostProcessingEffect {
     /// * `fbo_texture` - id to the texture containing the last scene drawn.
     /// * `fbo_depth` - the depth buffer as a texture.
     fn draw(&mut self, target: &RenderTarget);
+    /// Render the effect to a given output.
+    ///
+    /// This is what the window actually calls. By default, this selects `output` and calls
+    /// `self.draw(source)`. Effects needing their own intermediate render targets can override
+    /// this to select them with `framebuffers`, as long as `output` is selected back for the
+    /// final pass.
+    ///
+    /// # Arguments:
+    /// * `source` - the render target containing the last scene drawn.
+    /// * `output` - the render target the result must be drawn to.
+    /// * `framebuffers` - manager to switch between the different render targets.
+    fn draw_to_target(
+        &mut self,
+        source: &RenderTarget,
+        output: &RenderTarget,
+        framebuffers: &mut FramebufferManager,
+    ) {
+        framebuffers.select(output);
+        self.draw(source)
+    }
 }
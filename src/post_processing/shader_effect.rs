@@ -0,0 +1,175 @@
+//! Post-processing effect defined by a user-provided fragment shader.
+
+use na::Vector2;
+
+use crate::context::Context;
+use crate::post_processing::post_processing_effect::PostProcessingEffect;
+use crate::resource::{
+    AllocationType, BufferType, Effect, GPUVec, RenderTarget, ShaderAttribute, ShaderUniform,
+};
+
+#[path = "../error.rs"]
+mod error;
+
+/// Post processing effect executing a custom fragment shader on the rendered scene.
+///
+/// The fragment shader is run once per pixel of the output, and receives the following inputs,
+/// all optional:
+///
+/// * `varying vec2 f_texcoord` - the texture coordinates of the current pixel.
+/// * `uniform sampler2D tex` - the scene color.
+/// * `uniform sampler2D depth` - the scene depth. Not available on wasm.
+/// * `uniform float nx`, `uniform float ny` - the width and height of the output, in pixels.
+/// * `uniform float time` - the time elapsed since the creation of this effect, in seconds.
+/// * `uniform float znear`, `uniform float zfar` - the clip planes of the camera.
+///
+/// Other uniforms can be set with `ShaderEffect::set_uniforms_callback`.
+pub struct ShaderEffect {
+    shader: Effect,
+    tex: Option<ShaderUniform<i32>>,
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    depth: Option<ShaderUniform<i32>>,
+    nx: Option<ShaderUniform<f32>>,
+    ny: Option<ShaderUniform<f32>>,
+    time: Option<ShaderUniform<f32>>,
+    znear: Option<ShaderUniform<f32>>,
+    zfar: Option<ShaderUniform<f32>>,
+    v_coord: ShaderAttribute<Vector2<f32>>,
+    fbo_vertices: GPUVec<Vector2<f32>>,
+    uniforms_callback: Option<Box<dyn FnMut(&mut Effect)>>,
+    w: f32,
+    h: f32,
+    elapsed: f32,
+    zn: f32,
+    zf: f32,
+}
+
+impl ShaderEffect {
+    /// Creates a new post processing effect from the source code of a fragment shader.
+    ///
+    /// The shader must target GLSL ES 1.0, i.e., start with `#version 100`.
+    pub fn from_fragment_str(src: &str) -> ShaderEffect {
+        let fbo_vertices: Vec<Vector2<f32>> = vec![
+            Vector2::new(-1.0, -1.0),
+            Vector2::new(1.0, -1.0),
+            Vector2::new(-1.0, 1.0),
+            Vector2::new(1.0, 1.0),
+        ];
+
+        let mut fbo_vertices =
+            GPUVec::new(fbo_vertices, BufferType::Array, AllocationType::StaticDraw);
+        fbo_vertices.load_to_gpu();
+        fbo_vertices.unload_from_ram();
+
+        let mut shader = Effect::new_from_str(VERTEX_SHADER, src);
+
+        shader.use_program();
+
+        ShaderEffect {
+            tex: shader.get_uniform("tex"),
+            depth: shader.get_uniform("depth"),
+            nx: shader.get_uniform("nx"),
+            ny: shader.get_uniform("ny"),
+            time: shader.get_uniform("time"),
+            znear: shader.get_uniform("znear"),
+            zfar: shader.get_uniform("zfar"),
+            v_coord: shader.get_attrib("v_coord").unwrap(),
+            fbo_vertices: fbo_vertices,
+            shader: shader,
+            uniforms_callback: None,
+            w: 0.0,
+            h: 0.0,
+            elapsed: 0.0,
+            zn: 0.0,
+            zf: 0.0,
+        }
+    }
+
+    /// Sets a function called each time this effect is drawn, to upload custom uniforms.
+    ///
+    /// The shader program is in use when the function is called, so uniforms retrieved with
+    /// `Effect::get_uniform` can be uploaded directly.
+    pub fn set_uniforms_callback<F: FnMut(&mut Effect) + 'static>(&mut self, callback: F) {
+        self.uniforms_callback = Some(Box::new(callback))
+    }
+}
+
+impl PostProcessingEffect for ShaderEffect {
+    fn update(&mut self, dt: f32, w: f32, h: f32, znear: f32, zfar: f32) {
+        self.elapsed += dt;
+        self.w = w;
+        self.h = h;
+        self.zn = znear;
+        self.zf = zfar;
+    }
+
+    fn draw(&mut self, target: &RenderTarget) {
+        let ctxt = Context::get();
+        self.v_coord.enable();
+
+        /*
+         * Configure the post-process effect.
+         */
+        self.shader.use_program();
+
+        if let Some(ref mut nx) = self.nx {
+            nx.upload(&self.w);
+        }
+        if let Some(ref mut ny) = self.ny {
+            ny.upload(&self.h);
+        }
+        if let Some(ref mut time) = self.time {
+            time.upload(&self.elapsed);
+        }
+        if let Some(ref mut znear) = self.znear {
+            znear.upload(&self.zn);
+        }
+        if let Some(ref mut zfar) = self.zfar {
+            zfar.upload(&self.zf);
+        }
+
+        if let Some(ref mut callback) = self.uniforms_callback {
+            callback(&mut self.shader);
+        }
+
+        /*
+         * Finalize draw
+         */
+        verify!(ctxt.clear_color(0.0, 0.0, 0.0, 1.0));
+        verify!(ctxt.clear(Context::COLOR_BUFFER_BIT | Context::DEPTH_BUFFER_BIT));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(ref mut depth) = self.depth {
+                verify!(ctxt.active_texture(Context::TEXTURE1));
+                verify!(ctxt.bind_texture(
+                    Context::TEXTURE_2D,
+                    target.depth_id().and_then(|id| id.as_ref().left())
+                ));
+                depth.upload(&1);
+            }
+        }
+
+        verify!(ctxt.active_texture(Context::TEXTURE0));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, target.texture_id()));
+
+        if let Some(ref mut tex) = self.tex {
+            tex.upload(&0);
+        }
+
+        self.v_coord.bind(&mut self.fbo_vertices);
+
+        verify!(ctxt.draw_arrays(Context::TRIANGLE_STRIP, 0, 4));
+
+        self.v_coord.disable();
+    }
+}
+
+static VERTEX_SHADER: &'static str = "#version 100
+    attribute vec2    v_coord;
+    varying vec2      f_texcoord;
+
+    void main(void) {
+      gl_Position = vec4(v_coord, 0.0, 1.0);
+      f_texcoord  = (v_coord + 1.0) / 2.0;
+    }";
@@ -0,0 +1,339 @@
+//! Procedural generation of meshes with normals and texture coordinates.
+//!
+//! All the shapes are centered at the origin and have their principal axis aligned with the
+//! `y` axis.
+
+use na::{Point2, Point3, Vector3};
+use ncollide3d::procedural::{IndexBuffer, TriMesh};
+use std::collections::HashMap;
+use std::f32;
+
+/// Generates a cone pointing toward the positive `y` axis.
+///
+/// # Arguments
+/// * `r` - the cone base radius.
+/// * `h` - the cone height.
+/// * `subdivs` - the number of subdivisions of the base circle. Must be at least 3.
+pub fn cone(r: f32, h: f32, subdivs: u32) -> TriMesh<f32> {
+    assert!(subdivs >= 3, "A cone needs at least 3 subdivisions.");
+
+    let mut coords = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+    let slope = (r * r + h * h).sqrt();
+
+    // The side: the apex is duplicated once per subdivision so its normals follow the side.
+    for i in 0..=subdivs {
+        let u = i as f32 / subdivs as f32;
+        let (sin, cos) = (u * f32::consts::PI * 2.0).sin_cos();
+        let normal = Vector3::new(cos * h, r, sin * h) / slope;
+
+        coords.push(Point3::new(r * cos, -h / 2.0, r * sin));
+        coords.push(Point3::new(0.0, h / 2.0, 0.0));
+        normals.push(normal);
+        normals.push(normal);
+        uvs.push(Point2::new(u, 0.0));
+        uvs.push(Point2::new(u, 1.0));
+    }
+
+    for i in 0..subdivs {
+        let base = i * 2;
+        indices.push(Point3::new(base, base + 1, base + 2));
+    }
+
+    // The base disk.
+    let center = coords.len() as u32;
+    coords.push(Point3::new(0.0, -h / 2.0, 0.0));
+    normals.push(-Vector3::y());
+    uvs.push(Point2::new(0.5, 0.5));
+
+    for i in 0..=subdivs {
+        let (sin, cos) = (i as f32 / subdivs as f32 * f32::consts::PI * 2.0).sin_cos();
+
+        coords.push(Point3::new(r * cos, -h / 2.0, r * sin));
+        normals.push(-Vector3::y());
+        uvs.push(Point2::new(0.5 + cos * 0.5, 0.5 + sin * 0.5));
+    }
+
+    for i in 0..subdivs {
+        indices.push(Point3::new(center, center + 1 + i, center + 2 + i));
+    }
+
+    TriMesh::new(
+        coords,
+        Some(normals),
+        Some(uvs),
+        Some(IndexBuffer::Unified(indices)),
+    )
+}
+
+/// Generates a torus lying on the `xz` plane.
+///
+/// # Arguments
+/// * `major_r` - the distance from the torus center to the center of its tube.
+/// * `minor_r` - the radius of the torus tube.
+/// * `nsegs` - the number of subdivisions along the tube. Must be at least 3.
+/// * `nrings` - the number of subdivisions around the tube. Must be at least 3.
+pub fn torus(major_r: f32, minor_r: f32, nsegs: u32, nrings: u32) -> TriMesh<f32> {
+    assert!(
+        nsegs >= 3 && nrings >= 3,
+        "A torus needs at least 3 segments and 3 rings."
+    );
+
+    let mut coords = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+
+    for j in 0..=nrings {
+        let v = j as f32 / nrings as f32;
+        let (sin_theta, cos_theta) = (v * f32::consts::PI * 2.0).sin_cos();
+
+        for i in 0..=nsegs {
+            let u = i as f32 / nsegs as f32;
+            let (sin_phi, cos_phi) = (u * f32::consts::PI * 2.0).sin_cos();
+            let center = Point3::new(major_r * cos_phi, 0.0, major_r * sin_phi);
+            let normal = Vector3::new(cos_theta * cos_phi, sin_theta, cos_theta * sin_phi);
+
+            coords.push(center + normal * minor_r);
+            normals.push(normal);
+            uvs.push(Point2::new(u, v));
+        }
+    }
+
+    TriMesh::new(
+        coords,
+        Some(normals),
+        Some(uvs),
+        Some(IndexBuffer::Unified(grid_indices(nsegs, nrings))),
+    )
+}
+
+/// Generates a capsule, i.e., a cylinder with hemispherical caps.
+///
+/// # Arguments
+/// * `r` - the radius of the cylinder and of the caps.
+/// * `h` - the height of the cylinder, without the caps.
+/// * `subdivs` - the number of subdivisions around the capsule axis. Each cap is subdivided
+///   half as much along the capsule axis. Must be at least 3.
+pub fn capsule(r: f32, h: f32, subdivs: u32) -> TriMesh<f32> {
+    assert!(subdivs >= 3, "A capsule needs at least 3 subdivisions.");
+
+    let nlat = (subdivs / 2).max(2);
+    // The length of the profile of the capsule, used for the `v` texture coordinate.
+    let length = f32::consts::PI * r + h;
+    let mut coords = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut nrows = 0;
+
+    // The rows of both caps, from the bottom pole to the top pole. The two equators are
+    // linked by the cylinder.
+    for (shift, sign, arc) in [(-h / 2.0, -1.0f32, 0.0), (h / 2.0, 1.0, h)].iter() {
+        for k in 0..=nlat {
+            let t = if *sign < 0.0 { nlat - k } else { k };
+            let alpha = *sign * t as f32 / nlat as f32 * f32::consts::FRAC_PI_2;
+            let (sin_alpha, cos_alpha) = alpha.sin_cos();
+            let v = (arc + (alpha + f32::consts::FRAC_PI_2) * r) / length;
+
+            for i in 0..=subdivs {
+                let u = i as f32 / subdivs as f32;
+                let (sin_phi, cos_phi) = (u * f32::consts::PI * 2.0).sin_cos();
+                let normal = Vector3::new(cos_alpha * cos_phi, sin_alpha, cos_alpha * sin_phi);
+
+                coords.push(Point3::new(0.0, *shift, 0.0) + normal * r);
+                normals.push(normal);
+                uvs.push(Point2::new(u, v));
+            }
+
+            nrows += 1;
+        }
+    }
+
+    TriMesh::new(
+        coords,
+        Some(normals),
+        Some(uvs),
+        Some(IndexBuffer::Unified(grid_indices(subdivs, nrows - 1))),
+    )
+}
+
+/// Generates a cylinder with its principal axis aligned with the `y` axis.
+///
+/// # Arguments
+/// * `r` - the cylinder base radius.
+/// * `h` - the cylinder height.
+/// * `subdivs` - the number of subdivisions of the base circles. Must be at least 3.
+pub fn cylinder(r: f32, h: f32, subdivs: u32) -> TriMesh<f32> {
+    assert!(subdivs >= 3, "A cylinder needs at least 3 subdivisions.");
+
+    let mut coords = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+
+    // The side, made of two rows of vertices.
+    for (j, y) in [-h / 2.0, h / 2.0].iter().enumerate() {
+        for i in 0..=subdivs {
+            let u = i as f32 / subdivs as f32;
+            let (sin, cos) = (u * f32::consts::PI * 2.0).sin_cos();
+
+            coords.push(Point3::new(r * cos, *y, r * sin));
+            normals.push(Vector3::new(cos, 0.0, sin));
+            uvs.push(Point2::new(u, j as f32));
+        }
+    }
+
+    let mut indices = grid_indices(subdivs, 1);
+
+    // The bottom and top disks.
+    for (y, normal) in [(-h / 2.0, -Vector3::y()), (h / 2.0, Vector3::y())].iter() {
+        let center = coords.len() as u32;
+        coords.push(Point3::new(0.0, *y, 0.0));
+        normals.push(*normal);
+        uvs.push(Point2::new(0.5, 0.5));
+
+        for i in 0..=subdivs {
+            let (sin, cos) = (i as f32 / subdivs as f32 * f32::consts::PI * 2.0).sin_cos();
+
+            coords.push(Point3::new(r * cos, *y, r * sin));
+            normals.push(*normal);
+            uvs.push(Point2::new(0.5 + cos * 0.5, 0.5 + sin * 0.5));
+        }
+
+        for i in 0..subdivs {
+            if normal.y < 0.0 {
+                indices.push(Point3::new(center, center + 1 + i, center + 2 + i));
+            } else {
+                indices.push(Point3::new(center, center + 2 + i, center + 1 + i));
+            }
+        }
+    }
+
+    TriMesh::new(
+        coords,
+        Some(normals),
+        Some(uvs),
+        Some(IndexBuffer::Unified(indices)),
+    )
+}
+
+/// Generates a sphere by subdividing an icosahedron.
+///
+/// Each subdivision splits every triangle into four, so the sphere has `20 * 4^subdivs`
+/// triangles and `10 * 4^subdivs + 2` vertices. Unlike a UV sphere, the triangles have nearly
+/// the same size everywhere, including at the poles. The texture coordinates are spherical
+/// coordinates, but the vertices are not duplicated along the seam at `u = 0`, so textures are
+/// distorted on the triangles crossing it.
+///
+/// # Arguments
+/// * `r` - the sphere radius.
+/// * `subdivs` - the number of subdivisions of the icosahedron. 0 gives the icosahedron itself.
+pub fn icosphere(r: f32, subdivs: u32) -> TriMesh<f32> {
+    let t = (1.0 + 5.0f32.sqrt()) / 2.0;
+    let mut dirs: Vec<Vector3<f32>> = [
+        [-1.0, t, 0.0],
+        [1.0, t, 0.0],
+        [-1.0, -t, 0.0],
+        [1.0, -t, 0.0],
+        [0.0, -1.0, t],
+        [0.0, 1.0, t],
+        [0.0, -1.0, -t],
+        [0.0, 1.0, -t],
+        [t, 0.0, -1.0],
+        [t, 0.0, 1.0],
+        [-t, 0.0, -1.0],
+        [-t, 0.0, 1.0],
+    ]
+    .iter()
+    .map(|v| Vector3::new(v[0], v[1], v[2]).normalize())
+    .collect();
+    let mut indices: Vec<Point3<u32>> = [
+        [0, 11, 5],
+        [0, 5, 1],
+        [0, 1, 7],
+        [0, 7, 10],
+        [0, 10, 11],
+        [1, 5, 9],
+        [5, 11, 4],
+        [11, 10, 2],
+        [10, 7, 6],
+        [7, 1, 8],
+        [3, 9, 4],
+        [3, 4, 2],
+        [3, 2, 6],
+        [3, 6, 8],
+        [3, 8, 9],
+        [4, 9, 5],
+        [2, 4, 11],
+        [6, 2, 10],
+        [8, 6, 7],
+        [9, 8, 1],
+    ]
+    .iter()
+    .map(|f| Point3::new(f[0], f[1], f[2]))
+    .collect();
+
+    for _ in 0..subdivs {
+        // The vertex created at the middle of each edge, shared by its two triangles.
+        let mut middles = HashMap::new();
+        let mut middle = |a: u32, b: u32| {
+            *middles.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                let dir = (dirs[a as usize] + dirs[b as usize]).normalize();
+                dirs.push(dir);
+                dirs.len() as u32 - 1
+            })
+        };
+        let mut subdivided = Vec::with_capacity(indices.len() * 4);
+
+        for f in indices.iter() {
+            let ab = middle(f.x, f.y);
+            let bc = middle(f.y, f.z);
+            let ca = middle(f.z, f.x);
+
+            subdivided.push(Point3::new(f.x, ab, ca));
+            subdivided.push(Point3::new(ab, f.y, bc));
+            subdivided.push(Point3::new(ca, bc, f.z));
+            subdivided.push(Point3::new(ab, bc, ca));
+        }
+
+        indices = subdivided;
+    }
+
+    let coords = dirs.iter().map(|d| Point3::from(d * r)).collect();
+    let uvs = dirs
+        .iter()
+        .map(|d| {
+            let u = d.z.atan2(d.x) / (f32::consts::PI * 2.0);
+            let v = d.y.max(-1.0).min(1.0).asin() / f32::consts::PI + 0.5;
+            Point2::new(if u < 0.0 { u + 1.0 } else { u }, v)
+        })
+        .collect();
+
+    TriMesh::new(
+        coords,
+        Some(dirs),
+        Some(uvs),
+        Some(IndexBuffer::Unified(indices)),
+    )
+}
+
+// The triangles of a grid of `(ncols + 1) * (nrows + 1)` vertices stored row by row, facing
+// the direction of `row × column`.
+fn grid_indices(ncols: u32, nrows: u32) -> Vec<Point3<u32>> {
+    let mut indices = Vec::with_capacity(ncols as usize * nrows as usize * 2);
+    let id = |col: u32, row: u32| row * (ncols + 1) + col;
+
+    for row in 0..nrows {
+        for col in 0..ncols {
+            let a = id(col, row);
+            let b = id(col, row + 1);
+            let c = id(col + 1, row);
+            let d = id(col + 1, row + 1);
+
+            indices.push(Point3::new(a, b, c));
+            indices.push(Point3::new(c, b, d));
+        }
+    }
+
+    indices
+}
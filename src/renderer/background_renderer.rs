@@ -0,0 +1,145 @@
+//! A renderer for a gradient or an image filling the window behind the scene.
+
+use crate::camera::Camera;
+use crate::context::{Context, Texture};
+use crate::renderer::Renderer;
+use crate::resource::{AllocationType, BufferType, Effect, GPUVec, ShaderAttribute, ShaderUniform};
+use na::{Point2, Point3};
+use std::rc::Rc;
+
+#[path = "../error.rs"]
+mod error;
+
+/// What is drawn by a `BackgroundRenderer`.
+#[derive(Clone)]
+pub enum Background {
+    /// A vertical gradient from the `top` color to the `bottom` color.
+    Gradient {
+        /// The color at the top of the window.
+        top: Point3<f32>,
+        /// The color at the bottom of the window.
+        bottom: Point3<f32>,
+    },
+    /// A texture stretched to fill the whole window.
+    Image(Rc<Texture>),
+}
+
+/// Structure which draws a static backdrop covering the whole viewport.
+///
+/// The backdrop does not depend on the camera. It is meant to be rendered before the scene
+/// since it does not write to the depth buffer.
+pub struct BackgroundRenderer {
+    shader: Effect,
+    pos: ShaderAttribute<Point2<f32>>,
+    top: ShaderUniform<Point3<f32>>,
+    bottom: ShaderUniform<Point3<f32>>,
+    textured: ShaderUniform<i32>,
+    vertices: GPUVec<Point2<f32>>,
+    background: Background,
+}
+
+impl BackgroundRenderer {
+    /// Creates a new background renderer displaying the given background.
+    pub fn new(background: Background) -> BackgroundRenderer {
+        let mut shader = Effect::new_from_str(BACKGROUND_VERTEX_SRC, BACKGROUND_FRAGMENT_SRC);
+
+        shader.use_program();
+
+        // Two counterclockwise triangles covering the whole clip space.
+        let vertices = vec![
+            Point2::new(-1.0, -1.0),
+            Point2::new(1.0, -1.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(-1.0, -1.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(-1.0, 1.0),
+        ];
+
+        BackgroundRenderer {
+            pos: shader.get_attrib::<Point2<f32>>("position").unwrap(),
+            top: shader.get_uniform::<Point3<f32>>("top").unwrap(),
+            bottom: shader.get_uniform::<Point3<f32>>("bottom").unwrap(),
+            textured: shader.get_uniform::<i32>("textured").unwrap(),
+            vertices: GPUVec::new(vertices, BufferType::Array, AllocationType::StaticDraw),
+            shader,
+            background,
+        }
+    }
+
+    /// The background displayed by this renderer.
+    pub fn background(&self) -> &Background {
+        &self.background
+    }
+
+    /// Sets the background displayed by this renderer.
+    pub fn set_background(&mut self, background: Background) {
+        self.background = background
+    }
+}
+
+impl Renderer for BackgroundRenderer {
+    /// Actually draws the background.
+    fn render(&mut self, _: usize, _: &mut dyn Camera) {
+        let ctxt = Context::get();
+
+        self.shader.use_program();
+        self.pos.enable();
+
+        verify!(ctxt.active_texture(Context::TEXTURE0));
+
+        match self.background {
+            Background::Gradient { top, bottom } => {
+                self.top.upload(&top);
+                self.bottom.upload(&bottom);
+                self.textured.upload(&0);
+            }
+            Background::Image(ref texture) => {
+                self.textured.upload(&1);
+                verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&**texture)));
+            }
+        }
+
+        self.pos.bind(&mut self.vertices);
+
+        verify!(ctxt.depth_mask(false));
+        verify!(ctxt.draw_arrays(Context::TRIANGLES, 0, self.vertices.len() as i32));
+        verify!(ctxt.depth_mask(true));
+
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, None));
+        self.pos.disable();
+    }
+}
+
+/// Vertex shader used to display the background.
+pub static BACKGROUND_VERTEX_SRC: &'static str = A_VERY_LONG_STRING;
+/// Fragment shader used to display the background.
+pub static BACKGROUND_FRAGMENT_SRC: &'static str = ANOTHER_VERY_LONG_STRING;
+
+// The quad lies on the far plane. The first row of the texture is displayed at the top.
+const A_VERY_LONG_STRING: &'static str = "#version 100
+    attribute vec2 position;
+    varying   vec2 uv;
+    void main() {
+        gl_Position = vec4(position, 1.0, 1.0);
+        uv = vec2(position.x * 0.5 + 0.5, 0.5 - position.y * 0.5);
+    }";
+
+const ANOTHER_VERY_LONG_STRING: &'static str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+    varying vec2 uv;
+    uniform vec3 top;
+    uniform vec3 bottom;
+    uniform int textured;
+    uniform sampler2D tex;
+    void main() {
+        if (textured != 0) {
+            gl_FragColor = texture2D(tex, uv);
+        } else {
+            gl_FragColor = vec4(mix(top, bottom, uv.y), 1.0);
+        }
+    }";
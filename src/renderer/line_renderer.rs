@@ -4,7 +4,7 @@ use crate::camera::Camera;
 use crate::context::Context;
 use crate::renderer::Renderer;
 use crate::resource::{AllocationType, BufferType, Effect, GPUVec, ShaderAttribute, ShaderUniform};
-use na::{Matrix4, Point3};
+use na::{Matrix4, Point3, Vector2, Vector3};
 
 #[path = "../error.rs"]
 mod error;
@@ -17,7 +17,38 @@ pub struct LineRenderer {
     view: ShaderUniform<Matrix4<f32>>,
     proj: ShaderUniform<Matrix4<f32>>,
     lines: GPUVec<Point3<f32>>,
+    strips: GPUVec<Point3<f32>>,
+    // The first vertex and number of vertices of each line strip.
+    strip_ranges: Vec<(usize, usize)>,
     line_width: f32,
+    antialiased: bool,
+    // The length of the dashes and of the gaps between them, in pixels.
+    dash_pattern: Option<(f32, f32)>,
+    // Renders the lines as quads when they are antialiased or dashed.
+    quads: QuadLines,
+    viewport_size: Vector2<f32>,
+}
+
+// The shader and buffers drawing each line segment as a screen-space quad.
+struct QuadLines {
+    shader: Effect,
+    a: ShaderAttribute<Point3<f32>>,
+    b: ShaderAttribute<Point3<f32>>,
+    color_a: ShaderAttribute<Point3<f32>>,
+    color_b: ShaderAttribute<Point3<f32>>,
+    corner: ShaderAttribute<Vector3<f32>>,
+    view: ShaderUniform<Matrix4<f32>>,
+    proj: ShaderUniform<Matrix4<f32>>,
+    viewport: ShaderUniform<Vector2<f32>>,
+    width: ShaderUniform<f32>,
+    antialiased: ShaderUniform<i32>,
+    dashed: ShaderUniform<i32>,
+    dash: ShaderUniform<Vector2<f32>>,
+    // The endpoints of the segment of each vertex, and their colors.
+    vertices: GPUVec<Point3<f32>>,
+    // For each vertex, the position along the segment (0 or 1), the side (-1 or 1), and the
+    // length in pixels of the polyline before the segment.
+    corners: GPUVec<Vector3<f32>>,
 }
 
 impl LineRenderer {
@@ -29,6 +60,8 @@ impl LineRenderer {
 
         LineRenderer {
             lines: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            strips: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            strip_ranges: Vec::new(),
             pos: shader
                 .get_attrib::<Point3<f32>>("position")
                 .expect("Failed to get shader attribute."),
@@ -43,12 +76,52 @@ impl LineRenderer {
                 .expect("Failed to get shader uniform."),
             shader: shader,
             line_width: 1.0,
+            antialiased: false,
+            dash_pattern: None,
+            quads: QuadLines::new(),
+            viewport_size: Vector2::new(800.0, 600.0),
         }
     }
 
     /// Indicates whether some lines have to be drawn.
     pub fn needs_rendering(&self) -> bool {
-        self.lines.len() != 0
+        self.lines.len() != 0 || !self.strip_ranges.is_empty()
+    }
+
+    /// Reserves capacity for at least `additional` more line segments to be drawn during the
+    /// next frame, either with `draw_line` or as parts of polylines.
+    ///
+    /// The buffers of the lines drawn at each frame are emptied after rendering without being
+    /// deallocated, so reserving capacity once avoids reallocating them while they grow during
+    /// the first frames. The buffers of the quads are only reserved if the lines are currently
+    /// antialiased or dashed.
+    pub fn reserve(&mut self, additional: usize) {
+        for lines in self.lines.data_mut().iter_mut() {
+            lines.reserve(additional * 4);
+        }
+        for strips in self.strips.data_mut().iter_mut() {
+            strips.reserve(additional * 2);
+        }
+        self.strip_ranges.reserve(additional);
+
+        if self.antialiased || self.dash_pattern.is_some() {
+            let segments = self.lines.len() / 4
+                + self
+                    .strip_ranges
+                    .iter()
+                    .map(|&(_, count)| count - 1)
+                    .sum::<usize>()
+                + additional;
+
+            // The quads are rebuilt from scratch at each frame, so they still contain the
+            // previous frame.
+            for vertices in self.quads.vertices.data_mut().iter_mut() {
+                vertices.reserve((segments * 24).saturating_sub(vertices.len()));
+            }
+            for corners in self.quads.corners.data_mut().iter_mut() {
+                corners.reserve((segments * 6).saturating_sub(corners.len()));
+            }
+        }
     }
 
     /// Adds a line to be drawn during the next frame. Lines are not persistent between frames.
@@ -62,38 +135,509 @@ impl LineRenderer {
         }
     }
 
+    /// Adds a polyline to be drawn during the next frame. Lines are not persistent between frames.
+    ///
+    /// Each point is connected to the next one. Unlike calling `draw_line` for each segment, each
+    /// interior point is sent only once to the GPU. Distinct polylines are never joined together.
+    pub fn draw_polyline(&mut self, points: &[Point3<f32>], color: Point3<f32>) {
+        if points.len() < 2 {
+            return;
+        }
+
+        let first = self.strips.len() / 2;
+
+        for strips in self.strips.data_mut().iter_mut() {
+            for pt in points {
+                strips.push(*pt);
+                strips.push(color);
+            }
+        }
+
+        self.strip_ranges.push((first, points.len()));
+    }
+
+    /// Adds a polyline with per-point colors to be drawn during the next frame. Lines are not
+    /// persistent between frames.
+    ///
+    /// Each element of `points` is a point with its color. The color is interpolated along each
+    /// segment.
+    pub fn draw_colored_polyline(&mut self, points: &[(Point3<f32>, Point3<f32>)]) {
+        if points.len() < 2 {
+            return;
+        }
+
+        let first = self.strips.len() / 2;
+
+        for strips in self.strips.data_mut().iter_mut() {
+            for (pt, color) in points {
+                strips.push(*pt);
+                strips.push(*color);
+            }
+        }
+
+        self.strip_ranges.push((first, points.len()));
+    }
+
+    /// Adds a Catmull-Rom spline passing through `points` to be drawn during the next frame.
+    /// Lines are not persistent between frames.
+    ///
+    /// The curve is drawn as a polyline with `samples_per_segment` segments between two
+    /// consecutive points. If `closed` is `true`, the last point is also smoothly connected to
+    /// the first one. Otherwise, the curve starts at the first point and ends at the last one.
+    ///
+    /// # Failures:
+    /// Fails if `samples_per_segment` is zero.
+    pub fn draw_catmull_rom(
+        &mut self,
+        points: &[Point3<f32>],
+        samples_per_segment: usize,
+        closed: bool,
+        color: Point3<f32>,
+    ) {
+        assert!(
+            samples_per_segment > 0,
+            "Each curve segment must be sampled at least once."
+        );
+
+        let n = points.len();
+
+        if n < 2 {
+            return;
+        }
+
+        // The control point at index `i`, which may be out of bounds by one.
+        let control = |i: isize| {
+            if closed {
+                points[i.rem_euclid(n as isize) as usize]
+            } else {
+                points[i.max(0).min(n as isize - 1) as usize]
+            }
+        };
+
+        let nsegments = if closed { n } else { n - 1 };
+        let mut polyline = Vec::with_capacity(nsegments * samples_per_segment + 1);
+
+        for i in 0..nsegments as isize {
+            let (p0, p1, p2, p3) = (control(i - 1), control(i), control(i + 1), control(i + 2));
+
+            for j in 0..samples_per_segment {
+                let t = j as f32 / samples_per_segment as f32;
+                polyline.push(catmull_rom(&p0, &p1, &p2, &p3, t));
+            }
+        }
+
+        polyline.push(control(nsegments as isize));
+        self.draw_polyline(&polyline, color)
+    }
+
+    /// Adds a piecewise cubic Bézier curve to be drawn during the next frame. Lines are not
+    /// persistent between frames.
+    ///
+    /// `points` lists the start point of the curve, followed by the two control points and the
+    /// end point of each cubic segment, the end point of a segment being the start point of the
+    /// next one. If `closed` is `true`, the end point of the last segment is omitted since it is
+    /// the start point of the curve. Each segment is drawn as a polyline with
+    /// `samples_per_segment` segments.
+    ///
+    /// # Failures:
+    /// Fails if `samples_per_segment` is zero, or if the number of points is not `3 * k + 1` for
+    /// an open curve, or `3 * k` for a closed one.
+    pub fn draw_bezier(
+        &mut self,
+        points: &[Point3<f32>],
+        samples_per_segment: usize,
+        closed: bool,
+        color: Point3<f32>,
+    ) {
+        assert!(
+            samples_per_segment > 0,
+            "Each curve segment must be sampled at least once."
+        );
+
+        let n = points.len();
+
+        if n == 0 {
+            return;
+        }
+
+        if closed {
+            assert!(
+                n % 3 == 0,
+                "A closed Bézier curve must have 3 points per segment."
+            );
+        } else {
+            assert!(
+                n % 3 == 1,
+                "An open Bézier curve must have 3 points per segment, plus its start point."
+            );
+        }
+
+        let nsegments = n / 3;
+        let mut polyline = Vec::with_capacity(nsegments * samples_per_segment + 1);
+
+        for i in 0..nsegments {
+            let (p0, p1, p2, p3) = (
+                points[3 * i],
+                points[3 * i + 1],
+                points[3 * i + 2],
+                points[(3 * i + 3) % n],
+            );
+
+            for j in 0..samples_per_segment {
+                let t = j as f32 / samples_per_segment as f32;
+                polyline.push(cubic_bezier(&p0, &p1, &p2, &p3, t));
+            }
+        }
+
+        polyline.push(points[(3 * nsegments) % n]);
+        self.draw_polyline(&polyline, color)
+    }
+
     /// Sets the line width for the rendered lines.
+    ///
+    /// Note that many OpenGL core profile implementations (and most WebGL implementations) only
+    /// support a line width of `1.0`, in which case any other value is clamped by the driver.
+    /// Antialiased lines are not affected by this limitation.
     pub fn set_line_width(&mut self, line_width: f32) {
         self.line_width = line_width;
     }
+
+    /// Enables or disables the antialiasing of the rendered lines. Disabled by default.
+    ///
+    /// Antialiased lines are drawn as quads facing the camera, with the width set by
+    /// `set_line_width` in pixels, and edges fading out over about one pixel. They are blended
+    /// with what is behind them. Each segment of a polyline is drawn on its own, so sharp
+    /// corners of wide polylines show small notches.
+    pub fn set_antialiased(&mut self, antialiased: bool) {
+        self.antialiased = antialiased;
+    }
+
+    /// Whether the rendered lines are antialiased.
+    pub fn is_antialiased(&self) -> bool {
+        self.antialiased
+    }
+
+    /// Sets the dash pattern of the rendered lines, i.e., the length of the dashes and of the
+    /// gaps between them, in pixels. Set to `None` to render solid lines, the default.
+    ///
+    /// The lengths are measured on the screen, so the dashes keep the same size when zooming.
+    /// The pattern runs continuously along each polyline, and restarts at each line drawn with
+    /// `draw_line`. Dashed lines are drawn as quads like antialiased lines, so their width is
+    /// not limited by the driver either.
+    ///
+    /// # Failures:
+    /// Fails if the length of the dashes is not positive, or the length of the gaps is negative.
+    pub fn set_dash_pattern(&mut self, pattern: Option<(f32, f32)>) {
+        if let Some((dash, gap)) = pattern {
+            assert!(
+                dash > 0.0 && gap >= 0.0,
+                "The dash length must be positive and the gap length non-negative."
+            );
+        }
+
+        self.dash_pattern = pattern;
+    }
+
+    /// The dash pattern of the rendered lines, if they are dashed.
+    pub fn dash_pattern(&self) -> Option<(f32, f32)> {
+        self.dash_pattern
+    }
+
+    /// Sets the size, in pixels, of the viewport the lines are rendered to.
+    ///
+    /// This is needed to compute the width of the antialiased lines and the length of the
+    /// dashes, and is set automatically by the `Window`.
+    pub fn set_viewport_size(&mut self, width: u32, height: u32) {
+        self.viewport_size = Vector2::new(width.max(1) as f32, height.max(1) as f32);
+    }
+
+    // Fills the buffers of the quads with the segments of the lines and polylines.
+    fn build_quads(&mut self, camera: &dyn Camera) {
+        let quads = &mut self.quads;
+        let lines = self.lines.data().as_ref();
+        let strips = self.strips.data().as_ref();
+        let transformation = camera.transformation();
+        let viewport = self.viewport_size;
+        // The length on the screen, in pixels, of the part of a segment in front of the near
+        // plane, clipped as in the vertex shader.
+        let screen_length = |a: &Point3<f32>, b: &Point3<f32>| {
+            let ca = transformation * a.to_homogeneous();
+            let cb = transformation * b.to_homogeneous();
+            let (da, db) = (ca.z + ca.w, cb.z + cb.w);
+
+            if da < 0.0 && db < 0.0 {
+                return 0.0;
+            }
+
+            let ca = if da < 0.0 {
+                ca + (cb - ca) * (da / (da - db))
+            } else {
+                ca
+            };
+            let cb = if db < 0.0 {
+                cb + (ca - cb) * (db / (db - da))
+            } else {
+                cb
+            };
+            let sa = (ca.xy() / ca.w).component_mul(&viewport) * 0.5;
+            let sb = (cb.xy() / cb.w).component_mul(&viewport) * 0.5;
+
+            (sb - sa).norm()
+        };
+
+        if let (Some(vertices), Some(corners)) = (
+            quads.vertices.data_mut().as_mut(),
+            quads.corners.data_mut().as_mut(),
+        ) {
+            vertices.clear();
+            corners.clear();
+
+            let mut push_segment = |a: Point3<f32>,
+                                    b: Point3<f32>,
+                                    color_a: Point3<f32>,
+                                    color_b: Point3<f32>,
+                                    dist: f32| {
+                // Two triangles covering the quad around the segment.
+                for &(along, side) in &[
+                    (0.0, 1.0),
+                    (0.0, -1.0),
+                    (1.0, 1.0),
+                    (0.0, -1.0),
+                    (1.0, -1.0),
+                    (1.0, 1.0),
+                ] {
+                    vertices.push(a);
+                    vertices.push(b);
+                    vertices.push(color_a);
+                    vertices.push(color_b);
+                    corners.push(Vector3::new(along, side, dist));
+                }
+            };
+
+            if let Some(lines) = lines {
+                for l in lines.chunks_exact(4) {
+                    push_segment(l[0], l[2], l[1], l[3], 0.0);
+                }
+            }
+
+            if let Some(strips) = strips {
+                for &(first, count) in &self.strip_ranges {
+                    let mut dist = 0.0;
+
+                    for i in first..first + count - 1 {
+                        let (a, b) = (strips[i * 2], strips[i * 2 + 2]);
+                        push_segment(a, b, strips[i * 2 + 1], strips[i * 2 + 3], dist);
+                        dist += screen_length(&a, &b);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// The point at `t` in `[0, 1]` on the uniform Catmull-Rom segment between `p1` and `p2`.
+fn catmull_rom(
+    p0: &Point3<f32>,
+    p1: &Point3<f32>,
+    p2: &Point3<f32>,
+    p3: &Point3<f32>,
+    t: f32,
+) -> Point3<f32> {
+    let (p0, p1, p2, p3) = (p0.coords, p1.coords, p2.coords, p3.coords);
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    Point3::from(
+        (p1 * 2.0
+            + (p2 - p0) * t
+            + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+            + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+            * 0.5,
+    )
+}
+
+// The point at `t` in `[0, 1]` on the cubic Bézier segment from `p0` to `p3`.
+fn cubic_bezier(
+    p0: &Point3<f32>,
+    p1: &Point3<f32>,
+    p2: &Point3<f32>,
+    p3: &Point3<f32>,
+    t: f32,
+) -> Point3<f32> {
+    let s = 1.0 - t;
+
+    Point3::from(
+        p0.coords * (s * s * s)
+            + p1.coords * (3.0 * s * s * t)
+            + p2.coords * (3.0 * s * t * t)
+            + p3.coords * (t * t * t),
+    )
 }
 
 impl Renderer for LineRenderer {
     /// Actually draws the lines.
     fn render(&mut self, pass: usize, camera: &mut dyn Camera) {
-        if self.lines.len() == 0 {
+        if !self.needs_rendering() {
             return;
         }
 
-        self.shader.use_program();
-        self.pos.enable();
-        self.color.enable();
+        if self.antialiased || self.dash_pattern.is_some() {
+            self.build_quads(&*camera);
+            self.quads.render(
+                pass,
+                camera,
+                &self.viewport_size,
+                self.line_width,
+                self.antialiased,
+                self.dash_pattern,
+            );
+        } else {
+            self.shader.use_program();
+            self.pos.enable();
+            self.color.enable();
 
-        camera.upload(pass, &mut self.proj, &mut self.view);
+            camera.upload(pass, &mut self.proj, &mut self.view);
 
-        self.color.bind_sub_buffer(&mut self.lines, 1, 1);
-        self.pos.bind_sub_buffer(&mut self.lines, 1, 0);
+            let ctxt = Context::get();
+            // Wide lines may not be supported by the driver, so ignore the resulting error.
+            ignore!(ctxt.line_width(self.line_width));
 
-        let ctxt = Context::get();
-        verify!(ctxt.draw_arrays(Context::LINES, 0, (self.lines.len() / 2) as i32));
-        verify!(ctxt.line_width(self.line_width));
+            if self.lines.len() != 0 {
+                self.color.bind_sub_buffer(&mut self.lines, 1, 1);
+                self.pos.bind_sub_buffer(&mut self.lines, 1, 0);
+                verify!(ctxt.draw_arrays(Context::LINES, 0, (self.lines.len() / 2) as i32));
+            }
+
+            if !self.strip_ranges.is_empty() {
+                self.color.bind_sub_buffer(&mut self.strips, 1, 1);
+                self.pos.bind_sub_buffer(&mut self.strips, 1, 0);
 
-        self.pos.disable();
-        self.color.disable();
+                // One draw call per strip so that consecutive polylines are not joined.
+                for &(first, count) in &self.strip_ranges {
+                    verify!(ctxt.draw_arrays(Context::LINE_STRIP, first as i32, count as i32));
+                }
+            }
+
+            self.pos.disable();
+            self.color.disable();
+        }
 
         for lines in self.lines.data_mut().iter_mut() {
             lines.clear()
         }
+        for strips in self.strips.data_mut().iter_mut() {
+            strips.clear()
+        }
+        self.strip_ranges.clear();
+    }
+}
+
+impl QuadLines {
+    fn new() -> QuadLines {
+        let mut shader = Effect::new_from_str(QUAD_LINES_VERTEX_SRC, QUAD_LINES_FRAGMENT_SRC);
+
+        shader.use_program();
+
+        QuadLines {
+            a: shader
+                .get_attrib::<Point3<f32>>("a")
+                .expect("Failed to get shader attribute."),
+            b: shader
+                .get_attrib::<Point3<f32>>("b")
+                .expect("Failed to get shader attribute."),
+            color_a: shader
+                .get_attrib::<Point3<f32>>("color_a")
+                .expect("Failed to get shader attribute."),
+            color_b: shader
+                .get_attrib::<Point3<f32>>("color_b")
+                .expect("Failed to get shader attribute."),
+            corner: shader
+                .get_attrib::<Vector3<f32>>("corner")
+                .expect("Failed to get shader attribute."),
+            view: shader
+                .get_uniform::<Matrix4<f32>>("view")
+                .expect("Failed to get shader uniform."),
+            proj: shader
+                .get_uniform::<Matrix4<f32>>("proj")
+                .expect("Failed to get shader uniform."),
+            viewport: shader
+                .get_uniform::<Vector2<f32>>("viewport")
+                .expect("Failed to get shader uniform."),
+            width: shader
+                .get_uniform::<f32>("width")
+                .expect("Failed to get shader uniform."),
+            antialiased: shader
+                .get_uniform::<i32>("antialiased")
+                .expect("Failed to get shader uniform."),
+            dashed: shader
+                .get_uniform::<i32>("dashed")
+                .expect("Failed to get shader uniform."),
+            dash: shader
+                .get_uniform::<Vector2<f32>>("dash")
+                .expect("Failed to get shader uniform."),
+            shader,
+            vertices: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            corners: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+        }
+    }
+
+    fn render(
+        &mut self,
+        pass: usize,
+        camera: &mut dyn Camera,
+        viewport: &Vector2<f32>,
+        width: f32,
+        antialiased: bool,
+        dash_pattern: Option<(f32, f32)>,
+    ) {
+        if self.corners.len() == 0 {
+            return;
+        }
+
+        self.shader.use_program();
+        self.a.enable();
+        self.b.enable();
+        self.color_a.enable();
+        self.color_b.enable();
+        self.corner.enable();
+
+        camera.upload(pass, &mut self.proj, &mut self.view);
+        self.viewport.upload(viewport);
+        self.width.upload(&width);
+        self.antialiased.upload(&(antialiased as i32));
+
+        match dash_pattern {
+            Some((dash, gap)) => {
+                self.dashed.upload(&1);
+                self.dash.upload(&Vector2::new(dash, gap));
+            }
+            None => self.dashed.upload(&0),
+        }
+
+        self.a.bind_sub_buffer(&mut self.vertices, 3, 0);
+        self.b.bind_sub_buffer(&mut self.vertices, 3, 1);
+        self.color_a.bind_sub_buffer(&mut self.vertices, 3, 2);
+        self.color_b.bind_sub_buffer(&mut self.vertices, 3, 3);
+        self.corner.bind(&mut self.corners);
+
+        // The edges of antialiased quads fade out, so they must be blended with what is behind
+        // them.
+        let ctxt = Context::get();
+        verify!(ctxt.enable(Context::BLEND));
+        verify!(ctxt.blend_func_separate(
+            Context::SRC_ALPHA,
+            Context::ONE_MINUS_SRC_ALPHA,
+            Context::ONE,
+            Context::ONE_MINUS_SRC_ALPHA,
+        ));
+        verify!(ctxt.draw_arrays(Context::TRIANGLES, 0, self.corners.len() as i32));
+        verify!(ctxt.disable(Context::BLEND));
+
+        self.a.disable();
+        self.b.disable();
+        self.color_a.disable();
+        self.color_b.disable();
+        self.corner.disable();
     }
 }
 
@@ -124,3 +668,147 @@ const ANOTHER_VERY_LONG_STRING: &'static str = "#version 100
     void main() {
         gl_FragColor = vec4(vColor, 1.0);
     }";
+
+/// Vertex shader used to display antialiased lines as screen-space quads.
+pub static QUAD_LINES_VERTEX_SRC: &'static str = QUAD_LINES_VERTEX_STRING;
+/// Fragment shader used to display antialiased lines as screen-space quads.
+pub static QUAD_LINES_FRAGMENT_SRC: &'static str = QUAD_LINES_FRAGMENT_STRING;
+
+const QUAD_LINES_VERTEX_STRING: &'static str = "#version 100
+    attribute vec3 a;
+    attribute vec3 b;
+    attribute vec3 color_a;
+    attribute vec3 color_b;
+    // The position along the segment (0 at `a`, 1 at `b`), the side of the vertex, and the
+    // length in pixels of the polyline before the segment.
+    attribute vec3 corner;
+    varying   vec3 vColor;
+    // The signed distance to the segment, and the distance at which the line starts fading out,
+    // in pixels.
+    varying   float vEdge;
+    varying   float vHalfWidth;
+    // The start and direction of the segment on the screen, in pixels, and the length of the
+    // polyline before it.
+    varying   vec2 vStart;
+    varying   vec2 vDir;
+    varying   float vDist;
+    uniform   mat4 proj;
+    uniform   mat4 view;
+    uniform   vec2 viewport;
+    uniform   float width;
+    uniform   int antialiased;
+    void main() {
+        vec4 ca = proj * view * vec4(a, 1.0);
+        vec4 cb = proj * view * vec4(b, 1.0);
+        vec3 colorA = color_a;
+        vec3 colorB = color_b;
+        // The signed distances of the endpoints to the near plane, negative behind it.
+        float da = ca.z + ca.w;
+        float db = cb.z + cb.w;
+
+        // The segment is clipped against the near plane before the perspective division, which
+        // would otherwise mirror the endpoints behind the camera.
+        if (da < 0.0 && db < 0.0) {
+            // Entirely behind the camera: the quad is moved outside of the view volume.
+            gl_Position = vec4(2.0, 2.0, 2.0, 1.0);
+            return;
+        }
+
+        if (da < 0.0) {
+            float t = da / (da - db);
+            ca = mix(ca, cb, t);
+            colorA = mix(colorA, colorB, t);
+        } else if (db < 0.0) {
+            float t = db / (db - da);
+            cb = mix(cb, ca, t);
+            colorB = mix(colorB, colorA, t);
+        }
+
+        vec2 sa = (ca.xy / ca.w * 0.5 + 0.5) * viewport;
+        vec2 sb = (cb.xy / cb.w * 0.5 + 0.5) * viewport;
+        vec2 dir = sb - sa;
+
+        if (dot(dir, dir) < 1.0e-12) {
+            dir = vec2(1.0, 0.0);
+        }
+
+        dir = normalize(dir);
+
+        // Antialiased lines are one pixel wider for their edges to fade out.
+        float extent = width * 0.5;
+
+        if (antialiased != 0) {
+            extent += 1.0;
+            vHalfWidth = width * 0.5;
+        } else {
+            vHalfWidth = extent + 1.0;
+        }
+
+        vec4 pos = mix(ca, cb, corner.x);
+        vec2 offset = vec2(-dir.y, dir.x) * corner.y * extent;
+
+        // The offset is converted from pixels to clip coordinates.
+        gl_Position = pos + vec4(offset * 2.0 / viewport * pos.w, 0.0, 0.0);
+        vColor = mix(colorA, colorB, corner.x);
+        vEdge = corner.y * extent;
+        vStart = sa;
+        vDir = dir;
+        vDist = corner.z;
+    }";
+
+const QUAD_LINES_FRAGMENT_STRING: &'static str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+    varying vec3 vColor;
+    varying float vEdge;
+    varying float vHalfWidth;
+    varying vec2 vStart;
+    varying vec2 vDir;
+    varying float vDist;
+    uniform int dashed;
+    // The length of the dashes and of the gaps, in pixels.
+    uniform vec2 dash;
+    void main() {
+        if (dashed != 0) {
+            float dist = vDist + dot(gl_FragCoord.xy - vStart, vDir);
+
+            if (mod(dist, dash.x + dash.y) > dash.x) {
+                discard;
+            }
+        }
+
+        float alpha = clamp(vHalfWidth + 0.5 - abs(vEdge), 0.0, 1.0);
+        gl_FragColor = vec4(vColor, alpha);
+    }";
+
+#[cfg(test)]
+mod tests {
+    use crate::camera::ArcBall;
+    use crate::context::Context;
+    use crate::window::open_test_window;
+    use na::Point3;
+
+    #[test]
+    #[ignore = "requires a display and an OpenGL context"]
+    fn wide_lines_do_not_raise_gl_errors() {
+        let (_lock, mut window) = open_test_window();
+        let mut camera = ArcBall::new(Point3::new(0.0, 0.0, 10.0), Point3::origin());
+
+        window.set_background_color(0.0, 0.0, 0.0);
+        window.set_line_width(5.0);
+        window.draw_line(
+            &Point3::new(-1.0, 0.0, 0.0),
+            &Point3::new(1.0, 0.0, 0.0),
+            &Point3::new(1.0, 1.0, 1.0),
+        );
+
+        let img = window.render_to_image_with(Some(&mut camera), None, None);
+
+        assert_eq!(Context::get().get_error(), 0);
+        assert!(img.pixels().any(|px| px.0.iter().any(|c| *c > 127)));
+    }
+}
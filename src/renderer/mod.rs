@@ -1,13 +1,17 @@
 //! Structures responsible for rendering elements other than kiss3d's meshes.
 
+pub use self::background_renderer::{Background, BackgroundRenderer};
 #[cfg(feature = "conrod")]
 pub use self::conrod_renderer::ConrodRenderer;
 pub use self::line_renderer::LineRenderer;
-pub use self::point_renderer::PointRenderer;
+pub use self::point_renderer::{PointId, PointRenderer, PointShape};
 pub use self::renderer::Renderer;
+pub use self::skybox_renderer::SkyboxRenderer;
 
+pub mod background_renderer;
 #[cfg(feature = "conrod")]
 mod conrod_renderer;
 pub mod line_renderer;
 pub mod point_renderer;
 mod renderer;
+pub mod skybox_renderer;
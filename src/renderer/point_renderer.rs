@@ -4,22 +4,54 @@ use crate::camera::Camera;
 use crate::context::Context;
 use crate::renderer::Renderer;
 use crate::resource::{AllocationType, BufferType, Effect, GPUVec, ShaderAttribute, ShaderUniform};
-use na::{Matrix4, Point3};
+use na::{Matrix4, Point3, Point4, Vector3};
 
 #[path = "../error.rs"]
 mod error;
 
-/// Structure which manages the display of short-living points.
+/// The shape of the points rendered by a `PointRenderer`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PointShape {
+    /// Points are rendered as squares.
+    Square,
+    /// Points are rendered as disks.
+    Round,
+}
+
+/// Identifier of a persistent point added to a `PointRenderer`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PointId(usize);
+
+/// Structure which manages the display of short-living and persistent points.
 pub struct PointRenderer {
     shader: Effect,
     pos: ShaderAttribute<Point3<f32>>,
     color: ShaderAttribute<Point3<f32>>,
     size: ShaderAttribute<f32>,
+    alpha: ShaderAttribute<f32>,
     proj: ShaderUniform<Matrix4<f32>>,
     view: ShaderUniform<Matrix4<f32>>,
+    round: ShaderUniform<i32>,
     pub points: GPUVec<Point3<f32>>,
     pub sizes: GPUVec<f32>,
+    alphas: GPUVec<f32>,
+    // Whether a point with an opacity smaller than 1 was added since the last frame.
+    translucent: bool,
+    persistent_points: GPUVec<Point3<f32>>,
+    persistent_sizes: GPUVec<f32>,
+    persistent_alphas: GPUVec<f32>,
+    // Maps a point id to its index on the persistent buffers.
+    persistent_slots: Vec<Option<usize>>,
+    // Maps an index on the persistent buffers to the id of the point it contains.
+    persistent_ids: Vec<usize>,
+    free_ids: Vec<usize>,
     point_size: f32,
+    point_shape: PointShape,
+    transparent: bool,
+    // The persistent and per-frame points sorted from back to front, if transparent.
+    sorted_points: GPUVec<Point3<f32>>,
+    sorted_sizes: GPUVec<f32>,
+    sorted_alphas: GPUVec<f32>,
 }
 
 impl PointRenderer {
@@ -32,26 +64,118 @@ impl PointRenderer {
         PointRenderer {
             points: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
             sizes: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            alphas: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            translucent: false,
+            persistent_points: GPUVec::new(
+                Vec::new(),
+                BufferType::Array,
+                AllocationType::DynamicDraw,
+            ),
+            persistent_sizes: GPUVec::new(
+                Vec::new(),
+                BufferType::Array,
+                AllocationType::DynamicDraw,
+            ),
+            persistent_alphas: GPUVec::new(
+                Vec::new(),
+                BufferType::Array,
+                AllocationType::DynamicDraw,
+            ),
+            persistent_slots: Vec::new(),
+            persistent_ids: Vec::new(),
+            free_ids: Vec::new(),
             pos: shader.get_attrib::<Point3<f32>>("position").unwrap(),
             color: shader.get_attrib::<Point3<f32>>("color").unwrap(),
             size: shader.get_attrib::<f32>("size").unwrap(),
+            alpha: shader.get_attrib::<f32>("alpha").unwrap(),
             proj: shader.get_uniform::<Matrix4<f32>>("proj").unwrap(),
             view: shader.get_uniform::<Matrix4<f32>>("view").unwrap(),
+            round: shader.get_uniform::<i32>("round").unwrap(),
             shader,
             point_size: 1.0,
+            point_shape: PointShape::Square,
+            transparent: false,
+            sorted_points: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            sorted_sizes: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            sorted_alphas: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
         }
     }
 
     /// Indicates whether some points have to be drawn.
     pub fn needs_rendering(&self) -> bool {
-        self.points.len() != 0
+        self.points.len() != 0 || self.persistent_points.len() != 0
     }
 
-    /// Sets the point size for the rendered points.
+    /// Sets the point size for the points subsequently added with `draw_point`.
+    ///
+    /// Points already submitted, and points added with `draw_point_with_size`, keep their own
+    /// size.
     pub fn set_point_size(&mut self, pt_size: f32) {
         self.point_size = pt_size;
     }
 
+    /// Sets the shape of all the rendered points.
+    ///
+    /// Points are rendered as squares by default.
+    pub fn set_point_shape(&mut self, shape: PointShape) {
+        self.point_shape = shape;
+    }
+
+    /// The shape of the rendered points.
+    pub fn point_shape(&self) -> PointShape {
+        self.point_shape
+    }
+
+    /// Enables or disables the rendering of the points as transparent. Disabled by default.
+    ///
+    /// When enabled, the persistent and per-frame points are sorted at each frame from the
+    /// farthest to the nearest along the view direction of the camera, and blended with what is
+    /// behind them without writing into the depth buffer. This avoids the artifacts of
+    /// semi-transparent points hiding the points behind them, at the cost of sorting every point
+    /// at each frame.
+    pub fn set_transparent(&mut self, transparent: bool) {
+        self.transparent = transparent;
+    }
+
+    /// Whether the points are rendered as transparent.
+    pub fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+
+    /// Reserves capacity for at least `additional` more points to be drawn during the next frame.
+    ///
+    /// The buffers of the points drawn at each frame are emptied after rendering without being
+    /// deallocated, so reserving capacity once avoids reallocating them while they grow during
+    /// the first frames. The buffers of the sorted points are only reserved if the points are
+    /// currently transparent.
+    pub fn reserve(&mut self, additional: usize) {
+        for points in self.points.data_mut().iter_mut() {
+            points.reserve(additional * 2);
+        }
+        for sizes in self.sizes.data_mut().iter_mut() {
+            sizes.reserve(additional);
+        }
+        for alphas in self.alphas.data_mut().iter_mut() {
+            alphas.reserve(additional);
+        }
+
+        if self.transparent {
+            let npoints = self.persistent_sizes.len() + self.points.len() / 2 + additional;
+
+            // The sorted points are rebuilt from scratch at each frame, so they still contain
+            // the previous frame.
+            for points in self.sorted_points.data_mut().iter_mut() {
+                points.reserve((npoints * 2).saturating_sub(points.len()));
+            }
+            for sizes in self.sorted_sizes.data_mut().iter_mut() {
+                sizes.reserve(npoints.saturating_sub(sizes.len()));
+            }
+            for alphas in self.sorted_alphas.data_mut().iter_mut() {
+                alphas.reserve(npoints.saturating_sub(alphas.len()));
+            }
+        }
+    }
+
     /// Adds a point to be drawn during the next frame. Points are not persistent between frames.
     /// This method must be called for each point to draw, and at each update loop iteration.
     pub fn draw_point(&mut self, pt: Point3<f32>, color: Point3<f32>) {
@@ -62,9 +186,13 @@ impl PointRenderer {
         for sizes in self.sizes.data_mut().iter_mut() {
             sizes.push(self.point_size);
         }
+        for alphas in self.alphas.data_mut().iter_mut() {
+            alphas.push(1.0);
+        }
     }
-    
-    /// Adds a point to be drawn during the next frame. Points are not persistent between frames.
+
+    /// Adds a point with a specific size to be drawn during the next frame. Points are not
+    /// persistent between frames.
     /// This method must be called for each point to draw, and at each update loop iteration.
     pub fn draw_point_with_size(&mut self, pt: Point3<f32>, color: Point3<f32>, size: f32) {
         for points in self.points.data_mut().iter_mut() {
@@ -74,34 +202,273 @@ impl PointRenderer {
         for sizes in self.sizes.data_mut().iter_mut() {
             sizes.push(size);
         }
+        for alphas in self.alphas.data_mut().iter_mut() {
+            alphas.push(1.0);
+        }
+    }
+
+    /// Adds a semi-transparent point with a specific size to be drawn during the next frame.
+    /// Points are not persistent between frames.
+    ///
+    /// The last component of `color` is the opacity of the point, in `[0, 1]`. The points are
+    /// blended with what is behind them during the frames where a point with an opacity smaller
+    /// than 1 is added. Enable `set_transparent` to also sort them, so that they do not hide
+    /// the points drawn after them.
+    pub fn draw_point_rgba(&mut self, pt: Point3<f32>, color: Point4<f32>, size: f32) {
+        let alpha = color.w.max(0.0).min(1.0);
+        self.translucent = self.translucent || alpha < 1.0;
+
+        for points in self.points.data_mut().iter_mut() {
+            points.push(pt);
+            points.push(color.xyz());
+        }
+        for sizes in self.sizes.data_mut().iter_mut() {
+            sizes.push(size);
+        }
+        for alphas in self.alphas.data_mut().iter_mut() {
+            alphas.push(alpha);
+        }
+    }
+
+    /// Adds a point that will be drawn at each frame until it is removed.
+    ///
+    /// Returns an identifier that can be used to remove this point with
+    /// `remove_persistent_point`. Identifiers of points that are still alive are never
+    /// invalidated by the removal of other points.
+    pub fn add_persistent_point(
+        &mut self,
+        pt: Point3<f32>,
+        color: Point3<f32>,
+        size: f32,
+    ) -> PointId {
+        let slot = self.persistent_ids.len();
+        let id = match self.free_ids.pop() {
+            Some(id) => {
+                self.persistent_slots[id] = Some(slot);
+                id
+            }
+            None => {
+                self.persistent_slots.push(Some(slot));
+                self.persistent_slots.len() - 1
+            }
+        };
+
+        self.persistent_ids.push(id);
+
+        for points in self.persistent_points.data_mut().iter_mut() {
+            points.push(pt);
+            points.push(color);
+        }
+        for sizes in self.persistent_sizes.data_mut().iter_mut() {
+            sizes.push(size);
+        }
+        for alphas in self.persistent_alphas.data_mut().iter_mut() {
+            alphas.push(1.0);
+        }
+
+        PointId(id)
+    }
+
+    /// Removes a persistent point.
+    ///
+    /// Returns `false` if the point had already been removed.
+    pub fn remove_persistent_point(&mut self, id: PointId) -> bool {
+        let slot = match self.persistent_slots.get(id.0).cloned().flatten() {
+            Some(slot) => slot,
+            None => return false,
+        };
+        let last = self.persistent_ids.len() - 1;
+
+        for points in self.persistent_points.data_mut().iter_mut() {
+            points.swap(slot * 2, last * 2);
+            points.swap(slot * 2 + 1, last * 2 + 1);
+            points.truncate(last * 2);
+        }
+        for sizes in self.persistent_sizes.data_mut().iter_mut() {
+            let _ = sizes.swap_remove(slot);
+        }
+        for alphas in self.persistent_alphas.data_mut().iter_mut() {
+            let _ = alphas.swap_remove(slot);
+        }
+
+        let _ = self.persistent_ids.swap_remove(slot);
+
+        if slot != last {
+            let moved = self.persistent_ids[slot];
+            self.persistent_slots[moved] = Some(slot);
+        }
+
+        self.persistent_slots[id.0] = None;
+        self.free_ids.push(id.0);
+
+        true
+    }
+
+    /// Removes all the persistent points.
+    pub fn clear_persistent(&mut self) {
+        for points in self.persistent_points.data_mut().iter_mut() {
+            points.clear()
+        }
+        for sizes in self.persistent_sizes.data_mut().iter_mut() {
+            sizes.clear()
+        }
+        for alphas in self.persistent_alphas.data_mut().iter_mut() {
+            alphas.clear()
+        }
+
+        self.persistent_slots.clear();
+        self.persistent_ids.clear();
+        self.free_ids.clear();
+    }
+
+    /// The number of persistent points.
+    pub fn num_persistent_points(&self) -> usize {
+        self.persistent_ids.len()
+    }
+
+    // `points` and `sizes` are public, so they may have been modified without the opacities:
+    // resize the per-point attributes so that each point has exactly one of each.
+    fn sync_attributes(&mut self) {
+        let npoints = self.points.len() / 2;
+        let point_size = self.point_size;
+
+        if self.sizes.len() != npoints {
+            for sizes in self.sizes.data_mut().iter_mut() {
+                sizes.resize(npoints, point_size);
+            }
+        }
+
+        if self.alphas.len() != npoints {
+            for alphas in self.alphas.data_mut().iter_mut() {
+                alphas.resize(npoints, 1.0);
+            }
+        }
+    }
+
+    // Fills the sorted buffers with the persistent and per-frame points, from the farthest to the
+    // nearest along the view direction of the camera.
+    fn sort_points(&mut self, camera: &dyn Camera) {
+        // The view-space `z` of a point is its dot product with `dir`, up to a constant.
+        let dir = camera.view_transform().rotation.inverse() * Vector3::z();
+        let mut sorted = Vec::with_capacity(self.points.len() / 2 + self.persistent_sizes.len());
+
+        for (points, sizes, alphas) in [
+            (
+                &self.persistent_points,
+                &self.persistent_sizes,
+                &self.persistent_alphas,
+            ),
+            (&self.points, &self.sizes, &self.alphas),
+        ]
+        .iter()
+        {
+            if let (Some(points), Some(sizes), Some(alphas)) =
+                (points.data(), sizes.data(), alphas.data())
+            {
+                for ((pc, size), alpha) in points.chunks_exact(2).zip(sizes.iter()).zip(alphas) {
+                    sorted.push((pc[0].coords.dot(&dir), pc[0], pc[1], *size, *alpha));
+                }
+            }
+        }
+
+        // The farthest points have the smallest view-space `z`.
+        sorted.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        if let (Some(points), Some(sizes), Some(alphas)) = (
+            self.sorted_points.data_mut().as_mut(),
+            self.sorted_sizes.data_mut().as_mut(),
+            self.sorted_alphas.data_mut().as_mut(),
+        ) {
+            points.clear();
+            sizes.clear();
+            alphas.clear();
+
+            for (_, pt, color, size, alpha) in sorted {
+                points.push(pt);
+                points.push(color);
+                sizes.push(size);
+                alphas.push(alpha);
+            }
+        }
     }
 }
 
 impl Renderer for PointRenderer {
     /// Actually draws the points.
     fn render(&mut self, pass: usize, camera: &mut dyn Camera) {
-        if self.points.len() == 0 {
+        if !self.needs_rendering() {
             return;
         }
 
+        self.sync_attributes();
         self.shader.use_program();
         self.pos.enable();
         self.color.enable();
         self.size.enable();
+        self.alpha.enable();
 
         camera.upload(pass, &mut self.proj, &mut self.view);
-
-        self.color.bind_sub_buffer(&mut self.points, 1, 1);
-        self.pos.bind_sub_buffer(&mut self.points, 1, 0);
-        self.size.bind_sub_buffer(&mut self.sizes, 0, 0);
+        self.round
+            .upload(&((self.point_shape == PointShape::Round) as i32));
 
         let ctxt = Context::get();
-        verify!(ctxt.point_size(self.point_size));
-        verify!(ctxt.draw_arrays(Context::POINTS, 0, (self.points.len() / 2) as i32));
+        let blend = self.transparent || self.translucent;
+
+        if blend {
+            verify!(ctxt.enable(Context::BLEND));
+            verify!(ctxt.blend_func_separate(
+                Context::SRC_ALPHA,
+                Context::ONE_MINUS_SRC_ALPHA,
+                Context::ONE,
+                Context::ONE_MINUS_SRC_ALPHA,
+            ));
+        }
+
+        if self.transparent {
+            self.sort_points(&*camera);
+
+            verify!(ctxt.depth_mask(false));
+
+            draw_buffers(
+                &mut self.pos,
+                &mut self.color,
+                &mut self.size,
+                &mut self.alpha,
+                &mut self.sorted_points,
+                &mut self.sorted_sizes,
+                &mut self.sorted_alphas,
+            );
+
+            verify!(ctxt.depth_mask(true));
+        } else {
+            draw_buffers(
+                &mut self.pos,
+                &mut self.color,
+                &mut self.size,
+                &mut self.alpha,
+                &mut self.persistent_points,
+                &mut self.persistent_sizes,
+                &mut self.persistent_alphas,
+            );
+            draw_buffers(
+                &mut self.pos,
+                &mut self.color,
+                &mut self.size,
+                &mut self.alpha,
+                &mut self.points,
+                &mut self.sizes,
+                &mut self.alphas,
+            );
+        }
+
+        if blend {
+            verify!(ctxt.disable(Context::BLEND));
+        }
 
         self.pos.disable();
         self.color.disable();
         self.size.disable();
+        self.alpha.disable();
 
         for points in self.points.data_mut().iter_mut() {
             points.clear()
@@ -109,9 +476,38 @@ impl Renderer for PointRenderer {
         for sizes in self.sizes.data_mut().iter_mut() {
             sizes.clear()
         }
+        for alphas in self.alphas.data_mut().iter_mut() {
+            alphas.clear()
+        }
+
+        self.translucent = false;
     }
 }
 
+fn draw_buffers(
+    pos: &mut ShaderAttribute<Point3<f32>>,
+    color: &mut ShaderAttribute<Point3<f32>>,
+    size: &mut ShaderAttribute<f32>,
+    alpha: &mut ShaderAttribute<f32>,
+    points: &mut GPUVec<Point3<f32>>,
+    sizes: &mut GPUVec<f32>,
+    alphas: &mut GPUVec<f32>,
+) {
+    if points.len() == 0 {
+        return;
+    }
+
+    color.bind_sub_buffer(points, 1, 1);
+    pos.bind_sub_buffer(points, 1, 0);
+    size.bind_sub_buffer(sizes, 0, 0);
+    alpha.bind_sub_buffer(alphas, 0, 0);
+
+    // NOTE: we don't call `ctxt.point_size` here since it would override the per-point size
+    // attribute on some drivers. `gl_PointSize` is always set from the `size` attribute.
+    let ctxt = Context::get();
+    verify!(ctxt.draw_arrays(Context::POINTS, 0, (points.len() / 2) as i32));
+}
+
 /// Vertex shader used by the material to display point.
 pub static POINTS_VERTEX_SRC: &'static str = A_VERY_LONG_STRING;
 /// Fragment shader used by the material to display point.
@@ -122,13 +518,16 @@ const A_VERY_LONG_STRING: &'static str = "#version 100
     attribute vec3 position;
     attribute vec3 color;
     attribute float size;
+    attribute float alpha;
     varying   vec3 Color;
+    varying   float Alpha;
     uniform   mat4 proj;
     uniform   mat4 view;
     void main() {
         gl_Position = proj * view * vec4(position, 1.0);
         gl_PointSize = size;
         Color = color;
+        Alpha = alpha;
     }";
 
 const ANOTHER_VERY_LONG_STRING: &'static str = "#version 100
@@ -139,6 +538,57 @@ const ANOTHER_VERY_LONG_STRING: &'static str = "#version 100
 #endif
 
     varying vec3 Color;
+    varying float Alpha;
+    uniform int  round;
     void main() {
-        gl_FragColor = vec4(Color, 1.0);
+        if (round != 0) {
+            vec2 d = gl_PointCoord - vec2(0.5, 0.5);
+            if (dot(d, d) > 0.25) {
+                discard;
+            }
+        }
+        gl_FragColor = vec4(Color, Alpha);
     }";
+
+#[cfg(test)]
+mod tests {
+    use crate::camera::ArcBall;
+    use crate::window::open_test_window;
+    use na::Point3;
+
+    #[test]
+    #[ignore = "requires a display and an OpenGL context"]
+    fn per_point_sizes_are_honored() {
+        let (_lock, mut window) = open_test_window();
+        let mut camera = ArcBall::new(Point3::new(0.0, 0.0, 10.0), Point3::origin());
+        let white = Point3::new(1.0, 1.0, 1.0);
+
+        window.set_background_color(0.0, 0.0, 0.0);
+        window.set_point_size(1.0);
+        window.draw_point_with_size(&Point3::new(-3.0, 0.0, 0.0), &white, 2.0);
+        window.draw_point_with_size(&Point3::new(0.0, 0.0, 0.0), &white, 6.0);
+        window.draw_point_with_size(&Point3::new(3.0, 0.0, 0.0), &white, 12.0);
+
+        let img = window.render_to_image_with(Some(&mut camera), None, None);
+        // The number of lit pixels in each third of the image, one point per third.
+        let mut footprints = [0; 3];
+
+        for (x, _, px) in img.enumerate_pixels() {
+            if px.0.iter().any(|c| *c > 127) {
+                footprints[(x * 3 / img.width()) as usize] += 1;
+            }
+        }
+
+        assert!(footprints[0] > 0, "footprints: {:?}", footprints);
+        assert!(
+            footprints[0] < footprints[1],
+            "footprints: {:?}",
+            footprints
+        );
+        assert!(
+            footprints[1] < footprints[2],
+            "footprints: {:?}",
+            footprints
+        );
+    }
+}
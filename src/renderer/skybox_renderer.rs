@@ -0,0 +1,148 @@
+//! A renderer for an environment cube map drawn behind the scene.
+
+use crate::camera::Camera;
+use crate::context::{Context, Texture};
+use crate::renderer::Renderer;
+use crate::resource::{AllocationType, BufferType, Effect, GPUVec, ShaderAttribute, ShaderUniform};
+use na::{Matrix4, Point3};
+use std::rc::Rc;
+
+#[path = "../error.rs"]
+mod error;
+
+/// Structure which draws a cube map around the camera, as if it was infinitely far away.
+///
+/// The skybox follows the rotation of the camera but ignores its translation. It is meant to
+/// be rendered before the scene since it does not write to the depth buffer.
+pub struct SkyboxRenderer {
+    shader: Effect,
+    pos: ShaderAttribute<Point3<f32>>,
+    proj: ShaderUniform<Matrix4<f32>>,
+    view: ShaderUniform<Matrix4<f32>>,
+    vertices: GPUVec<Point3<f32>>,
+    cubemap: Rc<Texture>,
+}
+
+impl SkyboxRenderer {
+    /// Creates a new skybox renderer displaying the given cube map texture.
+    ///
+    /// The texture must have been created with `TextureManager::add_cubemap` or
+    /// `TextureManager::add_cubemap_from_images`.
+    pub fn new(cubemap: Rc<Texture>) -> SkyboxRenderer {
+        let mut shader = Effect::new_from_str(SKYBOX_VERTEX_SRC, SKYBOX_FRAGMENT_SRC);
+
+        shader.use_program();
+
+        SkyboxRenderer {
+            pos: shader.get_attrib::<Point3<f32>>("position").unwrap(),
+            proj: shader.get_uniform::<Matrix4<f32>>("proj").unwrap(),
+            view: shader.get_uniform::<Matrix4<f32>>("view").unwrap(),
+            vertices: GPUVec::new(
+                cube_vertices(),
+                BufferType::Array,
+                AllocationType::StaticDraw,
+            ),
+            shader,
+            cubemap,
+        }
+    }
+
+    /// The cube map texture displayed by this renderer.
+    pub fn cubemap(&self) -> &Rc<Texture> {
+        &self.cubemap
+    }
+
+    /// Sets the cube map texture displayed by this renderer.
+    pub fn set_cubemap(&mut self, cubemap: Rc<Texture>) {
+        self.cubemap = cubemap
+    }
+}
+
+impl Renderer for SkyboxRenderer {
+    /// Actually draws the skybox.
+    fn render(&mut self, pass: usize, camera: &mut dyn Camera) {
+        let ctxt = Context::get();
+        let cull = verify!(ctxt.is_enabled(Context::CULL_FACE));
+
+        self.shader.use_program();
+        self.pos.enable();
+
+        camera.upload(pass, &mut self.proj, &mut self.view);
+
+        verify!(ctxt.active_texture(Context::TEXTURE0));
+        verify!(ctxt.bind_texture(Context::TEXTURE_CUBE_MAP, Some(&*self.cubemap)));
+
+        self.pos.bind(&mut self.vertices);
+
+        // The camera is inside of the cube, so its faces are seen from the back.
+        verify!(ctxt.disable(Context::CULL_FACE));
+        verify!(ctxt.depth_mask(false));
+        verify!(ctxt.draw_arrays(Context::TRIANGLES, 0, self.vertices.len() as i32));
+        verify!(ctxt.depth_mask(true));
+
+        if cull {
+            verify!(ctxt.enable(Context::CULL_FACE));
+        }
+
+        verify!(ctxt.bind_texture(Context::TEXTURE_CUBE_MAP, None));
+        self.pos.disable();
+    }
+}
+
+// The triangles of a cube of half-extent 1 centered at the origin.
+fn cube_vertices() -> Vec<Point3<f32>> {
+    let mut res = Vec::with_capacity(36);
+
+    for axis in 0..3 {
+        for sign in [-1.0f32, 1.0].iter() {
+            let corner = |u: f32, v: f32| {
+                let mut pt = Point3::origin();
+                pt[axis] = *sign;
+                pt[(axis + 1) % 3] = u;
+                pt[(axis + 2) % 3] = v;
+                pt
+            };
+
+            res.extend_from_slice(&[
+                corner(-1.0, -1.0),
+                corner(1.0, -1.0),
+                corner(1.0, 1.0),
+                corner(-1.0, -1.0),
+                corner(1.0, 1.0),
+                corner(-1.0, 1.0),
+            ]);
+        }
+    }
+
+    res
+}
+
+/// Vertex shader used to display the skybox.
+pub static SKYBOX_VERTEX_SRC: &'static str = A_VERY_LONG_STRING;
+/// Fragment shader used to display the skybox.
+pub static SKYBOX_FRAGMENT_SRC: &'static str = ANOTHER_VERY_LONG_STRING;
+
+// The translation of the view matrix is removed, and the depth is set to the far plane.
+const A_VERY_LONG_STRING: &'static str = "#version 100
+    attribute vec3 position;
+    varying   vec3 direction;
+    uniform   mat4 proj;
+    uniform   mat4 view;
+    void main() {
+        vec4 pos = proj * vec4(mat3(view) * position, 1.0);
+        gl_Position = pos.xyww;
+        direction = position;
+    }";
+
+const ANOTHER_VERY_LONG_STRING: &'static str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+    varying vec3 direction;
+    uniform samplerCube skybox;
+    void main() {
+        gl_FragColor = textureCube(skybox, direction);
+    }";
@@ -1,12 +1,14 @@
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Read;
 use std::marker::PhantomData;
 use std::mem;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str;
+use std::time::SystemTime;
 
-use crate::context::{Context, GLintptr, Program, Shader, UniformLocation};
+use crate::context::{Context, GLenum, GLintptr, Program, Shader, UniformLocation};
 use crate::resource::{GLPrimitive, GPUVec};
+use na::Vector4;
 
 #[path = "../error.rs"]
 mod error;
@@ -52,6 +54,38 @@ impl Effect {
         }
     }
 
+    /// Recompiles this program from two files containing the vertex and fragment shader.
+    ///
+    /// See `reload_from_str` for the behavior on success and failure.
+    pub fn reload_from_files(
+        &mut self,
+        vshader_path: &Path,
+        fshader_path: &Path,
+    ) -> Result<(), String> {
+        let vshader = fs::read_to_string(vshader_path)
+            .map_err(|e| format!("Could not read {}: {}", vshader_path.display(), e))?;
+        let fshader = fs::read_to_string(fshader_path)
+            .map_err(|e| format!("Could not read {}: {}", fshader_path.display(), e))?;
+
+        self.reload_from_str(&vshader, &fshader)
+    }
+
+    /// Recompiles this program from strings of the vertex and fragment shader.
+    ///
+    /// If the shaders fail to compile or link, this program is left unchanged and the info log
+    /// is returned. Otherwise, the uniforms and attributes previously obtained from this effect
+    /// are invalidated: they must be fetched again with `get_uniform` and `get_attrib`.
+    pub fn reload_from_str(&mut self, vshader: &str, fshader: &str) -> Result<(), String> {
+        let (program, vshader, fshader) = try_load_shader_program(vshader, fshader)?;
+
+        delete_shader_program(&self.program, &self.vshader, &self.fshader);
+        self.program = program;
+        self.vshader = vshader;
+        self.fshader = fshader;
+
+        Ok(())
+    }
+
     /// Gets a uniform variable from the shader program.
     pub fn get_uniform<T: GLPrimitive>(&self, name: &str) -> Option<ShaderUniform<T>> {
         let ctxt = Context::get();
@@ -89,19 +123,76 @@ impl Effect {
 
 impl Drop for Effect {
     fn drop(&mut self) {
-        let ctxt = Context::get();
-        if verify!(ctxt.is_program(Some(&self.program))) {
-            verify!(ctxt.delete_program(Some(&self.program)));
-        }
-        if verify!(ctxt.is_shader(Some(&self.fshader))) {
-            verify!(ctxt.delete_shader(Some(&self.fshader)));
+        delete_shader_program(&self.program, &self.vshader, &self.fshader)
+    }
+}
+
+/// Watches the source files of an effect, to recompile it whenever they are modified.
+///
+/// This is meant to speed up the development of shaders:
+///
+/// ```ignore
+/// if let Some(result) = watcher.poll(&mut effect) {
+///     match result {
+///         // Fetch the uniforms and attributes again.
+///         Ok(()) => uniform = effect.get_uniform("color").unwrap(),
+///         // The previous program is still used.
+///         Err(log) => println!("{}", log),
+///     }
+/// }
+/// ```
+pub struct EffectWatcher {
+    vshader_path: PathBuf,
+    fshader_path: PathBuf,
+    modified: (Option<SystemTime>, Option<SystemTime>),
+}
+
+impl EffectWatcher {
+    /// Creates a watcher of the given vertex and fragment shader files.
+    ///
+    /// The files in their current state are assumed to be already loaded.
+    pub fn new<P1: AsRef<Path>, P2: AsRef<Path>>(
+        vshader_path: P1,
+        fshader_path: P2,
+    ) -> EffectWatcher {
+        let vshader_path = vshader_path.as_ref().to_path_buf();
+        let fshader_path = fshader_path.as_ref().to_path_buf();
+        let modified = (
+            modification_time(&vshader_path),
+            modification_time(&fshader_path),
+        );
+
+        EffectWatcher {
+            vshader_path,
+            fshader_path,
+            modified,
         }
-        if verify!(ctxt.is_shader(Some(&self.vshader))) {
-            verify!(ctxt.delete_shader(Some(&self.vshader)));
+    }
+
+    /// Recompiles `effect` if one of the watched files was modified since the last call.
+    ///
+    /// Returns `None` if the files did not change, and the result of
+    /// `Effect::reload_from_files` otherwise. Failed reloads are not retried until the files
+    /// are modified again.
+    pub fn poll(&mut self, effect: &mut Effect) -> Option<Result<(), String>> {
+        let modified = (
+            modification_time(&self.vshader_path),
+            modification_time(&self.fshader_path),
+        );
+
+        if modified == self.modified {
+            return None;
         }
+
+        self.modified = modified;
+        Some(effect.reload_from_files(&self.vshader_path, &self.fshader_path))
     }
 }
 
+fn modification_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
 /// Structure encapsulating an uniform variable.
 pub struct ShaderUniform<T> {
     id: UniformLocation,
@@ -132,6 +223,21 @@ impl<T: GLPrimitive> ShaderAttribute<T> {
         verify!(Context::get().enable_vertex_attrib_array(self.id));
     }
 
+    /// Sets the number of instances drawn before this attribute advances to its next value.
+    ///
+    /// A divisor of 0, the default, makes this attribute advance once per vertex. This must
+    /// not be used if `Context::supports_instancing` is `false`.
+    pub fn set_divisor(&mut self, divisor: u32) {
+        verify!(Context::get().vertex_attrib_divisor(self.id, divisor));
+    }
+
+    /// Sets the value of this attribute for all the vertices while it is disabled.
+    ///
+    /// Components missing from the attribute type are ignored.
+    pub fn set_constant(&mut self, value: &Vector4<f32>) {
+        verify!(Context::get().vertex_attrib4f(self.id, value.x, value.y, value.z, value.w));
+    }
+
     /// Binds this attribute to a gpu vector.
     pub fn bind(&mut self, vector: &mut GPUVec<T>) {
         vector.bind();
@@ -203,6 +309,67 @@ fn load_shader_program(vertex_shader: &str, fragment_shader: &str) -> (Program,
     (program, vshader, fshader)
 }
 
+/// Loads a shader program using the given source codes for the vertex and fragment shader.
+///
+/// Returns the info log of the compiler or linker if the shaders are invalid.
+fn try_load_shader_program(
+    vertex_shader: &str,
+    fragment_shader: &str,
+) -> Result<(Program, Shader, Shader), String> {
+    let ctxt = Context::get();
+    let vshader = try_compile_shader(Context::VERTEX_SHADER, vertex_shader)
+        .map_err(|log| format!("Vertex shader compilation failed: {}", log))?;
+    let fshader = match try_compile_shader(Context::FRAGMENT_SHADER, fragment_shader) {
+        Ok(fshader) => fshader,
+        Err(log) => {
+            verify!(ctxt.delete_shader(Some(&vshader)));
+            return Err(format!("Fragment shader compilation failed: {}", log));
+        }
+    };
+
+    let program = verify!(ctxt.create_program().expect("Could not create program."));
+    verify!(ctxt.attach_shader(&program, &vshader));
+    verify!(ctxt.attach_shader(&program, &fshader));
+    verify!(ctxt.link_program(&program));
+
+    if !ctxt.get_program_link_status(&program) {
+        let log = ctxt.get_program_info_log(&program).unwrap_or_default();
+        delete_shader_program(&program, &vshader, &fshader);
+        return Err(format!("Shader program linking failed: {}", log));
+    }
+
+    Ok((program, vshader, fshader))
+}
+
+fn try_compile_shader(type_: GLenum, source: &str) -> Result<Shader, String> {
+    let ctxt = Context::get();
+    let shader = verify!(ctxt.create_shader(type_).expect("Could not create shader."));
+
+    verify!(ctxt.shader_source(&shader, source));
+    verify!(ctxt.compile_shader(&shader));
+
+    if ctxt.get_shader_parameter_int(&shader, Context::COMPILE_STATUS) == Some(0) {
+        let log = ctxt.get_shader_info_log(&shader).unwrap_or_default();
+        verify!(ctxt.delete_shader(Some(&shader)));
+        return Err(log);
+    }
+
+    Ok(shader)
+}
+
+fn delete_shader_program(program: &Program, vshader: &Shader, fshader: &Shader) {
+    let ctxt = Context::get();
+    if verify!(ctxt.is_program(Some(program))) {
+        verify!(ctxt.delete_program(Some(program)));
+    }
+    if verify!(ctxt.is_shader(Some(fshader))) {
+        verify!(ctxt.delete_shader(Some(fshader)));
+    }
+    if verify!(ctxt.is_shader(Some(vshader))) {
+        verify!(ctxt.delete_shader(Some(vshader)));
+    }
+}
+
 /// Checks if a shader handle is valid.
 ///
 /// If it is not valid, it fails with a descriptive error message.
@@ -2,6 +2,7 @@
 
 use crate::context::{Context, Framebuffer, Renderbuffer, Texture};
 use either::Either;
+use std::rc::Rc;
 
 #[path = "../error.rs"]
 mod error;
@@ -16,8 +17,52 @@ pub enum RenderTarget {
 
 /// OpenGL identifiers to an off-screen buffer.
 pub struct OffscreenBuffers {
-    texture: Texture,
+    // Shared with the objects textured by this buffer, see `RenderTarget::texture`.
+    texture: Rc<Texture>,
+    width: usize,
+    height: usize,
     depth: Either<Texture, Renderbuffer>,
+    multisampled: Option<MultisampledBuffers>,
+}
+
+// The multisampled buffers rendered to instead of the off-screen buffer textures. They have to
+// be resolved into those textures before they are read.
+struct MultisampledBuffers {
+    fbo: Framebuffer,
+    color: Renderbuffer,
+    depth: Renderbuffer,
+    samples: u32,
+    width: i32,
+    height: i32,
+}
+
+impl MultisampledBuffers {
+    fn allocate(&mut self, width: i32, height: i32) {
+        let ctxt = Context::get();
+        let samples = self.samples as i32;
+
+        self.width = width;
+        self.height = height;
+
+        verify!(ctxt.bind_renderbuffer(Some(&self.color)));
+        verify!(ctxt.renderbuffer_storage_multisample(samples, Context::RGBA8, width, height));
+        verify!(ctxt.bind_renderbuffer(Some(&self.depth)));
+        verify!(ctxt.renderbuffer_storage_multisample(
+            samples,
+            Context::DEPTH_COMPONENT24,
+            width,
+            height
+        ));
+        verify!(ctxt.bind_renderbuffer(None));
+    }
+}
+
+// The framebuffer currently bound.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum BoundFramebuffer {
+    Screen,
+    Offscreen,
+    Multisampled,
 }
 
 impl RenderTarget {
@@ -27,7 +72,31 @@ impl RenderTarget {
     pub fn texture_id(&self) -> Option<&Texture> {
         match *self {
             RenderTarget::Screen => None,
-            RenderTarget::Offscreen(ref o) => Some(&o.texture),
+            RenderTarget::Offscreen(ref o) => Some(&*o.texture),
+        }
+    }
+
+    /// Returns a shared handle to the off-screen texture buffer, e.g., to texture an object
+    /// with what was rendered to this target.
+    ///
+    /// Returns `None` if the texture is on-screen. The rows of the texture are ordered from the
+    /// bottom to the top of the rendered image, so it appears flipped vertically compared to
+    /// textures loaded from image files. The texture is not deleted with this target while it is
+    /// still shared.
+    pub fn texture(&self) -> Option<Rc<Texture>> {
+        match *self {
+            RenderTarget::Screen => None,
+            RenderTarget::Offscreen(ref o) => Some(o.texture.clone()),
+        }
+    }
+
+    /// The size, in pixels, of the off-screen buffers.
+    ///
+    /// Returns `None` if the target is on-screen.
+    pub fn size(&self) -> Option<(usize, usize)> {
+        match *self {
+            RenderTarget::Screen => None,
+            RenderTarget::Offscreen(ref o) => Some((o.width, o.height)),
         }
     }
 
@@ -42,6 +111,17 @@ impl RenderTarget {
         }
     }
 
+    /// The number of samples used for multisample anti-aliasing while rendering to this target.
+    ///
+    /// Returns 0 if this target is not multisampled. The screen is reported as not multisampled
+    /// since its samples are configured at the window creation.
+    pub fn samples(&self) -> u32 {
+        match *self {
+            RenderTarget::Screen => 0,
+            RenderTarget::Offscreen(ref o) => o.multisampled.as_ref().map_or(0, |m| m.samples),
+        }
+    }
+
     /// Resizes this render target.
     pub fn resize(&mut self, w: f32, h: f32) {
         let ctxt = Context::get();
@@ -50,9 +130,16 @@ impl RenderTarget {
             RenderTarget::Screen => {
                 verify!(ctxt.viewport(0, 0, w as i32, h as i32));
             }
-            RenderTarget::Offscreen(ref o) => {
+            RenderTarget::Offscreen(ref mut o) => {
+                if let Some(ref mut multisampled) = o.multisampled {
+                    multisampled.allocate(w as i32, h as i32);
+                }
+
+                o.width = w as usize;
+                o.height = h as usize;
+
                 // Update the fbo
-                verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&o.texture)));
+                verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&*o.texture)));
                 verify!(ctxt.tex_image2d(
                     Context::TEXTURE_2D,
                     0,
@@ -98,8 +185,10 @@ impl RenderTarget {
 /// A framebuffer manager. It is a simple to to switch between an off-screen framebuffer and the
 /// default (window) framebuffer.
 pub struct FramebufferManager {
-    fbo_onscreen: bool,
+    bound: BoundFramebuffer,
     fbo: Framebuffer,
+    // Cleared the first time the depth of a multisampled target fails to be resolved.
+    depth_resolve_supported: bool,
 }
 
 impl FramebufferManager {
@@ -116,8 +205,9 @@ impl FramebufferManager {
         verify!(ctxt.bind_framebuffer(Context::FRAMEBUFFER, None));
 
         FramebufferManager {
-            fbo_onscreen: true,
+            bound: BoundFramebuffer::Screen,
             fbo: fbo,
+            depth_resolve_supported: true,
         }
     }
 
@@ -206,8 +296,11 @@ impl FramebufferManager {
             verify!(ctxt.bind_texture(Context::TEXTURE_2D, None));
 
             RenderTarget::Offscreen(OffscreenBuffers {
-                texture: fbo_texture,
+                texture: Rc::new(fbo_texture),
+                width,
+                height,
                 depth: Either::Left(fbo_depth),
+                multisampled: None,
             })
         } else {
             // Create a renderbuffer instead of the texture for the depth.
@@ -222,12 +315,68 @@ impl FramebufferManager {
             verify!(ctxt.bind_renderbuffer(None));
 
             RenderTarget::Offscreen(OffscreenBuffers {
-                texture: fbo_texture,
+                texture: Rc::new(fbo_texture),
+                width,
+                height,
                 depth: Either::Right(renderbuffer),
+                multisampled: None,
             })
         }
     }
 
+    /// Creates a new render target rendered with multisample anti-aliasing.
+    ///
+    /// Everything drawn to this target goes to multisampled buffers which must be resolved into
+    /// its textures with `FramebufferManager::resolve` before they are read. If the context
+    /// does not support `samples` samples, the highest supported number is used instead. If
+    /// `samples` is 0 or 1, or if multisampled render targets are not supported at all (e.g. on
+    /// WebGL 1), this is the same as `FramebufferManager::new_render_target`.
+    pub fn new_multisampled_render_target(
+        width: usize,
+        height: usize,
+        create_depth_texture: bool,
+        samples: u32,
+    ) -> RenderTarget {
+        let mut target = Self::new_render_target(width, height, create_depth_texture);
+
+        if samples <= 1 {
+            return target;
+        }
+
+        let ctxt = Context::get();
+        let max_samples = ctxt.max_samples();
+
+        if max_samples < samples {
+            println!(
+                "Warning: {} samples requested for multisampling but only {} are supported.",
+                samples, max_samples
+            );
+        }
+
+        let samples = samples.min(max_samples);
+
+        if samples <= 1 {
+            return target;
+        }
+
+        if let RenderTarget::Offscreen(ref mut o) = target {
+            let mut multisampled = MultisampledBuffers {
+                fbo: verify!(ctxt.create_framebuffer()).expect("Framebuffer creation failed."),
+                color: verify!(ctxt.create_renderbuffer())
+                    .expect("Failed to create a renderbuffer."),
+                depth: verify!(ctxt.create_renderbuffer())
+                    .expect("Failed to create a renderbuffer."),
+                samples,
+                width: 0,
+                height: 0,
+            };
+            multisampled.allocate(width as i32, height as i32);
+            o.multisampled = Some(multisampled);
+        }
+
+        target
+    }
+
     /// Returns the render target associated with the screen.
     pub fn screen() -> RenderTarget {
         RenderTarget::Screen
@@ -239,48 +388,105 @@ impl FramebufferManager {
             RenderTarget::Screen => {
                 self.select_onscreen();
             }
+            RenderTarget::Offscreen(OffscreenBuffers {
+                multisampled: Some(ref m),
+                ..
+            }) => {
+                let ctxt = Context::get();
+                verify!(ctxt.bind_framebuffer(Context::FRAMEBUFFER, Some(&m.fbo)));
+                self.bound = BoundFramebuffer::Multisampled;
+
+                verify!(ctxt.framebuffer_renderbuffer(Context::COLOR_ATTACHMENT0, Some(&m.color)));
+                verify!(ctxt.framebuffer_renderbuffer(Context::DEPTH_ATTACHMENT, Some(&m.depth)));
+            }
             RenderTarget::Offscreen(ref o) => {
+                self.select_textures(o);
+            }
+        }
+    }
+
+    /// Resolves the multisampled buffers of a render target into its textures.
+    ///
+    /// This does nothing if the target is not multisampled. Otherwise, the target textures are
+    /// selected afterward, so that whatever is drawn next is not anti-aliased.
+    pub fn resolve(&mut self, target: &RenderTarget) {
+        if let RenderTarget::Offscreen(ref o) = *target {
+            if let Some(ref m) = o.multisampled {
                 let ctxt = Context::get();
-                self.select_fbo();
+                let rect = (0, 0, m.width, m.height);
+
+                self.select_textures(o);
+                verify!(ctxt.bind_framebuffer(Context::READ_FRAMEBUFFER, Some(&m.fbo)));
+                verify!(ctxt.blit_framebuffer(
+                    rect,
+                    rect,
+                    Context::COLOR_BUFFER_BIT,
+                    Context::NEAREST
+                ));
+                // The depth can only be resolved if the depth texture format matches the
+                // multisampled depth buffer, which is up to the driver.
+                if self.depth_resolve_supported
+                    && checked!(ctxt.blit_framebuffer(
+                        rect,
+                        rect,
+                        Context::DEPTH_BUFFER_BIT,
+                        Context::NEAREST
+                    ))
+                    .is_none()
+                {
+                    self.depth_resolve_supported = false;
+                    println!(
+                        "Warning: the depth of multisampled render targets can't be resolved \
+                         by this driver. Effects reading the depth will use a stale depth."
+                    );
+                }
+                verify!(ctxt.bind_framebuffer(Context::FRAMEBUFFER, Some(&self.fbo)));
+            }
+        }
+    }
 
-                // FIXME: don't switch if the current texture is
-                // already o.texture ?
+    // Selects the textures of an off-screen render target, ignoring its multisampled buffers.
+    fn select_textures(&mut self, o: &OffscreenBuffers) {
+        let ctxt = Context::get();
+        self.select_fbo();
+
+        // FIXME: don't switch if the current texture is
+        // already o.texture ?
+        verify!(ctxt.framebuffer_texture2d(
+            Context::FRAMEBUFFER,
+            Context::COLOR_ATTACHMENT0,
+            Context::TEXTURE_2D,
+            Some(&*o.texture),
+            0
+        ));
+
+        match &o.depth {
+            Either::Left(texture) => {
                 verify!(ctxt.framebuffer_texture2d(
                     Context::FRAMEBUFFER,
-                    Context::COLOR_ATTACHMENT0,
+                    Context::DEPTH_ATTACHMENT,
                     Context::TEXTURE_2D,
-                    Some(&o.texture),
+                    Some(texture),
                     0
                 ));
-
-                match &o.depth {
-                    Either::Left(texture) => {
-                        verify!(ctxt.framebuffer_texture2d(
-                            Context::FRAMEBUFFER,
-                            Context::DEPTH_ATTACHMENT,
-                            Context::TEXTURE_2D,
-                            Some(texture),
-                            0
-                        ));
-                    }
-                    Either::Right(renderbuffer) => verify!(ctxt
-                        .framebuffer_renderbuffer(Context::DEPTH_ATTACHMENT, Some(renderbuffer))),
-                }
+            }
+            Either::Right(renderbuffer) => {
+                verify!(ctxt.framebuffer_renderbuffer(Context::DEPTH_ATTACHMENT, Some(renderbuffer)))
             }
         }
     }
 
     fn select_onscreen(&mut self) {
-        if !self.fbo_onscreen {
+        if self.bound != BoundFramebuffer::Screen {
             verify!(Context::get().bind_framebuffer(Context::FRAMEBUFFER, None));
-            self.fbo_onscreen = true;
+            self.bound = BoundFramebuffer::Screen;
         }
     }
 
     fn select_fbo(&mut self) {
-        if self.fbo_onscreen {
+        if self.bound != BoundFramebuffer::Offscreen {
             verify!(Context::get().bind_framebuffer(Context::FRAMEBUFFER, Some(&self.fbo)));
-            self.fbo_onscreen = false;
+            self.bound = BoundFramebuffer::Offscreen;
         }
     }
 }
@@ -298,8 +504,8 @@ impl Drop for FramebufferManager {
 impl Drop for OffscreenBuffers {
     fn drop(&mut self) {
         let ctxt = Context::get();
-        if verify!(ctxt.is_texture(Some(&self.texture))) {
-            verify!(ctxt.delete_texture(Some(&self.texture)));
+        if Rc::strong_count(&self.texture) == 1 && verify!(ctxt.is_texture(Some(&*self.texture))) {
+            verify!(ctxt.delete_texture(Some(&*self.texture)));
         }
 
         match &self.depth {
@@ -316,3 +522,18 @@ impl Drop for OffscreenBuffers {
         }
     }
 }
+
+impl Drop for MultisampledBuffers {
+    fn drop(&mut self) {
+        let ctxt = Context::get();
+        if verify!(ctxt.is_framebuffer(Some(&self.fbo))) {
+            verify!(ctxt.delete_framebuffer(Some(&self.fbo)));
+        }
+
+        for renderbuffer in [&self.color, &self.depth].iter() {
+            if verify!(ctxt.is_renderbuffer(Some(renderbuffer))) {
+                verify!(ctxt.delete_renderbuffer(Some(renderbuffer)));
+            }
+        }
+    }
+}
@@ -2,6 +2,8 @@
 
 use crate::context::{Buffer, Context};
 use crate::resource::gl_primitive::GLPrimitive;
+use std::mem;
+use std::ops::Range;
 
 #[path = "../error.rs"]
 mod error;
@@ -10,6 +12,8 @@ mod error;
 /// A vector of elements that can be loaded to the GPU, on the RAM, or both.
 pub struct GPUVec<T> {
     trash: bool,
+    // The range of elements modified since the last upload, if the whole vector is not trash.
+    dirty: Option<Range<usize>>,
     len: usize,
     buf_type: BufferType,
     alloc_type: AllocationType,
@@ -23,6 +27,7 @@ impl<T: GLPrimitive> GPUVec<T> {
     pub fn new(data: Vec<T>, buf_type: BufferType, alloc_type: AllocationType) -> GPUVec<T> {
         GPUVec {
             trash: true,
+            dirty: None,
             len: data.len(),
             buf_type: buf_type,
             alloc_type: alloc_type,
@@ -54,12 +59,65 @@ impl<T: GLPrimitive> GPUVec<T> {
         &mut self.data
     }
 
+    /// Mutably accesses a range of elements of the vector if it is available on RAM.
+    ///
+    /// Unlike `data_mut`, only the given range is uploaded to the GPU the next time this vector
+    /// is bound, which is much faster if only a small part of a large vector changes. Returns
+    /// `None` if the vector is not available on RAM.
+    ///
+    /// # Failures:
+    /// Fails if the range is out of bounds.
+    #[inline]
+    pub fn data_mut_range(&mut self, range: Range<usize>) -> Option<&mut [T]> {
+        self.mark_dirty(range.clone());
+        self.data.as_mut().map(|d| &mut d[range])
+    }
+
+    /// Marks a range of elements as modified, so that only this range is uploaded to the GPU the
+    /// next time this vector is bound.
+    ///
+    /// Successive ranges are merged into the smallest range containing all of them. This has no
+    /// effect if the whole vector has been marked as modified by `data_mut`.
+    #[inline]
+    pub fn mark_dirty(&mut self, range: Range<usize>) {
+        if range.start >= range.end {
+            return;
+        }
+
+        self.dirty = match self.dirty.take() {
+            Some(dirty) => Some(dirty.start.min(range.start)..dirty.end.max(range.end)),
+            None => Some(range),
+        };
+    }
+
     /// Immutably accesses the vector if it is available on RAM.
     #[inline]
     pub fn data(&self) -> &Option<Vec<T>> {
         &self.data
     }
 
+    /// The allocation type of the GPU buffer of this vector.
+    #[inline]
+    pub fn allocation_type(&self) -> AllocationType {
+        self.alloc_type
+    }
+
+    /// Sets the allocation type of the GPU buffer of this vector.
+    ///
+    /// Use `AllocationType::DynamicDraw` for vectors modified often. If this vector is already
+    /// on the GPU and still available on RAM, its GPU buffer is reallocated the next time it is
+    /// bound.
+    #[inline]
+    pub fn set_allocation_type(&mut self, alloc_type: AllocationType) {
+        if alloc_type != self.alloc_type {
+            self.alloc_type = alloc_type;
+
+            if self.is_on_ram() {
+                self.unload_from_gpu();
+            }
+        }
+    }
+
     /// Returns `true` if this vector is already uploaded to the GPU.
     #[inline]
     pub fn is_on_gpu(&self) -> bool {
@@ -69,7 +127,7 @@ impl<T: GLPrimitive> GPUVec<T> {
     /// Returns `true` if the cpu data and gpu data are out of sync.
     #[inline]
     pub fn trash(&self) -> bool {
-        self.trash
+        self.trash || self.dirty.is_some()
     }
 
     /// Returns `true` if this vector is available on RAM.
@@ -94,7 +152,7 @@ impl<T: GLPrimitive> GPUVec<T> {
                 *len = d.len();
                 (d.len(), upload_array(&d[..], buf_type, alloc_type))
             });
-        } else if self.trash() {
+        } else if self.trash {
             for d in self.data.iter() {
                 self.len = d.len();
 
@@ -102,9 +160,21 @@ impl<T: GLPrimitive> GPUVec<T> {
                     *len = update_buffer(&d[..], *len, buffer, self.buf_type, self.alloc_type)
                 }
             }
+        } else if let Some(dirty) = self.dirty.clone() {
+            for d in self.data.iter() {
+                if let Some((len, ref buffer)) = self.buffer {
+                    // The length did not change, so the GPU buffer is large enough.
+                    let end = dirty.end.min(d.len()).min(len);
+
+                    if dirty.start < end {
+                        update_buffer_range(&d[..], dirty.start..end, buffer, self.buf_type)
+                    }
+                }
+            }
         }
 
         self.trash = false;
+        self.dirty = None;
     }
 
     /// Binds this vector to the appropriate gpu array.
@@ -153,6 +223,7 @@ impl<T: GLPrimitive> GPUVec<T> {
         self.len = self.len();
         self.buffer = None;
         self.trash = false;
+        self.dirty = None;
     }
 
     /// Removes this resource from the RAM.
@@ -160,7 +231,7 @@ impl<T: GLPrimitive> GPUVec<T> {
     /// This is useful to save memory for vectors required on the GPU only.
     #[inline]
     pub fn unload_from_ram(&mut self) {
-        if self.trash && self.is_on_gpu() {
+        if self.trash() && self.is_on_gpu() {
             self.load_to_gpu();
         }
 
@@ -199,7 +270,7 @@ impl BufferType {
 }
 
 /// Allocation type of gpu buffers.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AllocationType {
     /// STATIC_DRAW allocation type.
     StaticDraw,
@@ -266,7 +337,7 @@ pub fn update_buffer<T: GLPrimitive>(
 
         verify!(ctxt.bind_buffer(gpu_buf_type.to_gl(), Some(gpu_buf)));
 
-        if arr.len() < gpu_buf_len {
+        if arr.len() <= gpu_buf_len {
             verify!(ctxt.buffer_sub_data(gpu_buf_type.to_gl(), 0, arr));
             gpu_buf_len
         } else {
@@ -275,3 +346,20 @@ pub fn update_buffer<T: GLPrimitive>(
         }
     }
 }
+
+/// Updates a range of the elements of a buffer on the gpu.
+///
+/// The buffer on the gpu must be able to hold at least `range.end` elements.
+#[inline]
+pub fn update_buffer_range<T: GLPrimitive>(
+    arr: &[T],
+    range: Range<usize>,
+    gpu_buf: &Buffer,
+    gpu_buf_type: BufferType,
+) {
+    let ctxt = Context::get();
+    let offset = range.start * mem::size_of::<T>();
+
+    verify!(ctxt.bind_buffer(gpu_buf_type.to_gl(), Some(gpu_buf)));
+    verify!(ctxt.buffer_sub_data(gpu_buf_type.to_gl(), offset as u32, &arr[range]));
+}
@@ -0,0 +1,78 @@
+//! Per-instance data of an instanced object.
+
+use crate::resource::gpu_vector::{AllocationType, BufferType, GPUVec};
+use na::{Isometry3, Point3, Vector4};
+
+/// The transformations of the instances of an object.
+///
+/// Each instance is drawn with the geometry of the object, with its own transformation
+/// appended to the object transformation.
+pub struct InstancesBuffer {
+    transforms: Vec<Isometry3<f32>>,
+    translations: GPUVec<Point3<f32>>,
+    rotations: GPUVec<Vector4<f32>>,
+}
+
+impl InstancesBuffer {
+    /// Creates a new set of instances from their transformations.
+    pub fn new(transforms: Vec<Isometry3<f32>>) -> InstancesBuffer {
+        let (translations, rotations) = split(&transforms);
+
+        InstancesBuffer {
+            transforms,
+            translations: GPUVec::new(translations, BufferType::Array, AllocationType::DynamicDraw),
+            rotations: GPUVec::new(rotations, BufferType::Array, AllocationType::DynamicDraw),
+        }
+    }
+
+    /// The transformations of the instances.
+    #[inline]
+    pub fn transforms(&self) -> &[Isometry3<f32>] {
+        &self.transforms[..]
+    }
+
+    /// Replaces the transformations of the instances.
+    ///
+    /// They will be re-uploaded to the GPU before the next draw.
+    pub fn set_transforms(&mut self, transforms: Vec<Isometry3<f32>>) {
+        let (translations, rotations) = split(&transforms);
+
+        *self.translations.data_mut() = Some(translations);
+        *self.rotations.data_mut() = Some(rotations);
+        self.transforms = transforms;
+    }
+
+    /// The number of instances.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.transforms.len()
+    }
+
+    /// Whether there is no instance.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
+    /// The translations of the instances.
+    #[inline]
+    pub fn translations_mut(&mut self) -> &mut GPUVec<Point3<f32>> {
+        &mut self.translations
+    }
+
+    /// The rotations of the instances, as the coordinates `(i, j, k, w)` of unit quaternions.
+    #[inline]
+    pub fn rotations_mut(&mut self) -> &mut GPUVec<Vector4<f32>> {
+        &mut self.rotations
+    }
+}
+
+fn split(transforms: &[Isometry3<f32>]) -> (Vec<Point3<f32>>, Vec<Vector4<f32>>) {
+    let translations = transforms
+        .iter()
+        .map(|t| Point3::from(t.translation.vector))
+        .collect();
+    let rotations = transforms.iter().map(|t| t.rotation.coords).collect();
+
+    (translations, rotations)
+}
@@ -1,7 +1,7 @@
 //! Trait implemented by materials.
 
 use crate::camera::Camera;
-use crate::light::Light;
+use crate::environment::Environment;
 use crate::planar_camera::PlanarCamera;
 use crate::resource::{Mesh, PlanarMesh};
 use crate::scene::{ObjectData, PlanarObjectData};
@@ -16,8 +16,8 @@ pub trait Material {
         pass: usize,
         transform: &Isometry3<f32>,
         scale: &Vector3<f32>,
-        camera: &mut dyn Camera, // FIXME: replace those two arguments by
-        light: &Light,           // a structure with all environment datas
+        camera: &mut dyn Camera,
+        environment: &Environment,
         data: &ObjectData,
         mesh: &mut Mesh,
     );
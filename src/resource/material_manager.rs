@@ -1,6 +1,6 @@
 //! A resource manager to load materials.
 
-use crate::builtin::{NormalsMaterial, ObjectMaterial, UvsMaterial};
+use crate::builtin::{NormalsMaterial, ObjectMaterial, UvsMaterial, VertexColorMaterial};
 use crate::resource::Material;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -42,6 +42,16 @@ impl MaterialManager {
         ));
         let _ = materials.insert("uvs".to_string(), um.clone());
 
+        let vm = Rc::new(RefCell::new(
+            Box::new(VertexColorMaterial::new()) as Box<dyn Material + 'static>
+        ));
+        let _ = materials.insert("vertex_colors".to_string(), vm.clone());
+
+        let mut lit_vm = VertexColorMaterial::new();
+        lit_vm.set_lighting(true);
+        let lit_vm = Rc::new(RefCell::new(Box::new(lit_vm) as Box<dyn Material + 'static>));
+        let _ = materials.insert("vertex_colors_lit".to_string(), lit_vm.clone());
+
         MaterialManager {
             default_material: om,
             materials: materials,
@@ -1,31 +1,55 @@
 //! Data structure of a scene node geometry.
 use std::iter;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, RwLock, RwLockWriteGuard};
 
+use crate::context::Context;
 use crate::resource::gpu_vector::{AllocationType, BufferType, GPUVec};
-use crate::resource::ShaderAttribute;
-use na::{self, Point2, Point3, Vector3};
+use crate::resource::{GLPrimitive, ShaderAttribute};
+use na::{self, Isometry3, Point2, Point3, Vector3};
+use ncollide3d::bounding_volume::AABB;
 use ncollide3d::procedural::{IndexBuffer, TriMesh};
+use ncollide3d::query::{Ray, RayCast};
+use ncollide3d::shape::Triangle;
 use num::Zero;
 
 #[path = "../error.rs"]
 mod error;
 
+// The maximum number of vertices of a mesh with 16-bits indices.
+const MAX_U16_VERTICES: usize = u16::max_value() as usize + 1;
+
+// The index buffer of a mesh.
+enum Faces {
+    U16(Arc<RwLock<GPUVec<Point3<u16>>>>),
+    U32(Arc<RwLock<GPUVec<Point3<u32>>>>),
+}
+
+// The index buffer of the edges of a mesh, with the same index type as its faces.
+enum Edges {
+    U16(GPUVec<Point2<u16>>),
+    U32(GPUVec<Point2<u32>>),
+}
+
 /// Aggregation of vertices, indices, normals and texture coordinates.
 ///
 /// It also contains the GPU location of those buffers.
 pub struct Mesh {
     coords: Arc<RwLock<GPUVec<Point3<f32>>>>,
-    faces: Arc<RwLock<GPUVec<Point3<u16>>>>,
+    faces: Faces,
     normals: Arc<RwLock<GPUVec<Vector3<f32>>>>,
     uvs: Arc<RwLock<GPUVec<Point2<f32>>>>,
-    edges: Option<Arc<RwLock<GPUVec<Point2<u16>>>>>,
+    edges: Option<Edges>,
+    colors: Option<Arc<RwLock<GPUVec<Point3<f32>>>>>,
+    // The bounding box of the vertices, computed when first needed. Cleared when the vertices
+    // may have been modified.
+    aabb: RwLock<Option<(Point3<f32>, Point3<f32>)>>,
 }
 
 impl Mesh {
     /// Creates a new mesh.
     ///
-    /// If the normals and uvs are not given, they are automatically computed.
+    /// If the normals and uvs are not given, they are automatically computed. Use
+    /// `new_with_u32_indices` for meshes with more than 65536 vertices.
     pub fn new(
         coords: Vec<Point3<f32>>,
         faces: Vec<Point3<u16>>,
@@ -33,6 +57,53 @@ impl Mesh {
         uvs: Option<Vec<Point2<f32>>>,
         dynamic_draw: bool,
     ) -> Mesh {
+        Mesh::new_with_faces(coords, faces, normals, uvs, dynamic_draw, Faces::U16)
+    }
+
+    /// Creates a new mesh with 32-bits vertex indices.
+    ///
+    /// If the normals and uvs are not given, they are automatically computed. Materials must
+    /// draw this mesh with `Context::UNSIGNED_INT` indices, as given by `index_type`. On WebGL 1,
+    /// this requires the `OES_element_index_uint` extension: binding the faces of this mesh fails
+    /// if it is not supported.
+    pub fn new_with_u32_indices(
+        coords: Vec<Point3<f32>>,
+        faces: Vec<Point3<u32>>,
+        normals: Option<Vec<Vector3<f32>>>,
+        uvs: Option<Vec<Point2<f32>>>,
+        dynamic_draw: bool,
+    ) -> Mesh {
+        Mesh::new_with_faces(coords, faces, normals, uvs, dynamic_draw, Faces::U32)
+    }
+
+    // Creates a new mesh with 16-bits indices if it has few enough vertices, and 32-bits indices
+    // otherwise.
+    pub(crate) fn new_with_smallest_indices(
+        coords: Vec<Point3<f32>>,
+        faces: Vec<Point3<u32>>,
+        normals: Option<Vec<Vector3<f32>>>,
+        uvs: Option<Vec<Point2<f32>>>,
+        dynamic_draw: bool,
+    ) -> Mesh {
+        if coords.len() <= MAX_U16_VERTICES {
+            let faces = faces.into_iter().map(to_u16).collect();
+            Mesh::new(coords, faces, normals, uvs, dynamic_draw)
+        } else {
+            Mesh::new_with_u32_indices(coords, faces, normals, uvs, dynamic_draw)
+        }
+    }
+
+    fn new_with_faces<I: na::Scalar + Copy + Into<u32>>(
+        coords: Vec<Point3<f32>>,
+        faces: Vec<Point3<I>>,
+        normals: Option<Vec<Vector3<f32>>>,
+        uvs: Option<Vec<Point2<f32>>>,
+        dynamic_draw: bool,
+        index_buffer: fn(Arc<RwLock<GPUVec<Point3<I>>>>) -> Faces,
+    ) -> Mesh
+    where
+        Point3<I>: GLPrimitive,
+    {
         let normals = match normals {
             Some(ns) => ns,
             None => Mesh::compute_normals_array(&coords[..], &faces[..]),
@@ -65,7 +136,7 @@ impl Mesh {
         )));
         let us = Arc::new(RwLock::new(GPUVec::new(uvs, BufferType::Array, location)));
 
-        Mesh::new_with_gpu_vectors(cs, fs, ns, us)
+        Mesh::new_with_index_buffer(cs, index_buffer(fs), ns, us)
     }
 
     /// Creates a new mesh from a mesh descr.
@@ -83,26 +154,121 @@ impl Mesh {
             indices,
         } = mesh;
 
-        Mesh::new(
+        Mesh::new_with_smallest_indices(
             coords,
-            indices
-                .unwrap_unified()
-                .into_iter()
-                .map(|e| na::convert(e))
-                .collect(),
+            indices.unwrap_unified(),
             normals,
             uvs,
             dynamic_draw,
         )
     }
 
+    /// Creates a terrain mesh from a regular grid of elevations.
+    ///
+    /// The grid lies on the `xz` plane, centered at the origin, and the elevations are given
+    /// along the `y` axis. The normals are smooth and the texture coordinates span the whole
+    /// grid. The cells having a NaN elevation at one of their corners are not generated, which
+    /// allows holes in the terrain.
+    ///
+    /// # Arguments
+    /// * `heights` - the `width * depth` elevations, stored row by row: the elevation at the
+    /// column `i` and the row `j` is `heights[j * width + i]`.
+    /// * `width` - the number of elevations along the `x` axis. Must be at least 2.
+    /// * `depth` - the number of elevations along the `z` axis. Must be at least 2.
+    /// * `cell_size` - the distance between two adjacent elevations.
+    pub fn from_heightmap(heights: &[f32], width: usize, depth: usize, cell_size: f32) -> Mesh {
+        assert!(
+            width >= 2 && depth >= 2,
+            "A heightmap must have at least 2 elevations along each axis."
+        );
+        assert!(
+            heights.len() == width * depth,
+            "The number of elevations must be equal to `width * depth`."
+        );
+
+        let mut coords = Vec::new();
+        let mut uvs = Vec::new();
+        let mut faces = Vec::new();
+        // The index of each elevation in `coords`, or `None` if it is a hole.
+        let mut ids = Vec::with_capacity(heights.len());
+        let shift_x = (width - 1) as f32 * cell_size / 2.0;
+        let shift_z = (depth - 1) as f32 * cell_size / 2.0;
+
+        for j in 0..depth {
+            for i in 0..width {
+                let height = heights[j * width + i];
+
+                if height.is_nan() {
+                    ids.push(None);
+                } else {
+                    ids.push(Some(coords.len() as u32));
+                    coords.push(Point3::new(
+                        i as f32 * cell_size - shift_x,
+                        height,
+                        j as f32 * cell_size - shift_z,
+                    ));
+                    uvs.push(Point2::new(
+                        i as f32 / (width - 1) as f32,
+                        j as f32 / (depth - 1) as f32,
+                    ));
+                }
+            }
+        }
+
+        for j in 0..depth - 1 {
+            for i in 0..width - 1 {
+                let corners = (
+                    ids[j * width + i],
+                    ids[(j + 1) * width + i],
+                    ids[j * width + i + 1],
+                    ids[(j + 1) * width + i + 1],
+                );
+
+                if let (Some(a), Some(b), Some(c), Some(d)) = corners {
+                    faces.push(Point3::new(a, b, c));
+                    faces.push(Point3::new(c, b, d));
+                }
+            }
+        }
+
+        Mesh::new_with_smallest_indices(coords, faces, None, Some(uvs), false)
+    }
+
+    /// Sets the per-vertex colors of this mesh, with components in `[0, 1]`, or removes them.
+    ///
+    /// The colors are only used by materials supporting them, e.g., `VertexColorMaterial`, which is
+    /// registered as `vertex_colors`, and as `vertex_colors_lit` with lighting enabled.
+    ///
+    /// # Failures:
+    /// Fails if the number of colors does not match the number of vertices, when the vertices
+    /// are available on the CPU.
+    pub fn set_colors(&mut self, colors: Option<Vec<Point3<f32>>>) {
+        self.colors = colors.map(|colors| {
+            let coords = self.coords.read().unwrap();
+
+            if let Some(coords) = coords.data() {
+                assert!(
+                    colors.len() == coords.len(),
+                    "The number of vertex colors must match the number of vertices."
+                );
+            }
+
+            let gpu_colors = GPUVec::new(colors, BufferType::Array, coords.allocation_type());
+            Arc::new(RwLock::new(gpu_colors))
+        });
+    }
+
+    /// This mesh per-vertex colors, if any.
+    pub fn colors(&self) -> Option<&Arc<RwLock<GPUVec<Point3<f32>>>>> {
+        self.colors.as_ref()
+    }
+
     // XXX: The `load_to_ram` require WebGL 2.
     /// Creates a triangle mesh from this mesh.
     ///
     /// Return `None` if the mesh data is not available on the CPU.
     pub fn to_trimesh(&self) -> Option<TriMesh<f32>> {
         if !self.coords.read().unwrap().is_on_ram()
-            || !self.faces.read().unwrap().is_on_ram()
             || !self.normals.read().unwrap().is_on_ram()
             || !self.uvs.read().unwrap().is_on_ram()
         {
@@ -110,7 +276,7 @@ impl Mesh {
         }
 
         let coords = self.coords.read().unwrap().to_owned();
-        let faces = self.faces.read().unwrap().to_owned();
+        let faces = self.faces_as_u32()?;
         let normals = self.normals.read().unwrap().to_owned();
         let uvs = self.uvs.read().unwrap().to_owned();
 
@@ -118,13 +284,7 @@ impl Mesh {
             coords.unwrap(),
             normals,
             uvs,
-            Some(IndexBuffer::Unified(
-                faces
-                    .unwrap()
-                    .into_iter()
-                    .map(|e| Point3::new(e.x as u32, e.y as u32, e.z as u32))
-                    .collect(),
-            )),
+            Some(IndexBuffer::Unified(faces)),
         ))
 
         /*
@@ -180,6 +340,27 @@ impl Mesh {
         faces: Arc<RwLock<GPUVec<Point3<u16>>>>,
         normals: Arc<RwLock<GPUVec<Vector3<f32>>>>,
         uvs: Arc<RwLock<GPUVec<Point2<f32>>>>,
+    ) -> Mesh {
+        Mesh::new_with_index_buffer(coords, Faces::U16(faces), normals, uvs)
+    }
+
+    /// Creates a new mesh with 32-bits vertex indices from buffers.
+    ///
+    /// See `new_with_u32_indices` for the requirements of 32-bits indices.
+    pub fn new_with_u32_gpu_vectors(
+        coords: Arc<RwLock<GPUVec<Point3<f32>>>>,
+        faces: Arc<RwLock<GPUVec<Point3<u32>>>>,
+        normals: Arc<RwLock<GPUVec<Vector3<f32>>>>,
+        uvs: Arc<RwLock<GPUVec<Point2<f32>>>>,
+    ) -> Mesh {
+        Mesh::new_with_index_buffer(coords, Faces::U32(faces), normals, uvs)
+    }
+
+    fn new_with_index_buffer(
+        coords: Arc<RwLock<GPUVec<Point3<f32>>>>,
+        faces: Faces,
+        normals: Arc<RwLock<GPUVec<Vector3<f32>>>>,
+        uvs: Arc<RwLock<GPUVec<Point2<f32>>>>,
     ) -> Mesh {
         Mesh {
             coords: coords,
@@ -187,6 +368,8 @@ impl Mesh {
             normals: normals,
             uvs: uvs,
             edges: None,
+            colors: None,
+            aabb: RwLock::new(None),
         }
     }
 
@@ -205,9 +388,32 @@ impl Mesh {
         uvs.bind(&mut *self.uvs.write().unwrap());
     }
 
+    /// Binds this mesh vertex colors buffer to a vertex attribute.
+    ///
+    /// Returns `false`, and does nothing, if this mesh has no vertex colors.
+    pub fn bind_colors(&mut self, colors: &mut ShaderAttribute<Point3<f32>>) -> bool {
+        match self.colors {
+            Some(ref buffer) => {
+                colors.bind(&mut *buffer.write().unwrap());
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Binds this mesh index buffer to a vertex attribute.
+    ///
+    /// # Failures:
+    /// Fails if this mesh has 32-bits indices and the `OES_element_index_uint` extension is not
+    /// supported, on WebGL 1.
     pub fn bind_faces(&mut self) {
-        self.faces.write().unwrap().bind();
+        match self.faces {
+            Faces::U16(ref faces) => faces.write().unwrap().bind(),
+            Faces::U32(ref faces) => {
+                assert_u32_indices_support();
+                faces.write().unwrap().bind()
+            }
+        }
     }
 
     /// Binds this mesh buffers to vertex attributes.
@@ -226,18 +432,19 @@ impl Mesh {
     /// Binds this mesh buffers to vertex attributes.
     pub fn bind_edges(&mut self) {
         if self.edges.is_none() {
-            let mut edges = Vec::new();
-            for face in self.faces.read().unwrap().data().as_ref().unwrap() {
-                edges.push(Point2::new(face.x, face.y));
-                edges.push(Point2::new(face.y, face.z));
-                edges.push(Point2::new(face.z, face.x));
-            }
-            let gpu_edges =
-                GPUVec::new(edges, BufferType::ElementArray, AllocationType::StaticDraw);
-            self.edges = Some(Arc::new(RwLock::new(gpu_edges)));
+            self.edges = Some(match self.faces {
+                Faces::U16(ref faces) => Edges::U16(edges(&faces.read().unwrap())),
+                Faces::U32(ref faces) => Edges::U32(edges(&faces.read().unwrap())),
+            });
         }
 
-        self.edges.as_mut().unwrap().write().unwrap().bind();
+        match self.edges.as_mut().unwrap() {
+            Edges::U16(ref mut edges) => edges.bind(),
+            Edges::U32(ref mut edges) => {
+                assert_u32_indices_support();
+                edges.bind()
+            }
+        }
     }
 
     /// Unbind this mesh buffers to vertex attributes.
@@ -245,26 +452,166 @@ impl Mesh {
         self.coords.write().unwrap().unbind();
         self.normals.write().unwrap().unbind();
         self.uvs.write().unwrap().unbind();
-        self.faces.write().unwrap().unbind();
+
+        match self.faces {
+            Faces::U16(ref faces) => faces.write().unwrap().unbind(),
+            Faces::U32(ref faces) => faces.write().unwrap().unbind(),
+        }
+
+        if let Some(ref colors) = self.colors {
+            colors.write().unwrap().unbind();
+        }
     }
 
     /// Number of points needed to draw this mesh.
     pub fn num_pts(&self) -> usize {
-        self.faces.read().unwrap().len() * 3
+        let num_faces = match self.faces {
+            Faces::U16(ref faces) => faces.read().unwrap().len(),
+            Faces::U32(ref faces) => faces.read().unwrap().len(),
+        };
+
+        num_faces * 3
+    }
+
+    /// The type of the indices of this mesh, to be given to `Context::draw_elements`.
+    ///
+    /// This is `Context::UNSIGNED_SHORT`, unless this mesh was created with 32-bits indices, in
+    /// which case this is `Context::UNSIGNED_INT`.
+    pub fn index_type(&self) -> u32 {
+        match self.faces {
+            Faces::U16(_) => Context::UNSIGNED_SHORT,
+            Faces::U32(_) => Context::UNSIGNED_INT,
+        }
     }
 
-    /// Recompute this mesh normals.
+    /// Recomputes this mesh normals from its current vertex coordinates and faces.
+    ///
+    /// The normals are re-uploaded to the GPU the next time this mesh is rendered. This does
+    /// nothing if the vertex coordinates or the faces are not available on the CPU.
     pub fn recompute_normals(&mut self) {
-        Mesh::compute_normals(
-            &self.coords.read().unwrap().data().as_ref().unwrap()[..],
-            &self.faces.read().unwrap().data().as_ref().unwrap()[..],
-            self.normals.write().unwrap().data_mut().as_mut().unwrap(),
-        );
+        let coords = self.coords.read().unwrap();
+        let coords = match coords.data() {
+            Some(coords) => coords,
+            None => return,
+        };
+        let mut normals = self.normals.write().unwrap();
+
+        match self.faces {
+            Faces::U16(ref faces) => {
+                if let Some(faces) = faces.read().unwrap().data() {
+                    let normals = normals.data_mut().get_or_insert_with(Vec::new);
+                    Mesh::compute_normals(&coords[..], &faces[..], normals);
+                }
+            }
+            Faces::U32(ref faces) => {
+                if let Some(faces) = faces.read().unwrap().data() {
+                    let normals = normals.data_mut().get_or_insert_with(Vec::new);
+                    Mesh::compute_normals(&coords[..], &faces[..], normals);
+                }
+            }
+        }
+    }
+
+    /// Appends the geometry of `other`, transformed by `transform`, to this mesh.
+    ///
+    /// The vertices, normals, and texture coordinates of `other` are appended to those of this
+    /// mesh, and its faces are offset accordingly. The normals are transformed by the
+    /// inverse-transpose of `transform`, which is its rotation since it is an isometry. If only
+    /// one of the meshes has vertex colors, the vertices of the other one are given white. The
+    /// merged buffers are new, so other meshes sharing buffers with this mesh are not modified.
+    /// The merged mesh has 32-bits indices if either mesh has them, or if it has more than 65536
+    /// vertices.
+    ///
+    /// # Failures:
+    /// Fails if the vertex data of either mesh is not available on the CPU.
+    pub fn merge(&mut self, other: &Mesh, transform: &Isometry3<f32>) {
+        let coords = {
+            let (a, b) = (self.coords.read().unwrap(), other.coords.read().unwrap());
+            let first = a.len() as u32;
+            let merged = concat(&a, &b, |pt| transform * pt);
+            let faces: Vec<_> = match (self.faces_as_u32(), other.faces_as_u32()) {
+                (Some(fa), Some(fb)) => fa
+                    .into_iter()
+                    .chain(
+                        fb.into_iter()
+                            .map(|f| Point3::new(f.x + first, f.y + first, f.z + first)),
+                    )
+                    .collect(),
+                _ => panic!(
+                    "The vertex data of both meshes must be available on the CPU to be merged."
+                ),
+            };
+            let u32_indices = self.index_type() == Context::UNSIGNED_INT
+                || other.index_type() == Context::UNSIGNED_INT
+                || merged.len() > MAX_U16_VERTICES;
+            let normals = concat(
+                &self.normals.read().unwrap(),
+                &other.normals.read().unwrap(),
+                |n| transform.rotation * n,
+            );
+            let uvs = concat(
+                &self.uvs.read().unwrap(),
+                &other.uvs.read().unwrap(),
+                |uv| *uv,
+            );
+
+            let colors = if self.colors.is_some() || other.colors.is_some() {
+                let mut colors = vertex_colors(self, a.len());
+                colors.extend(vertex_colors(other, b.len()));
+                Some(colors)
+            } else {
+                None
+            };
+
+            let alloc_type = a.allocation_type();
+            self.faces = if u32_indices {
+                Faces::U32(gpu_vec(faces, BufferType::ElementArray, alloc_type))
+            } else {
+                let faces = faces.into_iter().map(to_u16).collect();
+                Faces::U16(gpu_vec(faces, BufferType::ElementArray, alloc_type))
+            };
+            self.normals = gpu_vec(normals, BufferType::Array, alloc_type);
+            self.uvs = gpu_vec(uvs, BufferType::Array, alloc_type);
+            self.colors = colors.map(|c| gpu_vec(c, BufferType::Array, alloc_type));
+            self.edges = None;
+
+            gpu_vec(merged, BufferType::Array, alloc_type)
+        };
+
+        self.coords = coords;
+        self.invalidate_aabb();
     }
 
     /// This mesh faces.
+    ///
+    /// # Failures:
+    /// Fails if this mesh has 32-bits indices. Use `u32_faces` instead.
     pub fn faces(&self) -> &Arc<RwLock<GPUVec<Point3<u16>>>> {
-        &self.faces
+        match self.faces {
+            Faces::U16(ref faces) => faces,
+            Faces::U32(_) => panic!("This mesh has 32-bits indices, use `Mesh::u32_faces`."),
+        }
+    }
+
+    /// This mesh faces, if it has 32-bits indices.
+    pub fn u32_faces(&self) -> Option<&Arc<RwLock<GPUVec<Point3<u32>>>>> {
+        match self.faces {
+            Faces::U16(_) => None,
+            Faces::U32(ref faces) => Some(faces),
+        }
+    }
+
+    // This mesh faces converted to 32-bits indices, or `None` if they are not available on the
+    // CPU.
+    pub(crate) fn faces_as_u32(&self) -> Option<Vec<Point3<u32>>> {
+        match self.faces {
+            Faces::U16(ref faces) => faces.read().unwrap().data().as_ref().map(|fs| {
+                fs.iter()
+                    .map(|f| Point3::new(f.x as u32, f.y as u32, f.z as u32))
+                    .collect()
+            }),
+            Faces::U32(ref faces) => faces.read().unwrap().data().clone(),
+        }
     }
 
     /// This mesh normals.
@@ -273,19 +620,34 @@ impl Mesh {
     }
 
     /// This mesh vertex coordinates.
+    ///
+    /// If they are modified through the returned lock, `invalidate_aabb` must be called
+    /// afterward. `coords_mut` takes care of it.
     pub fn coords(&self) -> &Arc<RwLock<GPUVec<Point3<f32>>>> {
         &self.coords
     }
 
+    /// Mutable access to this mesh vertex coordinates.
+    ///
+    /// Modifying the coordinates through `GPUVec::data_mut` marks them for re-upload: only the
+    /// vertex buffer is updated on the GPU (with `glBufferSubData` if its size did not grow)
+    /// the next time this mesh is rendered, the other buffers are left untouched. For meshes
+    /// deformed every frame, consider setting the allocation type of the coordinates to
+    /// `AllocationType::DynamicDraw`, and call `recompute_normals` after editing them if needed.
+    pub fn coords_mut(&mut self) -> RwLockWriteGuard<GPUVec<Point3<f32>>> {
+        self.invalidate_aabb();
+        self.coords.write().unwrap()
+    }
+
     /// This mesh texture coordinates.
     pub fn uvs(&self) -> &Arc<RwLock<GPUVec<Point2<f32>>>> {
         &self.uvs
     }
 
     /// Computes normals from a set of faces.
-    pub fn compute_normals_array(
+    pub fn compute_normals_array<I: na::Scalar + Copy + Into<u32>>(
         coordinates: &[Point3<f32>],
-        faces: &[Point3<u16>],
+        faces: &[Point3<I>],
     ) -> Vec<Vector3<f32>> {
         let mut res = Vec::new();
 
@@ -294,42 +656,174 @@ impl Mesh {
         res
     }
 
-    /// Computes normals from a set of faces.
-    pub fn compute_normals(
+    /// The minimum and maximum corners of the axis-aligned bounding box of this mesh vertices.
+    ///
+    /// Returns `None` if this mesh has no vertex, or if they are not available on the CPU. The
+    /// bounding box is cached until `invalidate_aabb` is called.
+    pub fn aabb(&self) -> Option<(Point3<f32>, Point3<f32>)> {
+        if let Some(aabb) = *self.aabb.read().unwrap() {
+            return Some(aabb);
+        }
+
+        let coords = self.coords.read().unwrap();
+        let coords = coords.data().as_ref()?;
+        let first = *coords.first()?;
+        let aabb = coords.iter().fold((first, first), |(mins, maxs), pt| {
+            (mins.inf(pt), maxs.sup(pt))
+        });
+
+        *self.aabb.write().unwrap() = Some(aabb);
+        Some(aabb)
+    }
+
+    /// Discards the cached bounding box of this mesh, so that `aabb` recomputes it.
+    ///
+    /// This must be called after modifying the vertices through `coords`.
+    pub fn invalidate_aabb(&self) {
+        *self.aabb.write().unwrap() = None;
+    }
+
+    /// Computes the time of impact of a ray with the triangles of this mesh.
+    ///
+    /// The ray is expressed in the local space of this mesh. Returns `None` if the ray does not
+    /// hit this mesh before `max_toi`, or if its vertices or faces are not available on the CPU.
+    pub fn cast_ray(&self, ray: &Ray<f32>, max_toi: f32) -> Option<f32> {
+        let id = Isometry3::identity();
+
+        // Test the bounding box first to avoid testing all the triangles.
+        let (mins, maxs) = self.aabb()?;
+        let _ = AABB::new(mins, maxs).toi_with_ray(&id, ray, max_toi, true)?;
+
+        let coords = self.coords.read().unwrap();
+        let coords = coords.data().as_ref()?;
+
+        match self.faces {
+            Faces::U16(ref faces) => {
+                let faces = faces.read().unwrap();
+                toi_with_faces(coords, faces.data().as_ref()?, ray, max_toi)
+            }
+            Faces::U32(ref faces) => {
+                let faces = faces.read().unwrap();
+                toi_with_faces(coords, faces.data().as_ref()?, ray, max_toi)
+            }
+        }
+    }
+
+    /// Computes area-weighted per-vertex normals from a set of faces.
+    ///
+    /// Degenerate triangles are ignored. Vertices that do not belong to any valid triangle get a
+    /// zero normal.
+    pub fn compute_normals<I: na::Scalar + Copy + Into<u32>>(
         coordinates: &[Point3<f32>],
-        faces: &[Point3<u16>],
+        faces: &[Point3<I>],
         normals: &mut Vec<Vector3<f32>>,
     ) {
-        let mut divisor: Vec<f32> = iter::repeat(0f32).take(coordinates.len()).collect();
-
         normals.clear();
         normals.extend(iter::repeat(Vector3::<f32>::zero()).take(coordinates.len()));
 
-        // Accumulate normals ...
+        // Accumulate the face normals, weighted by the face areas ...
         for f in faces.iter() {
-            let edge1 = coordinates[f.y as usize] - coordinates[f.x as usize];
-            let edge2 = coordinates[f.z as usize] - coordinates[f.x as usize];
+            let (x, y, z) = (index(f.x), index(f.y), index(f.z));
+            let edge1 = coordinates[y] - coordinates[x];
+            let edge2 = coordinates[z] - coordinates[x];
             let cross = edge1.cross(&edge2);
-            let normal;
 
-            if !cross.is_zero() {
-                normal = cross.normalize()
-            } else {
-                normal = cross
+            if cross.is_zero() || !cross.iter().all(|e| e.is_finite()) {
+                continue;
             }
 
-            normals[f.x as usize] = normals[f.x as usize] + normal;
-            normals[f.y as usize] = normals[f.y as usize] + normal;
-            normals[f.z as usize] = normals[f.z as usize] + normal;
+            normals[x] += cross;
+            normals[y] += cross;
+            normals[z] += cross;
+        }
 
-            divisor[f.x as usize] = divisor[f.x as usize] + 1.0;
-            divisor[f.y as usize] = divisor[f.y as usize] + 1.0;
-            divisor[f.z as usize] = divisor[f.z as usize] + 1.0;
+        // ... and normalize them.
+        for n in normals.iter_mut() {
+            *n = n.try_normalize(0.0).unwrap_or_else(Vector3::zeros);
         }
+    }
+}
 
-        // ... and compute the mean
-        for (n, divisor) in normals.iter_mut().zip(divisor.iter()) {
-            *n = *n / *divisor
+#[inline]
+fn index<I: Into<u32>>(i: I) -> usize {
+    i.into() as usize
+}
+
+#[inline]
+fn to_u16(f: Point3<u32>) -> Point3<u16> {
+    Point3::new(f.x as u16, f.y as u16, f.z as u16)
+}
+
+fn assert_u32_indices_support() {
+    assert!(
+        Context::get().supports_u32_indices(),
+        "Meshes with 32-bits indices require the OES_element_index_uint extension, which is not supported."
+    );
+}
+
+// The edges of the triangles of `faces`.
+fn edges<I: na::Scalar + Copy + Into<u32>>(faces: &GPUVec<Point3<I>>) -> GPUVec<Point2<I>>
+where
+    Point2<I>: GLPrimitive,
+    Point3<I>: GLPrimitive,
+{
+    let mut edges = Vec::new();
+    for face in faces.data().as_ref().unwrap() {
+        edges.push(Point2::new(face.x, face.y));
+        edges.push(Point2::new(face.y, face.z));
+        edges.push(Point2::new(face.z, face.x));
+    }
+
+    GPUVec::new(edges, BufferType::ElementArray, AllocationType::StaticDraw)
+}
+
+// The time of impact of `ray` with the first triangle of `faces` it hits before `max_toi`.
+fn toi_with_faces<I: na::Scalar + Copy + Into<u32>>(
+    coords: &[Point3<f32>],
+    faces: &[Point3<I>],
+    ray: &Ray<f32>,
+    max_toi: f32,
+) -> Option<f32> {
+    let id = Isometry3::identity();
+    let mut best = None;
+
+    for f in faces.iter() {
+        let triangle = Triangle::new(coords[index(f.x)], coords[index(f.y)], coords[index(f.z)]);
+
+        if let Some(toi) = triangle.toi_with_ray(&id, ray, best.unwrap_or(max_toi), true) {
+            best = Some(toi);
         }
     }
+
+    best
+}
+
+// The elements of `a`, followed by the elements of `b` mapped by `f`.
+fn concat<T: Clone + GLPrimitive, F: Fn(&T) -> T>(a: &GPUVec<T>, b: &GPUVec<T>, f: F) -> Vec<T> {
+    let (a, b) = match (a.data(), b.data()) {
+        (Some(a), Some(b)) => (a, b),
+        _ => panic!("The vertex data of both meshes must be available on the CPU to be merged."),
+    };
+
+    a.iter().cloned().chain(b.iter().map(f)).collect()
+}
+
+// The vertex colors of `mesh`, or white for each of its `len` vertices if it has none.
+fn vertex_colors(mesh: &Mesh, len: usize) -> Vec<Point3<f32>> {
+    match mesh.colors {
+        Some(ref colors) => {
+            colors.read().unwrap().data().clone().expect(
+                "The vertex colors of both meshes must be available on the CPU to be merged.",
+            )
+        }
+        None => vec![Point3::new(1.0, 1.0, 1.0); len],
+    }
+}
+
+fn gpu_vec<T: GLPrimitive>(
+    data: Vec<T>,
+    buf_type: BufferType,
+    alloc_type: AllocationType,
+) -> Arc<RwLock<GPUVec<T>>> {
+    Arc::new(RwLock::new(GPUVec::new(data, buf_type, alloc_type)))
 }
@@ -1,7 +1,7 @@
 //! A resource manager to load meshes.
 
 use crate::loader::mtl::MtlMaterial;
-use crate::loader::obj;
+use crate::loader::{obj, stl};
 use crate::resource::Mesh;
 use ncollide3d::procedural;
 use ncollide3d::procedural::TriMesh;
@@ -80,16 +80,45 @@ impl MeshManager {
         mtl_dir: &Path,
         geometry_name: &str,
     ) -> IoResult<Vec<(String, Rc<RefCell<Mesh>>, Option<MtlMaterial>)>> {
-        obj::parse_file(path, mtl_dir, geometry_name).map(|ms| {
-            let mut res = Vec::new();
+        obj::parse_file(path, mtl_dir, geometry_name).map(MeshManager::share_meshes)
+    }
+
+    /// Loads the meshes described by an obj file, keeping its objects and groups apart.
+    ///
+    /// See `obj::parse_grouped` for details.
+    pub fn load_obj_grouped(
+        path: &Path,
+        mtl_dir: &Path,
+        geometry_name: &str,
+    ) -> IoResult<Vec<(String, Rc<RefCell<Mesh>>, Option<MtlMaterial>)>> {
+        obj::parse_file_grouped(path, mtl_dir, geometry_name).map(MeshManager::share_meshes)
+    }
 
-            for (n, m, mat) in ms.into_iter() {
-                let m = Rc::new(RefCell::new(m));
+    fn share_meshes(
+        ms: Vec<(String, Mesh, Option<MtlMaterial>)>,
+    ) -> Vec<(String, Rc<RefCell<Mesh>>, Option<MtlMaterial>)> {
+        let mut res = Vec::new();
 
-                res.push((n, m, mat));
-            }
+        for (n, m, mat) in ms.into_iter() {
+            let m = Rc::new(RefCell::new(m));
+
+            res.push((n, m, mat));
+        }
+
+        res
+    }
+
+    /// Loads the mesh described by an STL file, and registers it with the specified name.
+    ///
+    /// If a mesh with the same name exists, the file is not read and the old mesh is returned.
+    pub fn load_stl(&mut self, path: &Path, name: &str) -> IoResult<Rc<RefCell<Mesh>>> {
+        if let Some(mesh) = self.get(name) {
+            return Ok(mesh);
+        }
+
+        let mesh = Rc::new(RefCell::new(stl::parse_file(path)?));
+        self.add(mesh.clone(), name);
 
-            res
-        })
+        Ok(mesh)
     }
 }
@@ -1,12 +1,13 @@
 //! GPU resource managers
 
 pub use crate::context::Texture;
-pub use crate::resource::effect::{Effect, ShaderAttribute, ShaderUniform};
+pub use crate::resource::effect::{Effect, EffectWatcher, ShaderAttribute, ShaderUniform};
 pub use crate::resource::framebuffer_manager::{
     FramebufferManager, OffscreenBuffers, RenderTarget,
 };
 pub use crate::resource::gl_primitive::{GLPrimitive, PrimitiveArray};
 pub use crate::resource::gpu_vector::{AllocationType, BufferType, GPUVec};
+pub use crate::resource::instances_buffer::InstancesBuffer;
 pub use crate::resource::material::{Material, PlanarMaterial};
 pub use crate::resource::material_manager::MaterialManager;
 pub use crate::resource::mesh::Mesh;
@@ -14,12 +15,16 @@ pub use crate::resource::mesh_manager::MeshManager;
 pub use crate::resource::planar_material_manager::PlanarMaterialManager;
 pub use crate::resource::planar_mesh::PlanarMesh;
 pub use crate::resource::planar_mesh_manager::PlanarMeshManager;
-pub use crate::resource::texture_manager::{TextureManager, TextureWrapping};
+pub use crate::resource::shadow_map::ShadowMap;
+pub use crate::resource::texture_manager::{
+    TextureFiltering, TextureFormat, TextureManager, TextureWrapping,
+};
 
 mod effect;
 mod framebuffer_manager;
 mod gl_primitive;
 mod gpu_vector;
+mod instances_buffer;
 pub mod material;
 mod material_manager;
 mod mesh;
@@ -27,4 +32,5 @@ mod mesh_manager;
 mod planar_material_manager;
 mod planar_mesh;
 mod planar_mesh_manager;
+mod shadow_map;
 mod texture_manager;
@@ -0,0 +1,153 @@
+//! Depth of a scene seen from a light, used to draw shadows.
+
+use crate::context::{Context, Texture};
+use crate::light::Light;
+use crate::resource::framebuffer_manager::{FramebufferManager, RenderTarget};
+use na::{Isometry3, Matrix4, Orthographic3, Perspective3, Point3, Vector3};
+use std::cell::Cell;
+use std::f32;
+
+#[path = "../error.rs"]
+mod error;
+
+/// A square render target containing the depth of a scene seen from a light.
+///
+/// The depth is packed into the four 8-bits channels of the color texture so that it can be
+/// sampled even where depth textures are not supported.
+pub struct ShadowMap {
+    target: RenderTarget,
+    size: u32,
+    transformation: Cell<Option<Matrix4<f32>>>,
+    bias: Cell<f32>,
+}
+
+impl ShadowMap {
+    /// Creates a new shadow map of `size * size` pixels.
+    pub fn new(size: u32) -> ShadowMap {
+        assert!(size != 0, "The shadow map size must not be zero.");
+
+        let target = FramebufferManager::new_render_target(size as usize, size as usize, false);
+        let ctxt = Context::get();
+
+        // The packed depth must not be interpolated.
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, target.texture_id()));
+        verify!(ctxt.tex_parameteri(
+            Context::TEXTURE_2D,
+            Context::TEXTURE_MAG_FILTER,
+            Context::NEAREST as i32
+        ));
+        verify!(ctxt.tex_parameteri(
+            Context::TEXTURE_2D,
+            Context::TEXTURE_MIN_FILTER,
+            Context::NEAREST as i32
+        ));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, None));
+
+        ShadowMap {
+            target,
+            size,
+            transformation: Cell::new(None),
+            bias: Cell::new(0.005),
+        }
+    }
+
+    /// The width and height of this shadow map, in pixels.
+    #[inline]
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// The render target the depth is drawn to.
+    #[inline]
+    pub fn render_target(&self) -> &RenderTarget {
+        &self.target
+    }
+
+    /// The texture containing the packed depth.
+    #[inline]
+    pub fn texture(&self) -> Option<&Texture> {
+        self.target.texture_id()
+    }
+
+    /// The transformation from world coordinates to the clip space of the light.
+    ///
+    /// This is `None` if no shadow should be drawn with this shadow map.
+    #[inline]
+    pub fn transformation(&self) -> Option<Matrix4<f32>> {
+        self.transformation.get()
+    }
+
+    /// Sets the transformation from world coordinates to the clip space of the light.
+    #[inline]
+    pub fn set_transformation(&self, transformation: Option<Matrix4<f32>>) {
+        self.transformation.set(transformation)
+    }
+
+    /// The depth offset applied before comparing a fragment depth with this shadow map.
+    #[inline]
+    pub fn bias(&self) -> f32 {
+        self.bias.get()
+    }
+
+    /// Sets the depth offset applied before comparing a fragment depth with this shadow map.
+    ///
+    /// Larger values avoid self-shadowing artifacts ("shadow acne") but detach the shadows from
+    /// the objects casting them. Defaults to 0.005.
+    #[inline]
+    pub fn set_bias(&self, bias: f32) {
+        self.bias.set(bias)
+    }
+
+    /// Computes the transformation from world coordinates to the clip space of a light, such
+    /// that the given axis-aligned box is entirely seen by the light.
+    ///
+    /// Returns `None` for lights that cannot cast shadows, i.e., lights superimposed with the
+    /// camera.
+    pub fn light_transformation(
+        light: &Light,
+        mins: &Point3<f32>,
+        maxs: &Point3<f32>,
+    ) -> Option<Matrix4<f32>> {
+        let center = na::center(mins, maxs);
+        let radius = na::distance(mins, maxs).max(1.0e-3) / 2.0;
+
+        match *light {
+            Light::Directional { ref direction, .. } => {
+                let dir = direction.try_normalize(1.0e-6)?;
+                let eye = center - dir * radius * 2.0;
+                let view = Isometry3::look_at_rh(&eye, &center, &up_axis(&dir));
+                let proj =
+                    Orthographic3::new(-radius, radius, -radius, radius, radius, radius * 3.0);
+
+                Some(proj.to_homogeneous() * view.to_homogeneous())
+            }
+            Light::Absolute(ref pos) | Light::AbsoluteWithColor { ref pos, .. } => {
+                let dir = center - pos;
+                let dist = dir.norm();
+                let max_fov = f32::consts::FRAC_PI_2 * 1.5;
+                let (dir, fov, znear) = if dist > radius {
+                    let fov = (2.0 * (radius / dist).asin()).min(max_fov);
+                    (dir / dist, fov, (dist - radius).max(dist * 1.0e-3))
+                } else {
+                    // The light is inside of the box: only shadows in front of it are drawn.
+                    let dir = dir.try_normalize(1.0e-6).unwrap_or(-Vector3::y());
+                    (dir, max_fov, radius * 1.0e-3)
+                };
+                let view = Isometry3::look_at_rh(pos, &(pos + dir), &up_axis(&dir));
+                let proj = Perspective3::new(1.0, fov, znear, dist + radius);
+
+                Some(proj.to_homogeneous() * view.to_homogeneous())
+            }
+            Light::StickToCamera => None,
+        }
+    }
+}
+
+// An up vector not parallel to `dir`.
+fn up_axis(dir: &Vector3<f32>) -> Vector3<f32> {
+    if dir.y.abs() > 0.99 {
+        Vector3::z()
+    } else {
+        Vector3::y()
+    }
+}
@@ -34,6 +34,59 @@ impl Into<u32> for TextureWrapping {
     }
 }
 
+/// Filtering parameters for a texture.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum TextureFiltering {
+    /// Uses the color of the texel nearest to the texture coordinate.
+    Nearest,
+    /// Interpolates linearly the colors of the four texels nearest to the texture coordinate.
+    Linear,
+    /// Interpolates linearly between the two mipmaps closest to the texture size on screen,
+    /// each sampled as with `Linear`.
+    LinearMipmapLinear,
+}
+
+impl Into<u32> for TextureFiltering {
+    #[inline]
+    fn into(self) -> u32 {
+        match self {
+            TextureFiltering::Nearest => Context::NEAREST,
+            TextureFiltering::Linear => Context::LINEAR,
+            TextureFiltering::LinearMipmapLinear => Context::LINEAR_MIPMAP_LINEAR,
+        }
+    }
+}
+
+/// Pixel formats of the data used to update a texture.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum TextureFormat {
+    /// Three 8-bits components per pixel: red, green, and blue.
+    Rgb,
+    /// Four 8-bits components per pixel: red, green, blue, and alpha.
+    Rgba,
+}
+
+impl TextureFormat {
+    /// The number of bytes of a pixel with this format.
+    #[inline]
+    pub fn pixel_size(self) -> usize {
+        match self {
+            TextureFormat::Rgb => 3,
+            TextureFormat::Rgba => 4,
+        }
+    }
+}
+
+impl Into<u32> for TextureFormat {
+    #[inline]
+    fn into(self) -> u32 {
+        match self {
+            TextureFormat::Rgb => Context::RGB,
+            TextureFormat::Rgba => Context::RGBA,
+        }
+    }
+}
+
 impl Texture {
     /// Allocates a new texture on the gpu. The texture is not configured.
     pub fn new() -> Rc<Texture> {
@@ -43,8 +96,14 @@ impl Texture {
         Rc::new(tex)
     }
 
+    /// Sets the wrapping of this texture along both texture coordinates.
+    pub fn set_wrapping(&self, wrapping: TextureWrapping) {
+        self.set_wrapping_s(wrapping);
+        self.set_wrapping_t(wrapping);
+    }
+
     /// Sets the wrapping of this texture along the `s` texture coordinate.
-    pub fn set_wrapping_s(&mut self, wrapping: TextureWrapping) {
+    pub fn set_wrapping_s(&self, wrapping: TextureWrapping) {
         let ctxt = Context::get();
         verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&self)));
         let wrap: u32 = wrapping.into();
@@ -52,12 +111,121 @@ impl Texture {
     }
 
     /// Sets the wrapping of this texture along the `t` texture coordinate.
-    pub fn set_wrapping_t(&mut self, wrapping: TextureWrapping) {
+    pub fn set_wrapping_t(&self, wrapping: TextureWrapping) {
         let ctxt = Context::get();
         verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&self)));
         let wrap: u32 = wrapping.into();
         verify!(ctxt.tex_parameteri(Context::TEXTURE_2D, Context::TEXTURE_WRAP_T, wrap as i32));
     }
+
+    /// Sets the filtering used when this texture is displayed smaller than its actual size.
+    ///
+    /// Mipmaps are generated from the current content of this texture if `filtering` is
+    /// `TextureFiltering::LinearMipmapLinear`.
+    pub fn set_min_filtering(&self, filtering: TextureFiltering) {
+        let ctxt = Context::get();
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&self)));
+
+        let mipmaps = filtering == TextureFiltering::LinearMipmapLinear;
+        self.1.mipmaps.set(mipmaps);
+
+        if mipmaps {
+            verify!(ctxt.generate_mipmap(Context::TEXTURE_2D));
+        }
+
+        let filter: u32 = filtering.into();
+        verify!(ctxt.tex_parameteri(
+            Context::TEXTURE_2D,
+            Context::TEXTURE_MIN_FILTER,
+            filter as i32
+        ));
+    }
+
+    /// Sets the filtering used when this texture is displayed larger than its actual size.
+    ///
+    /// Mipmaps are irrelevant for magnification, so `TextureFiltering::LinearMipmapLinear` is
+    /// equivalent to `TextureFiltering::Linear` here.
+    pub fn set_mag_filtering(&self, filtering: TextureFiltering) {
+        let ctxt = Context::get();
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&self)));
+
+        let filter = match filtering {
+            TextureFiltering::Nearest => Context::NEAREST,
+            _ => Context::LINEAR,
+        };
+        verify!(ctxt.tex_parameteri(
+            Context::TEXTURE_2D,
+            Context::TEXTURE_MAG_FILTER,
+            filter as i32
+        ));
+    }
+
+    /// Replaces the pixels of a rectangular region of this texture, without reallocating it.
+    ///
+    /// `data` contains the rows of the region, from bottom to top. If the minification filter of
+    /// this texture was set to `TextureFiltering::LinearMipmapLinear`, its mipmaps are then
+    /// regenerated from its updated content. The region is checked against the size of this
+    /// texture only if it was uploaded by kiss3d, e.g., by the texture manager.
+    ///
+    /// # Failures:
+    /// Fails if the region does not fit into this texture, or if `data` does not contain exactly
+    /// `width * height` pixels.
+    pub fn update_subimage(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        data: &[u8],
+        format: TextureFormat,
+    ) {
+        assert!(
+            subimage_size(width, height, format) == Some(data.len()),
+            "The pixel data size does not match the size of the updated region."
+        );
+
+        if let Some((tex_width, tex_height)) = self.1.size.get() {
+            assert!(
+                x as u64 + width as u64 <= tex_width as u64
+                    && y as u64 + height as u64 <= tex_height as u64,
+                "The updated region does not fit into the {}x{} texture.",
+                tex_width,
+                tex_height
+            );
+        }
+
+        let ctxt = Context::get();
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&self)));
+        verify!(ctxt.pixel_storei(Context::UNPACK_ALIGNMENT, 1));
+        verify!(ctxt.tex_sub_image2d(
+            Context::TEXTURE_2D,
+            0,
+            x as i32,
+            y as i32,
+            width as i32,
+            height as i32,
+            format.into(),
+            data
+        ));
+
+        if self.1.mipmaps.get() {
+            verify!(ctxt.generate_mipmap(Context::TEXTURE_2D));
+        }
+    }
+
+    /// Sets the filtering used when this texture is displayed both smaller and larger than its
+    /// actual size.
+    pub fn set_filtering(&self, filtering: TextureFiltering) {
+        self.set_min_filtering(filtering);
+        self.set_mag_filtering(filtering);
+    }
+}
+
+// The number of bytes of a region of `width * height` pixels, or `None` if it overflows.
+fn subimage_size(width: u32, height: u32, format: TextureFormat) -> Option<usize> {
+    (width as usize)
+        .checked_mul(height as usize)?
+        .checked_mul(format.pixel_size())
 }
 
 impl Drop for Texture {
@@ -122,6 +290,8 @@ impl TextureManager {
             Context::RGB,
             Some(&default_tex_pixels)
         ));
+        default_tex.1.size.set(Some((1, 1)));
+        default_tex.1.mipmaps.set(true);
 
         TextureManager {
             textures: HashMap::new(),
@@ -151,6 +321,47 @@ impl TextureManager {
             .map(|t| (t.0.clone(), t.1))
     }
 
+    /// The size of a texture registered by this manager.
+    ///
+    /// Returns `None` if the texture has not been registered by this manager.
+    pub fn get_size_of(&self, texture: &Texture) -> Option<(u32, u32)> {
+        self.textures
+            .values()
+            .find(|t| &*t.0 as *const Texture == texture as *const Texture)
+            .map(|t| t.1)
+    }
+
+    /// Replaces the pixels of a rectangular region of a texture registered by this manager.
+    ///
+    /// See `Texture::update_subimage` for details. Returns `false`, without modifying the
+    /// texture, if it has not been registered by this manager, if the region does not fit into
+    /// the texture, or if `data` does not contain exactly `width * height` pixels.
+    pub fn update_subimage(
+        &self,
+        texture: &Texture,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        data: &[u8],
+        format: TextureFormat,
+    ) -> bool {
+        let (tex_width, tex_height) = match self.get_size_of(texture) {
+            Some(size) => size,
+            None => return false,
+        };
+
+        let fits = x as u64 + width as u64 <= tex_width as u64
+            && y as u64 + height as u64 <= tex_height as u64;
+
+        if !fits || subimage_size(width, height, format) != Some(data.len()) {
+            return false;
+        }
+
+        texture.update_subimage(x, y, width, height, data, format);
+        true
+    }
+
     /// Allocates a new texture that is not yet configured.
     ///
     /// If a texture with same name exists, nothing is created and the old texture is returned.
@@ -163,11 +374,12 @@ impl TextureManager {
 
     /// Allocates a new texture read from a `DynamicImage` object.
     ///
+    /// Images that are neither RGB nor RGBA with 8-bits components are converted to RGBA first.
     /// If a texture with same name exists, nothing is created and the old texture is returned.
     pub fn add_image(&mut self, dynamic_image: DynamicImage, name: &str) -> Rc<Texture> {
         self.textures
             .entry(name.to_string())
-            .or_insert_with(|| TextureManager::load_texture_into_context(dynamic_image).unwrap())
+            .or_insert_with(|| TextureManager::load_texture_into_context(dynamic_image))
             .0
             .clone()
     }
@@ -182,82 +394,140 @@ impl TextureManager {
         )
     }
 
+    /// Allocates a new cube map texture from the images of its six faces.
+    ///
+    /// The faces are given in the order `+x`, `-x`, `+y`, `-y`, `+z`, `-z`, and must all be
+    /// square and of the same size. The resulting texture must be bound to
+    /// `Context::TEXTURE_CUBE_MAP`, e.g., for use with `Window::set_skybox`. If a texture with
+    /// same name exists, nothing is created and the old texture is returned.
+    pub fn add_cubemap_from_images(&mut self, faces: [DynamicImage; 6], name: &str) -> Rc<Texture> {
+        self.textures
+            .entry(name.to_string())
+            .or_insert_with(|| TextureManager::load_cubemap_into_context(faces))
+            .0
+            .clone()
+    }
+
+    /// Allocates a new cube map texture from the image files of its six faces.
+    ///
+    /// See `add_cubemap_from_images` for details. If a texture with same name exists, nothing
+    /// is created and the old texture is returned.
+    pub fn add_cubemap(&mut self, paths: [&Path; 6], name: &str) -> Rc<Texture> {
+        let load = |i: usize| image::open(paths[i]).expect(paths[i].to_str().unwrap());
+
+        self.textures
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                let faces = [load(0), load(1), load(2), load(3), load(4), load(5)];
+                TextureManager::load_cubemap_into_context(faces)
+            })
+            .0
+            .clone()
+    }
+
     /// Allocates a new texture read from a file.
     fn load_texture_from_file(path: &Path) -> (Rc<Texture>, (u32, u32)) {
-        TextureManager::load_texture_into_context(image::open(path).unwrap())
-            .expect(path.to_str().unwrap())
+        TextureManager::load_texture_into_context(image::open(path).expect(path.to_str().unwrap()))
     }
 
-    fn load_texture_into_context(
-        dynamic_image: DynamicImage,
-    ) -> Result<(Rc<Texture>, (u32, u32)), &'static str> {
+    fn load_texture_into_context(dynamic_image: DynamicImage) -> (Rc<Texture>, (u32, u32)) {
         let ctxt = Context::get();
         let tex = Texture::new();
-        let width;
-        let height;
 
-        unsafe {
-            verify!(ctxt.active_texture(Context::TEXTURE0));
-            verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&*tex)));
-
-            match dynamic_image {
-                DynamicImage::ImageRgb8(image) => {
-                    width = image.width();
-                    height = image.height();
-
-                    verify!(ctxt.tex_image2d(
-                        Context::TEXTURE_2D,
-                        0,
-                        Context::RGB as i32,
-                        image.width() as i32,
-                        image.height() as i32,
-                        0,
-                        Context::RGB,
-                        Some(&image.into_raw()[..])
-                    ));
-                }
-                DynamicImage::ImageRgba8(image) => {
-                    width = image.width();
-                    height = image.height();
-
-                    verify!(ctxt.tex_image2d(
-                        Context::TEXTURE_2D,
-                        0,
-                        Context::RGBA as i32,
-                        image.width() as i32,
-                        image.height() as i32,
-                        0,
-                        Context::RGBA,
-                        Some(&image.into_raw()[..])
-                    ));
-                }
-                _ => {
-                    return Err("Failed to load texture, unsuported pixel format.");
-                }
-            }
+        verify!(ctxt.active_texture(Context::TEXTURE0));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&*tex)));
+
+        let size = TextureManager::upload_image(Context::TEXTURE_2D, dynamic_image);
+        tex.1.size.set(Some(size));
+
+        TextureManager::set_default_parameters(Context::TEXTURE_2D);
+
+        (tex, size)
+    }
+
+    fn load_cubemap_into_context(faces: [DynamicImage; 6]) -> (Rc<Texture>, (u32, u32)) {
+        let ctxt = Context::get();
+        let tex = Texture::new();
+        let [px, nx, py, ny, pz, nz] = faces;
+        let faces = vec![
+            (Context::TEXTURE_CUBE_MAP_POSITIVE_X, px),
+            (Context::TEXTURE_CUBE_MAP_NEGATIVE_X, nx),
+            (Context::TEXTURE_CUBE_MAP_POSITIVE_Y, py),
+            (Context::TEXTURE_CUBE_MAP_NEGATIVE_Y, ny),
+            (Context::TEXTURE_CUBE_MAP_POSITIVE_Z, pz),
+            (Context::TEXTURE_CUBE_MAP_NEGATIVE_Z, nz),
+        ];
+        let mut size = (0, 0);
 
-            verify!(ctxt.tex_parameteri(
-                Context::TEXTURE_2D,
-                Context::TEXTURE_WRAP_S,
-                Context::CLAMP_TO_EDGE as i32
-            ));
-            verify!(ctxt.tex_parameteri(
-                Context::TEXTURE_2D,
-                Context::TEXTURE_WRAP_T,
-                Context::CLAMP_TO_EDGE as i32
-            ));
-            verify!(ctxt.tex_parameteri(
-                Context::TEXTURE_2D,
-                Context::TEXTURE_MIN_FILTER,
-                Context::LINEAR as i32
-            ));
-            verify!(ctxt.tex_parameteri(
-                Context::TEXTURE_2D,
-                Context::TEXTURE_MAG_FILTER,
-                Context::LINEAR as i32
-            ));
+        verify!(ctxt.active_texture(Context::TEXTURE0));
+        verify!(ctxt.bind_texture(Context::TEXTURE_CUBE_MAP, Some(&*tex)));
+
+        for (target, face) in faces {
+            size = TextureManager::upload_image(target, face);
         }
-        Ok((tex, (width, height)))
+
+        TextureManager::set_default_parameters(Context::TEXTURE_CUBE_MAP);
+
+        (tex, size)
+    }
+
+    // Uploads an image to the texture currently bound to `target`, and returns its size.
+    fn upload_image(target: u32, dynamic_image: DynamicImage) -> (u32, u32) {
+        let ctxt = Context::get();
+        // Only RGB and RGBA images with 8-bits components can be uploaded directly.
+        let (format, width, height, pixels) = match dynamic_image {
+            DynamicImage::ImageRgb8(image) => (
+                Context::RGB,
+                image.width(),
+                image.height(),
+                image.into_raw(),
+            ),
+            DynamicImage::ImageRgba8(image) => (
+                Context::RGBA,
+                image.width(),
+                image.height(),
+                image.into_raw(),
+            ),
+            _ => {
+                let image = dynamic_image.to_rgba8();
+                (
+                    Context::RGBA,
+                    image.width(),
+                    image.height(),
+                    image.into_raw(),
+                )
+            }
+        };
+
+        verify!(ctxt.tex_image2d(
+            target,
+            0,
+            format as i32,
+            width as i32,
+            height as i32,
+            0,
+            format,
+            Some(&pixels[..])
+        ));
+
+        (width, height)
+    }
+
+    fn set_default_parameters(target: u32) {
+        let ctxt = Context::get();
+
+        verify!(ctxt.tex_parameteri(
+            target,
+            Context::TEXTURE_WRAP_S,
+            Context::CLAMP_TO_EDGE as i32
+        ));
+        verify!(ctxt.tex_parameteri(
+            target,
+            Context::TEXTURE_WRAP_T,
+            Context::CLAMP_TO_EDGE as i32
+        ));
+        verify!(ctxt.tex_parameteri(target, Context::TEXTURE_MIN_FILTER, Context::LINEAR as i32));
+        verify!(ctxt.tex_parameteri(target, Context::TEXTURE_MAG_FILTER, Context::LINEAR as i32));
     }
 
     /// Allocates a new texture read from a file. If a texture with same name exists, nothing is
@@ -0,0 +1,39 @@
+use na::{Matrix4, Point3, Vector4};
+
+/// The view frustum of a camera, used to discard objects that cannot be seen.
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    // Plane `(a, b, c, d)` contains the points satisfying `a * x + b * y + c * z + d >= 0`.
+    planes: [Vector4<f32>; 6],
+}
+
+impl Frustum {
+    /// Extracts the six planes of the frustum defined by a view-projection matrix.
+    ///
+    /// This is typically the matrix returned by `Camera::transformation`.
+    pub fn from_matrix(proj_view: &Matrix4<f32>) -> Frustum {
+        let r0 = proj_view.row(0).transpose();
+        let r1 = proj_view.row(1).transpose();
+        let r2 = proj_view.row(2).transpose();
+        let r3 = proj_view.row(3).transpose();
+
+        Frustum {
+            planes: [r3 + r0, r3 - r0, r3 + r1, r3 - r1, r3 + r2, r3 - r2],
+        }
+    }
+
+    /// Tests if an axis-aligned bounding box is at least partially inside of this frustum.
+    ///
+    /// This test is conservative: some boxes outside of the frustum near its corners may be
+    /// reported as intersecting it.
+    pub fn intersects_aabb(&self, mins: &Point3<f32>, maxs: &Point3<f32>) -> bool {
+        self.planes.iter().all(|p| {
+            // The corner of the box the furthest along the plane normal.
+            let x = if p.x >= 0.0 { maxs.x } else { mins.x };
+            let y = if p.y >= 0.0 { maxs.y } else { mins.y };
+            let z = if p.z >= 0.0 { maxs.z } else { mins.z };
+
+            p.x * x + p.y * y + p.z * z + p.w >= 0.0
+        })
+    }
+}
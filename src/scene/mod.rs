@@ -1,10 +1,12 @@
 //! Everything related to the scene graph.
 
+pub use self::frustum::Frustum;
 pub use self::object::{Object, ObjectData};
 pub use self::planar_object::{PlanarObject, PlanarObjectData};
 pub use self::planar_scene_node::{PlanarSceneNode, PlanarSceneNodeData};
 pub use self::scene_node::{SceneNode, SceneNodeData};
 
+mod frustum;
 mod object;
 mod planar_object;
 mod planar_scene_node;
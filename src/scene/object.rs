@@ -1,8 +1,9 @@
 //! Data structure of a scene node.
 
 use crate::camera::Camera;
-use crate::light::Light;
-use crate::resource::{Material, Mesh, Texture, TextureManager};
+use crate::context::Context;
+use crate::environment::Environment;
+use crate::resource::{InstancesBuffer, Material, Mesh, Texture, TextureManager};
 use na::{Isometry3, Point2, Point3, Vector3};
 use std::any::Any;
 use std::cell::RefCell;
@@ -17,11 +18,17 @@ pub struct ObjectData {
     material: Rc<RefCell<Box<dyn Material + 'static>>>,
     texture: Rc<Texture>,
     color: Point3<f32>,
+    alpha: f32,
+    specular_color: Point3<f32>,
+    shininess: f32,
+    emissive_color: Point3<f32>,
+    emissive_texture: Option<Rc<Texture>>,
     lines_color: Option<Point3<f32>>,
     wlines: f32,
     wpoints: f32,
     draw_surface: bool,
     cull: bool,
+    instances: Option<RefCell<InstancesBuffer>>,
     user_data: Box<dyn Any + 'static>,
 }
 
@@ -38,6 +45,42 @@ impl ObjectData {
         &self.color
     }
 
+    /// The opacity of this object, in `[0, 1]`.
+    #[inline]
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    /// Whether this object is see-through, i.e., has an opacity smaller than 1.
+    #[inline]
+    pub fn is_transparent(&self) -> bool {
+        self.alpha < 1.0
+    }
+
+    /// The color of the specular highlights of this object.
+    #[inline]
+    pub fn specular_color(&self) -> &Point3<f32> {
+        &self.specular_color
+    }
+
+    /// The shininess of this object, i.e., the exponent of its specular highlights.
+    #[inline]
+    pub fn shininess(&self) -> f32 {
+        self.shininess
+    }
+
+    /// The color emitted by this object, independently from the lighting.
+    #[inline]
+    pub fn emissive_color(&self) -> &Point3<f32> {
+        &self.emissive_color
+    }
+
+    /// The texture modulating the color emitted by this object, if any.
+    #[inline]
+    pub fn emissive_texture(&self) -> Option<&Rc<Texture>> {
+        self.emissive_texture.as_ref()
+    }
+
     /// The width of the lines draw for this object.
     #[inline]
     pub fn lines_width(&self) -> f32 {
@@ -68,6 +111,13 @@ impl ObjectData {
         self.cull
     }
 
+    /// The instances of this object, if it is drawn several times with different
+    /// transformations.
+    #[inline]
+    pub fn instances(&self) -> Option<&RefCell<InstancesBuffer>> {
+        self.instances.as_ref()
+    }
+
     /// An user-defined data.
     ///
     /// Use dynamic typing capabilities of the `Any` type to recover the actual data.
@@ -100,12 +150,18 @@ impl Object {
         let user_data = ();
         let data = ObjectData {
             color: Point3::new(r, g, b),
+            alpha: 1.0,
+            specular_color: Point3::new(0.4, 0.4, 0.4),
+            shininess: 30.0,
+            emissive_color: Point3::origin(),
+            emissive_texture: None,
             lines_color: None,
             texture,
             wlines: 0.0,
             wpoints: 0.0,
             draw_surface: true,
             cull: true,
+            instances: None,
             material,
             user_data: Box::new(user_data),
         };
@@ -120,17 +176,75 @@ impl Object {
         scale: &Vector3<f32>,
         pass: usize,
         camera: &mut dyn Camera,
-        light: &Light,
+        environment: &Environment,
+    ) {
+        let mut material = self.data.material.borrow_mut();
+
+        self.render_with_material(&mut **material, transform, scale, pass, camera, environment)
+    }
+
+    #[doc(hidden)]
+    pub fn render_with_material(
+        &self,
+        material: &mut dyn Material,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+        pass: usize,
+        camera: &mut dyn Camera,
+        environment: &Environment,
     ) {
-        self.data.material.borrow_mut().render(
+        // Materials may change the culling state to honor `backface_culling_enabled`, so it is
+        // restored afterward to avoid affecting the objects drawn next.
+        let ctxt = Context::get();
+        let cull = verify!(ctxt.is_enabled(Context::CULL_FACE));
+
+        material.render(
             pass,
             transform,
             scale,
             camera,
-            light,
+            environment,
             &self.data,
             &mut *self.mesh.borrow_mut(),
         );
+
+        if cull {
+            verify!(ctxt.enable(Context::CULL_FACE));
+        } else {
+            verify!(ctxt.disable(Context::CULL_FACE));
+        }
+    }
+
+    /// Creates a copy of this object, sharing its mesh, texture, and material.
+    ///
+    /// The user-defined data are not copied: the copy has no user-defined data.
+    pub fn duplicate(&self) -> Object {
+        let data = ObjectData {
+            material: self.data.material.clone(),
+            texture: self.data.texture.clone(),
+            color: self.data.color,
+            alpha: self.data.alpha,
+            specular_color: self.data.specular_color,
+            shininess: self.data.shininess,
+            emissive_color: self.data.emissive_color,
+            emissive_texture: self.data.emissive_texture.clone(),
+            lines_color: self.data.lines_color,
+            wlines: self.data.wlines,
+            wpoints: self.data.wpoints,
+            draw_surface: self.data.draw_surface,
+            cull: self.data.cull,
+            instances: self
+                .data
+                .instances
+                .as_ref()
+                .map(|i| RefCell::new(InstancesBuffer::new(i.borrow().transforms().to_vec()))),
+            user_data: Box::new(()),
+        };
+
+        Object {
+            data,
+            mesh: self.mesh.clone(),
+        }
     }
 
     /// Gets the data of this object.
@@ -151,6 +265,27 @@ impl Object {
         self.data.cull = active;
     }
 
+    /// Draws this object once per transformation of `instances`, appended to the object
+    /// transformation.
+    ///
+    /// Instances are only supported by the default material, which draws all of them with a
+    /// single draw call if the graphics context supports instancing. Other materials draw the
+    /// object once, ignoring its instances.
+    pub fn set_instances(&mut self, instances: &[Isometry3<f32>]) {
+        match self.data.instances {
+            Some(ref mut buffer) => buffer.get_mut().set_transforms(instances.to_vec()),
+            None => {
+                self.data.instances = Some(RefCell::new(InstancesBuffer::new(instances.to_vec())))
+            }
+        }
+    }
+
+    /// Draws this object only once, removing the transformations set by `set_instances`.
+    #[inline]
+    pub fn remove_instances(&mut self) {
+        self.data.instances = None
+    }
+
     /// Attaches user-defined data to this object.
     #[inline]
     pub fn set_user_data(&mut self, user_data: Box<dyn Any + 'static>) {
@@ -169,7 +304,10 @@ impl Object {
         self.data.material = material;
     }
 
-    /// Sets the width of the lines drawn for this object.
+    /// Sets the width of the lines drawn for this object. Set to 0 to disable the lines.
+    ///
+    /// If the surface is rendered too, the lines are drawn on top of it as a wireframe overlay.
+    /// The lines hidden by the surface are not visible.
     #[inline]
     pub fn set_lines_width(&mut self, width: f32) {
         self.data.wlines = width
@@ -181,7 +319,7 @@ impl Object {
         self.data.wlines
     }
 
-    /// Sets the width of the lines drawn for this object.
+    /// Sets the color of the lines drawn for this object. If `None`, the object color is used.
     #[inline]
     pub fn set_lines_color(&mut self, color: Option<Point3<f32>>) {
         self.data.lines_color = color
@@ -226,11 +364,10 @@ impl Object {
     /// Mutably access the object's vertices.
     #[inline(always)]
     pub fn modify_vertices<F: FnMut(&mut Vec<Point3<f32>>)>(&mut self, f: &mut F) {
-        let bmesh = self.mesh.borrow_mut();
-        let _ = bmesh
-            .coords()
-            .write()
-            .unwrap()
+        let _ = self
+            .mesh
+            .borrow_mut()
+            .coords_mut()
             .data_mut()
             .as_mut()
             .map(|coords| f(coords));
@@ -282,6 +419,9 @@ impl Object {
     }
 
     /// Mutably access the object's faces.
+    ///
+    /// # Failures:
+    /// Fails if the object's mesh has 32-bits indices.
     #[inline(always)]
     pub fn modify_faces<F: FnMut(&mut Vec<Point3<u16>>)>(&mut self, f: &mut F) {
         let bmesh = self.mesh.borrow_mut();
@@ -295,6 +435,9 @@ impl Object {
     }
 
     /// Access the object's faces.
+    ///
+    /// # Failures:
+    /// Fails if the object's mesh has 32-bits indices.
     #[inline(always)]
     pub fn read_faces<F: FnMut(&[Point3<u16>])>(&self, f: &mut F) {
         let bmesh = self.mesh.borrow();
@@ -343,6 +486,55 @@ impl Object {
         self.data.color.z = b;
     }
 
+    /// Sets the opacity of the object, in `[0, 1]`. Objects are opaque by default.
+    ///
+    /// With the default material, objects with an opacity smaller than 1 are blended with the
+    /// objects behind them. They are drawn after the opaque objects, from the farthest to the
+    /// nearest, and do not write to the depth buffer.
+    #[inline]
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.data.alpha = alpha.max(0.0).min(1.0);
+    }
+
+    /// Sets the color of the specular highlights of the object. Defaults to `(0.4, 0.4, 0.4)`.
+    ///
+    /// Set it to black to disable the highlights, e.g., for matte surfaces. Colors components
+    /// must be on the range `[0.0, 1.0]`.
+    #[inline]
+    pub fn set_specular_color(&mut self, r: f32, g: f32, b: f32) {
+        self.data.specular_color = Point3::new(r, g, b);
+    }
+
+    /// Sets the shininess of the object. Defaults to 30.
+    ///
+    /// This is the exponent of the Blinn-Phong specular term: higher values give smaller and
+    /// sharper highlights, as seen on polished surfaces.
+    #[inline]
+    pub fn set_shininess(&mut self, shininess: f32) {
+        assert!(shininess >= 0.0, "The shininess must be non-negative.");
+        self.data.shininess = shininess;
+    }
+
+    /// Sets the color emitted by the object. Defaults to black, i.e., no emission.
+    ///
+    /// With the default material, the emitted color is added to the lit color of the object, so
+    /// it remains visible without any light, e.g., for screens or glowing markers. Colors
+    /// components must be on the range `[0.0, 1.0]`.
+    #[inline]
+    pub fn set_emissive_color(&mut self, r: f32, g: f32, b: f32) {
+        self.data.emissive_color = Point3::new(r, g, b);
+    }
+
+    /// Sets the texture modulating the color emitted by the object. Set to `None` to emit the
+    /// emissive color uniformly, the default.
+    ///
+    /// The texture is multiplied by the emissive color, which must be set to a non-black color,
+    /// e.g., white, for the texture to be visible. It uses the texture coordinates of the object.
+    #[inline]
+    pub fn set_emissive_texture(&mut self, texture: Option<Rc<Texture>>) {
+        self.data.emissive_texture = texture;
+    }
+
     /// Sets the texture of the object.
     ///
     /// The texture is loaded from a file and registered by the global `TextureManager`.
@@ -20,6 +20,7 @@ pub struct PlanarSceneNodeData {
     world_scale: Vector2<f32>,
     world_transform: Isometry2<f32>,
     visible: bool,
+    z_order: i32,
     up_to_date: bool,
     children: Vec<PlanarSceneNode>,
     object: Option<PlanarObject>,
@@ -63,7 +64,8 @@ impl PlanarSceneNodeData {
                 == &*e.data as *const RefCell<PlanarSceneNodeData> as usize
         }) {
             Some(i) => {
-                let _ = self.children.swap_remove(i);
+                // Keep the insertion order, which is the drawing order for equal z-orders.
+                let _ = self.children.remove(i);
             }
             None => {}
         }
@@ -105,8 +107,13 @@ impl PlanarSceneNodeData {
             None => {}
         }
 
-        for c in self.children.iter_mut() {
-            let mut bc = c.data_mut();
+        // The children themselves are not reordered, so that they keep the order they were added
+        // in. The sort is stable, so siblings with the same z-order are drawn in that order.
+        let mut order: Vec<usize> = (0..self.children.len()).collect();
+        order.sort_by_key(|&i| self.children[i].data().z_order);
+
+        for i in order {
+            let mut bc = self.children[i].data_mut();
             if bc.visible {
                 bc.do_render(&self.world_transform, &self.world_scale, camera)
             }
@@ -263,6 +270,22 @@ impl PlanarSceneNodeData {
         self.visible
     }
 
+    /// The z-order of this node relative to its siblings.
+    #[inline]
+    pub fn z_order(&self) -> i32 {
+        self.z_order
+    }
+
+    /// Sets the z-order of this node relative to its siblings.
+    ///
+    /// A node is drawn, with all its children, above its siblings with a lower z-order and
+    /// below those with a higher z-order. Siblings with the same z-order are drawn in the order
+    /// they were added. A node is always drawn below its own children. Defaults to 0.
+    #[inline]
+    pub fn set_z_order(&mut self, z_order: i32) {
+        self.z_order = z_order;
+    }
+
     /// Sets the visibility of this node.
     ///
     /// The node and its children are not rendered if it is not visible.
@@ -555,6 +578,7 @@ impl PlanarSceneNode {
             world_transform: local_transform,
             world_scale: local_scale,
             visible: true,
+            z_order: 0,
             up_to_date: false,
             children: Vec::new(),
             object: object,
@@ -643,6 +667,45 @@ impl PlanarSceneNode {
         res.expect("Unable to load the default rectangle geometry.")
     }
 
+    /// Adds a rectangle textured with the whole `texture` as a children of this node. The
+    /// rectangle is initially axis-aligned and centered at (0, 0).
+    ///
+    /// # Arguments
+    /// * `wx` - the rectangle extent along the x axis
+    /// * `wy` - the rectangle extent along the y axis
+    /// * `texture` - the texture to display on the rectangle
+    pub fn add_textured_rectangle(
+        &mut self,
+        wx: f32,
+        wy: f32,
+        texture: Rc<Texture>,
+    ) -> PlanarSceneNode {
+        let mut res = self.add_rectangle(wx, wy);
+        res.set_texture(texture);
+        res
+    }
+
+    /// Adds a sprite, i.e., a rectangle displaying the texture registered as `name`, as a
+    /// children of this node.
+    ///
+    /// The sprite has the size of the texture in pixels so that, with the default planar camera,
+    /// each texel covers exactly one pixel. The texels are aligned to the pixel grid if the sprite
+    /// is translated by whole numbers of pixels and has even dimensions; a sprite with odd
+    /// dimensions must be translated by an extra half pixel. The transparent parts of the texture
+    /// are blended with the scene.
+    ///
+    /// # Failures:
+    /// Fails if no texture was registered as `name`.
+    pub fn add_sprite(&mut self, name: &str) -> PlanarSceneNode {
+        let (texture, (width, height)) = TextureManager::get_global_manager(|tm| {
+            tm.get_with_size(name).unwrap_or_else(|| {
+                panic!("Invalid attempt to use the unregistered texture: {}", name)
+            })
+        });
+
+        self.add_textured_rectangle(width as f32, height as f32, texture)
+    }
+
     /// Adds a circle as a children of this node. The circle is initially centered at (0, 0, 0).
     ///
     /// # Arguments
@@ -725,17 +788,53 @@ impl PlanarSceneNode {
     }
 
     /// Creates and adds a new object to this node children using a convex polyline
+    ///
+    /// The vertices may be given in clockwise or counterclockwise order.
     pub fn add_convex_polygon(
         &mut self,
         polygon: Vec<Point2<f32>>,
         scale: Vector2<f32>,
     ) -> PlanarSceneNode {
+        let ccw = signed_area(&polygon) >= 0.0;
         let mut indices = Vec::new();
 
-        for i in 1..polygon.len() - 1 {
-            indices.push(Point3::new(0, i as u16, i as u16 + 1));
+        for i in 1..polygon.len().max(2) - 1 {
+            if ccw {
+                indices.push(Point3::new(0, i as u16, i as u16 + 1));
+            } else {
+                indices.push(Point3::new(0, i as u16 + 1, i as u16));
+            }
         }
 
+        self.add_polygon_mesh(polygon, indices, scale)
+    }
+
+    /// Creates and adds a new object to this node children using a simple polyline, which may
+    /// be concave.
+    ///
+    /// The polygon is triangulated by ear clipping, and its vertices may be given in clockwise or
+    /// counterclockwise order. The edges must not intersect each other; self-intersecting
+    /// polygons are not filled correctly.
+    pub fn add_polygon(
+        &mut self,
+        polygon: Vec<Point2<f32>>,
+        scale: Vector2<f32>,
+    ) -> PlanarSceneNode {
+        let indices = triangulate(&polygon);
+        self.add_polygon_mesh(polygon, indices, scale)
+    }
+
+    fn add_polygon_mesh(
+        &mut self,
+        polygon: Vec<Point2<f32>>,
+        indices: Vec<Point3<u16>>,
+        scale: Vector2<f32>,
+    ) -> PlanarSceneNode {
+        assert!(
+            polygon.len() <= u16::MAX as usize + 1,
+            "A polygon cannot have more than 65536 vertices."
+        );
+
         let mesh = PlanarMesh::new(polygon, indices, None, false);
         let tex = TextureManager::get_global_manager(|tm| tm.get_default());
         let mat = PlanarMaterialManager::get_global_manager(|mm| mm.get_default());
@@ -874,6 +973,20 @@ impl PlanarSceneNode {
         self.data().is_visible()
     }
 
+    /// The z-order of this node relative to its siblings.
+    #[inline]
+    pub fn z_order(&self) -> i32 {
+        self.data().z_order()
+    }
+
+    /// Sets the z-order of this node relative to its siblings.
+    ///
+    /// See `PlanarSceneNodeData::set_z_order` for details.
+    #[inline]
+    pub fn set_z_order(&mut self, z_order: i32) {
+        self.data_mut().set_z_order(z_order)
+    }
+
     /// Sets the visibility of this node.
     ///
     /// The node and its children are not rendered if it is not visible.
@@ -993,3 +1106,68 @@ impl PlanarSceneNode {
         self.data_mut().set_local_rotation(r)
     }
 }
+
+// Twice the signed area of a polygon, positive if its vertices are counterclockwise.
+fn signed_area(polygon: &[Point2<f32>]) -> f32 {
+    let mut area = 0.0;
+
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        area += a.coords.perp(&b.coords);
+    }
+
+    area
+}
+
+// Triangulates a simple polygon by ear clipping. The triangles are counterclockwise.
+fn triangulate(polygon: &[Point2<f32>]) -> Vec<Point3<u16>> {
+    let mut ids: Vec<usize> = (0..polygon.len()).collect();
+    let mut triangles = Vec::with_capacity(polygon.len().saturating_sub(2));
+
+    if signed_area(polygon) < 0.0 {
+        ids.reverse();
+    }
+
+    while ids.len() > 3 {
+        let n = ids.len();
+        let triangle = |i: usize| (ids[(i + n - 1) % n], ids[i], ids[(i + 1) % n]);
+        let is_ear = |i: usize| {
+            let (ia, ib, ic) = triangle(i);
+            let (a, b, c) = (polygon[ia], polygon[ib], polygon[ic]);
+
+            // The vertex must be convex, and no other vertex may lie inside of the ear.
+            (b - a).perp(&(c - b)) > 0.0
+                && ids.iter().all(|&j| {
+                    let p = polygon[j];
+                    j == ia
+                        || j == ib
+                        || j == ic
+                        || (b - a).perp(&(p - a)) < 0.0
+                        || (c - b).perp(&(p - b)) < 0.0
+                        || (a - c).perp(&(p - c)) < 0.0
+                })
+        };
+
+        match (0..n).find(|&i| is_ear(i)) {
+            Some(i) => {
+                let (a, b, c) = triangle(i);
+                triangles.push(Point3::new(a as u16, b as u16, c as u16));
+                let _ = ids.remove(i);
+            }
+            None => {
+                // Degenerate or self-intersecting polygon: fill the rest with a fan.
+                for i in 1..n - 1 {
+                    triangles.push(Point3::new(ids[0] as u16, ids[i] as u16, ids[i + 1] as u16));
+                }
+
+                ids.clear();
+            }
+        }
+    }
+
+    if ids.len() == 3 {
+        triangles.push(Point3::new(ids[0] as u16, ids[1] as u16, ids[2] as u16));
+    }
+
+    triangles
+}
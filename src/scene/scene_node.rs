@@ -1,12 +1,23 @@
+use crate::animation::TransformTrack;
 use crate::camera::Camera;
-use crate::light::Light;
-use crate::resource::{Material, MaterialManager, Mesh, MeshManager, Texture, TextureManager};
-use crate::scene::Object;
+use crate::environment::Environment;
+#[cfg(feature = "gltf")]
+use crate::loader::gltf::{self, GltfNode, GltfScene};
+use crate::procedural as kiss3d_procedural;
+use crate::renderer::LineRenderer;
+use crate::resource::{
+    Material, MaterialManager, Mesh, MeshManager, Texture, TextureFormat, TextureManager,
+};
+use crate::scene::{Frustum, Object};
 use na;
 use na::{Isometry3, Point2, Point3, Translation3, UnitQuaternion, Vector3};
 use ncollide3d::procedural;
 use ncollide3d::procedural::TriMesh;
-use std::cell::{Ref, RefCell, RefMut};
+use ncollide3d::query::Ray;
+use std::cell::{Cell, Ref, RefCell, RefMut};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 use std::mem;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
@@ -23,8 +34,83 @@ pub struct SceneNodeData {
     up_to_date: bool,
     children: Vec<SceneNode>,
     object: Option<Object>,
+    // The merged objects of the frozen descendants of this node.
+    batch: Option<Batch>,
+    // Set if this node is frozen in the batch of one of its ancestors.
+    frozen: Option<Frozen>,
     // FIXME: use Weak pointers instead of the raw pointer.
     parent: Option<*const RefCell<SceneNodeData>>,
+    animation: Option<Animation>,
+    // The length of the normals drawn for debugging, if they are shown.
+    normals_length: Option<f32>,
+    render_priority: i32,
+}
+
+struct Batch {
+    objects: Vec<Object>,
+    // Set when the batch has to be rebuilt, e.g., because one of its nodes moved.
+    dirty: Rc<Cell<bool>>,
+    // The world scale of the frozen node when the batch was built.
+    scale: Vector3<f32>,
+}
+
+struct Frozen {
+    // The `dirty` flag of the batch this node is frozen in.
+    batch: Rc<Cell<bool>>,
+    // Whether the object of this node is merged into the batch instead of being drawn on its own.
+    batched: bool,
+}
+
+// A track played on a node, advanced by the window at each frame.
+struct Animation {
+    track: Rc<TransformTrack>,
+    // The time elapsed since the start of the track, in seconds.
+    time: f32,
+    looping: bool,
+    // Called when the end of the track is reached.
+    callback: Option<Rc<RefCell<dyn FnMut()>>>,
+}
+
+// An object whose rendering is deferred until the whole scene graph is traversed, so that the
+// objects can be drawn sorted.
+struct DeferredObject {
+    // The render priority of the node of the object.
+    priority: i32,
+    // The squared distance from the camera to the center of the object, for transparent objects.
+    depth: f32,
+    // The node of the object, or `None` for the node the rendering started from.
+    node: Option<SceneNode>,
+    // The index of the object in the batch of the node, or `None` for the object of the node.
+    batch_index: Option<usize>,
+}
+
+// The objects of a render pass, drawn once the scene graph is traversed: the opaque ones first,
+// then the transparent ones.
+#[derive(Default)]
+struct DeferredObjects {
+    opaque: Vec<DeferredObject>,
+    transparent: Vec<DeferredObject>,
+}
+
+// Objects being merged into a batch. They share everything but their geometry.
+struct BatchGroup {
+    material: Rc<RefCell<Box<dyn Material + 'static>>>,
+    texture: Rc<Texture>,
+    color: Point3<f32>,
+    alpha: f32,
+    specular_color: Point3<f32>,
+    shininess: f32,
+    emissive_color: Point3<f32>,
+    emissive_texture: Option<Rc<Texture>>,
+    lines_color: Option<Point3<f32>>,
+    lines_width: f32,
+    points_size: f32,
+    draw_surface: bool,
+    cull: bool,
+    coords: Vec<Point3<f32>>,
+    normals: Vec<Vector3<f32>>,
+    uvs: Vec<Point2<f32>>,
+    faces: Vec<Point3<u32>>,
 }
 
 /// A node of the scene graph.
@@ -82,9 +168,100 @@ impl SceneNodeData {
     }
 
     /// Render the scene graph rooted by this node.
-    pub fn render(&mut self, pass: usize, camera: &mut dyn Camera, light: &Light) {
+    ///
+    /// The objects are drawn by increasing render priority, set with `set_render_priority`, and
+    /// in the scene graph order for equal priorities. Transparent objects are drawn after the
+    /// opaque ones, by increasing priority, then from the farthest to the nearest to the camera.
+    pub fn render(&mut self, pass: usize, camera: &mut dyn Camera, environment: &Environment) {
+        self.render_sorted(pass, camera, environment, None)
+    }
+
+    /// Render the scene graph rooted by this node, skipping the objects outside of a frustum.
+    ///
+    /// An object is skipped if the bounding box of its vertices does not intersect the frustum.
+    /// Objects with vertices not available on the CPU are always rendered.
+    pub fn render_culled(
+        &mut self,
+        pass: usize,
+        camera: &mut dyn Camera,
+        environment: &Environment,
+        frustum: &Frustum,
+    ) {
+        self.render_sorted(pass, camera, environment, Some(frustum))
+    }
+
+    /// Render the scene graph rooted by this node, using the same material for every object
+    /// instead of their own materials.
+    ///
+    /// If a frustum is given, the objects outside of it are skipped as with `render_culled`.
+    /// The objects are drawn in the scene graph order, ignoring their render priority and
+    /// transparency.
+    pub fn render_with_material(
+        &mut self,
+        pass: usize,
+        camera: &mut dyn Camera,
+        environment: &Environment,
+        material: &mut dyn Material,
+        frustum: Option<&Frustum>,
+    ) {
         if self.visible {
-            self.do_render(&na::one(), &Vector3::from_element(1.0), pass, camera, light)
+            self.do_render(
+                &na::one(),
+                &Vector3::from_element(1.0),
+                pass,
+                camera,
+                environment,
+                frustum,
+                None,
+                Some(material),
+                None,
+            )
+        }
+    }
+
+    fn render_sorted(
+        &mut self,
+        pass: usize,
+        camera: &mut dyn Camera,
+        environment: &Environment,
+        frustum: Option<&Frustum>,
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        let mut deferred = DeferredObjects::default();
+
+        self.do_render(
+            &na::one(),
+            &Vector3::from_element(1.0),
+            pass,
+            camera,
+            environment,
+            frustum,
+            None,
+            None,
+            Some(&mut deferred),
+        );
+
+        // The sorts are stable, so equal priorities keep the scene graph order.
+        deferred.opaque.sort_by_key(|o| o.priority);
+        deferred.transparent.sort_by(|a, b| {
+            a.priority.cmp(&b.priority).then(
+                b.depth
+                    .partial_cmp(&a.depth)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            )
+        });
+
+        for d in deferred.opaque.iter().chain(deferred.transparent.iter()) {
+            match d.node {
+                Some(ref node) => {
+                    node.data()
+                        .draw_deferred(d.batch_index, pass, camera, environment)
+                }
+                None => self.draw_deferred(d.batch_index, pass, camera, environment),
+            }
         }
     }
 
@@ -94,7 +271,11 @@ impl SceneNodeData {
         scale: &Vector3<f32>,
         pass: usize,
         camera: &mut dyn Camera,
-        light: &Light,
+        environment: &Environment,
+        frustum: Option<&Frustum>,
+        node: Option<&SceneNode>,
+        mut material: Option<&mut dyn Material>,
+        mut deferred: Option<&mut DeferredObjects>,
     ) {
         if !self.up_to_date {
             self.up_to_date = true;
@@ -102,31 +283,269 @@ impl SceneNodeData {
             self.world_scale = scale.component_mul(&self.local_scale);
         }
 
+        let rebuild = self
+            .batch
+            .as_ref()
+            .map_or(false, |b| b.dirty.get() || b.scale != self.world_scale);
+
+        if rebuild {
+            self.rebuild_batch();
+        }
+
         match self.object {
-            Some(ref o) => o.render(
-                &self.world_transform,
-                &self.world_scale,
-                pass,
-                camera,
-                light,
-            ),
-            None => {}
+            Some(ref o) if !self.frozen.as_ref().map_or(false, |f| f.batched) => self
+                .render_object(
+                    o,
+                    node,
+                    None,
+                    pass,
+                    camera,
+                    environment,
+                    frustum,
+                    material.as_mut().map(|m| &mut **m as &mut dyn Material),
+                    deferred.as_mut().map(|d| &mut **d),
+                ),
+            _ => {}
         }
 
-        for c in self.children.iter_mut() {
-            let mut bc = c.data_mut();
+        if let Some(ref batch) = self.batch {
+            for (i, o) in batch.objects.iter().enumerate() {
+                self.render_object(
+                    o,
+                    node,
+                    Some(i),
+                    pass,
+                    camera,
+                    environment,
+                    frustum,
+                    material.as_mut().map(|m| &mut **m as &mut dyn Material),
+                    deferred.as_mut().map(|d| &mut **d),
+                )
+            }
+        }
+
+        // Children are traversed even if this node object is culled, since they may be
+        // located anywhere.
+        for c in self.children.iter() {
+            let mut bc = c.data.borrow_mut();
             if bc.visible {
                 bc.do_render(
                     &self.world_transform,
                     &self.world_scale,
                     pass,
                     camera,
-                    light,
+                    environment,
+                    frustum,
+                    Some(c),
+                    material.as_mut().map(|m| &mut **m as &mut dyn Material),
+                    deferred.as_mut().map(|d| &mut **d),
                 )
             }
         }
     }
 
+    /// Sets the render priority of the object of this node. Defaults to `0`.
+    ///
+    /// The objects with a lower priority are drawn first, e.g., a negative priority draws a
+    /// skybox behind everything else, if it does not write into the depth buffer, and a positive
+    /// one draws an overlay on top of the scene, if it does not test the depth buffer. Objects
+    /// with the same priority are drawn in the scene graph order. The transparent objects are
+    /// still drawn after all the opaque ones. The priority does not apply to the descendants of
+    /// this node, and the objects merged by `freeze` are drawn with the priority of the frozen
+    /// node.
+    #[inline]
+    pub fn set_render_priority(&mut self, priority: i32) {
+        self.render_priority = priority
+    }
+
+    /// The render priority of the object of this node.
+    #[inline]
+    pub fn render_priority(&self) -> i32 {
+        self.render_priority
+    }
+
+    /// Shows or hides the vertex normals of the object of this node, drawn at each frame as lines
+    /// of `length` starting at each vertex.
+    ///
+    /// The normals are read from the mesh of the object, and transformed by the world
+    /// transformation and scale of this node. They are drawn with the other lines of the window,
+    /// with the same width. Nothing is drawn if the vertices or the normals of the mesh are not
+    /// available on the CPU. The instances of the object, if any, are not considered.
+    #[inline]
+    pub fn set_show_normals(&mut self, enabled: bool, length: f32) {
+        self.normals_length = if enabled { Some(length) } else { None };
+    }
+
+    // Adds the lines of the normals shown on this node and its visible descendants for the next
+    // frame. The world transformations are updated as during rendering.
+    pub(crate) fn draw_normals(
+        &mut self,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+        lines: &mut LineRenderer,
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        if !self.up_to_date {
+            self.up_to_date = true;
+            self.world_transform = *transform * self.local_transform;
+            self.world_scale = scale.component_mul(&self.local_scale);
+        }
+
+        if let (Some(length), Some(object)) = (self.normals_length, self.object.as_ref()) {
+            let mesh = object.mesh().borrow();
+            let coords = mesh.coords().read().unwrap();
+            let normals = mesh.normals().read().unwrap();
+
+            if let (Some(coords), Some(normals)) = (coords.data(), normals.data()) {
+                let color = Point3::new(0.0, 1.0, 1.0);
+
+                for (pt, n) in coords.iter().zip(normals.iter()) {
+                    let a = self.world_transform
+                        * Point3::from(pt.coords.component_mul(&self.world_scale));
+                    // The normals are scaled by the inverse of the scale to remain orthogonal
+                    // to the faces.
+                    let dir = self.world_transform
+                        * n.component_div(&self.world_scale)
+                            .try_normalize(1.0e-6)
+                            .unwrap_or(*n);
+                    lines.draw_line(a, a + dir * length, color);
+                }
+            }
+        }
+
+        for c in self.children.iter_mut() {
+            c.data_mut()
+                .draw_normals(&self.world_transform, &self.world_scale, lines);
+        }
+    }
+
+    // Draws an object of this node, or defers it to `deferred` if it is given. `node` and
+    // `batch_index` identify the object once deferred.
+    fn render_object(
+        &self,
+        o: &Object,
+        node: Option<&SceneNode>,
+        batch_index: Option<usize>,
+        pass: usize,
+        camera: &mut dyn Camera,
+        environment: &Environment,
+        frustum: Option<&Frustum>,
+        material: Option<&mut dyn Material>,
+        deferred: Option<&mut DeferredObjects>,
+    ) {
+        if !frustum.map_or(true, |f| self.object_intersects_frustum(o, f)) {
+            return;
+        }
+
+        match deferred {
+            Some(deferred) if material.is_none() && !o.data().is_transparent() => {
+                deferred.opaque.push(DeferredObject {
+                    priority: self.render_priority,
+                    depth: 0.0,
+                    node: node.cloned(),
+                    batch_index,
+                })
+            }
+            Some(deferred) if material.is_none() => {
+                let center = match o.mesh().borrow().aabb() {
+                    Some((mins, maxs)) => na::center(&mins, &maxs),
+                    None => Point3::origin(),
+                };
+                let center = self.world_transform
+                    * Point3::from(center.coords.component_mul(&self.world_scale));
+
+                deferred.transparent.push(DeferredObject {
+                    priority: self.render_priority,
+                    depth: na::distance_squared(&center, &camera.eye()),
+                    node: node.cloned(),
+                    batch_index,
+                })
+            }
+            _ => self.draw_object(o, pass, camera, environment, material),
+        }
+    }
+
+    // Draws an object deferred by `render_object`.
+    fn draw_deferred(
+        &self,
+        batch_index: Option<usize>,
+        pass: usize,
+        camera: &mut dyn Camera,
+        environment: &Environment,
+    ) {
+        let object = match batch_index {
+            Some(i) => self.batch.as_ref().and_then(|b| b.objects.get(i)),
+            None => self.object.as_ref(),
+        };
+
+        if let Some(o) = object {
+            self.draw_object(o, pass, camera, environment, None)
+        }
+    }
+
+    fn draw_object(
+        &self,
+        o: &Object,
+        pass: usize,
+        camera: &mut dyn Camera,
+        environment: &Environment,
+        material: Option<&mut dyn Material>,
+    ) {
+        match material {
+            Some(material) => o.render_with_material(
+                material,
+                &self.world_transform,
+                &self.world_scale,
+                pass,
+                camera,
+                environment,
+            ),
+            None => o.render(
+                &self.world_transform,
+                &self.world_scale,
+                pass,
+                camera,
+                environment,
+            ),
+        }
+    }
+
+    fn object_intersects_frustum(&self, object: &Object, frustum: &Frustum) -> bool {
+        // Instances may be located anywhere, so they are never culled.
+        if object.data().instances().is_some() {
+            return true;
+        }
+
+        let (local_mins, local_maxs) = match object.mesh().borrow().aabb() {
+            Some(aabb) => aabb,
+            None => return true,
+        };
+        let bounds = [
+            local_mins.coords.component_mul(&self.world_scale),
+            local_maxs.coords.component_mul(&self.world_scale),
+        ];
+        let mut mins = Point3::from(Vector3::repeat(f32::MAX));
+        let mut maxs = Point3::from(Vector3::repeat(-f32::MAX));
+
+        // The world-space box enclosing the eight transformed corners of the local box.
+        for i in 0..8 {
+            let corner = Point3::new(
+                bounds[i & 1].x,
+                bounds[(i >> 1) & 1].y,
+                bounds[(i >> 2) & 1].z,
+            );
+            let corner = self.world_transform * corner;
+
+            mins = mins.inf(&corner);
+            maxs = maxs.sup(&corner);
+        }
+
+        frustum.intersects_aabb(&mins, &maxs)
+    }
+
     /// A reference to the object possibly contained by this node.
     #[inline]
     pub fn object(&self) -> Option<&Object> {
@@ -191,6 +610,9 @@ impl SceneNodeData {
     }
 
     /// Sets the width of the lines drawn for the objects contained by this node and its children.
+    ///
+    /// If the surfaces are rendered too, the lines are drawn on top of them as a wireframe
+    /// overlay, with the color set by `set_lines_color`. Set to 0 to disable the lines.
     #[inline]
     pub fn set_lines_width(&mut self, width: f32) {
         self.apply_to_objects_mut(&mut |o| o.set_lines_width(width))
@@ -217,6 +639,10 @@ impl SceneNodeData {
 
     /// Activates or deactivates backface culling for the objects contained by this node and its
     /// children.
+    ///
+    /// Backface culling is enabled by default. Disabling it makes both sides of the faces
+    /// visible, e.g., the inside of thin open meshes. The culling state of the graphics context
+    /// is restored after drawing each object.
     #[inline]
     pub fn enable_backface_culling(&mut self, active: bool) {
         self.apply_to_objects_mut(&mut |o| o.enable_backface_culling(active))
@@ -305,6 +731,11 @@ impl SceneNodeData {
     /// The node and its children are not rendered if it is not visible.
     #[inline]
     pub fn set_visible(&mut self, visible: bool) {
+        // Invisible nodes are not merged into batches.
+        if self.frozen.is_some() {
+            self.invalidate();
+        }
+
         self.visible = visible;
     }
 
@@ -316,6 +747,63 @@ impl SceneNodeData {
         self.apply_to_objects_mut(&mut |o| o.set_color(r, g, b))
     }
 
+    /// Sets the opacity of the objects contained by this node and its children.
+    ///
+    /// The opacity is clamped to `[0.0, 1.0]`. See `Object::set_alpha`.
+    #[inline]
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.apply_to_objects_mut(&mut |o| o.set_alpha(alpha))
+    }
+
+    /// Sets the color of the specular highlights of the objects contained by this node and its
+    /// children.
+    ///
+    /// Colors components must be on the range `[0.0, 1.0]`. See `Object::set_specular_color`.
+    #[inline]
+    pub fn set_specular_color(&mut self, r: f32, g: f32, b: f32) {
+        self.apply_to_objects_mut(&mut |o| o.set_specular_color(r, g, b))
+    }
+
+    /// Sets the shininess of the objects contained by this node and its children.
+    ///
+    /// See `Object::set_shininess`.
+    #[inline]
+    pub fn set_material_shininess(&mut self, shininess: f32) {
+        self.apply_to_objects_mut(&mut |o| o.set_shininess(shininess))
+    }
+
+    /// Sets the color emitted by the objects contained by this node and its children.
+    ///
+    /// Colors components must be on the range `[0.0, 1.0]`. See `Object::set_emissive_color`.
+    #[inline]
+    pub fn set_emissive_color(&mut self, r: f32, g: f32, b: f32) {
+        self.apply_to_objects_mut(&mut |o| o.set_emissive_color(r, g, b))
+    }
+
+    /// Sets the texture modulating the color emitted by the objects contained by this node and
+    /// its children.
+    ///
+    /// See `Object::set_emissive_texture`.
+    #[inline]
+    pub fn set_emissive_texture(&mut self, texture: Option<Rc<Texture>>) {
+        self.apply_to_objects_mut(&mut |o| o.set_emissive_texture(texture.clone()))
+    }
+
+    /// Sets the instances of the objects contained by this node and its children.
+    ///
+    /// Each object is drawn once per transformation of `instances`, appended to the object
+    /// transformation.
+    #[inline]
+    pub fn set_instances(&mut self, instances: &[Isometry3<f32>]) {
+        self.apply_to_objects_mut(&mut |o| o.set_instances(instances))
+    }
+
+    /// Removes the instances of the objects contained by this node and its children.
+    #[inline]
+    pub fn remove_instances(&mut self) {
+        self.apply_to_objects_mut(&mut |o| o.remove_instances())
+    }
+
     /// Sets the texture of the objects contained by this node and its children.
     ///
     /// The texture is loaded from a file and registered by the global `TextureManager`.
@@ -365,9 +853,51 @@ impl SceneNodeData {
         self.apply_to_objects_mut(&mut |o| o.set_texture(texture.clone()))
     }
 
+    /// Replaces the pixels of a rectangular region of the textures of the objects contained by
+    /// this node and its children.
+    ///
+    /// Each texture is updated only once, even if it is shared by several objects. Returns
+    /// `false` if at least one of the textures could not be updated, see
+    /// `TextureManager::update_subimage` for details.
+    pub fn update_texture_subimage(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        data: &[u8],
+        format: TextureFormat,
+    ) -> bool {
+        let mut textures: Vec<Rc<Texture>> = Vec::new();
+
+        self.apply_to_objects(&mut |o| {
+            let texture = o.data().texture();
+
+            if !textures.iter().any(|t| Rc::ptr_eq(t, texture)) {
+                textures.push(texture.clone())
+            }
+        });
+
+        TextureManager::get_global_manager(|tm| {
+            // Every texture is updated, even after a failure.
+            textures.iter().fold(true, |updated, texture| {
+                tm.update_subimage(texture, x, y, width, height, data, format) && updated
+            })
+        })
+    }
+
     /// Applies a closure to each object contained by this node and its children.
     #[inline]
     pub fn apply_to_objects_mut<F: FnMut(&mut Object)>(&mut self, f: &mut F) {
+        // The objects may be modified, so the batches containing them are rebuilt.
+        if let Some(ref batch) = self.batch {
+            batch.dirty.set(true);
+        }
+
+        if let Some(ref frozen) = self.frozen {
+            frozen.batch.set(true);
+        }
+
         match self.object {
             Some(ref mut o) => f(o),
             None => {}
@@ -415,6 +945,38 @@ impl SceneNodeData {
         self.local_transform = Isometry3::face_towards(eye, at, up)
     }
 
+    /// Moves the node to the point `eye` and orients it such that its `-z` axis points toward
+    /// `at` and its `y` axis is as close as possible to `up`, like a camera.
+    ///
+    /// The points and `up` are expressed in the local frame of the parent of this node. If the
+    /// direction is parallel to `up`, another up direction is chosen. If `eye` and `at` are
+    /// equal, only the translation of the node is changed.
+    #[inline]
+    pub fn look_at(&mut self, eye: &Point3<f32>, at: &Point3<f32>, up: &Vector3<f32>) {
+        self.invalidate();
+        self.local_transform.translation = Translation3::from(eye.coords);
+
+        if let Some(rotation) = rotation_towards(&(eye - at), up) {
+            self.local_transform.rotation = rotation;
+        }
+    }
+
+    /// Orients the node, without moving it, such that its `z` axis points toward `target` and
+    /// its `y` axis is as close as possible to `up`.
+    ///
+    /// `target` and `up` are expressed in the local frame of the parent of this node. If the
+    /// direction is parallel to `up`, another up direction is chosen. If `target` is the
+    /// position of the node, its orientation is not changed.
+    #[inline]
+    pub fn face_towards(&mut self, target: &Point3<f32>, up: &Vector3<f32>) {
+        let position = Point3::from(self.local_transform.translation.vector);
+
+        if let Some(rotation) = rotation_towards(&(target - position), up) {
+            self.invalidate();
+            self.local_transform.rotation = rotation;
+        }
+    }
+
     /// This node local transformation.
     #[inline]
     pub fn local_transformation(&self) -> Isometry3<f32> {
@@ -454,7 +1016,7 @@ impl SceneNodeData {
             let mself: &mut SceneNodeData = mem::transmute(self);
             mself.update();
         }
-        self.local_transform.inverse()
+        self.world_transform.inverse()
     }
 
     /// Appends a transformation to this node local transformation.
@@ -545,50 +1107,435 @@ impl SceneNodeData {
     }
 
     /// Sets the local rotation of this node.
+    ///
+    /// The local translation and scale of the node are kept: its vertices are scaled, then
+    /// rotated by `r`, then translated.
     #[inline]
     pub fn set_local_rotation(&mut self, r: UnitQuaternion<f32>) {
         self.invalidate();
         self.local_transform.rotation = r
     }
 
+    /// Plays a keyframe animation on this node.
+    ///
+    /// At each frame, the local translation and rotation of this node are set to the ones of
+    /// `track` at the time elapsed since the call to `play_animation`. Its local scale is kept.
+    /// When the end of the track is reached, the animation restarts if `looping` is `true`, and
+    /// stops otherwise, leaving the node at the last keyframe. This replaces the animation
+    /// currently played on this node, if any.
+    #[inline]
+    pub fn play_animation(&mut self, track: Rc<TransformTrack>, looping: bool) {
+        self.animation = Some(Animation {
+            track,
+            time: 0.0,
+            looping,
+            callback: None,
+        })
+    }
+
+    /// Plays a keyframe animation on this node, and calls `callback` when its end is reached.
+    ///
+    /// If `looping` is `true`, `callback` is called at the end of each loop. See `play_animation`
+    /// for details.
+    pub fn play_animation_with_callback<F: FnMut() + 'static>(
+        &mut self,
+        track: Rc<TransformTrack>,
+        looping: bool,
+        callback: F,
+    ) {
+        let callback: Rc<RefCell<dyn FnMut()>> = Rc::new(RefCell::new(callback));
+        self.animation = Some(Animation {
+            track,
+            time: 0.0,
+            looping,
+            callback: Some(callback),
+        })
+    }
+
+    /// Stops the animation played on this node, leaving the node at its current transformation.
+    #[inline]
+    pub fn stop_animation(&mut self) {
+        self.animation = None
+    }
+
+    /// Whether an animation is being played on this node.
+    #[inline]
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    // Advances the animations of this node and its descendants by `dt` seconds. The callbacks of
+    // the animations reaching their end are pushed to `finished` instead of being called here,
+    // so that they can modify the scene graph.
+    pub(crate) fn advance_animations(
+        &mut self,
+        dt: f32,
+        finished: &mut Vec<Rc<RefCell<dyn FnMut()>>>,
+    ) {
+        if let Some(mut animation) = self.animation.take() {
+            let duration = animation.track.duration();
+            animation.time += dt;
+
+            let ended = animation.time >= duration;
+
+            if ended && animation.looping && duration > 0.0 {
+                animation.time %= duration;
+            }
+
+            if let Some(transform) = animation.track.sample(animation.time) {
+                self.invalidate();
+                self.local_transform = transform;
+            }
+
+            if ended {
+                if let Some(ref callback) = animation.callback {
+                    finished.push(callback.clone());
+                }
+            }
+
+            // A looping track without duration would end at every frame.
+            if !ended || (animation.looping && duration > 0.0) {
+                self.animation = Some(animation);
+            }
+        }
+
+        for c in self.children.iter_mut() {
+            c.data_mut().advance_animations(dt, finished);
+        }
+    }
+
     fn invalidate(&mut self) {
+        self.do_invalidate(None)
+    }
+
+    // `rigid` is the batch of the frozen ancestor moving along with this node, if any: the
+    // nodes frozen in it do not move relative to each other, so they can remain batched.
+    fn do_invalidate(&mut self, rigid: Option<&Rc<Cell<bool>>>) {
         self.up_to_date = false;
 
+        let thaw = match self.frozen {
+            Some(ref frozen) => rigid.map_or(true, |r| !Rc::ptr_eq(&frozen.batch, r)),
+            None => false,
+        };
+
+        if thaw {
+            let frozen = self.frozen.take().unwrap();
+            frozen.batch.set(true);
+        }
+
+        let rigid = match self.batch {
+            Some(ref batch) => Some(batch.dirty.clone()),
+            None => rigid.cloned(),
+        };
+
         for c in self.children.iter_mut() {
             let mut dm = c.data_mut();
 
-            if dm.up_to_date {
-                dm.invalidate()
+            // Frozen descendants must be reached even if their transformation is outdated.
+            if dm.up_to_date || dm.frozen.is_some() {
+                dm.do_invalidate(rigid.as_ref())
             }
         }
     }
 
-    // FIXME: make this public?
-    fn update(&mut self) {
-        // NOTE: makin this test
-        if !self.up_to_date {
-            match self.parent {
-                Some(ref mut p) => unsafe {
-                    let mut dp = (**p).borrow_mut();
+    /// Merges the objects of the descendants of this node into a few large objects, to draw them
+    /// with a few draw calls.
+    ///
+    /// The objects sharing the same material, texture, color, and rendering parameters are
+    /// merged into a single object. This greatly speeds up the rendering of scenes made of many
+    /// small static objects. Only the visible objects with every vertex data available on the
+    /// CPU, and without instances nor vertex colors, are merged. This node can still be moved as
+    /// a whole.
+    ///
+    /// A descendant is removed from the batch, and drawn on its own again, as soon as it, or one
+    /// of its ancestors below this node, moves, is hidden, or is unlinked. The batch is rebuilt
+    /// when the objects are modified through this node or its descendants, e.g., with
+    /// `set_color`, but modifications made directly on an `Object` or on a shared mesh are not
+    /// detected. Descendants added after the call to `freeze` are drawn on their own.
+    pub fn freeze(&mut self) {
+        self.unfreeze();
+
+        // This node is no longer part of the batch of one of its ancestors.
+        if let Some(frozen) = self.frozen.take() {
+            frozen.batch.set(true);
+        }
 
-                    dp.update();
-                    self.world_transform = self.local_transform * dp.world_transform;
-                    self.world_scale = self.local_scale.component_mul(&dp.local_scale);
-                    self.up_to_date = true;
-                    return;
-                },
-                None => {}
-            }
+        let dirty = Rc::new(Cell::new(true));
 
-            // no parent
-            self.world_transform = self.local_transform;
-            self.world_scale = self.local_scale;
-            self.up_to_date = true;
+        for c in self.children.iter_mut() {
+            c.data_mut().mark_frozen(&dirty);
         }
+
+        self.batch = Some(Batch {
+            objects: Vec::new(),
+            dirty,
+            scale: self.world_scale,
+        });
     }
-}
 
-impl SceneNode {
+    /// Stops drawing the descendants of this node as merged objects.
+    ///
+    /// This reverts the effect of `freeze`, and does nothing if this node is not frozen.
+    pub fn unfreeze(&mut self) {
+        if let Some(batch) = self.batch.take() {
+            for c in self.children.iter_mut() {
+                c.data_mut().thaw(&batch.dirty);
+            }
+        }
+    }
+
+    /// Whether the descendants of this node have been merged by `freeze`.
+    #[inline]
+    pub fn is_frozen(&self) -> bool {
+        self.batch.is_some()
+    }
+
+    fn mark_frozen(&mut self, batch: &Rc<Cell<bool>>) {
+        // The outer batch replaces the inner one.
+        self.unfreeze();
+
+        self.frozen = Some(Frozen {
+            batch: batch.clone(),
+            batched: false,
+        });
+
+        for c in self.children.iter_mut() {
+            c.data_mut().mark_frozen(batch);
+        }
+    }
+
+    fn thaw(&mut self, batch: &Rc<Cell<bool>>) {
+        if self
+            .frozen
+            .as_ref()
+            .map_or(false, |f| Rc::ptr_eq(&f.batch, batch))
+        {
+            self.frozen = None;
+
+            for c in self.children.iter_mut() {
+                c.data_mut().thaw(batch);
+            }
+        }
+    }
+
+    fn rebuild_batch(&mut self) {
+        let scale = self.world_scale;
+        let mut groups = Vec::new();
+        let batch = self.batch.as_mut().unwrap();
+
+        batch.dirty.set(false);
+        batch.scale = scale;
+
+        for c in self.children.iter_mut() {
+            c.data_mut()
+                .collect_batched(&batch.dirty, &na::one(), &scale, &scale, &mut groups);
+        }
+
+        batch.objects = groups
+            .into_iter()
+            .map(|g| {
+                let mesh = Mesh::new_with_smallest_indices(
+                    g.coords,
+                    g.faces,
+                    Some(g.normals),
+                    Some(g.uvs),
+                    false,
+                );
+                let mut object = Object::new(
+                    Rc::new(RefCell::new(mesh)),
+                    g.color.x,
+                    g.color.y,
+                    g.color.z,
+                    g.texture,
+                    g.material,
+                );
+
+                object.set_alpha(g.alpha);
+                object.set_specular_color(
+                    g.specular_color.x,
+                    g.specular_color.y,
+                    g.specular_color.z,
+                );
+                object.set_shininess(g.shininess);
+                object.set_emissive_color(
+                    g.emissive_color.x,
+                    g.emissive_color.y,
+                    g.emissive_color.z,
+                );
+                object.set_emissive_texture(g.emissive_texture);
+                object.set_lines_color(g.lines_color);
+                object.set_lines_width(g.lines_width);
+                object.set_points_size(g.points_size);
+                object.set_surface_rendering_activation(g.draw_surface);
+                object.enable_backface_culling(g.cull);
+                object
+            })
+            .collect();
+    }
+
+    // Appends the geometry of the visible descendants frozen in `batch` to `groups`, expressed
+    // in the frame of the frozen node. `transform` and `scale` are the transformation and scale
+    // of the parent of this node relative to the frozen node, and `root_scale` is the world
+    // scale of the frozen node.
+    fn collect_batched(
+        &mut self,
+        batch: &Rc<Cell<bool>>,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+        root_scale: &Vector3<f32>,
+        groups: &mut Vec<BatchGroup>,
+    ) {
+        if !self
+            .frozen
+            .as_ref()
+            .map_or(false, |f| Rc::ptr_eq(&f.batch, batch))
+        {
+            return;
+        }
+
+        let transform = *transform * self.local_transform;
+        let scale = scale.component_mul(&self.local_scale);
+        let batched = self.visible
+            && !root_scale.iter().any(|s| *s == 0.0)
+            && self.object.as_ref().map_or(false, |o| {
+                append_to_batch(o, &transform, &scale, root_scale, groups)
+            });
+
+        self.frozen.as_mut().unwrap().batched = batched;
+
+        // The descendants of invisible nodes are not rendered.
+        if self.visible {
+            for c in self.children.iter_mut() {
+                c.data_mut()
+                    .collect_batched(batch, &transform, &scale, root_scale, groups);
+            }
+        }
+    }
+
+    // FIXME: make this public?
+    fn update(&mut self) {
+        // NOTE: makin this test
+        if !self.up_to_date {
+            match self.parent {
+                Some(ref mut p) => unsafe {
+                    let mut dp = (**p).borrow_mut();
+
+                    dp.update();
+                    self.world_transform = dp.world_transform * self.local_transform;
+                    self.world_scale = self.local_scale.component_mul(&dp.world_scale);
+                    self.up_to_date = true;
+                    return;
+                },
+                None => {}
+            }
+
+            // no parent
+            self.world_transform = self.local_transform;
+            self.world_scale = self.local_scale;
+            self.up_to_date = true;
+        }
+    }
+}
+
+// Appends the geometry of `object` to the group of `groups` it can be merged with, and returns
+// `false` if the object cannot be merged, i.e., if it has instances or vertex colors, or if its
+// vertex data are not available on the CPU.
+fn append_to_batch(
+    object: &Object,
+    transform: &Isometry3<f32>,
+    scale: &Vector3<f32>,
+    root_scale: &Vector3<f32>,
+    groups: &mut Vec<BatchGroup>,
+) -> bool {
+    let mesh = object.mesh().borrow();
+
+    if object.data().instances().is_some() || mesh.colors().is_some() {
+        return false;
+    }
+
+    let coords = mesh.coords().read().unwrap();
+    let normals = mesh.normals().read().unwrap();
+    let uvs = mesh.uvs().read().unwrap();
+
+    let (coords, normals, uvs, faces) = match (
+        coords.data(),
+        normals.data(),
+        uvs.data(),
+        mesh.faces_as_u32(),
+    ) {
+        (Some(c), Some(n), Some(u), Some(f)) => (c, n, u, f),
+        _ => return false,
+    };
+
+    let data = object.data();
+    let material = object.material();
+    let position = groups.iter().position(|g| {
+        Rc::ptr_eq(&g.material, &material)
+            && Rc::ptr_eq(&g.texture, data.texture())
+            && g.color == *data.color()
+            && g.alpha == data.alpha()
+            && g.specular_color == *data.specular_color()
+            && g.shininess == data.shininess()
+            && g.emissive_color == *data.emissive_color()
+            && match (&g.emissive_texture, data.emissive_texture()) {
+                (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+            && g.lines_color.as_ref() == data.lines_color()
+            && g.lines_width == data.lines_width()
+            && g.points_size == data.points_size()
+            && g.draw_surface == data.surface_rendering_active()
+            && g.cull == data.backface_culling_enabled()
+    });
+    let group = match position {
+        Some(i) => &mut groups[i],
+        None => {
+            groups.push(BatchGroup {
+                material,
+                texture: data.texture().clone(),
+                color: *data.color(),
+                alpha: data.alpha(),
+                specular_color: *data.specular_color(),
+                shininess: data.shininess(),
+                emissive_color: *data.emissive_color(),
+                emissive_texture: data.emissive_texture().cloned(),
+                lines_color: data.lines_color().cloned(),
+                lines_width: data.lines_width(),
+                points_size: data.points_size(),
+                draw_surface: data.surface_rendering_active(),
+                cull: data.backface_culling_enabled(),
+                coords: Vec::new(),
+                normals: Vec::new(),
+                uvs: Vec::new(),
+                faces: Vec::new(),
+            });
+            groups.last_mut().unwrap()
+        }
+    };
+    let first = group.coords.len() as u32;
+
+    // The batch is drawn with the world scale of the frozen node, which is applied before its
+    // transformation, so it has to be divided out of the merged vertices. Only the rotation
+    // is applied to the normals, as done by the shaders.
+    group.coords.extend(coords.iter().map(|pt| {
+        let pt = transform * Point3::from(pt.coords.component_mul(scale));
+        Point3::from(pt.coords.component_div(root_scale))
+    }));
+    group
+        .normals
+        .extend(normals.iter().map(|n| transform.rotation * *n));
+    group.uvs.extend_from_slice(uvs);
+    group.faces.extend(
+        faces
+            .iter()
+            .map(|f| Point3::new(f.x + first, f.y + first, f.z + first)),
+    );
+
+    true
+}
+
+impl SceneNode {
     /// Creates a new scene node that is not rooted.
     pub fn new(
         local_scale: Vector3<f32>,
@@ -604,7 +1551,12 @@ impl SceneNode {
             up_to_date: false,
             children: Vec::new(),
             object: object,
+            batch: None,
+            frozen: None,
             parent: None,
+            animation: None,
+            normals_length: None,
+            render_priority: 0,
         };
 
         SceneNode {
@@ -621,7 +1573,88 @@ impl SceneNode {
     pub fn unlink(&mut self) {
         let self_self = self.clone();
         self.data_mut().remove_from_parent(&self_self);
-        self.data_mut().parent = None
+        self.data_mut().parent = None;
+        // Removes this node from the batch of its former ancestors.
+        self.data_mut().invalidate();
+    }
+
+    /// Moves this node to the children of `new_parent`, keeping its world transformation.
+    ///
+    /// The local transformation and scale of this node are recomputed so that its world
+    /// transformation and scale remain unchanged. If this node has no parent, it is simply
+    /// added to the children of `new_parent`.
+    ///
+    /// # Failures:
+    /// Fails if `new_parent` is this node or one of its descendants.
+    pub fn reparent(&mut self, new_parent: &mut SceneNode) {
+        assert!(
+            !new_parent.is_in_subtree_of(self),
+            "A node cannot be reparented to itself or to one of its descendants."
+        );
+
+        let (world_transform, world_scale) = {
+            let data = self.data();
+            (data.world_transformation(), data.world_scale)
+        };
+        let (parent_transform, parent_scale) = {
+            let parent_data = new_parent.data();
+            (parent_data.world_transformation(), parent_data.world_scale)
+        };
+
+        self.unlink();
+
+        {
+            let mut data = self.data_mut();
+            data.local_transform = parent_transform.inverse() * world_transform;
+            data.local_scale = world_scale.component_div(&parent_scale);
+            data.invalidate();
+        }
+
+        new_parent.add_child(self.clone());
+    }
+
+    /// Creates a copy of this node and all its descendants.
+    ///
+    /// Meshes, textures, and materials are shared with the original nodes, but each copy has its
+    /// own transformation, visibility, render priority, displayed normals, and object state. The
+    /// copy has no parent.
+    pub fn deep_clone(&self) -> SceneNode {
+        let data = self.data();
+        let mut res = SceneNode::new(
+            data.local_scale,
+            data.local_transform,
+            data.object.as_ref().map(|o| o.duplicate()),
+        );
+
+        {
+            let mut res_data = res.data_mut();
+            res_data.visible = data.visible;
+            res_data.render_priority = data.render_priority;
+            res_data.normals_length = data.normals_length;
+        }
+
+        for c in data.children.iter() {
+            res.add_child(c.deep_clone())
+        }
+
+        res
+    }
+
+    // Whether this node is `node` or one of its descendants.
+    fn is_in_subtree_of(&self, node: &SceneNode) -> bool {
+        let target = &*node.data as *const RefCell<SceneNodeData>;
+        let mut curr = &*self.data as *const RefCell<SceneNodeData>;
+
+        loop {
+            if curr == target {
+                return true;
+            }
+
+            match unsafe { (*curr).borrow().parent } {
+                Some(parent) => curr = parent,
+                None => return false,
+            }
+        }
     }
 
     /// The data of this scene node.
@@ -737,6 +1770,137 @@ impl SceneNode {
         )
     }
 
+    /// Adds a cone with a custom number of subdivisions to this node children. The cone is
+    /// initially centered at (0, 0, 0) and points toward the positive `y` axis.
+    ///
+    /// The mesh is registered by the global mesh manager so that cones with the same parameters
+    /// share it.
+    ///
+    /// # Arguments
+    /// * `r` - the cone base radius
+    /// * `h` - the cone height
+    /// * `subdivs` - the number of subdivisions of the base circle. Must be at least 3.
+    pub fn add_cone_with_subdivs(&mut self, r: f32, h: f32, subdivs: u32) -> SceneNode {
+        let name = format!("cone_{}_{}_{}", r, h, subdivs);
+        let mesh = managed_mesh(&name, || kiss3d_procedural::cone(r, h, subdivs));
+
+        self.add_mesh(mesh, Vector3::from_element(1.0))
+    }
+
+    /// Adds a sphere with a custom tessellation to this node children. The sphere is initially
+    /// centered at (0, 0, 0).
+    ///
+    /// The sphere is an icosahedron whose triangles are subdivided `subdivs` times, which
+    /// gives `10 * 4^subdivs + 2` vertices evenly distributed on the sphere. The mesh is
+    /// registered by the global mesh manager so that spheres with the same parameters share it.
+    ///
+    /// # Arguments
+    /// * `r` - the sphere radius
+    /// * `subdivs` - the number of subdivisions of the icosahedron
+    pub fn add_sphere_with_subdivs(&mut self, r: f32, subdivs: u32) -> SceneNode {
+        let name = format!("icosphere_{}_{}", r, subdivs);
+        let mesh = managed_mesh(&name, || kiss3d_procedural::icosphere(r, subdivs));
+
+        self.add_mesh(mesh, Vector3::from_element(1.0))
+    }
+
+    /// Adds a cylinder with a custom number of subdivisions to this node children. The cylinder
+    /// is initially centered at (0, 0, 0) and has its principal axis aligned with the `y` axis.
+    ///
+    /// The mesh is registered by the global mesh manager so that cylinders with the same
+    /// parameters share it.
+    ///
+    /// # Arguments
+    /// * `r` - the cylinder base radius
+    /// * `h` - the cylinder height
+    /// * `subdivs` - the number of subdivisions of the base circles. Must be at least 3.
+    pub fn add_cylinder_with_subdivs(&mut self, r: f32, h: f32, subdivs: u32) -> SceneNode {
+        let name = format!("cylinder_{}_{}_{}", r, h, subdivs);
+        let mesh = managed_mesh(&name, || kiss3d_procedural::cylinder(r, h, subdivs));
+
+        self.add_mesh(mesh, Vector3::from_element(1.0))
+    }
+
+    /// Adds a capsule with a custom number of subdivisions to this node children. The capsule
+    /// is initially centered at (0, 0, 0) and has its principal axis aligned with the `y` axis.
+    ///
+    /// The mesh is registered by the global mesh manager so that capsules with the same
+    /// parameters share it.
+    ///
+    /// # Arguments
+    /// * `r` - the capsule caps radius
+    /// * `h` - the capsule height, without the caps
+    /// * `subdivs` - the number of subdivisions around the capsule axis. Must be at least 3.
+    pub fn add_capsule_with_subdivs(&mut self, r: f32, h: f32, subdivs: u32) -> SceneNode {
+        let name = format!("capsule_{}_{}_{}", r, h, subdivs);
+        let mesh = managed_mesh(&name, || kiss3d_procedural::capsule(r, h, subdivs));
+
+        self.add_mesh(mesh, Vector3::from_element(1.0))
+    }
+
+    /// Adds a torus to this node children. The torus is initially centered at (0, 0, 0) and
+    /// lies on the `xz` plane.
+    ///
+    /// The mesh is registered by the global mesh manager so that tori with the same parameters
+    /// share it.
+    ///
+    /// # Arguments
+    /// * `major_r` - the distance from the torus center to the center of its tube
+    /// * `minor_r` - the radius of the torus tube
+    /// * `nsegs` - the number of subdivisions along the tube. Must be at least 3.
+    /// * `nrings` - the number of subdivisions around the tube. Must be at least 3.
+    pub fn add_torus(&mut self, major_r: f32, minor_r: f32, nsegs: u32, nrings: u32) -> SceneNode {
+        let name = format!("torus_{}_{}_{}_{}", major_r, minor_r, nsegs, nrings);
+        let mesh = managed_mesh(&name, || {
+            kiss3d_procedural::torus(major_r, minor_r, nsegs, nrings)
+        });
+
+        self.add_mesh(mesh, Vector3::from_element(1.0))
+    }
+
+    /// Adds an arrow going from `from` to `to` to this node children.
+    ///
+    /// The arrow is a group made of a cylindrical shaft and a conical head, so its color and
+    /// transformation can be set as for any other node. The head is twice as wide as the shaft and
+    /// never longer than half the arrow. Nothing is drawn if `from` and `to` are equal.
+    ///
+    /// # Arguments
+    /// * `from` - the tail of the arrow, i.e., the origin of the returned node
+    /// * `to` - the tip of the arrow
+    /// * `shaft_radius` - the radius of the arrow shaft
+    pub fn add_arrow(
+        &mut self,
+        from: Point3<f32>,
+        to: Point3<f32>,
+        shaft_radius: f32,
+    ) -> SceneNode {
+        let mut arrow = self.add_group();
+        let dir = to - from;
+        let length = dir.norm();
+        let rot = UnitQuaternion::rotation_between(&Vector3::y(), &dir).unwrap_or_else(|| {
+            UnitQuaternion::from_axis_angle(&Vector3::x_axis(), std::f32::consts::PI)
+        });
+
+        arrow.set_local_transformation(Isometry3::from_parts(Translation3::from(from.coords), rot));
+
+        if length > 0.0 {
+            let head_length = (shaft_radius * 6.0).min(length / 2.0);
+            let shaft_length = length - head_length;
+
+            let mut shaft = arrow.add_cylinder(shaft_radius, shaft_length);
+            shaft.set_local_translation(Translation3::new(0.0, shaft_length / 2.0, 0.0));
+
+            let mut head = arrow.add_cone(shaft_radius * 2.0, head_length);
+            head.set_local_translation(Translation3::new(
+                0.0,
+                shaft_length + head_length / 2.0,
+                0.0,
+            ));
+        }
+
+        arrow
+    }
+
     /// Adds a double-sided quad to this node children. The quad is initially centered at (0, 0,
     /// 0). The quad itself is composed of a user-defined number of triangles regularly spaced on a
     /// grid. This is the main way to draw height maps.
@@ -792,6 +1956,21 @@ impl SceneNode {
         self.add_object(scale, na::one(), object)
     }
 
+    /// Creates and adds a new object to this node children, drawn once per transformation of
+    /// `instances`.
+    ///
+    /// This is much faster than adding one node per instance when they are numerous.
+    pub fn add_instanced(
+        &mut self,
+        mesh: Rc<RefCell<Mesh>>,
+        scale: Vector3<f32>,
+        instances: &[Isometry3<f32>],
+    ) -> SceneNode {
+        let mut node = self.add_mesh(mesh, scale);
+        node.set_instances(instances);
+        node
+    }
+
     /// Creates and adds a new object using a mesh descriptor.
     pub fn add_trimesh(&mut self, descr: TriMesh<f32>, scale: Vector3<f32>) -> SceneNode {
         self.add_mesh(
@@ -803,7 +1982,9 @@ impl SceneNode {
     /// Creates and adds multiple nodes created from an obj file.
     ///
     /// This will create a new node serving as a root of the scene described by the obj file. This
-    /// newly created node is added to this node's children.
+    /// newly created node is added to this node's children. If the file has a single group, its
+    /// object is added directly to this node's children instead. See `add_obj_grouped` to also
+    /// retrieve the node of each group.
     pub fn add_obj(&mut self, path: &Path, mtl_dir: &Path, scale: Vector3<f32>) -> SceneNode {
         let tex = TextureManager::get_global_manager(|tm| tm.get_default());
         let mat = MaterialManager::get_global_manager(|mm| mm.get_default());
@@ -865,6 +2046,360 @@ impl SceneNode {
         result.unwrap()
     }
 
+    /// Creates and adds a node per group of an obj file.
+    ///
+    /// A new node, serving as the root of the model, is added to this node's children. It has
+    /// one child per group (`g`) or object (`o`) of the file, in order of appearance, each with
+    /// the color, opacity, and diffuse texture of the material referenced by its `usemtl`. The
+    /// faces of a group using several materials are split into one child per material. Returns
+    /// the root node, and the children with the names of their groups, so that each part can be
+    /// hidden or recolored individually. The faces declared before any group belong to a part
+    /// named after `path`.
+    ///
+    /// Unlike `add_obj`, this always creates a root node, even if the file has a single group.
+    pub fn add_obj_grouped(
+        &mut self,
+        path: &Path,
+        mtl_dir: &Path,
+        scale: Vector3<f32>,
+    ) -> (SceneNode, Vec<(String, SceneNode)>) {
+        let tex = TextureManager::get_global_manager(|tm| tm.get_default());
+        let mat = MaterialManager::get_global_manager(|mm| mm.get_default());
+        let basename = path.to_str().unwrap();
+        let objs = MeshManager::load_obj_grouped(path, mtl_dir, basename)
+            .unwrap_or_else(|e| panic!("Unable to load the obj file {:?}: {}", path, e));
+
+        let mut root = SceneNode::new(scale, na::one(), None);
+        self.add_child(root.clone());
+
+        let mut parts = Vec::with_capacity(objs.len());
+        // The loader prefixes the group names with the name of the file.
+        let prefix = format!("{}/", basename);
+
+        for (name, mesh, mtl) in objs.into_iter() {
+            let mut object = Object::new(mesh, 1.0, 1.0, 1.0, tex.clone(), mat.clone());
+
+            if let Some(mtl) = mtl {
+                object.set_color(mtl.diffuse.x, mtl.diffuse.y, mtl.diffuse.z);
+                object.set_alpha(mtl.alpha);
+
+                if let Some(t) = mtl
+                    .diffuse_texture
+                    .as_ref()
+                    .or(mtl.ambiant_texture.as_ref())
+                {
+                    let tpath = mtl_dir.join(t);
+                    object.set_texture_from_file(&tpath, tpath.to_str().unwrap())
+                }
+            }
+
+            let node = root.add_object(Vector3::from_element(1.0), na::one(), object);
+            let name = name
+                .strip_prefix(&prefix[..])
+                .unwrap_or(&name[..])
+                .to_string();
+
+            parts.push((name, node));
+        }
+
+        (root, parts)
+    }
+
+    /// Saves the objects of this node and its visible descendants into a Wavefront OBJ file,
+    /// with their colors in an MTL file.
+    ///
+    /// The vertices are written in world coordinates, i.e., the world transformation and scale
+    /// of each node are applied to the vertices of its object, so a mesh shared by several nodes
+    /// is written once per node. Each object is written as a group `object_{i}`, using a material
+    /// with its color and opacity. The MTL file has the same name as the OBJ file, with the `mtl`
+    /// extension. Textures are not saved. The objects without vertices available on the CPU, and
+    /// the instances of the objects, are not saved.
+    pub fn save_obj<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let mtl_path = path.with_extension("mtl");
+        let mut writer = ObjWriter {
+            obj: BufWriter::new(File::create(path)?),
+            mtl: BufWriter::new(File::create(&mtl_path)?),
+            materials: HashMap::new(),
+            num_coords: 0,
+            num_uvs: 0,
+            num_normals: 0,
+            num_objects: 0,
+        };
+
+        let mtl_name = mtl_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        writeln!(writer.obj, "mtllib {}", mtl_name)?;
+
+        let data = self.data();
+        let transform = data.world_transformation();
+        writer.write_node(&data, &transform, &data.world_scale)?;
+
+        writer.obj.flush()?;
+        writer.mtl.flush()
+    }
+
+    /// Creates and adds a new object from an ASCII or binary STL file.
+    ///
+    /// The mesh is registered with the `MeshManager` using the file path as its name.
+    pub fn add_stl<P: AsRef<Path>>(&mut self, path: P, scale: Vector3<f32>) -> SceneNode {
+        let path = path.as_ref();
+        let mesh = MeshManager::get_global_manager(|mm| mm.load_stl(path, path.to_str().unwrap()))
+            .unwrap_or_else(|e| panic!("Unable to load the STL file {:?}: {}", path, e));
+
+        self.add_mesh(mesh, scale)
+    }
+
+    /// Loads a glTF 2.0 file (either `.gltf` or `.glb`), and adds its default scene as a child of
+    /// this node.
+    ///
+    /// The returned node is the root of a subtree mirroring the glTF node hierarchy. Only static
+    /// meshes are supported, with their base color and base color texture.
+    ///
+    /// # Arguments
+    /// * `path` - the path to the glTF file. External buffers and images are looked up relative
+    /// to it.
+    /// * `scale` - the scale to apply to the loaded model.
+    #[cfg(feature = "gltf")]
+    pub fn add_gltf<P: AsRef<Path>>(&mut self, path: P, scale: Vector3<f32>) -> SceneNode {
+        let path = path.as_ref();
+        let scene = gltf::parse_file(path)
+            .unwrap_or_else(|e| panic!("Unable to load the glTF file {:?}: {}", path, e));
+
+        self.add_gltf_scene(scene, path.to_str().unwrap(), scale)
+    }
+
+    /// Loads a binary glTF 2.0 file from memory, and adds its default scene as a child of this
+    /// node.
+    ///
+    /// All the buffers and images must be embedded in `data`. See `add_gltf` for details.
+    ///
+    /// # Arguments
+    /// * `data` - the content of the `.glb` file.
+    /// * `name` - the name used to register the textures of the model.
+    /// * `scale` - the scale to apply to the loaded model.
+    #[cfg(feature = "gltf")]
+    pub fn add_glb(&mut self, data: &[u8], name: &str, scale: Vector3<f32>) -> SceneNode {
+        let scene = gltf::parse(data)
+            .unwrap_or_else(|e| panic!("Unable to load the glTF data {}: {}", name, e));
+
+        self.add_gltf_scene(scene, name, scale)
+    }
+
+    #[cfg(feature = "gltf")]
+    fn add_gltf_scene(&mut self, scene: GltfScene, name: &str, scale: Vector3<f32>) -> SceneNode {
+        let textures: Vec<_> = scene
+            .images
+            .into_iter()
+            .enumerate()
+            .map(|(i, image)| {
+                image.map(|image| {
+                    let tex_name = format!("{}#image{}", name, i);
+                    TextureManager::get_global_manager(|tm| tm.add_image(image.clone(), &tex_name))
+                })
+            })
+            .collect();
+
+        let mut root = SceneNode::new(scale, na::one(), None);
+        self.add_child(root.clone());
+
+        for node in scene.roots.into_iter() {
+            root.add_gltf_node(node, &textures[..]);
+        }
+
+        root
+    }
+
+    #[cfg(feature = "gltf")]
+    fn add_gltf_node(&mut self, node: GltfNode, textures: &[Option<Rc<Texture>>]) {
+        let tex = TextureManager::get_global_manager(|tm| tm.get_default());
+        let mat = MaterialManager::get_global_manager(|mm| mm.get_default());
+
+        let mut group = SceneNode::new(node.scale, node.transform, None);
+        self.add_child(group.clone());
+
+        for primitive in node.primitives.into_iter() {
+            let mesh = Rc::new(RefCell::new(primitive.mesh));
+            let color = primitive.base_color;
+            let mut object = Object::new(mesh, color.x, color.y, color.z, tex.clone(), mat.clone());
+
+            if let Some(Some(texture)) = primitive.base_color_texture.map(|i| &textures[i]) {
+                object.set_texture(texture.clone());
+            }
+
+            let _ = group.add_object(Vector3::from_element(1.0), na::one(), object);
+        }
+
+        for child in node.children.into_iter() {
+            group.add_gltf_node(child, textures);
+        }
+    }
+
+    /// Casts a ray on the visible objects of the scene graph rooted by this node.
+    ///
+    /// The ray is expressed in world space. Returns the node containing the nearest object hit
+    /// by the ray, together with the time of impact, i.e., the hit point is at
+    /// `origin + dir * toi`. Only objects with vertices and faces available on the CPU can be hit.
+    /// If this node is not the root of its scene graph, the transformations of its ancestors are
+    /// still taken into account.
+    pub fn cast_ray(&self, origin: &Point3<f32>, dir: &Vector3<f32>) -> Option<(SceneNode, f32)> {
+        let mut best = None;
+        let ray = Ray::new(*origin, *dir);
+        // Start from the world transformation of the parent.
+        let (transform, scale) = match self.data().parent {
+            Some(p) => unsafe {
+                let dp = (*p).borrow();
+                (dp.world_transformation(), dp.world_scale)
+            },
+            None => (na::one(), Vector3::from_element(1.0)),
+        };
+
+        self.do_cast_ray(&transform, &scale, &ray, &mut best);
+
+        best
+    }
+
+    fn do_cast_ray(
+        &self,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+        ray: &Ray<f32>,
+        best: &mut Option<(SceneNode, f32)>,
+    ) {
+        let data = self.data();
+
+        if !data.visible {
+            return;
+        }
+
+        let transform = *transform * data.local_transform;
+        let scale = scale.component_mul(&data.local_scale);
+
+        if let Some(ref object) = data.object {
+            if scale.iter().all(|s| *s != 0.0) {
+                // Express the ray in the local space of the mesh. The time of impact is
+                // preserved because the direction is not renormalized.
+                let inv = transform.inverse();
+                let local_ray = Ray::new(
+                    Point3::from((inv * ray.origin).coords.component_div(&scale)),
+                    (inv * ray.dir).component_div(&scale),
+                );
+                let max_toi = best.as_ref().map_or(f32::MAX, |b| b.1);
+
+                if let Some(toi) = object.mesh().borrow().cast_ray(&local_ray, max_toi) {
+                    *best = Some((self.clone(), toi));
+                }
+            }
+        }
+
+        for c in data.children.iter() {
+            c.do_cast_ray(&transform, &scale, ray, best);
+        }
+    }
+
+    /// The world-space axis-aligned bounding box of this node and all its descendants.
+    ///
+    /// Returns the minimum and maximum corners of the box, or `None` if neither this node nor
+    /// its descendants contain any vertex. Only objects with vertices available on the CPU are
+    /// taken into account.
+    pub fn aabb(&self) -> Option<(Point3<f32>, Point3<f32>)> {
+        self.world_aabb(false)
+    }
+
+    // A world-space axis-aligned box enclosing the visible objects of this node and its
+    // descendants, computed from the cached bounding boxes of their meshes instead of their
+    // vertices. It is cheap enough to be computed at each frame, but may be larger than `aabb`.
+    pub(crate) fn loose_aabb(&self) -> Option<(Point3<f32>, Point3<f32>)> {
+        self.world_aabb(true)
+    }
+
+    fn world_aabb(&self, loose: bool) -> Option<(Point3<f32>, Point3<f32>)> {
+        let (transform, scale) = {
+            let data = self.data();
+            let transform = data.world_transformation();
+            // `world_transformation` updated the world scale as well.
+            (transform, data.world_scale)
+        };
+        let mut res = None;
+
+        self.do_aabb(&transform, &scale, loose, &mut res);
+
+        res
+    }
+
+    fn do_aabb(
+        &self,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+        loose: bool,
+        res: &mut Option<(Point3<f32>, Point3<f32>)>,
+    ) {
+        let data = self.data();
+
+        if loose && !data.visible {
+            return;
+        }
+
+        if let Some(ref object) = data.object {
+            let mesh = object.mesh().borrow();
+            let instances = object.data().instances().map(|i| i.borrow());
+            let identity = [Isometry3::identity()];
+            let instance_transforms = instances.as_ref().map_or(&identity[..], |i| i.transforms());
+            // The corners of the bounding box of the mesh.
+            let corners: Option<Vec<Point3<f32>>> = if loose {
+                mesh.aabb().map(|(mins, maxs)| {
+                    (0..8)
+                        .map(|i| {
+                            Point3::new(
+                                if i & 1 == 0 { mins.x } else { maxs.x },
+                                if i & 2 == 0 { mins.y } else { maxs.y },
+                                if i & 4 == 0 { mins.z } else { maxs.z },
+                            )
+                        })
+                        .collect()
+                })
+            } else {
+                None
+            };
+            let coords = mesh.coords().read().unwrap();
+            let coords = if loose {
+                corners.as_ref()
+            } else {
+                coords.data().as_ref()
+            };
+
+            if let Some(coords) = coords {
+                for pt in coords.iter() {
+                    // Scale first, as done by the shaders.
+                    let pt = Point3::from(pt.coords.component_mul(scale));
+
+                    for instance_transform in instance_transforms.iter() {
+                        let pt = transform * (instance_transform * pt);
+
+                        *res = match *res {
+                            Some((mins, maxs)) => Some((mins.inf(&pt), maxs.sup(&pt))),
+                            None => Some((pt, pt)),
+                        };
+                    }
+                }
+            }
+        }
+
+        for c in data.children.iter() {
+            let (transform, scale) = {
+                let cdata = c.data();
+                (
+                    *transform * cdata.local_transform,
+                    scale.component_mul(&cdata.local_scale),
+                )
+            };
+
+            c.do_aabb(&transform, &scale, loose, res);
+        }
+    }
+
     /// Applies a closure to each object contained by this node and its children.
     #[inline]
     pub fn apply_to_scene_nodes_mut<F: FnMut(&mut SceneNode)>(&mut self, f: &mut F) {
@@ -892,8 +2427,36 @@ impl SceneNode {
     //
 
     /// Render the scene graph rooted by this node.
-    pub fn render(&mut self, pass: usize, camera: &mut dyn Camera, light: &Light) {
-        self.data_mut().render(pass, camera, light)
+    pub fn render(&mut self, pass: usize, camera: &mut dyn Camera, environment: &Environment) {
+        self.data_mut().render(pass, camera, environment)
+    }
+
+    /// Render the scene graph rooted by this node, skipping the objects outside of a frustum.
+    #[inline]
+    pub fn render_culled(
+        &mut self,
+        pass: usize,
+        camera: &mut dyn Camera,
+        environment: &Environment,
+        frustum: &Frustum,
+    ) {
+        self.data_mut()
+            .render_culled(pass, camera, environment, frustum)
+    }
+
+    /// Render the scene graph rooted by this node, using the same material for every object
+    /// instead of their own materials.
+    #[inline]
+    pub fn render_with_material(
+        &mut self,
+        pass: usize,
+        camera: &mut dyn Camera,
+        environment: &Environment,
+        material: &mut dyn Material,
+        frustum: Option<&Frustum>,
+    ) {
+        self.data_mut()
+            .render_with_material(pass, camera, environment, material, frustum)
     }
 
     /// Sets the material of the objects contained by this node and its children.
@@ -1026,6 +2589,27 @@ impl SceneNode {
         self.data_mut().set_visible(visible)
     }
 
+    /// Merges the objects of the descendants of this node into a few large objects, to draw them
+    /// with a few draw calls.
+    ///
+    /// See `SceneNodeData::freeze` for details.
+    #[inline]
+    pub fn freeze(&mut self) {
+        self.data_mut().freeze()
+    }
+
+    /// Stops drawing the descendants of this node as merged objects.
+    #[inline]
+    pub fn unfreeze(&mut self) {
+        self.data_mut().unfreeze()
+    }
+
+    /// Whether the descendants of this node have been merged by `freeze`.
+    #[inline]
+    pub fn is_frozen(&self) -> bool {
+        self.data().is_frozen()
+    }
+
     /// Sets the color of the objects contained by this node and its children.
     ///
     /// Colors components must be on the range `[0.0, 1.0]`.
@@ -1034,6 +2618,63 @@ impl SceneNode {
         self.data_mut().set_color(r, g, b)
     }
 
+    /// Sets the opacity of the objects contained by this node and its children.
+    ///
+    /// The opacity is clamped to `[0.0, 1.0]`. See `Object::set_alpha`.
+    #[inline]
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.data_mut().set_alpha(alpha)
+    }
+
+    /// Sets the color of the specular highlights of the objects contained by this node and its
+    /// children.
+    ///
+    /// Colors components must be on the range `[0.0, 1.0]`. See `Object::set_specular_color`.
+    #[inline]
+    pub fn set_specular_color(&mut self, r: f32, g: f32, b: f32) {
+        self.data_mut().set_specular_color(r, g, b)
+    }
+
+    /// Sets the shininess of the objects contained by this node and its children.
+    ///
+    /// See `Object::set_shininess`.
+    #[inline]
+    pub fn set_material_shininess(&mut self, shininess: f32) {
+        self.data_mut().set_material_shininess(shininess)
+    }
+
+    /// Sets the color emitted by the objects contained by this node and its children.
+    ///
+    /// Colors components must be on the range `[0.0, 1.0]`. See `Object::set_emissive_color`.
+    #[inline]
+    pub fn set_emissive_color(&mut self, r: f32, g: f32, b: f32) {
+        self.data_mut().set_emissive_color(r, g, b)
+    }
+
+    /// Sets the texture modulating the color emitted by the objects contained by this node and
+    /// its children.
+    ///
+    /// See `Object::set_emissive_texture`.
+    #[inline]
+    pub fn set_emissive_texture(&mut self, texture: Option<Rc<Texture>>) {
+        self.data_mut().set_emissive_texture(texture)
+    }
+
+    /// Sets the instances of the objects contained by this node and its children.
+    ///
+    /// Each object is drawn once per transformation of `instances`, appended to the object
+    /// transformation.
+    #[inline]
+    pub fn set_instances(&mut self, instances: &[Isometry3<f32>]) {
+        self.data_mut().set_instances(instances)
+    }
+
+    /// Removes the instances of the objects contained by this node and its children.
+    #[inline]
+    pub fn remove_instances(&mut self) {
+        self.data_mut().remove_instances()
+    }
+
     /// Sets the texture of the objects contained by this node and its children.
     ///
     /// The texture is loaded from a file and registered by the global `TextureManager`.
@@ -1070,6 +2711,24 @@ impl SceneNode {
         self.data_mut().set_texture(texture)
     }
 
+    /// Replaces the pixels of a rectangular region of the textures of the objects contained by
+    /// this node and its children.
+    ///
+    /// Returns `false` if at least one of the textures could not be updated.
+    #[inline]
+    pub fn update_texture_subimage(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        data: &[u8],
+        format: TextureFormat,
+    ) -> bool {
+        self.data()
+            .update_texture_subimage(x, y, width, height, data, format)
+    }
+
     /// Sets the local scaling factors of the object.
     #[inline]
     pub fn set_local_scale(&mut self, sx: f32, sy: f32, sz: f32) {
@@ -1083,6 +2742,23 @@ impl SceneNode {
         self.data_mut().reorient(eye, at, up)
     }
 
+    /// Moves the node to the point `eye` and orients it such that its `-z` axis points toward
+    /// `at`, like a camera.
+    ///
+    /// See `SceneNodeData::look_at` for details.
+    #[inline]
+    pub fn look_at(&mut self, eye: &Point3<f32>, at: &Point3<f32>, up: &Vector3<f32>) {
+        self.data_mut().look_at(eye, at, up)
+    }
+
+    /// Orients the node, without moving it, such that its `z` axis points toward `target`.
+    ///
+    /// See `SceneNodeData::face_towards` for details.
+    #[inline]
+    pub fn face_towards(&mut self, target: &Point3<f32>, up: &Vector3<f32>) {
+        self.data_mut().face_towards(target, up)
+    }
+
     /// Appends a transformation to this node local transformation.
     #[inline]
     pub fn append_transformation(&mut self, t: &Isometry3<f32>) {
@@ -1137,9 +2813,312 @@ impl SceneNode {
         self.data_mut().prepend_to_local_rotation(r)
     }
 
-    /// Sets the local rotation of this node.
+    /// This node local rotation.
+    #[inline]
+    pub fn local_rotation(&self) -> UnitQuaternion<f32> {
+        self.data().local_rotation()
+    }
+
+    /// Sets the local rotation of this node, e.g., from the orientation given by a physics
+    /// engine or an IMU.
+    ///
+    /// The local translation and scale of the node are kept: its vertices are scaled, then
+    /// rotated by `r`, then translated.
     #[inline]
     pub fn set_local_rotation(&mut self, r: UnitQuaternion<f32>) {
         self.data_mut().set_local_rotation(r)
     }
+
+    /// Plays a keyframe animation on this node, restarting it at its end if `looping` is `true`.
+    ///
+    /// See `SceneNodeData::play_animation` for details.
+    #[inline]
+    pub fn play_animation(&mut self, track: Rc<TransformTrack>, looping: bool) {
+        self.data_mut().play_animation(track, looping)
+    }
+
+    /// Plays a keyframe animation on this node, and calls `callback` when its end is reached.
+    ///
+    /// See `SceneNodeData::play_animation_with_callback` for details.
+    #[inline]
+    pub fn play_animation_with_callback<F: FnMut() + 'static>(
+        &mut self,
+        track: Rc<TransformTrack>,
+        looping: bool,
+        callback: F,
+    ) {
+        self.data_mut()
+            .play_animation_with_callback(track, looping, callback)
+    }
+
+    /// Sets the render priority of the object of this node. Lower priorities are drawn first.
+    ///
+    /// See `SceneNodeData::set_render_priority` for details.
+    #[inline]
+    pub fn set_render_priority(&mut self, priority: i32) {
+        self.data_mut().set_render_priority(priority)
+    }
+
+    /// The render priority of the object of this node.
+    #[inline]
+    pub fn render_priority(&self) -> i32 {
+        self.data().render_priority()
+    }
+
+    /// Shows or hides the vertex normals of the object of this node, drawn at each frame as lines
+    /// of `length`.
+    ///
+    /// See `SceneNodeData::set_show_normals` for details.
+    #[inline]
+    pub fn set_show_normals(&mut self, enabled: bool, length: f32) {
+        self.data_mut().set_show_normals(enabled, length)
+    }
+
+    /// Stops the animation played on this node.
+    #[inline]
+    pub fn stop_animation(&mut self) {
+        self.data_mut().stop_animation()
+    }
+
+    /// Whether an animation is being played on this node.
+    #[inline]
+    pub fn is_animating(&self) -> bool {
+        self.data().is_animating()
+    }
+}
+
+// Writes the objects of a subtree in world coordinates to an OBJ file and its MTL file.
+struct ObjWriter {
+    obj: BufWriter<File>,
+    mtl: BufWriter<File>,
+    // The names of the materials already written, by color and opacity.
+    materials: HashMap<[u32; 4], String>,
+    // The number of elements written to the OBJ file, to offset the indices of the faces.
+    num_coords: usize,
+    num_uvs: usize,
+    num_normals: usize,
+    num_objects: usize,
+}
+
+impl ObjWriter {
+    // `transform` and `scale` are the world transformation and scale of `node`.
+    fn write_node(
+        &mut self,
+        node: &SceneNodeData,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+    ) -> io::Result<()> {
+        if !node.visible {
+            return Ok(());
+        }
+
+        if let Some(ref object) = node.object {
+            self.write_object(object, transform, scale)?;
+        }
+
+        for c in node.children.iter() {
+            let c = c.data();
+            let child_transform = transform * c.local_transform;
+            let child_scale = scale.component_mul(&c.local_scale);
+
+            self.write_node(&c, &child_transform, &child_scale)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_object(
+        &mut self,
+        object: &Object,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+    ) -> io::Result<()> {
+        let mesh = object.mesh().borrow();
+        let coords = mesh.coords().read().unwrap();
+        let normals = mesh.normals().read().unwrap();
+        let uvs = mesh.uvs().read().unwrap();
+
+        let (coords, faces) = match (coords.data(), mesh.faces_as_u32()) {
+            (Some(c), Some(f)) => (c, f),
+            _ => return Ok(()),
+        };
+        // The normals and texture coordinates are written only if there is one per vertex.
+        let normals = normals.data().as_ref().filter(|n| n.len() == coords.len());
+        let uvs = uvs.data().as_ref().filter(|u| u.len() == coords.len());
+
+        let data = object.data();
+        let material = self.material(data.color(), data.alpha())?;
+
+        writeln!(self.obj, "g object_{}", self.num_objects)?;
+        writeln!(self.obj, "usemtl {}", material)?;
+        self.num_objects += 1;
+
+        for pt in coords.iter() {
+            let pt = transform * Point3::from(pt.coords.component_mul(scale));
+            writeln!(self.obj, "v {} {} {}", pt.x, pt.y, pt.z)?;
+        }
+
+        for uv in uvs.iter().flat_map(|u| u.iter()) {
+            writeln!(self.obj, "vt {} {}", uv.x, uv.y)?;
+        }
+
+        for n in normals.iter().flat_map(|n| n.iter()) {
+            // The normals are scaled by the inverse of the scale to remain orthogonal to the
+            // faces.
+            let n = transform * n.component_div(scale).try_normalize(1.0e-6).unwrap_or(*n);
+            writeln!(self.obj, "vn {} {} {}", n.x, n.y, n.z)?;
+        }
+
+        // The indices of the OBJ format start at 1.
+        let vertex = |i: u32| {
+            let v = i as usize + self.num_coords + 1;
+            let t = i as usize + self.num_uvs + 1;
+            let n = i as usize + self.num_normals + 1;
+
+            match (uvs.is_some(), normals.is_some()) {
+                (true, true) => format!("{}/{}/{}", v, t, n),
+                (true, false) => format!("{}/{}", v, t),
+                (false, true) => format!("{}//{}", v, n),
+                (false, false) => format!("{}", v),
+            }
+        };
+        let mut lines = String::new();
+
+        for f in faces.iter() {
+            lines.push_str(&format!(
+                "f {} {} {}\n",
+                vertex(f.x),
+                vertex(f.y),
+                vertex(f.z)
+            ));
+        }
+
+        self.obj.write_all(lines.as_bytes())?;
+
+        self.num_coords += coords.len();
+        self.num_uvs += uvs.map_or(0, |u| u.len());
+        self.num_normals += normals.map_or(0, |n| n.len());
+
+        Ok(())
+    }
+
+    // The name of the material with the given color and opacity, written to the MTL file the
+    // first time it is used.
+    fn material(&mut self, color: &Point3<f32>, alpha: f32) -> io::Result<String> {
+        let key = [
+            color.x.to_bits(),
+            color.y.to_bits(),
+            color.z.to_bits(),
+            alpha.to_bits(),
+        ];
+
+        if let Some(name) = self.materials.get(&key) {
+            return Ok(name.clone());
+        }
+
+        let name = format!("material_{}", self.materials.len());
+        writeln!(self.mtl, "newmtl {}", name)?;
+        writeln!(self.mtl, "Kd {} {} {}", color.x, color.y, color.z)?;
+        writeln!(self.mtl, "d {}", alpha)?;
+        writeln!(self.mtl)?;
+
+        let _ = self.materials.insert(key, name.clone());
+
+        Ok(name)
+    }
+}
+
+// The rotation mapping the `z` axis to `dir` and the `y` axis as close as possible to `up`, or
+// `None` if `dir` is zero. Another up direction is used if `dir` is parallel to `up`.
+fn rotation_towards(dir: &Vector3<f32>, up: &Vector3<f32>) -> Option<UnitQuaternion<f32>> {
+    let norm = dir.norm();
+
+    if norm <= f32::EPSILON {
+        return None;
+    }
+
+    let dir = dir / norm;
+    let up = if dir.cross(up).norm_squared() > 1.0e-6 * up.norm_squared() {
+        *up
+    } else {
+        // The axis along which `dir` has its smallest component is never parallel to it.
+        let abs = dir.abs();
+
+        if abs.x <= abs.y && abs.x <= abs.z {
+            Vector3::x()
+        } else if abs.y <= abs.z {
+            Vector3::y()
+        } else {
+            Vector3::z()
+        }
+    };
+
+    Some(UnitQuaternion::face_towards(&dir, &up))
+}
+
+// Retrieves the mesh registered as `name` by the global mesh manager, building and registering
+// it first if needed.
+fn managed_mesh<F: FnOnce() -> TriMesh<f32>>(name: &str, build: F) -> Rc<RefCell<Mesh>> {
+    if let Some(mesh) = MeshManager::get_global_manager(|mm| mm.get(name)) {
+        return mesh;
+    }
+
+    let mesh = Rc::new(RefCell::new(Mesh::from_trimesh(build(), false)));
+    MeshManager::get_global_manager(|mm| mm.add(mesh.clone(), name));
+
+    mesh
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::camera::{ArcBall, Camera};
+    use crate::environment::Environment;
+    use crate::resource::{Material, Mesh};
+    use crate::scene::ObjectData;
+    use crate::window::open_test_window;
+    use na::{Isometry3, Point3, Translation3, Vector3};
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    // A material counting how many times it is asked to render an object, without drawing.
+    struct CountingMaterial(Rc<Cell<usize>>);
+
+    impl Material for CountingMaterial {
+        fn render(
+            &mut self,
+            _: usize,
+            _: &Isometry3<f32>,
+            _: &Vector3<f32>,
+            _: &mut dyn Camera,
+            _: &Environment,
+            _: &ObjectData,
+            _: &mut Mesh,
+        ) {
+            self.0.set(self.0.get() + 1)
+        }
+    }
+
+    #[test]
+    #[ignore = "requires a display and an OpenGL context"]
+    fn nodes_behind_the_camera_are_culled() {
+        let (_lock, mut window) = open_test_window();
+        let mut camera = ArcBall::new(Point3::new(0.0, 0.0, 10.0), Point3::origin());
+        let in_front = Rc::new(Cell::new(0));
+        let behind = Rc::new(Cell::new(0));
+
+        let mut cube = window.add_cube(1.0, 1.0, 1.0);
+        let material: Box<dyn Material> = Box::new(CountingMaterial(in_front.clone()));
+        cube.set_material(Rc::new(RefCell::new(material)));
+
+        let mut cube = window.add_cube(1.0, 1.0, 1.0);
+        cube.set_local_translation(Translation3::new(0.0, 0.0, 20.0));
+        let material: Box<dyn Material> = Box::new(CountingMaterial(behind.clone()));
+        cube.set_material(Rc::new(RefCell::new(material)));
+
+        window.set_frustum_culling(true);
+        let _ = window.render_to_image_with(Some(&mut camera), None, None);
+
+        assert!(in_front.get() > 0);
+        assert_eq!(behind.get(), 0);
+    }
 }
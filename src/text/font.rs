@@ -5,6 +5,7 @@ use std::path::Path;
 use std::rc::Rc;
 use std::sync::Once;
 
+use na::Vector2;
 use rusttype;
 
 #[path = "../error.rs"]
@@ -17,16 +18,20 @@ pub struct Font {
 
 impl Font {
     /// Loads a new ttf font from a file.
+    ///
+    /// Returns `None` if the file cannot be read or is not a valid font.
     pub fn new(path: &Path) -> Option<Rc<Font>> {
         let mut memory = Vec::new();
-        let mut file = File::open(path).unwrap();
-        let _ = file.read_to_end(&mut memory).unwrap();
+        let mut file = File::open(path).ok()?;
+        let _ = file.read_to_end(&mut memory).ok()?;
         Font::from_bytes(&memory)
     }
 
     /// Loads a new ttf font from the memory.
+    ///
+    /// Returns `None` if `memory` does not contain a valid font.
     pub fn from_bytes(memory: &[u8]) -> Option<Rc<Font>> {
-        let font = rusttype::Font::from_bytes(memory.to_vec()).unwrap();
+        let font = rusttype::Font::from_bytes(memory.to_vec()).ok()?;
         Some(Rc::new(Font { font }))
     }
 
@@ -52,6 +57,80 @@ impl Font {
         &self.font
     }
 
+    /// The size of the area covered by `text` when drawn with this font at the given scale.
+    ///
+    /// The width is the one of the widest line, and the height is the sum of the heights of all
+    /// the lines of `text`. This is expressed in the same unit as the positions given to
+    /// `TextRenderer::draw_text`.
+    pub fn text_size(&self, text: &str, scale: f32) -> Vector2<f32> {
+        let scale = rusttype::Scale::uniform(scale);
+        let vmetrics = self.font.v_metrics(scale);
+        let line_height = vmetrics.ascent - vmetrics.descent;
+        let mut size = Vector2::zeros();
+
+        for line in text.lines() {
+            size.x = self.line_width(line, scale).max(size.x);
+            size.y += line_height;
+        }
+
+        size
+    }
+
+    // The sum of the advances of the glyphs of a line, including kerning.
+    pub(crate) fn line_width(&self, line: &str, scale: rusttype::Scale) -> f32 {
+        self.font
+            .layout(line, scale, rusttype::point(0.0, 0.0))
+            .last()
+            .map(|g| g.position().x + g.unpositioned().h_metrics().advance_width)
+            .unwrap_or(0.0)
+    }
+
+    // Splits `text` into lines, breaking them between words so that they are not wider than
+    // `max_width`. Words wider than `max_width` are kept on their own line.
+    pub(crate) fn wrap_lines<'a>(
+        &self,
+        text: &'a str,
+        scale: rusttype::Scale,
+        max_width: Option<f32>,
+    ) -> Vec<&'a str> {
+        let mut res = Vec::new();
+
+        for line in text.lines() {
+            let max_width = match max_width {
+                Some(max_width) if self.line_width(line, scale) > max_width => max_width,
+                _ => {
+                    res.push(line);
+                    continue;
+                }
+            };
+
+            let mut start = None;
+            let mut end = 0;
+
+            for word in line.split_whitespace() {
+                let word_start = word.as_ptr() as usize - line.as_ptr() as usize;
+                let word_end = word_start + word.len();
+
+                match start {
+                    Some(line_start)
+                        if self.line_width(&line[line_start..word_end], scale) > max_width =>
+                    {
+                        res.push(&line[line_start..end]);
+                        start = Some(word_start);
+                    }
+                    Some(_) => {}
+                    None => start = Some(word_start),
+                }
+
+                end = word_end;
+            }
+
+            res.push(&line[start.unwrap_or(0)..end]);
+        }
+
+        res
+    }
+
     /// The unique identifier of the specified font instance.
     #[inline]
     pub fn uid(font: &Rc<Font>) -> usize {
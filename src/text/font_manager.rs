@@ -0,0 +1,88 @@
+//! A resource manager to load fonts.
+
+use crate::text::Font;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+
+thread_local!(static KEY_FONT_MANAGER: RefCell<FontManager> = RefCell::new(FontManager::new()));
+
+/// The font manager.
+///
+/// Upon construction, it contains:
+/// * the `default` font, which is also returned by `Font::default()`.
+///
+/// It keeps a cache of already-loaded fonts. Note that this is only a cache, nothing more.
+/// Thus, its usage is not required to load fonts.
+pub struct FontManager {
+    default_font: Rc<Font>,
+    fonts: HashMap<String, Rc<Font>>,
+}
+
+impl FontManager {
+    /// Creates a new font manager.
+    pub fn new() -> FontManager {
+        let default_font = Font::default();
+        let mut fonts = HashMap::new();
+        let _ = fonts.insert("default".to_string(), default_font.clone());
+
+        FontManager {
+            default_font,
+            fonts,
+        }
+    }
+
+    /// Mutably applies a function to the font manager.
+    pub fn get_global_manager<T, F: FnMut(&mut FontManager) -> T>(mut f: F) -> T {
+        KEY_FONT_MANAGER.with(|manager| f(&mut *manager.borrow_mut()))
+    }
+
+    /// Gets the default font, embedded into kiss3d.
+    pub fn get_default(&self) -> Rc<Font> {
+        self.default_font.clone()
+    }
+
+    /// Get a font with the specified name. Returns `None` if the font is not registered.
+    pub fn get(&mut self, name: &str) -> Option<Rc<Font>> {
+        self.fonts.get(name).cloned()
+    }
+
+    /// Adds a font with the specified name to this cache.
+    pub fn add(&mut self, font: Rc<Font>, name: &str) {
+        let _ = self.fonts.insert(name.to_string(), font);
+    }
+
+    /// Loads a ttf font from a file and registers it with the specified name.
+    ///
+    /// If a font with same name exists, nothing is loaded and the old font is returned.
+    pub fn load(&mut self, path: &Path, name: &str) -> Option<Rc<Font>> {
+        if let Some(font) = self.get(name) {
+            return Some(font);
+        }
+
+        let font = Font::new(path)?;
+        self.add(font.clone(), name);
+
+        Some(font)
+    }
+
+    /// Loads a ttf font from memory and registers it with the specified name.
+    ///
+    /// If a font with same name exists, nothing is loaded and the old font is returned.
+    pub fn load_from_bytes(&mut self, memory: &[u8], name: &str) -> Option<Rc<Font>> {
+        if let Some(font) = self.get(name) {
+            return Some(font);
+        }
+
+        let font = Font::from_bytes(memory)?;
+        self.add(font.clone(), name);
+
+        Some(font)
+    }
+
+    /// Removes a font from this cache.
+    pub fn remove(&mut self, name: &str) {
+        let _ = self.fonts.remove(name);
+    }
+}
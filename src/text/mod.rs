@@ -1,9 +1,11 @@
 //! Text rendering.
 
 pub use crate::text::font::Font;
+pub use crate::text::font_manager::FontManager;
 pub use crate::text::glyph::Glyph;
-pub use crate::text::renderer::TextRenderer;
+pub use crate::text::renderer::{TextAlignment, TextRenderer};
 
 mod font;
+mod font_manager;
 mod glyph;
 mod renderer;
@@ -14,12 +14,25 @@ use crate::text::Font;
 #[path = "../error.rs"]
 mod error;
 
+/// The horizontal alignment of the lines of a text.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextAlignment {
+    /// The lines start at the text position.
+    Left,
+    /// The lines are centered on the text position.
+    Center,
+    /// The lines end at the text position.
+    Right,
+}
+
 struct TextRenderContext {
     len: usize,
     scale: f32,
     color: Point3<f32>,
     pos: Point2<f32>,
     font: Rc<Font>,
+    alignment: TextAlignment,
+    max_width: Option<f32>,
 }
 
 /// A ttf text renderer.
@@ -127,6 +140,24 @@ impl TextRenderer {
         scale: f32,
         font: &Rc<Font>,
         color: &Point3<f32>,
+    ) {
+        self.draw_aligned_text(text, pos, scale, font, color, TextAlignment::Left, None)
+    }
+
+    /// Adds a piece of text to be drawn during the next frame, with its lines aligned
+    /// horizontally with respect to `pos.x`.
+    ///
+    /// If `max_width` is set, the lines wider than `max_width` are wrapped between words. The
+    /// text is not persistent between frames.
+    pub fn draw_aligned_text(
+        &mut self,
+        text: &str,
+        pos: &Point2<f32>,
+        scale: f32,
+        font: &Rc<Font>,
+        color: &Point3<f32>,
+        alignment: TextAlignment,
+        max_width: Option<f32>,
     ) {
         self.text.push_str(text);
         self.contexts.push(TextRenderContext {
@@ -135,6 +166,8 @@ impl TextRenderer {
             color: *color,
             pos: *pos,
             font: font.clone(),
+            alignment,
+            max_width,
         })
     }
 
@@ -184,9 +217,14 @@ impl TextRenderer {
             let font_uid = Font::uid(&context.font);
             let mut vshift = 0.0;
 
-            for line in text.lines() {
+            for line in context.font.wrap_lines(text, scale, context.max_width) {
+                let hshift = match context.alignment {
+                    TextAlignment::Left => 0.0,
+                    TextAlignment::Center => -context.font.line_width(line, scale) / 2.0,
+                    TextAlignment::Right => -context.font.line_width(line, scale),
+                };
                 let orig = rusttype::Point {
-                    x: context.pos.x,
+                    x: context.pos.x + hshift,
                     y: context.pos.y + vshift,
                 };
 
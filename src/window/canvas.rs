@@ -1,6 +1,8 @@
 use std::sync::mpsc::Sender;
 
 use crate::event::{Action, Key, MouseButton, WindowEvent};
+#[cfg(feature = "gamepad")]
+use crate::window::gamepads::Gamepads;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::window::GLCanvas as CanvasImpl;
 #[cfg(target_arch = "wasm32")]
@@ -40,6 +42,21 @@ impl NumSamples {
     }
 }
 
+impl From<u32> for NumSamples {
+    /// Converts a number of samples to the closest `NumSamples` that does not exceed it, or to
+    /// `NumSamples::Sixteen` if it is larger.
+    fn from(i: u32) -> NumSamples {
+        match i {
+            0 => NumSamples::Zero,
+            1 => NumSamples::One,
+            2..=3 => NumSamples::Two,
+            4..=7 => NumSamples::Four,
+            8..=15 => NumSamples::Eight,
+            _ => NumSamples::Sixteen,
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
 /// Canvas options.
 pub struct CanvasSetup {
@@ -49,9 +66,21 @@ pub struct CanvasSetup {
     pub samples: NumSamples,
 }
 
+impl Default for CanvasSetup {
+    /// Vsync enabled, without multisampling.
+    fn default() -> CanvasSetup {
+        CanvasSetup {
+            vsync: true,
+            samples: NumSamples::Zero,
+        }
+    }
+}
+
 /// An abstract structure representing a window for native applications, and a canvas for web applications.
 pub struct Canvas {
     canvas: CanvasImpl,
+    #[cfg(feature = "gamepad")]
+    gamepads: Option<Gamepads>,
 }
 
 impl Canvas {
@@ -63,9 +92,33 @@ impl Canvas {
         height: u32,
         canvas_setup: Option<CanvasSetup>,
         out_events: Sender<WindowEvent>,
+    ) -> Self {
+        Canvas::do_open(title, hide, width, height, canvas_setup, false, out_events)
+    }
+
+    // Opens a canvas whose framebuffer has an alpha channel composited with the desktop if
+    // `transparent` is set. See `WindowBuilder::transparent`.
+    pub(crate) fn do_open(
+        title: &str,
+        hide: bool,
+        width: u32,
+        height: u32,
+        canvas_setup: Option<CanvasSetup>,
+        transparent: bool,
+        out_events: Sender<WindowEvent>,
     ) -> Self {
         Canvas {
-            canvas: CanvasImpl::open(title, hide, width, height, canvas_setup, out_events),
+            #[cfg(feature = "gamepad")]
+            gamepads: Gamepads::new(out_events.clone()),
+            canvas: CanvasImpl::open(
+                title,
+                hide,
+                width,
+                height,
+                canvas_setup,
+                transparent,
+                out_events,
+            ),
         }
     }
 
@@ -76,7 +129,36 @@ impl Canvas {
 
     /// Poll all events tha occurred since the last call to this method.
     pub fn poll_events(&mut self) {
-        self.canvas.poll_events()
+        self.canvas.poll_events();
+
+        #[cfg(feature = "gamepad")]
+        {
+            if let Some(ref mut gamepads) = self.gamepads {
+                gamepads.poll_events()
+            }
+        }
+    }
+
+    /// The magnitude below which gamepad axis values are reported as zero.
+    ///
+    /// Returns `None` if gamepads are not supported on this platform.
+    #[cfg(feature = "gamepad")]
+    pub fn gamepad_deadzone(&self) -> Option<f32> {
+        self.gamepads.as_ref().map(|g| g.deadzone())
+    }
+
+    /// Sets the magnitude below which gamepad axis values are reported as zero. See
+    /// `Window::set_gamepad_deadzone` for details.
+    #[cfg(feature = "gamepad")]
+    pub fn set_gamepad_deadzone(&mut self, deadzone: f32) {
+        assert!(
+            deadzone >= 0.0 && deadzone < 1.0,
+            "The gamepad deadzone must be in [0, 1[."
+        );
+
+        if let Some(ref mut gamepads) = self.gamepads {
+            gamepads.set_deadzone(deadzone)
+        }
     }
 
     /// If double-buffering is supported, swap the buffers.
@@ -89,6 +171,22 @@ impl Canvas {
         self.canvas.size()
     }
 
+    /// Sets the size of the window, in physical pixels. See `Window::set_size` for details.
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        self.canvas.set_size(width, height)
+    }
+
+    /// The position of the top-left corner of the window on the desktop, if supported.
+    pub fn position(&self) -> Option<(i32, i32)> {
+        self.canvas.position()
+    }
+
+    /// Moves the top-left corner of the window on the desktop. See `Window::set_position` for
+    /// details.
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.canvas.set_position(x, y)
+    }
+
     /// The current position of the cursor, if known.
     ///
     /// This position may not be known if, e.g., the cursor has not been moved since the
@@ -117,6 +215,12 @@ impl Canvas {
         self.canvas.set_cursor_grab(grab);
     }
 
+    /// Enables or disables vertical synchronization, and returns whether the change was
+    /// applied. See `Window::set_vsync` for details.
+    pub fn set_vsync(&mut self, enabled: bool) -> bool {
+        self.canvas.set_vsync(enabled)
+    }
+
     pub fn set_cursor_position(&self, x: f64, y: f64) {
         self.canvas.set_cursor_position(x, y);
     }
@@ -125,6 +229,17 @@ impl Canvas {
         self.canvas.hide_cursor(hide);
     }
 
+    /// Switches the window to borderless fullscreen, or back to windowed mode. See
+    /// `Window::set_fullscreen` for details.
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.canvas.set_fullscreen(fullscreen)
+    }
+
+    /// Whether the window is fullscreen.
+    pub fn is_fullscreen(&self) -> bool {
+        self.canvas.is_fullscreen()
+    }
+
     /// Hide the window.
     pub fn hide(&mut self) {
         self.canvas.hide()
@@ -153,20 +268,27 @@ pub(crate) trait AbstractCanvas {
         width: u32,
         height: u32,
         window_setup: Option<CanvasSetup>,
+        transparent: bool,
         out_events: Sender<WindowEvent>,
     ) -> Self;
     fn render_loop(data: impl FnMut(f64) -> bool + 'static);
     fn poll_events(&mut self);
     fn swap_buffers(&mut self);
     fn size(&self) -> (u32, u32);
+    fn set_size(&mut self, width: u32, height: u32);
+    fn position(&self) -> Option<(i32, i32)>;
+    fn set_position(&mut self, x: i32, y: i32);
     fn cursor_pos(&self) -> Option<(f64, f64)>;
     fn scale_factor(&self) -> f64;
 
     fn set_title(&mut self, title: &str);
     fn set_icon(&mut self, icon: impl GenericImage<Pixel = impl Pixel<Subpixel = u8>>);
     fn set_cursor_grab(&self, grab: bool);
+    fn set_vsync(&mut self, enabled: bool) -> bool;
     fn set_cursor_position(&self, x: f64, y: f64);
     fn hide_cursor(&self, hide: bool);
+    fn set_fullscreen(&mut self, fullscreen: bool);
+    fn is_fullscreen(&self) -> bool;
     fn hide(&mut self);
     fn show(&mut self);
 
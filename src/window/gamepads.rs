@@ -0,0 +1,110 @@
+//! Gamepad events, polled with gilrs.
+
+use std::sync::mpsc::Sender;
+
+use crate::event::{Action, GamepadAxis, GamepadButton, WindowEvent};
+
+/// The gamepads connected to the computer.
+pub(crate) struct Gamepads {
+    gilrs: gilrs::Gilrs,
+    deadzone: f32,
+    out_events: Sender<WindowEvent>,
+}
+
+impl Gamepads {
+    /// Initializes the gamepad backend. Returns `None` if it is not supported on this platform.
+    pub fn new(out_events: Sender<WindowEvent>) -> Option<Gamepads> {
+        let gilrs = gilrs::Gilrs::new().ok()?;
+
+        Some(Gamepads {
+            gilrs,
+            deadzone: 0.1,
+            out_events,
+        })
+    }
+
+    pub fn deadzone(&self) -> f32 {
+        self.deadzone
+    }
+
+    pub fn set_deadzone(&mut self, deadzone: f32) {
+        self.deadzone = deadzone
+    }
+
+    /// Sends the gamepad events that occurred since the last call to this method.
+    pub fn poll_events(&mut self) {
+        use gilrs::EventType;
+
+        while let Some(gilrs::Event { id, event, .. }) = self.gilrs.next_event() {
+            let id = usize::from(id);
+            let event = match event {
+                EventType::Connected => WindowEvent::GamepadConnected(id),
+                EventType::Disconnected => WindowEvent::GamepadDisconnected(id),
+                EventType::ButtonPressed(button, _) => {
+                    WindowEvent::GamepadButton(id, translate_button(button), Action::Press)
+                }
+                EventType::ButtonReleased(button, _) => {
+                    WindowEvent::GamepadButton(id, translate_button(button), Action::Release)
+                }
+                EventType::AxisChanged(axis, value, _) => WindowEvent::GamepadAxis(
+                    id,
+                    translate_axis(axis),
+                    apply_deadzone(value, self.deadzone),
+                ),
+                _ => continue,
+            };
+
+            let _ = self.out_events.send(event);
+        }
+    }
+}
+
+// Zeroes the values smaller than the deadzone, and rescales the others to keep the full range.
+fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    let value = value.max(-1.0).min(1.0);
+
+    if value.abs() <= deadzone {
+        0.0
+    } else {
+        value.signum() * (value.abs() - deadzone) / (1.0 - deadzone)
+    }
+}
+
+fn translate_button(button: gilrs::Button) -> GamepadButton {
+    match button {
+        gilrs::Button::South => GamepadButton::South,
+        gilrs::Button::East => GamepadButton::East,
+        gilrs::Button::North => GamepadButton::North,
+        gilrs::Button::West => GamepadButton::West,
+        gilrs::Button::C => GamepadButton::C,
+        gilrs::Button::Z => GamepadButton::Z,
+        gilrs::Button::LeftTrigger => GamepadButton::LeftTrigger,
+        gilrs::Button::LeftTrigger2 => GamepadButton::LeftTrigger2,
+        gilrs::Button::RightTrigger => GamepadButton::RightTrigger,
+        gilrs::Button::RightTrigger2 => GamepadButton::RightTrigger2,
+        gilrs::Button::Select => GamepadButton::Select,
+        gilrs::Button::Start => GamepadButton::Start,
+        gilrs::Button::Mode => GamepadButton::Mode,
+        gilrs::Button::LeftThumb => GamepadButton::LeftThumb,
+        gilrs::Button::RightThumb => GamepadButton::RightThumb,
+        gilrs::Button::DPadUp => GamepadButton::DPadUp,
+        gilrs::Button::DPadDown => GamepadButton::DPadDown,
+        gilrs::Button::DPadLeft => GamepadButton::DPadLeft,
+        gilrs::Button::DPadRight => GamepadButton::DPadRight,
+        gilrs::Button::Unknown => GamepadButton::Unknown,
+    }
+}
+
+fn translate_axis(axis: gilrs::Axis) -> GamepadAxis {
+    match axis {
+        gilrs::Axis::LeftStickX => GamepadAxis::LeftStickX,
+        gilrs::Axis::LeftStickY => GamepadAxis::LeftStickY,
+        gilrs::Axis::LeftZ => GamepadAxis::LeftZ,
+        gilrs::Axis::RightStickX => GamepadAxis::RightStickX,
+        gilrs::Axis::RightStickY => GamepadAxis::RightStickY,
+        gilrs::Axis::RightZ => GamepadAxis::RightZ,
+        gilrs::Axis::DPadX => GamepadAxis::DPadX,
+        gilrs::Axis::DPadY => GamepadAxis::DPadY,
+        gilrs::Axis::Unknown => GamepadAxis::Unknown,
+    }
+}
@@ -1,8 +1,9 @@
+use std::cell::Cell;
 use std::sync::mpsc::Sender;
 
 use crate::context::Context;
 use crate::event::{Action, Key, Modifiers, MouseButton, TouchAction, WindowEvent};
-use crate::window::canvas::{CanvasSetup, NumSamples};
+use crate::window::canvas::CanvasSetup;
 use crate::window::AbstractCanvas;
 use glutin::{
     self,
@@ -20,6 +21,7 @@ pub struct GLCanvas {
     window: WindowedContext<PossiblyCurrent>,
     events: EventLoop<()>,
     cursor_pos: Option<(f64, f64)>,
+    cursor_grabbed: Cell<bool>,
     key_states: [Action; Key::Unknown as usize + 1],
     button_states: [Action; MouseButton::Button8 as usize + 1],
     out_events: Sender<WindowEvent>,
@@ -33,6 +35,7 @@ impl AbstractCanvas for GLCanvas {
         width: u32,
         height: u32,
         canvas_setup: Option<CanvasSetup>,
+        transparent: bool,
         out_events: Sender<WindowEvent>,
     ) -> Self {
         #[cfg(any(
@@ -61,23 +64,42 @@ impl AbstractCanvas for GLCanvas {
         )))]
         let events = EventLoop::new();
 
+        let canvas_setup = canvas_setup.unwrap_or_default();
         let window = WindowBuilder::new()
             .with_title(title)
             .with_inner_size(LogicalSize::new(width as f64, height as f64))
-            .with_visible(!hide);
-        let canvas_setup = canvas_setup.unwrap_or(CanvasSetup {
-            vsync: true,
-            samples: NumSamples::Zero,
-        });
-        let window = ContextBuilder::new()
-            .with_vsync(canvas_setup.vsync)
-            .with_multisampling(canvas_setup.samples as u16)
-            .with_gl(GlRequest::GlThenGles {
-                opengl_version: (3, 2),
-                opengles_version: (2, 0),
-            })
-            .build_windowed(window, &events)
-            .unwrap();
+            .with_visible(!hide)
+            .with_transparent(transparent);
+        let mut samples = canvas_setup.samples as u16;
+        let window = loop {
+            let mut context = ContextBuilder::new()
+                .with_vsync(canvas_setup.vsync)
+                .with_multisampling(samples)
+                .with_gl(GlRequest::GlThenGles {
+                    opengl_version: (3, 2),
+                    opengles_version: (2, 0),
+                });
+
+            // The default pixel format is kept for opaque windows.
+            if transparent {
+                context = context.with_pixel_format(24, 8);
+            }
+
+            let context = context.build_windowed(window.clone(), &events);
+
+            match context {
+                Ok(context) => break context,
+                // Retry with fewer samples if the multisampling request cannot be satisfied.
+                Err(e) if samples > 1 => {
+                    samples /= 2;
+                    println!(
+                        "Warning: failed to create a multisampled context ({}), retrying with {} samples.",
+                        e, samples
+                    );
+                }
+                Err(e) => panic!("Failed to create the OpenGL context: {}", e),
+            }
+        };
         let window = unsafe { window.make_current().unwrap() };
         Context::init(|| unsafe {
             glow::Context::from_loader_function(|name| window.get_proc_address(name) as *const _)
@@ -91,6 +113,7 @@ impl AbstractCanvas for GLCanvas {
             window,
             events,
             cursor_pos: None,
+            cursor_grabbed: Cell::new(false),
             key_states: [Action::Release; Key::Unknown as usize + 1],
             button_states: [Action::Release; MouseButton::Button8 as usize + 1],
             out_events,
@@ -111,6 +134,7 @@ impl AbstractCanvas for GLCanvas {
         let button_states = &mut self.button_states;
         let key_states = &mut self.key_states;
         let cursor_pos = &mut self.cursor_pos;
+        let cursor_grabbed = self.cursor_grabbed.get();
 
         self.events.run_return(|event, _, control_flow| {
             use glutin::event::Event;
@@ -120,11 +144,23 @@ impl AbstractCanvas for GLCanvas {
                     glutin::event::WindowEvent::CloseRequested => {
                         let _ = out_events.send(WindowEvent::Close);
                     }
+                    glutin::event::WindowEvent::Focused(focused) => {
+                        // The releases happening while the window is unfocused are not reported.
+                        if !focused {
+                            key_states.iter_mut().for_each(|s| *s = Action::Release);
+                            button_states.iter_mut().for_each(|s| *s = Action::Release);
+                        }
+
+                        let _ = out_events.send(WindowEvent::Focus(focused));
+                    }
                     glutin::event::WindowEvent::Resized(physical_size) => {
                         window.resize(physical_size);
                         let fb_size: (u32, u32) = physical_size.into();
                         let _ = out_events.send(WindowEvent::FramebufferSize(fb_size.0, fb_size.1));
                     }
+                    glutin::event::WindowEvent::Moved(position) => {
+                        let _ = out_events.send(WindowEvent::Pos(position.x, position.y));
+                    }
                     glutin::event::WindowEvent::CursorMoved {
                         position,
                         modifiers,
@@ -186,8 +222,23 @@ impl AbstractCanvas for GLCanvas {
                     glutin::event::WindowEvent::ReceivedCharacter(c) => {
                         let _ = out_events.send(WindowEvent::Char(c));
                     }
+                    glutin::event::WindowEvent::DroppedFile(path) => {
+                        let _ = out_events.send(WindowEvent::FileDropped(path));
+                    }
+                    glutin::event::WindowEvent::HoveredFile(path) => {
+                        let _ = out_events.send(WindowEvent::FileHovered(path));
+                    }
+                    glutin::event::WindowEvent::HoveredFileCancelled => {
+                        let _ = out_events.send(WindowEvent::FileHoverCancelled);
+                    }
                     _ => {}
                 },
+                Event::DeviceEvent {
+                    event: glutin::event::DeviceEvent::MouseMotion { delta },
+                    ..
+                } if cursor_grabbed => {
+                    let _ = out_events.send(WindowEvent::MouseMotion(delta.0, delta.1));
+                }
                 Event::RedrawEventsCleared => {
                     *control_flow = ControlFlow::Exit;
                 }
@@ -204,6 +255,24 @@ impl AbstractCanvas for GLCanvas {
         self.window.window().inner_size().into()
     }
 
+    fn set_size(&mut self, width: u32, height: u32) {
+        // The resulting resize is reported as a `Resized` event.
+        self.window
+            .window()
+            .set_inner_size(glutin::dpi::PhysicalSize::new(width, height))
+    }
+
+    fn position(&self) -> Option<(i32, i32)> {
+        let position = self.window.window().outer_position().ok()?;
+        Some((position.x, position.y))
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.window
+            .window()
+            .set_outer_position(glutin::dpi::PhysicalPosition::new(x, y))
+    }
+
     fn cursor_pos(&self) -> Option<(f64, f64)> {
         self.cursor_pos
     }
@@ -227,7 +296,15 @@ impl AbstractCanvas for GLCanvas {
     }
 
     fn set_cursor_grab(&self, grab: bool) {
-        let _ = self.window.window().set_cursor_grab(grab);
+        if self.window.window().set_cursor_grab(grab).is_ok() {
+            self.cursor_grabbed.set(grab);
+        }
+    }
+
+    fn set_vsync(&mut self, enabled: bool) -> bool {
+        // glutin only sets the swap interval when creating the context, so it is changed with the
+        // platform-specific extensions.
+        unsafe { set_swap_interval(&self.window, enabled as i32) }
     }
 
     fn set_cursor_position(&self, x: f64, y: f64) {
@@ -241,6 +318,24 @@ impl AbstractCanvas for GLCanvas {
         self.window.window().set_cursor_visible(!hide)
     }
 
+    fn set_fullscreen(&mut self, fullscreen: bool) {
+        let window = self.window.window();
+        let fullscreen = if fullscreen {
+            Some(glutin::window::Fullscreen::Borderless(
+                window.current_monitor(),
+            ))
+        } else {
+            None
+        };
+
+        // The resulting resize is reported as a `Resized` event.
+        window.set_fullscreen(fullscreen)
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        self.window.window().fullscreen().is_some()
+    }
+
     fn hide(&mut self) {
         self.window.window().set_visible(false)
     }
@@ -257,6 +352,120 @@ impl AbstractCanvas for GLCanvas {
     }
 }
 
+// The extension functions are only called if their extension is listed by the driver, since
+// `glXGetProcAddress` returns non-null pointers even for the functions it does not support.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+unsafe fn set_swap_interval(window: &WindowedContext<PossiblyCurrent>, interval: i32) -> bool {
+    use glutin::platform::unix::WindowExtUnix;
+    use std::ffi::CStr;
+    use std::mem::transmute;
+    use std::os::raw::{c_char, c_int, c_uint, c_ulong, c_void};
+
+    // There is no GLX context on Wayland.
+    let (display, screen) = match (
+        window.window().xlib_display(),
+        window.window().xlib_screen_id(),
+    ) {
+        (Some(display), Some(screen)) => (display, screen),
+        _ => return false,
+    };
+    let function = |name| Some(window.get_proc_address(name)).filter(|ptr| !ptr.is_null());
+
+    let extensions = match function("glXQueryExtensionsString") {
+        Some(query) => {
+            let query: extern "C" fn(*mut c_void, c_int) -> *const c_char = transmute(query);
+            query(display, screen)
+        }
+        None => return false,
+    };
+
+    if extensions.is_null() {
+        return false;
+    }
+
+    let extensions = CStr::from_ptr(extensions).to_string_lossy();
+    let supported = |name| extensions.split_whitespace().any(|e| e == name);
+
+    if supported("GLX_EXT_swap_control") {
+        if let (Some(drawable), Some(swap_interval)) = (
+            function("glXGetCurrentDrawable"),
+            function("glXSwapIntervalEXT"),
+        ) {
+            let drawable: extern "C" fn() -> c_ulong = transmute(drawable);
+            let swap_interval: extern "C" fn(*mut c_void, c_ulong, c_int) =
+                transmute(swap_interval);
+            let drawable = drawable();
+
+            if drawable != 0 {
+                swap_interval(display, drawable, interval);
+                return true;
+            }
+        }
+    }
+
+    if supported("GLX_MESA_swap_control") {
+        if let Some(swap_interval) = function("glXSwapIntervalMESA") {
+            let swap_interval: extern "C" fn(c_uint) -> c_int = transmute(swap_interval);
+            return swap_interval(interval as c_uint) == 0;
+        }
+    }
+
+    false
+}
+
+#[cfg(windows)]
+unsafe fn set_swap_interval(window: &WindowedContext<PossiblyCurrent>, interval: i32) -> bool {
+    use std::ffi::CStr;
+    use std::mem::transmute;
+    use std::os::raw::c_char;
+
+    let function = |name| Some(window.get_proc_address(name)).filter(|ptr| !ptr.is_null());
+
+    let extensions = match function("wglGetExtensionsStringEXT") {
+        Some(query) => {
+            let query: extern "system" fn() -> *const c_char = transmute(query);
+            query()
+        }
+        None => return false,
+    };
+
+    if extensions.is_null()
+        || !CStr::from_ptr(extensions)
+            .to_string_lossy()
+            .split_whitespace()
+            .any(|e| e == "WGL_EXT_swap_control")
+    {
+        return false;
+    }
+
+    match function("wglSwapIntervalEXT") {
+        Some(swap_interval) => {
+            let swap_interval: extern "system" fn(i32) -> i32 = transmute(swap_interval);
+            swap_interval(interval) != 0
+        }
+        None => false,
+    }
+}
+
+// EGL and CGL contexts do not expose the swap interval after their creation.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    windows
+)))]
+unsafe fn set_swap_interval(_: &WindowedContext<PossiblyCurrent>, _: i32) -> bool {
+    false
+}
+
 fn translate_action(action: glutin::event::ElementState) -> Action {
     match action {
         glutin::event::ElementState::Pressed => Action::Press,
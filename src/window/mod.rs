@@ -4,15 +4,23 @@ pub(crate) use self::canvas::AbstractCanvas;
 pub use self::canvas::{Canvas, CanvasSetup, NumSamples};
 #[cfg(not(target_arch = "wasm32"))]
 pub use self::gl_canvas::GLCanvas;
-pub use self::state::State;
+pub use self::state::{State, MAX_FRAME_DT};
 #[cfg(target_arch = "wasm32")]
 pub use self::webgl_canvas::WebGLCanvas;
-pub use self::window::Window;
+#[cfg(test)]
+pub(crate) use self::window::open_test_window;
+pub use self::window::{Grid, Window};
+pub use self::window_builder::WindowBuilder;
 
 mod canvas;
+#[cfg(feature = "gamepad")]
+mod gamepads;
 #[cfg(not(target_arch = "wasm32"))]
 mod gl_canvas;
+#[cfg(not(target_arch = "wasm32"))]
+mod recorder;
 mod state;
 #[cfg(target_arch = "wasm32")]
 mod webgl_canvas;
 mod window;
+mod window_builder;
@@ -0,0 +1,125 @@
+//! Recording of the rendered frames as numbered PNG files.
+
+use image::{imageops, ImageBuffer, ImageFormat, Rgb};
+use instant::Instant;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+/// The name of the file listing the recorded frames and their durations.
+const MANIFEST_NAME: &'static str = "frames.txt";
+
+// The pixels of a frame as read from the framebuffer, i.e., from the bottom to the top, with its
+// width, height, and time since the start of the recording in seconds.
+struct Frame {
+    pixels: Vec<u8>,
+    width: u32,
+    height: u32,
+    time: f64,
+}
+
+/// Captures one frame every `every_n_frames` frames, and writes them from a background thread
+/// so that the render loop only pays for reading the framebuffer.
+pub(crate) struct Recorder {
+    every_n_frames: usize,
+    frame: usize,
+    start: Instant,
+    sender: Option<Sender<Frame>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl Recorder {
+    /// Starts recording into `dir`, which is created if it does not exist.
+    pub fn start(dir: &Path, every_n_frames: usize) -> io::Result<Recorder> {
+        assert!(
+            every_n_frames > 0,
+            "At least one frame out of `every_n_frames` must be recorded."
+        );
+
+        fs::create_dir_all(dir)?;
+        let manifest = File::create(dir.join(MANIFEST_NAME))?;
+        let (sender, receiver) = mpsc::channel();
+        let dir = dir.to_path_buf();
+        let writer = thread::spawn(move || write_frames(dir, manifest, receiver));
+
+        Ok(Recorder {
+            every_n_frames,
+            frame: 0,
+            start: Instant::now(),
+            sender: Some(sender),
+            writer: Some(writer),
+        })
+    }
+
+    /// Advances to the next rendered frame, and returns whether it must be captured.
+    pub fn next_frame(&mut self) -> bool {
+        let capture = self.frame % self.every_n_frames == 0;
+        self.frame += 1;
+        capture
+    }
+
+    /// Queues the pixels of a frame, as read by `Window::snap`, to be written.
+    pub fn record(&mut self, pixels: Vec<u8>, width: u32, height: u32) {
+        let frame = Frame {
+            pixels,
+            width,
+            height,
+            time: self.start.elapsed().as_secs_f64(),
+        };
+
+        if let Some(ref sender) = self.sender {
+            let _ = sender.send(frame);
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        // Closing the channel ends the writer thread once all the queued frames are written.
+        self.sender = None;
+
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+// Writes the frames to `dir`, and lists them with their durations in the manifest, using the
+// format of the concat demuxer of ffmpeg.
+fn write_frames(dir: PathBuf, manifest: File, receiver: Receiver<Frame>) {
+    let mut manifest = BufWriter::new(manifest);
+    let mut last: Option<(String, f64)> = None;
+
+    for (i, frame) in receiver.iter().enumerate() {
+        let name = format!("frame_{:06}.png", i);
+
+        match ImageBuffer::<Rgb<u8>, _>::from_vec(frame.width, frame.height, frame.pixels) {
+            Some(img) => {
+                if let Err(e) = imageops::flip_vertical(&img)
+                    .save_with_format(dir.join(&name), ImageFormat::Png)
+                {
+                    println!("Warning: could not save the recorded frame {}: {}", name, e);
+                }
+            }
+            None => println!("Warning: the recorded frame {} is incomplete.", name),
+        }
+
+        if let Some((last_name, last_time)) = last.take() {
+            let _ = writeln!(manifest, "file '{}'", last_name);
+            let _ = writeln!(manifest, "duration {:.6}", frame.time - last_time);
+        }
+
+        last = Some((name, frame.time));
+    }
+
+    // The duration of the last frame is unknown. It is listed twice since ffmpeg otherwise
+    // ignores it.
+    if let Some((last_name, _)) = last {
+        let _ = writeln!(manifest, "file '{}'", last_name);
+        let _ = writeln!(manifest, "file '{}'", last_name);
+    }
+
+    let _ = manifest.flush();
+}
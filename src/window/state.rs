@@ -3,6 +3,10 @@ use crate::planar_camera::PlanarCamera;
 use crate::post_processing::PostProcessingEffect;
 use crate::renderer::Renderer;
 use crate::window::Window;
+use instant::Instant;
+
+/// The largest time step, in seconds, given to the callback of `Window::render_loop_with_dt`.
+pub const MAX_FRAME_DT: f32 = 0.1;
 
 /// Trait implemented by objects describing state of an application.
 ///
@@ -46,3 +50,28 @@ pub trait State: 'static {
 impl State for () {
     fn step(&mut self, _: &mut Window) {}
 }
+
+/// The state of `Window::render_loop_with_dt`, which calls a closure with the frame duration.
+pub(crate) struct ClosureState<F> {
+    callback: F,
+    last_step: Instant,
+}
+
+impl<F> ClosureState<F> {
+    pub fn new(callback: F) -> Self {
+        ClosureState {
+            callback,
+            last_step: Instant::now(),
+        }
+    }
+}
+
+impl<F: FnMut(&mut Window, f32) + 'static> State for ClosureState<F> {
+    fn step(&mut self, window: &mut Window) {
+        let now = Instant::now();
+        let dt = now.duration_since(self.last_step).as_secs_f32();
+        self.last_step = now;
+
+        (self.callback)(window, dt.min(MAX_FRAME_DT))
+    }
+}
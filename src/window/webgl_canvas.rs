@@ -106,7 +106,9 @@ impl AbstractCanvas for WebGLCanvas {
         _: bool,
         _: u32,
         _: u32,
-        _setup: Option<CanvasSetup>,
+        setup: Option<CanvasSetup>,
+        // The canvas is always composited with the page.
+        _: bool,
         out_events: Sender<WindowEvent>,
     ) -> Self {
         fn get_scale_factor() -> f64 {
@@ -122,13 +124,21 @@ impl AbstractCanvas for WebGLCanvas {
             .dyn_into::<HtmlCanvasElement>()
             .expect("Canvas element is not an actual canvas.");
 
+        // The browser picks the number of samples, so we can only enable or disable multisampling.
+        let mut attributes = web_sys::WebGlContextAttributes::new();
+        let _ = attributes.antialias(setup.map(|s| s.samples as u32 > 1).unwrap_or(false));
+
         Context::init(|| {
             let webgl_context = canvas
-                .get_context("webgl")
+                .get_context_with_context_options("webgl", &attributes)
                 .unwrap()
                 .unwrap()
                 .dyn_into::<web_sys::WebGlRenderingContext>()
                 .unwrap();
+            // Required by meshes with 32-bits indices.
+            let _ = webgl_context.get_extension("OES_element_index_uint");
+            // Required by the normal maps of the `PbrMaterial`.
+            let _ = webgl_context.get_extension("OES_standard_derivatives");
             glow::Context::from_webgl1_context(webgl_context)
         });
 
@@ -271,6 +281,25 @@ impl AbstractCanvas for WebGLCanvas {
                 }
             }
             let scale_factor = edata.scale_factor;
+
+            // The pointer is locked by `set_cursor_grab`: only its displacement is meaningful.
+            let pointer_locked = web_sys::window()
+                .and_then(|w| w.document())
+                .and_then(|d| d.pointer_lock_element())
+                .map_or(false, |element| {
+                    let element: &JsValue = element.as_ref();
+                    let canvas: &JsValue = edata.canvas.as_ref();
+                    element == canvas
+                });
+
+            if pointer_locked {
+                let _ = edata.pending_events.push(WindowEvent::MouseMotion(
+                    e.movement_x() as f64 * scale_factor,
+                    e.movement_y() as f64 * scale_factor,
+                ));
+                return;
+            }
+
             let bounding_client_rect = edata.canvas.get_bounding_client_rect();
             let x = (e.client_x() as f64 - bounding_client_rect.x()) * scale_factor;
             let y = (e.client_y() as f64 - bounding_client_rect.y()) * scale_factor;
@@ -471,6 +500,27 @@ impl AbstractCanvas for WebGLCanvas {
         )
     }
 
+    fn set_size(&mut self, width: u32, height: u32) {
+        let mut data = self.data.borrow_mut();
+        let scale_factor = data.scale_factor;
+        let style = data.canvas.style();
+        let _ = style.set_property("width", &format!("{}px", width as f64 / scale_factor));
+        let _ = style.set_property("height", &format!("{}px", height as f64 / scale_factor));
+        data.canvas.set_width(width);
+        data.canvas.set_height(height);
+        data.pending_events
+            .push(WindowEvent::FramebufferSize(width, height));
+    }
+
+    fn position(&self) -> Option<(i32, i32)> {
+        // Not supported.
+        None
+    }
+
+    fn set_position(&mut self, _: i32, _: i32) {
+        // Not supported.
+    }
+
     fn cursor_pos(&self) -> Option<(f64, f64)> {
         self.data.borrow().cursor_pos
     }
@@ -483,16 +533,49 @@ impl AbstractCanvas for WebGLCanvas {
         // Not supported.
     }
 
-    fn set_cursor_grab(&self, _: bool) {
-        // Not supported.
+    fn set_cursor_grab(&self, grab: bool) {
+        let data = self.data.borrow();
+
+        if grab {
+            data.canvas.request_pointer_lock();
+        } else if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+            document.exit_pointer_lock();
+        }
+    }
+
+    fn set_vsync(&mut self, _: bool) -> bool {
+        // Not supported: the browser controls the frame presentation.
+        false
     }
 
     fn set_cursor_position(&self, _: f64, _: f64) {
         // Not supported.
     }
 
-    fn hide_cursor(&self, _: bool) {
-        // Not supported.
+    fn hide_cursor(&self, hide: bool) {
+        let cursor = if hide { "none" } else { "" };
+        let _ = self
+            .data
+            .borrow()
+            .canvas
+            .style()
+            .set_property("cursor", cursor);
+    }
+
+    fn set_fullscreen(&mut self, fullscreen: bool) {
+        // The resulting resize is reported by the `resize` listener of the window.
+        if fullscreen {
+            let _ = self.data.borrow().canvas.request_fullscreen();
+        } else if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+            document.exit_fullscreen();
+        }
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        web_sys::window()
+            .and_then(|w| w.document())
+            .and_then(|d| d.fullscreen_element())
+            .is_some()
     }
 
     fn hide(&mut self) {
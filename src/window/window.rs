@@ -3,7 +3,11 @@
  * FIXME: this file is too big. Some heavy refactoring need to be done here.
  */
 use std::cell::RefCell;
+use std::collections::VecDeque;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io;
 use std::iter::repeat;
+use std::mem;
 use std::path::Path;
 use std::rc::Rc;
 use std::sync::mpsc::{self, Receiver};
@@ -11,35 +15,66 @@ use std::thread;
 use std::time::Duration;
 
 use instant::Instant;
-use na::{Point2, Point3, Vector2, Vector3};
+use na::{Isometry3, Point2, Point3, Point4, Vector2, Vector3, Vector4};
 
+use crate::builtin::DepthMaterial;
 use crate::camera::{ArcBall, Camera};
 use crate::context::Context;
-use crate::event::{Action, EventManager, Key, WindowEvent};
+use crate::environment::{Environment, Fog};
+use crate::event::{Action, EventManager, Key, MouseButton, WindowEvent};
 use crate::light::Light;
 use crate::planar_camera::{FixedView, PlanarCamera};
 use crate::planar_line_renderer::PlanarLineRenderer;
 use crate::post_processing::PostProcessingEffect;
 #[cfg(feature = "conrod")]
 use crate::renderer::ConrodRenderer;
-use crate::renderer::{LineRenderer, PointRenderer, Renderer};
+use crate::renderer::{
+    Background, BackgroundRenderer, LineRenderer, PointRenderer, PointShape, Renderer,
+    SkyboxRenderer,
+};
 use crate::resource::{
-    FramebufferManager, Mesh, PlanarMesh, RenderTarget, Texture, TextureManager,
+    FramebufferManager, Mesh, PlanarMesh, RenderTarget, ShadowMap, Texture, TextureManager,
 };
-use crate::scene::{PlanarSceneNode, SceneNode};
-use crate::text::{Font, TextRenderer};
+use crate::scene::{Frustum, PlanarSceneNode, SceneNode};
+use crate::text::{Font, TextAlignment, TextRenderer};
 use crate::window::canvas::CanvasSetup;
-use crate::window::{Canvas, State};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::window::recorder::Recorder;
+use crate::window::state::ClosureState;
+use crate::window::{Canvas, State, WindowBuilder, MAX_FRAME_DT};
 use image::imageops;
 use image::{GenericImage, Pixel};
-use image::{ImageBuffer, Rgb};
+use image::{ImageBuffer, ImageFormat, ImageResult, Rgb, Rgba};
 use ncollide3d::procedural::TriMesh;
 
 #[cfg(feature = "conrod")]
 use std::collections::HashMap;
 
-static DEFAULT_WIDTH: u32 = 800u32;
-static DEFAULT_HEIGHT: u32 = 600u32;
+pub(crate) static DEFAULT_WIDTH: u32 = 800u32;
+pub(crate) static DEFAULT_HEIGHT: u32 = 600u32;
+// The number of frames over which the frame time reported by `Window::fps` is averaged.
+static FPS_AVERAGED_FRAMES: usize = 30;
+
+/// A grid drawn on the ground, i.e., the `XZ` plane, by `Window::set_ground_grid`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Grid {
+    /// The half-size of the grid: it covers `[-extent, extent]` along the `X` and `Z` axes.
+    pub extent: f32,
+    /// The distance between two parallel lines of the grid.
+    pub spacing: f32,
+    /// The color of the grid lines.
+    pub color: Point3<f32>,
+}
+
+// A text drawn at the projection of a 3D point.
+struct AnchoredText {
+    text: String,
+    world_pos: Point3<f32>,
+    offset: Vector2<f32>,
+    scale: f32,
+    font: Rc<Font>,
+    color: Point3<f32>,
+}
 
 #[cfg(feature = "conrod")]
 struct ConrodContext {
@@ -66,18 +101,35 @@ pub struct Window {
     events: Rc<Receiver<WindowEvent>>,
     unhandled_events: Rc<RefCell<Vec<WindowEvent>>>,
     max_dur_per_frame: Option<Duration>,
+    vsync: bool,
+    frustum_culling: bool,
     scene: SceneNode,
     scene2: PlanarSceneNode,
-    light_mode: Light, // FIXME: move that to the scene graph
-    background: Vector3<f32>,
+    environment: Environment, // FIXME: move that to the scene graph
+    background: Vector4<f32>,
+    background_renderer: Option<BackgroundRenderer>,
+    skybox: Option<SkyboxRenderer>,
+    shadow_material: Option<DepthMaterial>,
     line_renderer: LineRenderer,
     planar_line_renderer: PlanarLineRenderer,
     pub point_renderer: PointRenderer,
     text_renderer: TextRenderer,
+    anchored_texts: Vec<AnchoredText>,
     framebuffer_manager: FramebufferManager,
     post_process_render_target: RenderTarget,
+    post_process_swap_render_target: Option<RenderTarget>,
+    post_processing_effects: Vec<Box<dyn PostProcessingEffect>>,
+    offscreen_render_target: Option<RenderTarget>,
+    samples: u32,
     #[cfg(not(target_arch = "wasm32"))]
     curr_time: Instant,
+    #[cfg(not(target_arch = "wasm32"))]
+    recorder: Option<Recorder>,
+    frame_times: VecDeque<Duration>,
+    last_frame_end: Option<Instant>,
+    last_animation_step: Option<Instant>,
+    axes_length: Option<f32>,
+    ground_grid: Option<Grid>,
     planar_camera: Rc<RefCell<FixedView>>,
     camera: Rc<RefCell<ArcBall>>,
     should_close: bool,
@@ -112,15 +164,92 @@ impl Window {
         Vector2::new(w, h)
     }
 
+    /// Resizes the window, in physical pixels.
+    ///
+    /// The new size is reported by a `WindowEvent::FramebufferSize` event, like any other resize,
+    /// so the viewport, the render targets and the cameras are updated accordingly. On web
+    /// platforms, this sets the size of the canvas element.
+    #[inline]
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        self.canvas.set_size(width, height)
+    }
+
+    /// The position of the top-left corner of the window on the desktop, in physical pixels.
+    ///
+    /// Returns `None` if it is not known, e.g., on web platforms.
+    #[inline]
+    pub fn position(&self) -> Option<(i32, i32)> {
+        self.canvas.position()
+    }
+
+    /// Moves the top-left corner of the window to the given position on the desktop, in
+    /// physical pixels. Does nothing on web platforms.
+    #[inline]
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.canvas.set_position(x, y)
+    }
+
     /// Sets the maximum number of frames per second. Cannot be 0. `None` means there is no limit.
+    ///
+    /// The time spent rendering each frame is taken into account so the effective framerate is
+    /// close to `fps`. This has no effect on wasm where the browser controls the framerate.
     #[inline]
     pub fn set_framerate_limit(&mut self, fps: Option<u64>) {
         self.max_dur_per_frame = fps.map(|f| {
             assert!(f != 0);
-            Duration::from_millis(1000 / f)
+            Duration::from_secs_f64(1.0 / f as f64)
         })
     }
 
+    /// Enables or disables vertical synchronization, and returns whether the change was applied.
+    ///
+    /// The swap interval can only be changed after the window creation through the
+    /// `WGL_EXT_swap_control` extension on Windows, and the `GLX_EXT_swap_control` or
+    /// `GLX_MESA_swap_control` extensions on X11. Nothing is done, and `false` is returned, if
+    /// they are not supported by the driver, or on other platforms (Wayland, macOS, and wasm where
+    /// the browser controls the frame presentation). Use `WindowBuilder::vsync` to choose vsync
+    /// when creating the window instead, which is supported everywhere.
+    pub fn set_vsync(&mut self, enabled: bool) -> bool {
+        let applied = self.canvas.set_vsync(enabled);
+
+        if applied {
+            self.vsync = enabled;
+        }
+
+        applied
+    }
+
+    /// Returns `true` if vertical synchronization is enabled, as requested when creating this
+    /// window or by the last successful call to `set_vsync`.
+    #[inline]
+    pub fn vsync(&self) -> bool {
+        self.vsync
+    }
+
+    /// The number of samples requested for multisample anti-aliasing at the window creation.
+    ///
+    /// The scene is rendered with multisampling to the screen, as well as to the
+    /// intermediate render targets of post-processing effects and off-screen rendering. Those
+    /// fall back to the highest number of samples the context supports.
+    #[inline]
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// Enables or disables frustum culling.
+    ///
+    /// When enabled, which is the default, objects outside of the field of view of the camera
+    /// are not rendered.
+    pub fn set_frustum_culling(&mut self, enabled: bool) {
+        self.frustum_culling = enabled
+    }
+
+    /// Returns `true` if frustum culling is enabled.
+    #[inline]
+    pub fn frustum_culling(&self) -> bool {
+        self.frustum_culling
+    }
+
     /// Set window title
     pub fn set_title(&mut self, title: &str) {
         self.canvas.set_title(title)
@@ -143,24 +272,85 @@ impl Window {
         self.canvas.set_icon(icon)
     }
 
+    /// Set the window icon from raw pixels. On wasm this does nothing.
+    ///
+    /// `rgba` contains the 8-bit red, green, blue, and alpha components of the `width * height`
+    /// pixels of the icon, row by row from the top-left corner. See `set_icon` to use an image
+    /// instead.
+    ///
+    /// # Failures:
+    /// Fails if the length of `rgba` is not `width * height * 4`.
+    pub fn set_icon_from_rgba(&mut self, rgba: &[u8], width: u32, height: u32) {
+        assert_eq!(
+            rgba.len(),
+            width as usize * height as usize * 4,
+            "The icon must have 4 components per pixel."
+        );
+
+        let icon = ImageBuffer::<Rgba<u8>, _>::from_raw(width, height, rgba.to_vec()).unwrap();
+        self.canvas.set_icon(icon)
+    }
+
+    /// The magnitude below which gamepad axis values are reported as zero.
+    ///
+    /// Returns `None` if gamepads are not supported on this platform.
+    #[cfg(feature = "gamepad")]
+    pub fn gamepad_deadzone(&self) -> Option<f32> {
+        self.canvas.gamepad_deadzone()
+    }
+
+    /// Sets the magnitude below which gamepad axis values are reported as zero.
+    ///
+    /// The values of `WindowEvent::GamepadAxis` events are in `[-1, 1]`, and are rescaled so
+    /// that the range stays continuous outside of the deadzone. Defaults to 0.1.
+    ///
+    /// # Failures:
+    /// Fails if `deadzone` is not in `[0, 1[`.
+    #[cfg(feature = "gamepad")]
+    pub fn set_gamepad_deadzone(&mut self, deadzone: f32) {
+        self.canvas.set_gamepad_deadzone(deadzone)
+    }
+
     /// Set the cursor grabbing behaviour.
     ///
-    /// If cursor grabbing is on, the cursor is prevented from leaving the window.
-    /// Does nothing on web platforms.
+    /// If cursor grabbing is on, the cursor is prevented from leaving the window, and the
+    /// relative displacements of the mouse are reported by `WindowEvent::MouseMotion` events.
+    /// On web platforms, this requests a pointer lock on the canvas, which the browser may
+    /// refuse outside of a user input handler.
     pub fn set_cursor_grab(&self, grab: bool) {
         self.canvas.set_cursor_grab(grab);
     }
 
+    /// Moves the cursor to the given position, in physical pixels relative to the top-left
+    /// corner of the window. Does nothing on web platforms.
     #[inline]
     pub fn set_cursor_position(&self, x: f64, y: f64) {
         self.canvas.set_cursor_position(x, y);
     }
 
+    /// Hides or shows the cursor while it is over the window.
     #[inline]
     pub fn hide_cursor(&self, hide: bool) {
         self.canvas.hide_cursor(hide);
     }
 
+    /// Switches the window to borderless fullscreen on its current monitor, or back to windowed
+    /// mode.
+    ///
+    /// The rendering context is preserved, and the new size is reported by a
+    /// `WindowEvent::FramebufferSize` event, like any other resize. On web platforms, the
+    /// browser may refuse to switch to fullscreen outside of a user input handler.
+    #[inline]
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.canvas.set_fullscreen(fullscreen)
+    }
+
+    /// Whether the window is fullscreen.
+    #[inline]
+    pub fn is_fullscreen(&self) -> bool {
+        self.canvas.is_fullscreen()
+    }
+
     /// Closes the window.
     #[inline]
     pub fn close(&mut self) {
@@ -179,12 +369,222 @@ impl Window {
         self.canvas.show()
     }
 
-    /// Sets the background color.
+    /// Sets the background color. The background is opaque.
+    ///
+    /// This removes any background gradient or image.
     #[inline]
     pub fn set_background_color(&mut self, r: f32, g: f32, b: f32) {
-        self.background.x = r;
-        self.background.y = g;
-        self.background.z = b;
+        self.set_background_color_rgba(r, g, b, 1.0)
+    }
+
+    /// Sets the background color, with an alpha component.
+    ///
+    /// On native platforms, the background is only see-through if the window has been created
+    /// with `WindowBuilder::transparent` set, and if the window manager composites the windows.
+    /// Compositors usually expect colors with premultiplied alpha.
+    ///
+    /// This removes any background gradient or image.
+    #[inline]
+    pub fn set_background_color_rgba(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        self.background = Vector4::new(r, g, b, a);
+        self.background_renderer = None;
+    }
+
+    /// Fills the background with a vertical gradient from the `top` color to the `bottom` color.
+    ///
+    /// The gradient covers the whole window behind the scene, and does not move with the camera.
+    /// Use `set_background_color` to go back to a flat background.
+    pub fn set_background_gradient(&mut self, top: Point3<f32>, bottom: Point3<f32>) {
+        self.set_background(Background::Gradient { top, bottom })
+    }
+
+    /// Fills the background with a texture stretched to cover the whole window.
+    ///
+    /// The image is drawn behind the scene, and does not move with the camera. Use
+    /// `set_background_color` to go back to a flat background.
+    pub fn set_background_image(&mut self, texture: Rc<Texture>) {
+        self.set_background(Background::Image(texture))
+    }
+
+    fn set_background(&mut self, background: Background) {
+        match self.background_renderer {
+            Some(ref mut renderer) => renderer.set_background(background),
+            None => self.background_renderer = Some(BackgroundRenderer::new(background)),
+        }
+    }
+
+    /// Sets the cube map drawn behind the scene, or removes it if `cubemap` is `None`.
+    ///
+    /// The cube map is drawn as if it was infinitely far away: it follows the rotations of the
+    /// camera, but not its translations. It must have been created with
+    /// `TextureManager::add_cubemap` or `TextureManager::add_cubemap_from_images`.
+    pub fn set_skybox(&mut self, cubemap: Option<Rc<Texture>>) {
+        match (cubemap, &mut self.skybox) {
+            (Some(cubemap), Some(skybox)) => skybox.set_cubemap(cubemap),
+            (Some(cubemap), None) => self.skybox = Some(SkyboxRenderer::new(cubemap)),
+            (None, _) => self.skybox = None,
+        }
+    }
+
+    /// Shows or hides the axes of the world frame, drawn at each frame as lines of `length`
+    /// starting at the origin: red for `X`, green for `Y`, and blue for `Z`.
+    ///
+    /// The axes are hidden by default. They are drawn with the lines of `draw_line`, with the
+    /// same width.
+    pub fn set_show_axes(&mut self, show: bool, length: f32) {
+        self.axes_length = if show { Some(length) } else { None };
+    }
+
+    /// Sets the grid drawn at each frame on the ground, i.e., the `XZ` plane. Set to `None` to
+    /// hide it, the default.
+    ///
+    /// The grid is drawn with the lines of `draw_line`, with the same width.
+    ///
+    /// # Failures:
+    /// Fails if the grid spacing is not positive.
+    pub fn set_ground_grid(&mut self, grid: Option<Grid>) {
+        if let Some(ref grid) = grid {
+            assert!(grid.spacing > 0.0, "The grid spacing must be positive.");
+        }
+
+        self.ground_grid = grid;
+    }
+
+    // Adds the lines of the axes, of the ground grid, and of the normals shown on the nodes of the
+    // scene for the next frame.
+    fn draw_helpers(&mut self) {
+        self.scene.data_mut().draw_normals(
+            &na::one(),
+            &Vector3::from_element(1.0),
+            &mut self.line_renderer,
+        );
+
+        if let Some(grid) = self.ground_grid {
+            let n = (grid.extent / grid.spacing).floor() as i32;
+
+            for i in -n..=n {
+                let t = i as f32 * grid.spacing;
+                self.line_renderer.draw_line(
+                    Point3::new(t, 0.0, -grid.extent),
+                    Point3::new(t, 0.0, grid.extent),
+                    grid.color,
+                );
+                self.line_renderer.draw_line(
+                    Point3::new(-grid.extent, 0.0, t),
+                    Point3::new(grid.extent, 0.0, t),
+                    grid.color,
+                );
+            }
+        }
+
+        if let Some(length) = self.axes_length {
+            let origin = Point3::origin();
+            self.line_renderer.draw_line(
+                origin,
+                Point3::new(length, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+            );
+            self.line_renderer.draw_line(
+                origin,
+                Point3::new(0.0, length, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+            );
+            self.line_renderer.draw_line(
+                origin,
+                Point3::new(0.0, 0.0, length),
+                Point3::new(0.0, 0.0, 1.0),
+            );
+        }
+    }
+
+    /// Sets the fog of the scene. Set to `None` to disable fog, the default.
+    ///
+    /// Fog is only drawn on objects using the default material.
+    #[inline]
+    pub fn set_fog(&mut self, fog: Option<Fog>) {
+        self.environment.set_fog(fog)
+    }
+
+    /// The fog of the scene.
+    #[inline]
+    pub fn fog(&self) -> Option<&Fog> {
+        self.environment.fog()
+    }
+
+    /// Sets the `i`-th clipping plane of the scene. Set to `None` to disable it.
+    ///
+    /// The plane `(a, b, c, d)` keeps the points satisfying `a * x + b * y + c * z + d >= 0`, in
+    /// world coordinates. Up to `environment::MAX_CLIP_PLANES` planes can be enabled
+    /// independently. Clipping planes are only taken into account by the default material.
+    #[inline]
+    pub fn set_clip_plane(&mut self, i: usize, plane: Option<Vector4<f32>>) {
+        self.environment.set_clip_plane(i, plane)
+    }
+
+    /// The clipping planes of the scene, `None` for the disabled ones.
+    #[inline]
+    pub fn clip_planes(&self) -> &[Option<Vector4<f32>>] {
+        self.environment.clip_planes()
+    }
+
+    /// Sets the resolution of the shadow map of the first light of the scene, enabling shadows.
+    ///
+    /// The shadow map is a square texture of `size * size` pixels containing the depth of the
+    /// scene seen from the first light, covering the whole scene. Only the first light casts
+    /// shadows, and lights superimposed with the camera cast none. Shadows are only drawn on
+    /// objects using the default material. Set to `None` to disable shadows, the default.
+    pub fn set_shadow_map_size(&mut self, size: Option<u32>) {
+        match size {
+            Some(size) => {
+                let previous = self.environment.shadow_map().map(|s| (s.size(), s.bias()));
+
+                if previous.map(|p| p.0) != Some(size) {
+                    let shadow_map = ShadowMap::new(size);
+
+                    // Keep the bias chosen for the previous shadow map.
+                    if let Some((_, bias)) = previous {
+                        shadow_map.set_bias(bias);
+                    }
+
+                    self.environment.set_shadow_map(Some(Rc::new(shadow_map)));
+                }
+
+                if self.shadow_material.is_none() {
+                    self.shadow_material = Some(DepthMaterial::new());
+                }
+            }
+            None => {
+                self.environment.set_shadow_map(None);
+                self.shadow_material = None;
+            }
+        }
+    }
+
+    /// The shadow map of the first light of the scene, if shadows are enabled.
+    #[inline]
+    pub fn shadow_map(&self) -> Option<&ShadowMap> {
+        self.environment.shadow_map()
+    }
+
+    /// Appends a post-processing effect to the ones executed after each frame is rendered.
+    ///
+    /// Effects are executed in the order they were added: each one processes the image output
+    /// by the previous one, and the last one draws to the window. Only the first effect has
+    /// access to the depth buffer of the scene. An effect given to `Window::render_with` is
+    /// executed after all the effects added here.
+    pub fn add_post_processing_effect(&mut self, effect: Box<dyn PostProcessingEffect>) {
+        self.post_processing_effects.push(effect)
+    }
+
+    /// Replaces all the post-processing effects added to this window by a single one.
+    pub fn set_post_processing_effect(&mut self, effect: Box<dyn PostProcessingEffect>) {
+        self.clear_post_processing_effects();
+        self.add_post_processing_effect(effect)
+    }
+
+    /// Removes all the post-processing effects added to this window.
+    pub fn clear_post_processing_effects(&mut self) {
+        self.post_processing_effects.clear()
     }
 
     /// Set the size of all subsequent points to be drawn until the next time this function is envoked.
@@ -193,12 +593,58 @@ impl Window {
         self.point_renderer.set_point_size(pt_size);
     }
 
+    /// Set the shape of all the rendered points.
+    #[inline]
+    pub fn set_point_shape(&mut self, shape: PointShape) {
+        self.point_renderer.set_point_shape(shape);
+    }
+
+    /// Enables or disables the rendering of the points as transparent, sorted from back to front
+    /// at each frame.
+    ///
+    /// See `PointRenderer::set_transparent` for details.
+    #[inline]
+    pub fn set_points_transparent(&mut self, transparent: bool) {
+        self.point_renderer.set_transparent(transparent);
+    }
+
     /// Set the width of all subsequent lines to be drawn until the next time this function is envoked.
     #[inline]
     pub fn set_line_width(&mut self, line_width: f32) {
         self.line_renderer.set_line_width(line_width);
     }
 
+    /// Enables or disables the antialiasing of the lines drawn with `draw_line` and friends.
+    ///
+    /// See `LineRenderer::set_antialiased` for details.
+    #[inline]
+    pub fn set_lines_antialiased(&mut self, antialiased: bool) {
+        self.line_renderer.set_antialiased(antialiased);
+    }
+
+    /// Sets the dash pattern of the lines drawn with `draw_line` and friends, i.e., the length
+    /// of the dashes and of the gaps between them, in pixels. Set to `None` for solid lines.
+    ///
+    /// See `LineRenderer::set_dash_pattern` for details.
+    #[inline]
+    pub fn set_lines_dash_pattern(&mut self, pattern: Option<(f32, f32)>) {
+        self.line_renderer.set_dash_pattern(pattern);
+    }
+
+    /// Reserves capacity for at least `additional` more lines drawn with `draw_line` at each
+    /// frame.
+    #[inline]
+    pub fn reserve_lines(&mut self, additional: usize) {
+        self.line_renderer.reserve(additional);
+    }
+
+    /// Reserves capacity for at least `additional` more points drawn with `draw_point` or
+    /// `draw_point_with_size` at each frame.
+    #[inline]
+    pub fn reserve_points(&mut self, additional: usize) {
+        self.point_renderer.reserve(additional);
+    }
+
     /// Adds a 3D line to be drawn during the next render.
     ///
     /// The line is being drawn only during the next frame after this call.
@@ -208,6 +654,45 @@ impl Window {
         self.line_renderer.draw_line(*a, *b, *color);
     }
 
+    /// Adds a 3D polyline to be drawn during the next render.
+    ///
+    /// Each point is connected to the next one by a line segment.
+    /// The polyline is being drawn only during the next frame after this call.
+    #[inline]
+    pub fn draw_polyline(&mut self, points: &[Point3<f32>], color: &Point3<f32>) {
+        self.line_renderer.draw_polyline(points, *color);
+    }
+
+    /// Adds a Catmull-Rom spline passing through `points` to be drawn during the next render.
+    ///
+    /// See `LineRenderer::draw_catmull_rom` for details.
+    #[inline]
+    pub fn draw_catmull_rom(
+        &mut self,
+        points: &[Point3<f32>],
+        samples_per_segment: usize,
+        closed: bool,
+        color: &Point3<f32>,
+    ) {
+        self.line_renderer
+            .draw_catmull_rom(points, samples_per_segment, closed, *color);
+    }
+
+    /// Adds a piecewise cubic Bézier curve to be drawn during the next render.
+    ///
+    /// See `LineRenderer::draw_bezier` for details.
+    #[inline]
+    pub fn draw_bezier(
+        &mut self,
+        points: &[Point3<f32>],
+        samples_per_segment: usize,
+        closed: bool,
+        color: &Point3<f32>,
+    ) {
+        self.line_renderer
+            .draw_bezier(points, samples_per_segment, closed, *color);
+    }
+
     /// Draws a 2D line to be drawn during the next render.
     ///
     /// The line is being drawn only during the next frame after this call.
@@ -222,13 +707,22 @@ impl Window {
     pub fn draw_point(&mut self, pt: &Point3<f32>, color: &Point3<f32>) {
         self.point_renderer.draw_point(*pt, *color);
     }
-    
+
     /// Adds a point to be drawn during the next frame.
     #[inline]
     pub fn draw_point_with_size(&mut self, pt: &Point3<f32>, color: &Point3<f32>, size: f32) {
         self.point_renderer.draw_point_with_size(*pt, *color, size);
     }
 
+    /// Adds a semi-transparent point to be drawn during the next frame.
+    ///
+    /// The last component of `color` is the opacity of the point. See
+    /// `PointRenderer::draw_point_rgba` for details.
+    #[inline]
+    pub fn draw_point_rgba(&mut self, pt: &Point3<f32>, color: &Point4<f32>, size: f32) {
+        self.point_renderer.draw_point_rgba(*pt, *color, size);
+    }
+
     /// Adds a string to be drawn during the next frame.
     #[inline]
     pub fn draw_text(
@@ -242,6 +736,65 @@ impl Window {
         self.text_renderer.draw_text(text, pos, scale, font, color);
     }
 
+    /// Adds a string to be drawn during the next frame, with its lines aligned horizontally with
+    /// respect to `pos.x`.
+    ///
+    /// If `max_width` is set, the lines wider than `max_width` are wrapped between words.
+    #[inline]
+    pub fn draw_aligned_text(
+        &mut self,
+        text: &str,
+        pos: &Point2<f32>,
+        scale: f32,
+        font: &Rc<Font>,
+        color: &Point3<f32>,
+        alignment: TextAlignment,
+        max_width: Option<f32>,
+    ) {
+        self.text_renderer
+            .draw_aligned_text(text, pos, scale, font, color, alignment, max_width);
+    }
+
+    /// Adds a string to be drawn during the next frame at the projection of a 3D point.
+    ///
+    /// The top-left corner of the text is placed where `world_pos` is seen by the camera used
+    /// to render the frame. The text is not drawn if `world_pos` is behind the camera.
+    #[inline]
+    pub fn draw_text_at(
+        &mut self,
+        text: &str,
+        world_pos: &Point3<f32>,
+        scale: f32,
+        font: &Rc<Font>,
+        color: &Point3<f32>,
+    ) {
+        self.draw_text_at_with_offset(text, world_pos, &Vector2::zeros(), scale, font, color)
+    }
+
+    /// Adds a string to be drawn during the next frame at the projection of a 3D point, shifted
+    /// by `offset` on the screen.
+    ///
+    /// The offset is expressed in the same unit as the positions given to `draw_text`, with the
+    /// `y` axis pointing downward: a negative `offset.y` places the label above its anchor.
+    pub fn draw_text_at_with_offset(
+        &mut self,
+        text: &str,
+        world_pos: &Point3<f32>,
+        offset: &Vector2<f32>,
+        scale: f32,
+        font: &Rc<Font>,
+        color: &Point3<f32>,
+    ) {
+        self.anchored_texts.push(AnchoredText {
+            text: text.to_string(),
+            world_pos: *world_pos,
+            offset: *offset,
+            scale,
+            font: font.clone(),
+            color: *color,
+        })
+    }
+
     /// Removes an object from the scene.
     #[deprecated(note = "Use `remove_node` instead.")]
     pub fn remove(&mut self, sn: &mut SceneNode) {
@@ -281,11 +834,34 @@ impl Window {
         self.scene.add_obj(path, mtl_dir, scale)
     }
 
+    /// Adds an obj model to the scene, with one node per group of the file.
+    ///
+    /// Returns the root of the model, and its children with the names of their groups. See
+    /// `SceneNode::add_obj_grouped` for details.
+    pub fn add_obj_grouped(
+        &mut self,
+        path: &Path,
+        mtl_dir: &Path,
+        scale: Vector3<f32>,
+    ) -> (SceneNode, Vec<(String, SceneNode)>) {
+        self.scene.add_obj_grouped(path, mtl_dir, scale)
+    }
+
     /// Adds an unnamed mesh to the scene.
     pub fn add_mesh(&mut self, mesh: Rc<RefCell<Mesh>>, scale: Vector3<f32>) -> SceneNode {
         self.scene.add_mesh(mesh, scale)
     }
 
+    /// Adds an unnamed mesh to the scene, drawn once per transformation of `instances`.
+    pub fn add_instanced(
+        &mut self,
+        mesh: Rc<RefCell<Mesh>>,
+        scale: Vector3<f32>,
+        instances: &[Isometry3<f32>],
+    ) -> SceneNode {
+        self.scene.add_instanced(mesh, scale, instances)
+    }
+
     /// Adds an unnamed planar mesh to the scene.
     pub fn add_planar_mesh(
         &mut self,
@@ -358,6 +934,79 @@ impl Window {
         self.scene.add_capsule(r, h)
     }
 
+    /// Adds a cone with a custom number of subdivisions to the scene. The cone is initially
+    /// centered at (0, 0, 0) and points toward the positive `y` axis.
+    ///
+    /// # Arguments
+    /// * `r` - the cone base radius
+    /// * `h` - the cone height
+    /// * `subdivs` - the number of subdivisions of the base circle. Must be at least 3.
+    pub fn add_cone_with_subdivs(&mut self, r: f32, h: f32, subdivs: u32) -> SceneNode {
+        self.scene.add_cone_with_subdivs(r, h, subdivs)
+    }
+
+    /// Adds a sphere with a custom tessellation to the scene. The sphere is initially centered
+    /// at (0, 0, 0).
+    ///
+    /// # Arguments
+    /// * `r` - the sphere radius
+    /// * `subdivs` - the number of subdivisions of the icosahedron approximating the sphere. See
+    ///   `SceneNode::add_sphere_with_subdivs` for details.
+    pub fn add_sphere_with_subdivs(&mut self, r: f32, subdivs: u32) -> SceneNode {
+        self.scene.add_sphere_with_subdivs(r, subdivs)
+    }
+
+    /// Adds a cylinder with a custom number of subdivisions to the scene. The cylinder is
+    /// initially centered at (0, 0, 0) and has its principal axis aligned with the `y` axis.
+    ///
+    /// # Arguments
+    /// * `r` - the cylinder base radius
+    /// * `h` - the cylinder height
+    /// * `subdivs` - the number of subdivisions of the base circles. Must be at least 3.
+    pub fn add_cylinder_with_subdivs(&mut self, r: f32, h: f32, subdivs: u32) -> SceneNode {
+        self.scene.add_cylinder_with_subdivs(r, h, subdivs)
+    }
+
+    /// Adds a capsule with a custom number of subdivisions to the scene. The capsule is
+    /// initially centered at (0, 0, 0) and has its principal axis aligned with the `y` axis.
+    ///
+    /// # Arguments
+    /// * `r` - the capsule caps radius
+    /// * `h` - the capsule height, without the caps
+    /// * `subdivs` - the number of subdivisions around the capsule axis. Must be at least 3.
+    pub fn add_capsule_with_subdivs(&mut self, r: f32, h: f32, subdivs: u32) -> SceneNode {
+        self.scene.add_capsule_with_subdivs(r, h, subdivs)
+    }
+
+    /// Adds a torus to the scene. The torus is initially centered at (0, 0, 0) and lies on the
+    /// `xz` plane.
+    ///
+    /// # Arguments
+    /// * `major_r` - the distance from the torus center to the center of its tube
+    /// * `minor_r` - the radius of the torus tube
+    /// * `nsegs` - the number of subdivisions along the tube. Must be at least 3.
+    /// * `nrings` - the number of subdivisions around the tube. Must be at least 3.
+    pub fn add_torus(&mut self, major_r: f32, minor_r: f32, nsegs: u32, nrings: u32) -> SceneNode {
+        self.scene.add_torus(major_r, minor_r, nsegs, nrings)
+    }
+
+    /// Adds an arrow going from `from` to `to` to the scene.
+    ///
+    /// The arrow is a group made of a cylindrical shaft and a conical head.
+    ///
+    /// # Arguments
+    /// * `from` - the tail of the arrow, i.e., the origin of the returned node
+    /// * `to` - the tip of the arrow
+    /// * `shaft_radius` - the radius of the arrow shaft
+    pub fn add_arrow(
+        &mut self,
+        from: Point3<f32>,
+        to: Point3<f32>,
+        shaft_radius: f32,
+    ) -> SceneNode {
+        self.scene.add_arrow(from, to, shaft_radius)
+    }
+
     /// Adds a 2D capsule to the scene. The capsule is initially centered at (0, 0) and has its
     /// principal axis aligned with the `y` axis.
     ///
@@ -409,6 +1058,30 @@ impl Window {
         self.scene2.add_rectangle(wx, wy)
     }
 
+    /// Adds a rectangle textured with the whole `texture` to the scene. The rectangle is
+    /// initially axis-aligned and centered at (0, 0).
+    ///
+    /// # Arguments
+    /// * `wx` - the rectangle extent along the x axis
+    /// * `wy` - the rectangle extent along the y axis
+    /// * `texture` - the texture to display on the rectangle
+    pub fn add_textured_rectangle(
+        &mut self,
+        wx: f32,
+        wy: f32,
+        texture: Rc<Texture>,
+    ) -> PlanarSceneNode {
+        self.scene2.add_textured_rectangle(wx, wy, texture)
+    }
+
+    /// Adds a sprite displaying the texture registered as `name` to the scene, with the size of
+    /// the texture in pixels.
+    ///
+    /// See `PlanarSceneNode::add_sprite` for details.
+    pub fn add_sprite(&mut self, name: &str) -> PlanarSceneNode {
+        self.scene2.add_sprite(name)
+    }
+
     /// Adds a circle to the scene. The circle is initially centered at (0, 0, 0).
     ///
     /// # Arguments
@@ -420,7 +1093,8 @@ impl Window {
     /// Adds a convex polygon to the scene.
     ///
     /// # Arguments
-    /// * `r` - the circle radius
+    /// * `polygon` - the polygon vertices, in clockwise or counterclockwise order
+    /// * `scale` - the scale applied to the polygon
     pub fn add_convex_polygon(
         &mut self,
         polygon: Vec<Point2<f32>>,
@@ -429,6 +1103,21 @@ impl Window {
         self.scene2.add_convex_polygon(polygon, scale)
     }
 
+    /// Adds a filled simple polygon, which may be concave, to the scene.
+    ///
+    /// See `PlanarSceneNode::add_polygon` for details.
+    ///
+    /// # Arguments
+    /// * `polygon` - the polygon vertices, in clockwise or counterclockwise order
+    /// * `scale` - the scale applied to the polygon
+    pub fn add_polygon(
+        &mut self,
+        polygon: Vec<Point2<f32>>,
+        scale: Vector2<f32>,
+    ) -> PlanarSceneNode {
+        self.scene2.add_polygon(polygon, scale)
+    }
+
     /// Returns whether this window is closed or not.
     pub fn is_closed(&self) -> bool {
         false // FIXME
@@ -439,9 +1128,52 @@ impl Window {
         self.canvas.scale_factor()
     }
 
-    /// Sets the light mode. Only one light is supported.
-    pub fn set_light(&mut self, pos: Light) {
-        self.light_mode = pos;
+    /// Sets the light mode, replacing all the lights of the scene by `light`.
+    pub fn set_light(&mut self, light: Light) {
+        let lights = self.environment.lights_mut();
+        lights.clear();
+        lights.push(light);
+    }
+
+    /// Adds a light to the scene.
+    ///
+    /// The built-in materials only take the first `light::MAX_LIGHTS` lights into account.
+    pub fn add_light(&mut self, light: Light) {
+        self.environment.lights_mut().push(light);
+    }
+
+    /// Removes the `i`-th light of the scene and returns it.
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn remove_light(&mut self, i: usize) -> Light {
+        self.environment.lights_mut().remove(i)
+    }
+
+    /// Replaces all the lights of the scene.
+    ///
+    /// The built-in materials only take the first `light::MAX_LIGHTS` lights into account.
+    pub fn set_lights(&mut self, lights: Vec<Light>) {
+        *self.environment.lights_mut() = lights;
+    }
+
+    /// The lights of the scene.
+    #[inline]
+    pub fn lights(&self) -> &[Light] {
+        self.environment.lights()
+    }
+
+    /// Sets the color of the ambient light.
+    ///
+    /// The ambient light lights every object uniformly, regardless of its orientation.
+    /// Defaults to `(1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0)`.
+    pub fn set_ambient_color(&mut self, color: Point3<f32>) {
+        self.environment.set_ambient_color(color)
+    }
+
+    /// The color of the ambient light.
+    #[inline]
+    pub fn ambient_color(&self) -> &Point3<f32> {
+        self.environment.ambient_color()
     }
 
     /// Retrieve a mutable reference to the UI based on Conrod.
@@ -495,7 +1227,7 @@ impl Window {
     /// # Arguments
     /// * `title` - the window title
     pub fn new_hidden(title: &str) -> Window {
-        Window::do_new(title, true, DEFAULT_WIDTH, DEFAULT_HEIGHT, None)
+        WindowBuilder::new(title).hidden(true).build()
     }
 
     /// Opens a window then calls a user-defined procedure.
@@ -503,7 +1235,7 @@ impl Window {
     /// # Arguments
     /// * `title` - the window title
     pub fn new(title: &str) -> Window {
-        Window::do_new(title, false, DEFAULT_WIDTH, DEFAULT_HEIGHT, None)
+        WindowBuilder::new(title).build()
     }
 
     /// Opens a window with a custom size then calls a user-defined procedure.
@@ -513,51 +1245,82 @@ impl Window {
     /// * `width` - the window width.
     /// * `height` - the window height.
     pub fn new_with_size(title: &str, width: u32, height: u32) -> Window {
-        Window::do_new(title, false, width, height, None)
+        WindowBuilder::new(title).size(width, height).build()
     }
 
     /// Opens a window with custom options for vsync and AA.
     pub fn new_with_setup(title: &str, width: u32, height: u32, setup: CanvasSetup) -> Window {
-        Window::do_new(title, false, width, height, Some(setup))
+        WindowBuilder::new(title)
+            .size(width, height)
+            .setup(setup)
+            .build()
+    }
+
+    /// Creates a builder to open a window with more options than the other constructors.
+    ///
+    /// This is a shortcut for `WindowBuilder::new(title)`.
+    #[inline]
+    pub fn builder(title: &str) -> WindowBuilder {
+        WindowBuilder::new(title)
     }
 
-    // FIXME: make this pub?
-    fn do_new(
+    pub(crate) fn do_new(
         title: &str,
         hide: bool,
         width: u32,
         height: u32,
         setup: Option<CanvasSetup>,
+        transparent: bool,
     ) -> Window {
         let (event_send, event_receive) = mpsc::channel();
-        let canvas = Canvas::open(title, hide, width, height, setup, event_send);
+        let canvas = Canvas::do_open(title, hide, width, height, setup, transparent, event_send);
+        // The canvas multisamples the screen itself, the off-screen targets are multisampled here.
+        let samples = setup.map(|s| s.samples as u32).unwrap_or(0);
 
         init_gl();
 
         let mut usr_window = Window {
             should_close: false,
             max_dur_per_frame: None,
+            vsync: setup.map(|s| s.vsync).unwrap_or(true),
+            frustum_culling: true,
             canvas: canvas,
             events: Rc::new(event_receive),
             unhandled_events: Rc::new(RefCell::new(Vec::new())),
             scene: SceneNode::new_empty(),
             scene2: PlanarSceneNode::new_empty(),
-            light_mode: Light::Absolute(Point3::new(0.0, 10.0, 0.0)),
-            background: Vector3::new(0.0, 0.0, 0.0),
+            environment: Environment::new(vec![Light::absolute(Point3::new(0.0, 10.0, 0.0))]),
+            background: Vector4::new(0.0, 0.0, 0.0, 1.0),
+            background_renderer: None,
+            skybox: None,
+            shadow_material: None,
             line_renderer: LineRenderer::new(),
             planar_line_renderer: PlanarLineRenderer::new(),
             point_renderer: PointRenderer::new(),
             text_renderer: TextRenderer::new(),
+            anchored_texts: Vec::new(),
             #[cfg(feature = "conrod")]
             conrod_context: ConrodContext::new(width as f64, height as f64),
-            post_process_render_target: FramebufferManager::new_render_target(
+            post_process_render_target: FramebufferManager::new_multisampled_render_target(
                 width as usize,
                 height as usize,
                 true,
+                samples,
             ),
+            post_process_swap_render_target: None,
+            post_processing_effects: Vec::new(),
+            offscreen_render_target: None,
+            samples,
             framebuffer_manager: FramebufferManager::new(),
             #[cfg(not(target_arch = "wasm32"))]
             curr_time: Instant::now(),
+            #[cfg(not(target_arch = "wasm32"))]
+            recorder: None,
+            frame_times: VecDeque::with_capacity(FPS_AVERAGED_FRAMES),
+            last_frame_end: None,
+            last_animation_step: None,
+            axes_length: None,
+            ground_grid: None,
             planar_camera: Rc::new(RefCell::new(FixedView::new())),
             camera: Rc::new(RefCell::new(ArcBall::new(
                 Point3::new(0.0f32, 0.0, -1.0),
@@ -570,9 +1333,6 @@ impl Window {
         }
 
         // usr_window.framebuffer_size_callback(DEFAULT_WIDTH, DEFAULT_HEIGHT);
-        let light = usr_window.light_mode.clone();
-        usr_window.set_light(light);
-
         usr_window
     }
 
@@ -582,6 +1342,39 @@ impl Window {
         &self.scene
     }
 
+    /// Moves the default camera so that `node` and all its descendants are entirely visible.
+    ///
+    /// See `ArcBall::fit_to_aabb` for details. This does nothing if the node has no vertex on
+    /// the CPU (see `SceneNode::aabb`).
+    pub fn look_at_node(&mut self, node: &SceneNode) {
+        if let Some((min, max)) = node.aabb() {
+            self.camera.borrow_mut().fit_to_aabb(min, max)
+        }
+    }
+
+    /// The visible node nearest to the camera under a point of the screen, using the default
+    /// camera.
+    ///
+    /// `screen_pos` is expressed in pixels from the top-left corner of the window, like the
+    /// cursor positions given by `WindowEvent::CursorPos`.
+    pub fn pick(&self, screen_pos: &Point2<f32>) -> Option<SceneNode> {
+        self.pick_with_camera(&*self.camera.borrow(), screen_pos)
+    }
+
+    /// The visible node nearest to the camera under a point of the screen.
+    ///
+    /// See `pick` for details.
+    pub fn pick_with_camera(
+        &self,
+        camera: &dyn Camera,
+        screen_pos: &Point2<f32>,
+    ) -> Option<SceneNode> {
+        let (w, h) = self.canvas.size();
+        let (origin, dir) = camera.unproject(screen_pos, &Vector2::new(w as f32, h as f32));
+
+        self.scene.cast_ray(&origin, &dir).map(|(node, _)| node)
+    }
+
     /// Mutable reference to the scene associated with this window.
     #[inline]
     pub fn scene_mut(&mut self) -> &mut SceneNode {
@@ -626,6 +1419,53 @@ impl Window {
         );
     }
 
+    /// Reads the depth buffer of the window.
+    ///
+    /// The depths are in `[0, 1]`, with 1 on the far plane, and are not linear: see
+    /// `camera::linearize_depth` to get distances to the camera. The rows are ordered from the
+    /// top to the bottom of the window, i.e., the depth of the pixel `(x, y)` from the top-left
+    /// corner is at the index `x + y * width`.
+    ///
+    /// Call this after `render` to read the depths of the last rendered frame. The depths of the
+    /// scene are not available if a post-processing effect is active. This is not supported on
+    /// WebGL, where only zeros are returned.
+    pub fn read_depth(&self) -> Vec<f32> {
+        let (width, height) = self.canvas.size();
+        let (width, height) = (width as usize, height as usize);
+        let mut depths = vec![0.0; width * height];
+
+        Context::get().read_depth_pixels(0, 0, width as i32, height as i32, &mut depths);
+
+        // OpenGL returns the rows from the bottom to the top.
+        let mut flipped = Vec::with_capacity(depths.len());
+
+        for row in depths.chunks(width.max(1)).rev() {
+            flipped.extend_from_slice(row);
+        }
+
+        flipped
+    }
+
+    /// Reads the depth buffer of the window under a point of the screen.
+    ///
+    /// `screen_pos` is expressed in pixels from the top-left corner of the window, like the
+    /// cursor positions given by `WindowEvent::CursorPos`. Returns `None` if the point is outside
+    /// of the window. See `read_depth` for details.
+    pub fn read_depth_at(&self, screen_pos: &Point2<f32>) -> Option<f32> {
+        let (width, height) = self.canvas.size();
+        let (x, y) = (screen_pos.x.floor(), screen_pos.y.floor());
+
+        if x < 0.0 || y < 0.0 || x >= width as f32 || y >= height as f32 {
+            return None;
+        }
+
+        let mut depth = [0.0];
+        let y = height as i32 - 1 - y as i32;
+        Context::get().read_depth_pixels(x as i32, y, 1, 1, &mut depth);
+
+        Some(depth[0])
+    }
+
     /// Get the current screen as an image
     pub fn snap_image(&self) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
         let (width, height) = self.canvas.size();
@@ -636,6 +1476,66 @@ impl Window {
         imageops::flip_vertical(&img)
     }
 
+    /// Saves the current screen to a PNG file.
+    ///
+    /// The saved image has the size of the framebuffer in physical pixels, so it will be larger
+    /// than the logical window size on HiDPI screens. Call this after `render` to capture the
+    /// last rendered frame, including any post-processing effect.
+    pub fn save_screenshot<P: AsRef<Path>>(&self, path: P) -> ImageResult<()> {
+        self.snap_image().save_with_format(path, ImageFormat::Png)
+    }
+
+    /// Starts saving the rendered frames to numbered PNG files in the directory `dir`.
+    ///
+    /// One frame out of `every_n_frames` is saved, starting with the next one, as
+    /// `frame_000000.png`, `frame_000001.png`, etc. The directory is created if needed, and
+    /// existing frames are overwritten. The frames are listed with their durations in a
+    /// `frames.txt` file, so that a correctly timed video can be made with
+    /// `ffmpeg -f concat -i frames.txt video.mp4` from within `dir`.
+    ///
+    /// The frames are captured like `save_screenshot` at the end of each qualifying frame, but
+    /// written to the disk in a background thread. If writing is slower than rendering, the
+    /// captured frames are queued in memory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start_recording<P: AsRef<Path>>(
+        &mut self,
+        dir: P,
+        every_n_frames: usize,
+    ) -> io::Result<()> {
+        // Any previous recording is finished first.
+        self.recorder = None;
+        self.recorder = Some(Recorder::start(dir.as_ref(), every_n_frames)?);
+        Ok(())
+    }
+
+    /// Stops the recording started by `start_recording`.
+    ///
+    /// This blocks until all the captured frames are written. This does nothing if no recording
+    /// is in progress.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn stop_recording(&mut self) {
+        self.recorder = None;
+    }
+
+    /// Whether the rendered frames are being recorded.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn record_frame(&mut self) {
+        if self.recorder.as_mut().map_or(false, |r| r.next_frame()) {
+            let (width, height) = self.canvas.size();
+            let mut pixels = Vec::new();
+            self.snap(&mut pixels);
+
+            if let Some(ref mut recorder) = self.recorder {
+                recorder.record(pixels, width, height);
+            }
+        }
+    }
+
     /// Gets the events manager that gives access to an event iterator.
     pub fn events(&self) -> EventManager {
         EventManager::new(self.events.clone(), self.unhandled_events.clone())
@@ -646,6 +1546,29 @@ impl Window {
         self.canvas.get_key(key)
     }
 
+    /// Tests if a key is currently held down.
+    ///
+    /// The keyboard state is updated from the events received before the last call to
+    /// `Window::render`, so this can be polled at each iteration of the render loop.
+    #[inline]
+    pub fn is_key_pressed(&self, key: Key) -> bool {
+        self.get_key(key) == Action::Press
+    }
+
+    /// Gets the status of a mouse button.
+    pub fn get_mouse_button(&self, button: MouseButton) -> Action {
+        self.canvas.get_mouse_button(button)
+    }
+
+    /// Tests if a mouse button is currently held down.
+    ///
+    /// The mouse state is updated from the events received before the last call to
+    /// `Window::render`, so this can be polled at each iteration of the render loop.
+    #[inline]
+    pub fn mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.get_mouse_button(button) == Action::Press
+    }
+
     /// Gets the last known position of the mouse.
     ///
     /// The position of the mouse is automatically updated when the mouse moves over the canvas.
@@ -882,7 +1805,7 @@ impl Window {
         {
             let (size, scale) = (self.size(), self.scale_factor());
             let conrod_ui = self.conrod_ui_mut();
-            if let Some(input) = window_event_to_conrod_input(*event, size, scale) {
+            if let Some(input) = window_event_to_conrod_input(event.clone(), size, scale) {
                 conrod_ui.handle_event(input);
             }
 
@@ -915,11 +1838,45 @@ impl Window {
         }
     }
 
+    /// The number of frames rendered per second, averaged over the last 30 frames.
+    ///
+    /// This accounts for the whole duration of the frames, including the time spent outside of
+    /// the rendering and the time spent waiting because of `set_framerate_limit`. Returns 0
+    /// until two frames are rendered.
+    pub fn fps(&self) -> f64 {
+        let total: Duration = self.frame_times.iter().sum();
+
+        if total == Duration::from_secs(0) {
+            0.0
+        } else {
+            self.frame_times.len() as f64 / total.as_secs_f64()
+        }
+    }
+
+    /// The time elapsed between the ends of the last two rendered frames.
+    ///
+    /// Returns zero until two frames are rendered.
+    pub fn last_frame_time(&self) -> Duration {
+        self.frame_times
+            .back()
+            .cloned()
+            .unwrap_or_else(|| Duration::from_secs(0))
+    }
+
     /// Runs the render and event loop until the window is closed.
     pub fn render_loop<S: State>(mut self, mut state: S) {
         Canvas::render_loop(move |_| self.do_render_with_state(&mut state))
     }
 
+    /// Runs the render and event loop until the window is closed, calling `callback` after each
+    /// frame with the time elapsed since its previous call, in seconds.
+    ///
+    /// The time step is capped to `window::MAX_FRAME_DT`, so that the first frame, or a frame
+    /// stalled by, e.g., a window resize, does not make a simulation explode.
+    pub fn render_loop_with_dt<F: FnMut(&mut Window, f32) + 'static>(self, callback: F) {
+        self.render_loop(ClosureState::new(callback))
+    }
+
     /// Render one frame using the specified state.
     #[cfg(not(target_arch = "wasm32"))]
     pub fn render_with_state<S: State>(&mut self, state: &mut S) -> bool {
@@ -929,7 +1886,8 @@ impl Window {
     fn do_render_with_state<S: State>(&mut self, state: &mut S) -> bool {
         {
             let (camera, planar_camera, renderer, effect) = state.cameras_and_effect_and_renderer();
-            self.should_close = !self.do_render_with(camera, planar_camera, renderer, effect);
+            self.should_close =
+                !self.do_render_with(camera, planar_camera, renderer, effect, false);
         }
 
         if !self.should_close {
@@ -1011,7 +1969,79 @@ impl Window {
         post_processing: Option<&mut dyn PostProcessingEffect>,
     ) -> bool {
         // FIXME: for backward-compatibility, we don't accept any custom renderer here.
-        self.do_render_with(camera, planar_camera, None, post_processing)
+        self.do_render_with(camera, planar_camera, None, post_processing, false)
+    }
+
+    /// Renders the scene off-screen using the default camera and returns the rendered image.
+    ///
+    /// The rendered frame is not presented to the screen so this can be used with a window
+    /// created by `Window::new_hidden`. The image has the size of the framebuffer, in pixels.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn render_to_image(&mut self) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+        self.render_to_image_with(None, None, None)
+    }
+
+    /// Renders the scene off-screen with the given cameras and post-processing effect, and returns
+    /// the rendered image.
+    ///
+    /// See `Window::render_to_image` for details.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn render_to_image_with(
+        &mut self,
+        camera: Option<&mut dyn Camera>,
+        planar_camera: Option<&mut dyn PlanarCamera>,
+        post_processing: Option<&mut dyn PostProcessingEffect>,
+    ) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+        let _ = self.do_render_with(camera, planar_camera, None, post_processing, true);
+        // The off-screen render target is still selected at this point.
+        let img = self.snap_image();
+        self.framebuffer_manager
+            .select(&FramebufferManager::screen());
+        img
+    }
+
+    /// Renders the scene seen by `camera` into an off-screen render target.
+    ///
+    /// The target is typically created with `FramebufferManager::new_render_target`, and its
+    /// texture, given by `RenderTarget::texture`, can then be applied to objects of the scene,
+    /// e.g., to display a security camera on a monitor, a mirror, or a minimap. The target is
+    /// not cleared between frames, so this should be called before each `render` to keep it up
+    /// to date.
+    ///
+    /// Only the background, the skybox, and the objects of the scene are rendered: the lines,
+    /// points, texts, and 2D scene of the window are not, nor are post-processing effects.
+    /// Objects textured by `target` must not be visible from `camera`. The aspect ratio of
+    /// `camera` is adjusted to the size of the target, and the shadow map of the last frame is
+    /// reused.
+    pub fn render_to_texture(&mut self, target: &RenderTarget, camera: &mut dyn Camera) {
+        let (width, height) = match target.size() {
+            Some((width, height)) => (width as u32, height as u32),
+            None => (self.width(), self.height()),
+        };
+
+        camera.update(&self.canvas);
+        camera.handle_event(&self.canvas, &WindowEvent::FramebufferSize(width, height));
+
+        let ctxt = Context::get();
+        self.framebuffer_manager.select(target);
+        verify!(ctxt.viewport(0, 0, width as i32, height as i32));
+        verify!(ctxt.scissor(0, 0, width as i32, height as i32));
+
+        for pass in 0usize..camera.num_passes() {
+            camera.start_pass(pass, &self.canvas);
+            self.render_background(camera, pass);
+            self.render_objects(camera, pass);
+        }
+
+        camera.render_complete(&self.canvas);
+
+        self.framebuffer_manager.resolve(target);
+        self.framebuffer_manager
+            .select(&FramebufferManager::screen());
+
+        let (w, h) = (self.width(), self.height());
+        verify!(ctxt.viewport(0, 0, w as i32, h as i32));
+        verify!(ctxt.scissor(0, 0, w as i32, h as i32));
     }
 
     fn do_render_with(
@@ -1020,6 +2050,7 @@ impl Window {
         planar_camera: Option<&mut dyn PlanarCamera>,
         renderer: Option<&mut dyn Renderer>,
         post_processing: Option<&mut dyn PostProcessingEffect>,
+        offscreen: bool,
     ) -> bool {
         let mut camera = camera;
         let mut planar_camera = planar_camera;
@@ -1033,51 +2064,130 @@ impl Window {
 
         match (camera, planar_camera) {
             (Some(cam), Some(cam2)) => {
-                self.render_single_frame(cam, cam2, renderer, post_processing)
-            }
-            (None, Some(cam2)) => {
-                self.render_single_frame(&mut *bself_cam, cam2, renderer, post_processing)
-            }
-            (Some(cam), None) => {
-                self.render_single_frame(cam, &mut *bself_cam2, renderer, post_processing)
+                self.render_single_frame(cam, cam2, renderer, post_processing, offscreen)
             }
+            (None, Some(cam2)) => self.render_single_frame(
+                &mut *bself_cam,
+                cam2,
+                renderer,
+                post_processing,
+                offscreen,
+            ),
+            (Some(cam), None) => self.render_single_frame(
+                cam,
+                &mut *bself_cam2,
+                renderer,
+                post_processing,
+                offscreen,
+            ),
             (None, None) => self.render_single_frame(
                 &mut *bself_cam,
                 &mut *bself_cam2,
                 renderer,
                 post_processing,
+                offscreen,
             ),
         }
     }
 
+    fn draw_anchored_texts(&mut self, camera: &mut dyn Camera, w: f32, h: f32) {
+        let transformation = camera.transformation();
+
+        for anchored in self.anchored_texts.drain(..) {
+            let clip = transformation * anchored.world_pos.to_homogeneous();
+
+            // Behind the camera or in front of its near plane.
+            if clip.w <= 0.0 || clip.z < -clip.w {
+                continue;
+            }
+
+            // The text renderer maps [0, 2 * w] x [0, 2 * h] to the whole screen, with the `y`
+            // axis pointing downward.
+            let pos = Point2::new((1.0 + clip.x / clip.w) * w, (1.0 - clip.y / clip.w) * h)
+                + anchored.offset;
+
+            self.text_renderer.draw_text(
+                &anchored.text,
+                &pos,
+                anchored.scale,
+                &anchored.font,
+                &anchored.color,
+            );
+        }
+    }
+
+    // Advances the animations played on the nodes of the scene by the time elapsed since the
+    // previous frame, capped to `MAX_FRAME_DT`.
+    fn advance_animations(&mut self) {
+        let now = Instant::now();
+        let dt = match self.last_animation_step {
+            Some(last) => now.duration_since(last).as_secs_f32().min(MAX_FRAME_DT),
+            None => 0.0,
+        };
+        self.last_animation_step = Some(now);
+
+        let mut finished = Vec::new();
+        self.scene.data_mut().advance_animations(dt, &mut finished);
+
+        // The scene is no longer borrowed, so the callbacks may modify it.
+        for callback in finished {
+            (&mut *callback.borrow_mut())();
+        }
+    }
+
     fn render_single_frame(
         &mut self,
         camera: &mut dyn Camera,
         planar_camera: &mut dyn PlanarCamera,
         mut renderer: Option<&mut dyn Renderer>,
-        mut post_processing: Option<&mut dyn PostProcessingEffect>,
+        post_processing: Option<&mut dyn PostProcessingEffect>,
+        offscreen: bool,
     ) -> bool {
         // XXX: too bad we have to do this at each frame…
         let w = self.width();
         let h = self.height();
 
+        self.advance_animations();
+        self.line_renderer.set_viewport_size(w, h);
+
+        if offscreen && self.offscreen_render_target.is_none() {
+            self.offscreen_render_target =
+                Some(FramebufferManager::new_multisampled_render_target(
+                    w as usize,
+                    h as usize,
+                    false,
+                    self.samples,
+                ));
+        }
+
         planar_camera.handle_event(&self.canvas, &WindowEvent::FramebufferSize(w, h));
         camera.handle_event(&self.canvas, &WindowEvent::FramebufferSize(w, h));
         planar_camera.update(&self.canvas);
         camera.update(&self.canvas);
 
-        match self.light_mode {
-            Light::StickToCamera => self.set_light(Light::StickToCamera),
-            _ => {}
+        self.render_shadow_map(camera, w, h);
+        self.draw_helpers();
+
+        // The effects are moved out of `self` while they are executed.
+        let mut effects = mem::replace(&mut self.post_processing_effects, Vec::new());
+        let mut chain: Vec<&mut dyn PostProcessingEffect> = effects
+            .iter_mut()
+            .map(|e| &mut **e as &mut dyn PostProcessingEffect)
+            .collect();
+        chain.extend(post_processing);
+
+        if chain.len() > 1 && self.post_process_swap_render_target.is_none() {
+            self.post_process_swap_render_target = Some(FramebufferManager::new_render_target(
+                w as usize, h as usize, false,
+            ));
         }
 
-        if post_processing.is_some() {
+        if !chain.is_empty() {
             // if we need post-processing, render to our own frame buffer
             self.framebuffer_manager
                 .select(&self.post_process_render_target);
         } else {
-            self.framebuffer_manager
-                .select(&FramebufferManager::screen());
+            self.select_output_target(offscreen);
         }
 
         for pass in 0usize..camera.num_passes() {
@@ -1095,22 +2205,54 @@ impl Window {
 
         let (znear, zfar) = camera.clip_planes();
 
+        if !chain.is_empty() {
+            self.framebuffer_manager
+                .resolve(&self.post_process_render_target);
+        }
+
         // FIXME: remove this completely?
         // swatch off the wireframe mode for post processing and text rendering.
         // if self.wireframe_mode {
         //     verify!(gl::PolygonMode(Context::FRONT_AND_BACK, Context::FILL));
         // }
 
-        if let Some(ref mut p) = post_processing {
-            // switch back to the screen framebuffer …
-            self.framebuffer_manager
-                .select(&FramebufferManager::screen());
+        let num_effects = chain.len();
+        let screen = FramebufferManager::screen();
+
+        for (i, p) in chain.iter_mut().enumerate() {
+            // Ping-pong between the two post-processing render targets, and switch back to the
+            // screen (or off-screen output) framebuffer for the last effect …
+            let (source, next) = if i % 2 == 0 {
+                (
+                    &self.post_process_render_target,
+                    self.post_process_swap_render_target.as_ref(),
+                )
+            } else {
+                (
+                    self.post_process_swap_render_target.as_ref().unwrap(),
+                    Some(&self.post_process_render_target),
+                )
+            };
+
+            let output = if i + 1 == num_effects {
+                match self.offscreen_render_target {
+                    Some(ref target) if offscreen => target,
+                    _ => &screen,
+                }
+            } else {
+                next.unwrap()
+            };
+
             // … and execute the post-process
             // FIXME: use the real time value instead of 0.016!
             p.update(0.016, w as f32, h as f32, znear, zfar);
-            p.draw(&self.post_process_render_target);
+            p.draw_to_target(source, output, &mut self.framebuffer_manager);
         }
 
+        drop(chain);
+        self.post_processing_effects = effects;
+
+        self.draw_anchored_texts(camera, w as f32, h as f32);
         self.text_renderer.render(w as f32, h as f32);
         #[cfg(feature = "conrod")]
         self.conrod_context.renderer.render(
@@ -1120,40 +2262,151 @@ impl Window {
             &self.conrod_context.textures,
         );
 
-        // We are done: swap buffers
-        self.canvas.swap_buffers();
+        // We are done: swap buffers, or resolve the off-screen target so it can be read. The
+        // frame is recorded before the swap on screen, and after the resolve off-screen since
+        // the pixels of a multisampled target can't be read.
+        if !offscreen {
+            #[cfg(not(target_arch = "wasm32"))]
+            self.record_frame();
+
+            self.canvas.swap_buffers();
+        } else {
+            if let Some(ref target) = self.offscreen_render_target {
+                self.framebuffer_manager.resolve(target);
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            self.record_frame();
+        }
 
         #[cfg(not(target_arch = "wasm32"))]
         {
             // Limit the fps if needed.
             if let Some(dur) = self.max_dur_per_frame {
-                let elapsed = self.curr_time.elapsed();
-                if elapsed < dur {
-                    thread::sleep(dur - elapsed);
+                let frame_end = self.curr_time + dur;
+                let now = Instant::now();
+
+                if now < frame_end {
+                    thread::sleep(frame_end - now);
+                    // Start the next frame where this one was supposed to end so that
+                    // oversleeping does not accumulate.
+                    self.curr_time = frame_end;
+                } else {
+                    self.curr_time = now;
                 }
+            } else {
+                self.curr_time = Instant::now();
             }
+        }
 
-            self.curr_time = Instant::now();
+        let now = Instant::now();
+
+        if let Some(last_frame_end) = self.last_frame_end {
+            if self.frame_times.len() == FPS_AVERAGED_FRAMES {
+                let _ = self.frame_times.pop_front();
+            }
+
+            self.frame_times
+                .push_back(now.duration_since(last_frame_end));
         }
 
+        self.last_frame_end = Some(now);
+
         // self.transparent_objects.clear();
         // self.opaque_objects.clear();
 
         !self.should_close()
     }
 
+    fn select_output_target(&mut self, offscreen: bool) {
+        match self.offscreen_render_target {
+            Some(ref target) if offscreen => self.framebuffer_manager.select(target),
+            _ => self
+                .framebuffer_manager
+                .select(&FramebufferManager::screen()),
+        }
+    }
+
+    fn render_shadow_map(&mut self, camera: &mut dyn Camera, w: u32, h: u32) {
+        let (material, shadow_map) =
+            match (&mut self.shadow_material, self.environment.shadow_map()) {
+                (Some(material), Some(shadow_map)) => (material, shadow_map),
+                _ => return,
+            };
+
+        let transformation = match self.environment.lights().first() {
+            // The scene box is only computed for lights casting shadows.
+            Some(Light::StickToCamera) | None => None,
+            Some(light) => self
+                .scene
+                .loose_aabb()
+                .and_then(|(mins, maxs)| ShadowMap::light_transformation(light, &mins, &maxs)),
+        };
+
+        shadow_map.set_transformation(transformation);
+
+        if let Some(transformation) = transformation {
+            let ctxt = Context::get();
+            let size = shadow_map.size() as i32;
+            let frustum = Frustum::from_matrix(&transformation);
+
+            self.framebuffer_manager.select(shadow_map.render_target());
+            verify!(ctxt.viewport(0, 0, size, size));
+            verify!(ctxt.scissor(0, 0, size, size));
+            // The farthest depth.
+            verify!(ctxt.clear_color(1.0, 1.0, 1.0, 1.0));
+            verify!(ctxt.clear(Context::COLOR_BUFFER_BIT | Context::DEPTH_BUFFER_BIT));
+
+            material.set_light_transformation(transformation);
+            self.scene
+                .render_with_material(0, camera, &self.environment, material, Some(&frustum));
+
+            verify!(ctxt.viewport(0, 0, w as i32, h as i32));
+            verify!(ctxt.scissor(0, 0, w as i32, h as i32));
+        }
+    }
+
     fn render_scene(&mut self, camera: &mut dyn Camera, pass: usize) {
+        self.render_background(camera, pass);
+        self.line_renderer.render(pass, camera);
+        self.point_renderer.render(pass, camera);
+        self.render_objects(camera, pass);
+    }
+
+    fn render_background(&mut self, camera: &mut dyn Camera, pass: usize) {
         let ctxt = Context::get();
         // Activate the default texture
         verify!(ctxt.active_texture(Context::TEXTURE0));
         // Clear the screen to black
-        verify!(ctxt.clear_color(self.background.x, self.background.y, self.background.z, 1.0));
+        verify!(ctxt.clear_color(
+            self.background.x,
+            self.background.y,
+            self.background.z,
+            self.background.w
+        ));
         verify!(ctxt.clear(Context::COLOR_BUFFER_BIT));
         verify!(ctxt.clear(Context::DEPTH_BUFFER_BIT));
 
-        self.line_renderer.render(pass, camera);
-        self.point_renderer.render(pass, camera);
-        self.scene.data_mut().render(pass, camera, &self.light_mode);
+        if let Some(ref mut background) = self.background_renderer {
+            background.render(pass, camera);
+        }
+
+        if let Some(ref mut skybox) = self.skybox {
+            skybox.render(pass, camera);
+        }
+    }
+
+    fn render_objects(&mut self, camera: &mut dyn Camera, pass: usize) {
+        if self.frustum_culling {
+            let frustum = Frustum::from_matrix(&camera.transformation());
+            self.scene
+                .data_mut()
+                .render_culled(pass, camera, &self.environment, &frustum);
+        } else {
+            self.scene
+                .data_mut()
+                .render(pass, camera, &self.environment);
+        }
     }
 
     fn render_planar_scene(&mut self, camera: &mut dyn PlanarCamera) {
@@ -1178,6 +2431,14 @@ impl Window {
         verify!(Context::get().scissor(0, 0, w as i32, h as i32));
         FramebufferManager::screen().resize(w, h);
         self.post_process_render_target.resize(w, h);
+
+        if let Some(ref mut target) = self.post_process_swap_render_target {
+            target.resize(w, h);
+        }
+
+        if let Some(ref mut target) = self.offscreen_render_target {
+            target.resize(w, h);
+        }
     }
 }
 
@@ -1198,3 +2459,13 @@ fn init_gl() {
     verify!(ctxt.enable(Context::CULL_FACE));
     verify!(ctxt.cull_face(Context::BACK));
 }
+
+// Opens a hidden window for a test. The GL context is global, so the returned guard prevents
+// the tests from opening their windows concurrently.
+#[cfg(test)]
+pub(crate) fn open_test_window() -> (std::sync::MutexGuard<'static, ()>, Window) {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    let lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    (lock, Window::new_hidden("kiss3d test"))
+}
@@ -0,0 +1,118 @@
+//! A builder to configure and open windows.
+
+use crate::window::canvas::{CanvasSetup, NumSamples};
+use crate::window::window::{DEFAULT_HEIGHT, DEFAULT_WIDTH};
+use crate::window::Window;
+
+/// A builder to configure and open a window.
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::window::WindowBuilder;
+/// let window = WindowBuilder::new("Kiss3d: builder")
+///     .size(1024, 768)
+///     .samples(4)
+///     .build();
+/// ```
+#[derive(Clone, Debug)]
+pub struct WindowBuilder {
+    title: String,
+    width: u32,
+    height: u32,
+    hidden: bool,
+    fullscreen: bool,
+    transparent: bool,
+    setup: CanvasSetup,
+}
+
+impl WindowBuilder {
+    /// Creates a builder for an 800x600 visible window, with vsync and without multisampling.
+    pub fn new(title: &str) -> WindowBuilder {
+        WindowBuilder {
+            title: title.to_string(),
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            hidden: false,
+            fullscreen: false,
+            transparent: false,
+            setup: CanvasSetup::default(),
+        }
+    }
+
+    /// Sets the window title.
+    pub fn title(mut self, title: &str) -> WindowBuilder {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Sets the initial window size. Ignored on web platforms, where the size of the canvas
+    /// element is used.
+    pub fn size(mut self, width: u32, height: u32) -> WindowBuilder {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Sets whether the window is initially hidden. Use `Window::show` to make it visible.
+    pub fn hidden(mut self, hidden: bool) -> WindowBuilder {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Sets whether the window is initially fullscreen. See `Window::set_fullscreen`.
+    pub fn fullscreen(mut self, fullscreen: bool) -> WindowBuilder {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    /// Enables or disables vertical synchronization.
+    pub fn vsync(mut self, vsync: bool) -> WindowBuilder {
+        self.setup.vsync = vsync;
+        self
+    }
+
+    /// Sets the number of samples used for multisample anti-aliasing, e.g., `samples(4)`.
+    ///
+    /// This applies to the screen as well as the intermediate render targets of
+    /// post-processing effects and off-screen rendering. Requesting 0 or 1 sample disables
+    /// multisampling. If the context can't provide that many samples, the highest supported
+    /// number is used instead and a warning is printed.
+    pub fn samples(mut self, samples: impl Into<NumSamples>) -> WindowBuilder {
+        self.setup.samples = samples.into();
+        self
+    }
+
+    /// Sets whether the framebuffer has an alpha channel composited with the desktop.
+    ///
+    /// This is required for `Window::set_background_color_rgba` to make the window
+    /// transparent, and only works with a compositing window manager. Ignored on web platforms,
+    /// where the canvas is always composited with the page.
+    pub fn transparent(mut self, transparent: bool) -> WindowBuilder {
+        self.transparent = transparent;
+        self
+    }
+
+    /// Sets all the canvas options at once.
+    pub fn setup(mut self, setup: CanvasSetup) -> WindowBuilder {
+        self.setup = setup;
+        self
+    }
+
+    /// Opens the window.
+    pub fn build(self) -> Window {
+        let mut window = Window::do_new(
+            &self.title,
+            self.hidden,
+            self.width,
+            self.height,
+            Some(self.setup),
+            self.transparent,
+        );
+
+        if self.fullscreen {
+            window.set_fullscreen(true);
+        }
+
+        window
+    }
+}